@@ -1,6 +1,7 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn free_string(s: *mut c_char) {
     if !s.is_null() {
@@ -24,6 +25,7 @@ use serde_json;
 use std::ptr;
 use web5::dids::methods::did_dht;
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn did_dht_resolve(uri: *const c_char, gateway_url: *const c_char) -> *mut c_char {
     let uri = match unsafe { CStr::from_ptr(uri).to_str() } {
@@ -56,6 +58,7 @@ pub extern "C" fn did_dht_resolve(uri: *const c_char, gateway_url: *const c_char
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn free_bytes(ptr: *mut u8) {
     if !ptr.is_null() {
         unsafe {
@@ -64,6 +67,7 @@ pub fn free_bytes(ptr: *mut u8) {
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn opt_cstr_to_string(c_str: *const c_char) -> Option<String> {
     if c_str.is_null() {
         None
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use web5::crypto::dsa::{ed25519::Ed25519Signer, Signer};
 use web5::crypto::jwk::Jwk;
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn new_ed25519_signer(cjwk_ptr: *const CJwk) -> *mut CSigner {
     if cjwk_ptr.is_null() {
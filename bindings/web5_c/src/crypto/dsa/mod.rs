@@ -43,6 +43,7 @@ pub extern "C" fn alloc_csigner() -> *mut CSigner {
     Box::into_raw(Box::new(signer))
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn free_csigner(signer: *mut CSigner) {
     if !signer.is_null() {
@@ -52,6 +53,7 @@ pub extern "C" fn free_csigner(signer: *mut CSigner) {
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn rust_signer_sign(
     signer_id: i32,
     payload: *const u8,
@@ -72,6 +74,7 @@ pub extern "C" fn rust_signer_sign(
     ptr::null_mut()
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn call_sign(
     signer: *const CSigner,
@@ -1,6 +1,7 @@
 use super::{call_sign, CSigner};
 use crate::c::free_bytes;
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn poc_signer_from_foreign(signer: *const CSigner) {
     if signer.is_null() {
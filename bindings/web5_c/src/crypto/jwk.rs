@@ -27,6 +27,7 @@ pub extern "C" fn alloc_cjwk() -> *mut CJwk {
     Box::into_raw(Box::new(jwk))
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn free_cjwk(jwk: *mut CJwk) {
     if !jwk.is_null() {
@@ -80,6 +81,7 @@ impl From<Jwk> for CJwk {
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn jwk_compute_thumbprint(jwk_ptr: *const CJwk) -> *mut c_char {
     if jwk_ptr.is_null() {
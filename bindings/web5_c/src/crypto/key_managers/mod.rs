@@ -49,6 +49,7 @@ pub extern "C" fn alloc_ckeymanager() -> *mut CKeyManager {
     Box::into_raw(Box::new(manager))
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn free_ckeymanager(manager: *mut CKeyManager) {
     if !manager.is_null() {
@@ -58,6 +59,7 @@ pub extern "C" fn free_ckeymanager(manager: *mut CKeyManager) {
     }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn rust_key_manager_import_private_jwk(
     manager_id: i32,
     private_jwk: *const CJwk,
@@ -73,6 +75,7 @@ pub extern "C" fn rust_key_manager_import_private_jwk(
     ptr::null_mut()
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn rust_key_manager_get_signer(
     manager_id: i32,
     public_jwk: *const CJwk,
@@ -92,6 +95,7 @@ pub extern "C" fn rust_key_manager_get_signer(
     ptr::null_mut()
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn call_import_private_jwk(
     manager: *const CKeyManager,
@@ -100,6 +104,7 @@ pub extern "C" fn call_import_private_jwk(
     unsafe { ((*manager).import_private_jwk)((*manager).manager_id, private_jwk) }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn call_get_signer(
     manager: *const CKeyManager,
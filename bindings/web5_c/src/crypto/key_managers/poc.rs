@@ -8,6 +8,7 @@ use crate::{
 };
 use web5::crypto::jwk::Jwk;
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
 pub extern "C" fn poc_key_manager_from_foreign(manager: *const CKeyManager) {
     if manager.is_null() {
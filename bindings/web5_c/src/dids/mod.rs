@@ -0,0 +1 @@
+pub mod portable_did;
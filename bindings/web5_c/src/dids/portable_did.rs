@@ -0,0 +1,65 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+use web5::{
+    dids::portable_did::PortableDid,
+    json::{FromJson, ToJson},
+};
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn portable_did_encrypt(
+    portable_did_json: *const c_char,
+    password: *const c_char,
+) -> *mut c_char {
+    let (portable_did_json, password) = match unsafe {
+        (
+            CStr::from_ptr(portable_did_json).to_str(),
+            CStr::from_ptr(password).to_str(),
+        )
+    } {
+        (Ok(portable_did_json), Ok(password)) => (portable_did_json, password),
+        _ => return ptr::null_mut(),
+    };
+
+    let encrypted = match PortableDid::from_json_string(portable_did_json)
+        .and_then(|portable_did| portable_did.encrypt(password))
+    {
+        Ok(encrypted) => encrypted,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    match CString::new(encrypted) {
+        Ok(c_str) => c_str.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn portable_did_from_encrypted(
+    encrypted_portable_did: *const c_char,
+    password: *const c_char,
+) -> *mut c_char {
+    let (encrypted_portable_did, password) = match unsafe {
+        (
+            CStr::from_ptr(encrypted_portable_did).to_str(),
+            CStr::from_ptr(password).to_str(),
+        )
+    } {
+        (Ok(encrypted_portable_did), Ok(password)) => (encrypted_portable_did, password),
+        _ => return ptr::null_mut(),
+    };
+
+    let portable_did_json = match PortableDid::from_encrypted(encrypted_portable_did, password)
+        .and_then(|portable_did| portable_did.to_json_string())
+    {
+        Ok(portable_did_json) => portable_did_json,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    match CString::new(portable_did_json) {
+        Ok(c_str) => c_str.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
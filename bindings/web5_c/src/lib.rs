@@ -1,3 +1,4 @@
 pub mod crypto;
+pub mod dids;
 
 pub mod c;
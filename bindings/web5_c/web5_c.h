@@ -58,4 +58,9 @@ char *did_dht_resolve(const char *uri, const char *gateway_url);
 
 /** --- */
 
+/** portable did */
+char *portable_did_encrypt(const char *portable_did_json, const char *password);
+char *portable_did_from_encrypted(const char *encrypted_portable_did, const char *password);
+/** --- */
+
 #endif // WEB5_C_H
\ No newline at end of file
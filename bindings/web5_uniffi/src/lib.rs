@@ -31,7 +31,7 @@ use web5_uniffi_wrapper::{
         methods::{
             did_dht::{did_dht_create, did_dht_publish, did_dht_resolve, DidDhtCreateOptions},
             did_jwk::{did_jwk_create, did_jwk_resolve, DidJwkCreateOptions},
-            did_web::{did_web_create, did_web_resolve, DidWebCreateOptions},
+            did_web::{did_web_create, did_web_hosting_path, did_web_resolve, DidWebCreateOptions},
         },
         portable_did::PortableDid,
         resolution::resolution_result::ResolutionResult,
@@ -42,11 +42,13 @@ use web5_uniffi_wrapper::{
 use web5::{
     credentials::{
         CredentialSchema as CredentialSchemaData, CredentialStatus as CredentialStatusData,
+        StatusMessage as StatusMessageData,
     },
     crypto::{dsa::Dsa, jwk::Jwk as JwkData},
     dids::{
         data_model::{
-            document::Document as DocumentData, service::Service as ServiceData,
+            document::{Document as DocumentData, VerificationRelationship},
+            service::{Service as ServiceData, ServiceEndpoint},
             verification_method::VerificationMethod as VerificationMethodData,
         },
         did::Did as DidData,
@@ -59,6 +61,39 @@ use web5::{
             resolution_result::ResolutionResult as ResolutionResultData,
         },
     },
+    json::{FromJson, JsonObject, ToJson},
 };
 
-uniffi::include_scaffolding!("web5");
+// the generated scaffolding has doc comments followed by empty lines
+#[allow(clippy::empty_line_after_doc_comments)]
+mod scaffolding {
+    use super::*;
+
+    uniffi::include_scaffolding!("web5");
+}
+
+pub use scaffolding::*;
+
+impl UniffiCustomTypeConverter for ServiceEndpoint {
+    type Builtin = String;
+
+    fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
+        Ok(ServiceEndpoint::from_json_string(&val)?)
+    }
+
+    fn from_custom(obj: Self) -> Self::Builtin {
+        obj.to_json_string().unwrap_or_default()
+    }
+}
+
+impl UniffiCustomTypeConverter for JsonObject {
+    type Builtin = String;
+
+    fn into_custom(val: Self::Builtin) -> uniffi::Result<Self> {
+        Ok(JsonObject::from_json_string(&val)?)
+    }
+
+    fn from_custom(obj: Self) -> Self::Builtin {
+        obj.to_json_string().unwrap_or_default()
+    }
+}
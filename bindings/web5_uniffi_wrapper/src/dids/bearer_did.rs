@@ -36,6 +36,15 @@ impl BearerDid {
         Ok(Self(inner_bearer_did))
     }
 
+    pub fn from_encrypted_portable_did(
+        encrypted_portable_did: String,
+        password: String,
+    ) -> Result<Self> {
+        let inner_bearer_did =
+            InnerBearerDid::from_encrypted_portable_did(&encrypted_portable_did, &password)?;
+        Ok(Self(inner_bearer_did))
+    }
+
     pub fn get_data(&self) -> BearerDidData {
         let outer_key_manager = ToOuterKeyManager(self.0.key_manager.clone());
 
@@ -57,4 +66,16 @@ impl BearerDid {
         let inner_portable_did = self.0.to_portable_did(inner_key_exporter)?;
         Ok(Arc::new(PortableDid(inner_portable_did)))
     }
+
+    pub fn to_encrypted_portable_did(
+        &self,
+        key_exporter: Arc<dyn KeyExporter>,
+        password: String,
+    ) -> Result<String> {
+        let inner_key_exporter = Arc::new(ToInnerKeyExporter(key_exporter));
+        let encrypted_portable_did = self
+            .0
+            .to_encrypted_portable_did(inner_key_exporter, &password)?;
+        Ok(encrypted_portable_did)
+    }
 }
@@ -4,9 +4,12 @@ use crate::{
     errors::Result,
     get_rt,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use web5::dids::{
-    data_model::{service::Service, verification_method::VerificationMethod},
+    data_model::{
+        document::VerificationRelationship, service::Service,
+        verification_method::VerificationMethod,
+    },
     methods::did_dht::{DidDht as InnerDidDht, DidDhtCreateOptions as InnerDidDhtCreateOptions},
 };
 
@@ -25,6 +28,8 @@ pub struct DidDhtCreateOptions {
     pub controller: Option<Vec<String>>,
     pub also_known_as: Option<Vec<String>>,
     pub verification_method: Option<Vec<VerificationMethod>>,
+    pub verification_relationships: Option<HashMap<String, Vec<VerificationRelationship>>>,
+    pub key_agreement: Option<bool>,
 }
 
 pub fn did_dht_create(options: Option<DidDhtCreateOptions>) -> Result<Arc<BearerDid>> {
@@ -39,6 +44,8 @@ pub fn did_dht_create(options: Option<DidDhtCreateOptions>) -> Result<Arc<Bearer
         controller: o.controller,
         also_known_as: o.also_known_as,
         verification_method: o.verification_method,
+        verification_relationships: o.verification_relationships,
+        key_agreement: o.key_agreement,
     });
 
     let rt = get_rt()?;
@@ -20,6 +20,7 @@ pub fn did_jwk_resolve(uri: &str) -> Arc<ResolutionResult> {
 pub struct DidJwkCreateOptions {
     pub key_manager: Option<Arc<dyn KeyManager>>,
     pub dsa: Option<Dsa>,
+    pub key_agreement: Option<bool>,
 }
 
 pub fn did_jwk_create(options: Option<DidJwkCreateOptions>) -> Result<Arc<BearerDid>> {
@@ -29,6 +30,7 @@ pub fn did_jwk_create(options: Option<DidJwkCreateOptions>) -> Result<Arc<Bearer
             None => None,
             Some(km) => Some(Arc::new(ToInnerKeyManager(km))),
         },
+        key_agreement: o.key_agreement,
     });
 
     let inner_bearer_did = InnerDidJwk::create(inner_options)?;
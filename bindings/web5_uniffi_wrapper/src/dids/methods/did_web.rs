@@ -1,13 +1,17 @@
 use crate::{
     crypto::key_manager::{KeyManager, ToInnerKeyManager},
     dids::{bearer_did::BearerDid, resolution::resolution_result::ResolutionResult},
-    errors::Result, get_rt,
+    errors::Result,
+    get_rt,
 };
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use web5::{
     crypto::dsa::Dsa,
     dids::{
-        data_model::{service::Service, verification_method::VerificationMethod},
+        data_model::{
+            document::VerificationRelationship, service::Service,
+            verification_method::VerificationMethod,
+        },
         methods::did_web::{
             DidWeb as InnerDidWeb, DidWebCreateOptions as InnerDidWebCreateOptions,
         },
@@ -28,6 +32,8 @@ pub struct DidWebCreateOptions {
     pub controller: Option<Vec<String>>,
     pub also_known_as: Option<Vec<String>>,
     pub verification_method: Option<Vec<VerificationMethod>>,
+    pub verification_relationships: Option<HashMap<String, Vec<VerificationRelationship>>>,
+    pub key_agreement: Option<bool>,
 }
 
 pub fn did_web_create(
@@ -44,8 +50,14 @@ pub fn did_web_create(
         controller: o.controller,
         also_known_as: o.also_known_as,
         verification_method: o.verification_method,
+        verification_relationships: o.verification_relationships,
+        key_agreement: o.key_agreement,
     });
 
     let inner_bearer_did = InnerDidWeb::create(&domain, inner_options)?;
     Ok(Arc::new(BearerDid(inner_bearer_did)))
 }
+
+pub fn did_web_hosting_path(did_uri: &str) -> Result<String> {
+    Ok(InnerDidWeb::hosting_path(did_uri)?)
+}
@@ -21,6 +21,12 @@ impl PortableDid {
         Ok(Self(inner_portable_did))
     }
 
+    pub fn from_encrypted(encrypted_portable_did: &str, password: &str) -> Result<Self> {
+        let inner_portable_did =
+            InnerPortableDid::from_encrypted(encrypted_portable_did, password)?;
+        Ok(Self(inner_portable_did))
+    }
+
     pub fn get_data(&self) -> InnerPortableDid {
         self.0.clone()
     }
@@ -29,4 +35,9 @@ impl PortableDid {
         let json_string = self.0.to_json_string()?;
         Ok(json_string)
     }
+
+    pub fn encrypt(&self, password: &str) -> Result<String> {
+        let encrypted_portable_did = self.0.encrypt(password)?;
+        Ok(encrypted_portable_did)
+    }
 }
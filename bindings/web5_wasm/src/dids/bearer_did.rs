@@ -38,6 +38,17 @@ impl WasmBearerDid {
         })
     }
 
+    #[wasm_bindgen]
+    pub fn from_encrypted_portable_did(
+        encrypted_portable_did: &str,
+        password: &str,
+    ) -> Result<WasmBearerDid> {
+        Ok(Self {
+            inner: BearerDid::from_encrypted_portable_did(encrypted_portable_did, password)
+                .map_err(map_err)?,
+        })
+    }
+
     // todo key exporter for to_portable_did
 
     #[wasm_bindgen]
@@ -1,12 +1,12 @@
+use crate::crypto::jwk::WasmJwk;
 use crate::errors::{map_err, Result};
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 use web5::{
     dids::data_model::{
         document::Document, service::Service, verification_method::VerificationMethod,
     },
     json::{FromJson, ToJson},
 };
-use crate::crypto::jwk::WasmJwk;
 
 #[wasm_bindgen]
 pub struct WasmDocument {
@@ -210,14 +210,27 @@ impl From<Service> for WasmService {
 #[wasm_bindgen]
 impl WasmService {
     #[wasm_bindgen(constructor)]
-    pub fn new(id: String, r#type: String, service_endpoint: Vec<String>) -> Self {
-        Self {
+    pub fn new(
+        id: String,
+        r#type: String,
+        service_endpoint: JsValue,
+        additional_properties: JsValue,
+    ) -> Result<Self> {
+        let service_endpoint = serde_wasm_bindgen::from_value(service_endpoint)?;
+        let additional_properties = if additional_properties.is_undefined() {
+            None
+        } else {
+            serde_wasm_bindgen::from_value(additional_properties)?
+        };
+
+        Ok(Self {
             inner: Service {
                 id,
                 r#type,
                 service_endpoint,
+                additional_properties,
             },
-        }
+        })
     }
 
     #[wasm_bindgen(getter)]
@@ -231,7 +244,17 @@ impl WasmService {
     }
 
     #[wasm_bindgen(getter)]
-    pub fn service_endpoint(&self) -> Vec<String> {
-        self.inner.service_endpoint.clone()
+    pub fn service_endpoint(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.inner.service_endpoint).unwrap_or(JsValue::undefined())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn additional_properties(&self) -> JsValue {
+        match &self.inner.additional_properties {
+            Some(properties) => {
+                serde_wasm_bindgen::to_value(properties).unwrap_or(JsValue::undefined())
+            }
+            None => JsValue::undefined(),
+        }
     }
 }
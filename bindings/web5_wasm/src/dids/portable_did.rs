@@ -49,6 +49,19 @@ impl WasmPortableDid {
         self.inner.to_json_string().map_err(map_err)
     }
 
+    #[wasm_bindgen]
+    pub fn from_encrypted(encrypted_portable_did: &str, password: &str) -> Result<WasmPortableDid> {
+        Ok(Self {
+            inner: PortableDid::from_encrypted(encrypted_portable_did, password)
+                .map_err(map_err)?,
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn encrypt(&self, password: &str) -> Result<String> {
+        self.inner.encrypt(password).map_err(map_err)
+    }
+
     #[wasm_bindgen(getter)]
     pub fn did_uri(&self) -> String {
         self.inner.did_uri.clone()
@@ -13,6 +13,9 @@ pub struct FetchOptions {
     pub method: Option<Method>,
     pub headers: Option<HashMap<String, String>>,
     pub body: Option<Vec<u8>>,
+    /// The largest response body to accept, in bytes. Larger responses fail with
+    /// [`Error::Response`] rather than being read to the end.
+    pub max_response_size: Option<usize>,
 }
 
 #[derive(Serialize, Deserialize)]
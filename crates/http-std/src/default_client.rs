@@ -10,6 +10,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
+/// The allowance for the status line and headers, on top of a response's `max_response_size`.
+const MAX_HEADER_LEN: usize = 16 * 1024;
+
 struct Destination {
     pub host: String,
     pub path: String,
@@ -45,7 +48,8 @@ fn parse_destination(url: &str) -> Result<Destination> {
     })
 }
 
-fn transmit(destination: &Destination, request: &[u8]) -> Result<Vec<u8>> {
+/// Sends the request and reads the response, up to `limit` bytes.
+fn transmit(destination: &Destination, request: &[u8], limit: u64) -> Result<Vec<u8>> {
     let mut buffer = Vec::new();
 
     if destination.schema == "https" {
@@ -88,6 +92,7 @@ fn transmit(destination: &Destination, request: &[u8]) -> Result<Vec<u8>> {
             .map_err(|err| Error::Network(err.to_string()))?;
 
         tls_stream
+            .take(limit)
             .read_to_end(&mut buffer)
             .map_err(|err| Error::Network(err.to_string()))?;
     } else {
@@ -110,6 +115,7 @@ fn transmit(destination: &Destination, request: &[u8]) -> Result<Vec<u8>> {
             .map_err(|err| Error::Network(err.to_string()))?;
 
         stream
+            .take(limit)
             .read_to_end(&mut buffer)
             .map_err(|err| Error::Network(err.to_string()))?;
     }
@@ -186,8 +192,61 @@ impl Client for DefaultClient {
             request_bytes.extend_from_slice(body);
         }
 
-        let response_bytes = transmit(&destination, &request_bytes)?;
+        // one byte past the limit, to tell a response of exactly the limit from a larger one
+        let limit = options
+            .max_response_size
+            .map_or(u64::MAX, |max| (max + MAX_HEADER_LEN) as u64 + 1);
+        let response_bytes = transmit(&destination, &request_bytes, limit)?;
+
+        let response = parse_response(&response_bytes)?;
+        if let Some(max_response_size) = options.max_response_size {
+            if response.body.len() > max_response_size {
+                return Err(Error::Response(format!(
+                    "response body is larger than {} bytes",
+                    max_response_size
+                )));
+            }
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serves one request with the body, on a thread of its own as the client blocks.
+    fn serve_once(body: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+            stream.write_all(header.as_bytes()).unwrap();
+            let _ = stream.write_all(body);
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn options(max_response_size: usize) -> Option<FetchOptions> {
+        Some(FetchOptions {
+            max_response_size: Some(max_response_size),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_max_response_size() {
+        let url = serve_once(b"hello");
+        let response = DefaultClient.fetch(&url, options(5)).await.unwrap();
+        assert_eq!(response.body, b"hello");
 
-        parse_response(&response_bytes)
+        let url = serve_once(b"hello");
+        let result = DefaultClient.fetch(&url, options(4)).await;
+        assert!(matches!(result, Err(Error::Response(_))));
     }
 }
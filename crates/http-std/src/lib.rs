@@ -4,6 +4,8 @@ mod default_client;
 mod error;
 #[cfg(not(target_arch = "wasm32"))]
 mod reqwest_client;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
 
 #[cfg(target_arch = "wasm32")]
 use async_trait::async_trait;
@@ -11,7 +13,11 @@ use lazy_static::lazy_static;
 use std::sync::{Arc, Mutex};
 
 pub use client::{Client, FetchOptions, Method, Response};
+#[cfg(not(target_arch = "wasm32"))]
+pub use default_client::DefaultClient;
 pub use error::{Error, Result};
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::{Handler, Request, Server};
 
 #[cfg(not(target_arch = "wasm32"))]
 lazy_static! {
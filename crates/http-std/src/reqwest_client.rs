@@ -1,4 +1,4 @@
-use crate::{Client, FetchOptions, Method, Response, Result};
+use crate::{Client, Error, FetchOptions, Method, Response, Result};
 use async_trait::async_trait;
 use reqwest::header::HeaderMap;
 use std::collections::HashMap;
@@ -44,7 +44,7 @@ impl Client for ReqwestClient {
             req = req.body(body);
         }
 
-        let res = req.send().await.map_err(crate::Error::from)?;
+        let mut res = req.send().await.map_err(crate::Error::from)?;
 
         let status_code = res.status().as_u16();
         let mut headers = HashMap::new();
@@ -52,7 +52,26 @@ impl Client for ReqwestClient {
             headers.insert(key.to_string(), value.to_str().unwrap().to_string());
         }
 
-        let body = res.bytes().await.map_err(crate::Error::from)?.to_vec();
+        let body = match options.max_response_size {
+            Some(max_response_size) => {
+                if res
+                    .content_length()
+                    .is_some_and(|length| length > max_response_size as u64)
+                {
+                    return Err(response_too_large(max_response_size));
+                }
+
+                let mut body = Vec::new();
+                while let Some(chunk) = res.chunk().await.map_err(crate::Error::from)? {
+                    if body.len() + chunk.len() > max_response_size {
+                        return Err(response_too_large(max_response_size));
+                    }
+                    body.extend_from_slice(&chunk);
+                }
+                body
+            }
+            None => res.bytes().await.map_err(crate::Error::from)?.to_vec(),
+        };
 
         Ok(Response {
             status_code,
@@ -61,3 +80,10 @@ impl Client for ReqwestClient {
         })
     }
 }
+
+fn response_too_large(max_response_size: usize) -> Error {
+    Error::Response(format!(
+        "response body is larger than {} bytes",
+        max_response_size
+    ))
+}
@@ -0,0 +1,373 @@
+use crate::{Error, Method, Response, Result};
+use async_trait::async_trait;
+use std::{collections::HashMap, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+const MAX_HEADER_LEN: usize = 16 * 1024;
+const MAX_BODY_LEN: usize = 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Request {
+    pub method: Method,
+    pub path: String,
+    pub query: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Case-insensitive header lookup.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[async_trait]
+pub trait Handler: Send + Sync {
+    async fn handle(&self, request: Request) -> Response;
+}
+
+/// A minimal HTTP/1.1 server which answers each connection with a single response
+/// produced by a [`Handler`] and then closes it.
+///
+/// Requests must be read within 10 seconds and have bodies of at most 1 MiB, and are otherwise
+/// answered with `408` or `413`. Methods other than those of [`Method`] are answered with `501`.
+pub struct Server {
+    listener: TcpListener,
+    read_timeout: Duration,
+    max_body_len: usize,
+}
+
+impl Server {
+    pub async fn bind(addr: &str) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|err| Error::Network(format!("failed to bind {}: {}", addr, err)))?;
+        Ok(Self {
+            listener,
+            read_timeout: READ_TIMEOUT,
+            max_body_len: MAX_BODY_LEN,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener
+            .local_addr()
+            .map_err(|err| Error::Network(err.to_string()))
+    }
+
+    pub async fn serve(self, handler: Arc<dyn Handler>) -> Result<()> {
+        loop {
+            let (stream, _) = self
+                .listener
+                .accept()
+                .await
+                .map_err(|err| Error::Network(err.to_string()))?;
+            let handler = handler.clone();
+            let limits = (self.read_timeout, self.max_body_len);
+            tokio::spawn(async move {
+                let _ = handle_connection(stream, handler, limits).await;
+            });
+        }
+    }
+}
+
+/// A request which can't be handled, and the status code to answer it with.
+struct RequestError {
+    status_code: u16,
+    message: String,
+}
+
+impl RequestError {
+    fn new(status_code: u16, message: impl Into<String>) -> Self {
+        Self {
+            status_code,
+            message: message.into(),
+        }
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    handler: Arc<dyn Handler>,
+    (read_timeout, max_body_len): (Duration, usize),
+) -> Result<()> {
+    let request = match timeout(read_timeout, read_request(&mut stream, max_body_len)).await {
+        Ok(request) => request,
+        Err(_) => Err(RequestError::new(408, "timed out reading request")),
+    };
+    let response = match request {
+        Ok(request) => handler.handle(request).await,
+        Err(err) => Response {
+            status_code: err.status_code,
+            headers: HashMap::new(),
+            body: err.message.into_bytes(),
+        },
+    };
+
+    stream
+        .write_all(&serialize_response(&response))
+        .await
+        .map_err(|err| Error::Network(err.to_string()))?;
+    stream
+        .flush()
+        .await
+        .map_err(|err| Error::Network(err.to_string()))?;
+    let _ = stream.shutdown().await;
+
+    Ok(())
+}
+
+async fn read_request(
+    stream: &mut TcpStream,
+    max_body_len: usize,
+) -> std::result::Result<Request, RequestError> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position;
+        }
+        if buffer.len() > MAX_HEADER_LEN {
+            return Err(RequestError::new(431, "request headers too large"));
+        }
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|err| RequestError::new(400, err.to_string()))?;
+        if read == 0 {
+            return Err(RequestError::new(400, "connection closed before headers"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let header_str = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut header_lines = header_str.lines();
+    let request_line = header_lines
+        .next()
+        .ok_or_else(|| RequestError::new(400, "missing request line"))?;
+
+    let request_parts: Vec<&str> = request_line.split_whitespace().collect();
+    if request_parts.len() < 3 {
+        return Err(RequestError::new(400, "invalid request line format"));
+    }
+
+    let method = Method::from_str(request_parts[0])
+        .map_err(|err| RequestError::new(501, err.to_string()))?;
+    let (path, query) = match request_parts[1].split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (request_parts[1].to_string(), None),
+    };
+
+    let mut headers = HashMap::new();
+    for line in header_lines {
+        if let Some((key, value)) = line.split_once(':') {
+            headers.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    let content_length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("Content-Length"))
+        .map(|(_, value)| value.parse::<usize>())
+        .transpose()
+        .map_err(|_| RequestError::new(400, "invalid content-length"))?
+        .unwrap_or(0);
+    if content_length > max_body_len {
+        return Err(RequestError::new(413, "request body too large"));
+    }
+
+    let mut body = buffer[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|err| RequestError::new(400, err.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    })
+}
+
+fn serialize_response(response: &Response) -> Vec<u8> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status_code,
+        reason_phrase(response.status_code)
+    );
+    for (key, value) in &response.headers {
+        if key.eq_ignore_ascii_case("Content-Length") || key.eq_ignore_ascii_case("Connection") {
+            continue;
+        }
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    ));
+
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(&response.body);
+    bytes
+}
+
+fn reason_phrase(status_code: u16) -> &'static str {
+    match status_code {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        408 => "Request Timeout",
+        410 => "Gone",
+        413 => "Content Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fetch, Error, FetchOptions};
+    use tokio::io::AsyncWriteExt;
+
+    struct EchoHandler;
+
+    #[async_trait]
+    impl Handler for EchoHandler {
+        async fn handle(&self, request: Request) -> Response {
+            let mut headers = HashMap::new();
+            headers.insert("Content-Type".to_string(), "text/plain".to_string());
+            let body = format!(
+                "{} {} {} {}",
+                request.method,
+                request.path,
+                request.query.unwrap_or_default(),
+                String::from_utf8_lossy(&request.body)
+            );
+            Response {
+                status_code: 200,
+                headers,
+                body: body.into_bytes(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serve_round_trip() {
+        let server = Server::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve(Arc::new(EchoHandler)));
+
+        let response = fetch(
+            &format!("http://{}/some/path?a=b", addr),
+            Some(FetchOptions {
+                method: Some(Method::Post),
+                body: Some(b"hello".to_vec()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            String::from_utf8(response.body).unwrap(),
+            "POST /some/path a=b hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_response_size() {
+        let server = Server::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve(Arc::new(EchoHandler)));
+        let url = format!("http://{}/some/path", addr);
+
+        // the echoed body is "POST /some/path  hello", 22 bytes
+        let options = |max_response_size| {
+            Some(FetchOptions {
+                method: Some(Method::Post),
+                body: Some(b"hello".to_vec()),
+                max_response_size: Some(max_response_size),
+                ..Default::default()
+            })
+        };
+
+        let response = fetch(&url, options(22)).await.unwrap();
+        assert_eq!(response.body.len(), 22);
+
+        let result = fetch(&url, options(21)).await;
+        assert!(matches!(result, Err(Error::Response(_))));
+    }
+
+    async fn raw_request(server: Server, request: &[u8]) -> String {
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(server.serve(Arc::new(EchoHandler)));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        String::from_utf8_lossy(&response).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method() {
+        let server = Server::bind("127.0.0.1:0").await.unwrap();
+
+        let response = raw_request(server, b"BREW /pot HTTP/1.1\r\n\r\n").await;
+
+        assert!(response.starts_with("HTTP/1.1 501 "));
+    }
+
+    #[tokio::test]
+    async fn test_body_too_large() {
+        let mut server = Server::bind("127.0.0.1:0").await.unwrap();
+        server.max_body_len = 4;
+
+        let response = raw_request(
+            server,
+            b"POST /some/path HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello",
+        )
+        .await;
+
+        assert!(response.starts_with("HTTP/1.1 413 "));
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout() {
+        let mut server = Server::bind("127.0.0.1:0").await.unwrap();
+        server.read_timeout = Duration::from_millis(50);
+
+        // the body is never sent
+        let response = raw_request(
+            server,
+            b"POST /some/path HTTP/1.1\r\nContent-Length: 5\r\n\r\n",
+        )
+        .await;
+
+        assert!(response.starts_with("HTTP/1.1 408 "));
+    }
+}
@@ -0,0 +1,899 @@
+use super::status_list_credential::CredentialStatusResult;
+use super::verifiable_credential_1_1::{CredentialStatus, StatusMessage};
+use super::verifiable_credential_2_0::{
+    VerifiableCredentialV2, VerifiableCredentialV2CreateOptions,
+};
+use crate::credentials::{CredentialSubject, Issuer};
+use crate::errors::{Result, Web5Error};
+use crate::json::{JsonObject, JsonValue};
+use base64::Engine;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::{Read, Write};
+use std::time::SystemTime;
+
+pub const BITSTRING_STATUS_LIST_CREDENTIAL_TYPE: &str = "BitstringStatusListCredential";
+pub const BITSTRING_STATUS_LIST: &str = "BitstringStatusList";
+pub const BITSTRING_STATUS_LIST_ENTRY: &str = "BitstringStatusListEntry";
+
+/// The status purposes defined by the Bitstring Status List specification.
+pub const STATUS_PURPOSES: &[&str] = &["revocation", "suspension", "refresh", "message"];
+
+/// The minimum size of an uncompressed bitstring, 16KB, for herd privacy.
+const MINIMUM_BITSTRING_LENGTH: usize = 16 * 1024 * 8;
+
+/// The maximum size of an uncompressed bitstring, 16MB, bounding the decompression of a list.
+const MAXIMUM_BITSTRING_LENGTH: usize = 16 * 1024 * 1024 * 8;
+
+/// The multibase prefix of base64url without padding.
+const MULTIBASE_BASE64URL_PREFIX: char = 'u';
+
+/// A [Bitstring Status List](https://www.w3.org/TR/vc-bitstring-status-list/), in which the
+/// status of each credential is a `status_size`-bit value at its index, most significant bit
+/// first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitstringStatusList {
+    status_size: u32,
+    bitstring: Vec<u8>,
+}
+
+impl BitstringStatusList {
+    /// Creates a status list of all zero statuses.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The number of entries. The list is padded to at least 16KB, so it may hold
+    ///   more entries than requested, and may be at most 16MB.
+    /// * `status_size` - The number of bits of each status, from 1 to 64.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut status_list = BitstringStatusList::new(131_072, 2).unwrap();
+    /// status_list.set(94567, 2).unwrap();
+    /// let encoded_list = status_list.encode().unwrap();
+    /// ```
+    pub fn new(length: usize, status_size: u32) -> Result<Self> {
+        validate_status_size(status_size)?;
+        let bits = length
+            .checked_mul(status_size as usize)
+            .ok_or(Web5Error::Parameter(format!(
+                "status list length {} is too large",
+                length
+            )))?
+            .max(MINIMUM_BITSTRING_LENGTH);
+        if bits > MAXIMUM_BITSTRING_LENGTH {
+            return Err(Web5Error::Parameter(format!(
+                "status list length {} is too large",
+                length
+            )));
+        }
+
+        Ok(Self {
+            status_size,
+            bitstring: vec![0u8; bits.div_ceil(8)],
+        })
+    }
+
+    /// Decodes the `encodedList` of a status list, the multibase base64url encoding of the
+    /// GZIP-compressed bitstring. Lists larger than 16MB uncompressed are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded_list` - The encoded list.
+    /// * `status_size` - The number of bits of each status, from the status entry of the
+    ///   credential to check.
+    pub fn decode(encoded_list: &str, status_size: u32) -> Result<Self> {
+        validate_status_size(status_size)?;
+
+        let encoded = encoded_list
+            .strip_prefix(MULTIBASE_BASE64URL_PREFIX)
+            .ok_or(Web5Error::Parameter(
+                "encoded list must be multibase base64url encoded".to_string(),
+            ))?;
+        let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|e| Web5Error::Parameter(format!("failed to decode base64: {}", e)))?;
+
+        // one byte past the maximum, to tell a list of exactly the maximum from a larger one
+        let mut decoder =
+            GzDecoder::new(&compressed[..]).take((MAXIMUM_BITSTRING_LENGTH / 8) as u64 + 1);
+        let mut bitstring = Vec::new();
+        decoder
+            .read_to_end(&mut bitstring)
+            .map_err(|e| Web5Error::Parameter(format!("failed to decompress data: {}", e)))?;
+
+        if bitstring.len() * 8 > MAXIMUM_BITSTRING_LENGTH {
+            return Err(Web5Error::Parameter(
+                "status list is larger than the maximum of 16MB".to_string(),
+            ));
+        }
+
+        if bitstring.len() * 8 < MINIMUM_BITSTRING_LENGTH {
+            return Err(Web5Error::Parameter(format!(
+                "status list is {} bytes, smaller than the minimum of 16KB",
+                bitstring.len()
+            )));
+        }
+
+        Ok(Self {
+            status_size,
+            bitstring,
+        })
+    }
+
+    /// Encodes the status list as an `encodedList`, the multibase base64url encoding of the
+    /// GZIP-compressed bitstring.
+    pub fn encode(&self) -> Result<String> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.bitstring).map_err(|e| {
+            Web5Error::Parameter(format!(
+                "encoder write_all issue while creating bitstring: {}",
+                e
+            ))
+        })?;
+        let compressed = encoder.finish().map_err(|e| {
+            Web5Error::Parameter(format!(
+                "encoder finish issue while creating bitstring: {}",
+                e
+            ))
+        })?;
+
+        Ok(format!(
+            "{}{}",
+            MULTIBASE_BASE64URL_PREFIX,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed)
+        ))
+    }
+
+    /// The number of bits of each status.
+    pub fn status_size(&self) -> u32 {
+        self.status_size
+    }
+
+    /// The number of entries of the list.
+    pub fn length(&self) -> usize {
+        self.bitstring.len() * 8 / self.status_size as usize
+    }
+
+    /// Gets the status at the index.
+    pub fn get(&self, index: usize) -> Result<u64> {
+        let first_bit = self.first_bit(index)?;
+        Ok((0..self.status_size as usize).fold(0, |status, offset| {
+            let bit = first_bit + offset;
+            let value = (self.bitstring[bit / 8] >> (7 - bit % 8)) & 1;
+            (status << 1) | value as u64
+        }))
+    }
+
+    /// Sets the status at the index.
+    pub fn set(&mut self, index: usize, status: u64) -> Result<()> {
+        if self.status_size < 64 && status >> self.status_size != 0 {
+            return Err(Web5Error::Parameter(format!(
+                "status {} does not fit in {} bits",
+                status, self.status_size
+            )));
+        }
+
+        let first_bit = self.first_bit(index)?;
+        for offset in 0..self.status_size as usize {
+            let bit = first_bit + offset;
+            let mask = 1 << (7 - bit % 8);
+            if (status >> (self.status_size as usize - 1 - offset)) & 1 == 1 {
+                self.bitstring[bit / 8] |= mask;
+            } else {
+                self.bitstring[bit / 8] &= !mask;
+            }
+        }
+        Ok(())
+    }
+
+    fn first_bit(&self, index: usize) -> Result<usize> {
+        if index >= self.length() {
+            return Err(Web5Error::Parameter(format!(
+                "status list index {} is out of range for a list of {} entries",
+                index,
+                self.length()
+            )));
+        }
+        Ok(index * self.status_size as usize)
+    }
+}
+
+fn validate_status_size(status_size: u32) -> Result<()> {
+    if !(1..=64).contains(&status_size) {
+        return Err(Web5Error::Parameter(format!(
+            "status size {} must be from 1 to 64",
+            status_size
+        )));
+    }
+    Ok(())
+}
+
+/// Represents the options available when creating a Bitstring Status List Credential.
+#[derive(Debug, Default, Clone)]
+pub struct BitstringStatusListCredentialCreateOptions {
+    /// The unique identifier for the credential, which should be the URL it's published at, as
+    /// referenced by the `statusListCredential` of status entries. If not provided then the
+    /// default value will be of format urn:uuid:{uuid}.
+    pub id: Option<String>,
+
+    /// The time, in milliseconds, that verifiers may cache the status list for.
+    pub ttl: Option<u64>,
+
+    /// The date from which the credential is valid. If not provided, defaults to the current date and time.
+    pub valid_from: Option<SystemTime>,
+
+    /// The optional date after which the credential is no longer valid.
+    pub valid_until: Option<SystemTime>,
+}
+
+/// Represents a [Bitstring Status List Credential](https://www.w3.org/TR/vc-bitstring-status-list/),
+/// the successor of the Status List 2021 Credential, which maintains the status of multiple
+/// Verifiable Credentials as values of one or more bits.
+pub struct BitstringStatusListCredential {
+    /// The base Verifiable Credential associated with the Status List.
+    pub base: VerifiableCredentialV2,
+}
+
+impl BitstringStatusListCredential {
+    /// Creates a new Bitstring Status List Credential with the specified issuer, status purpose,
+    /// and status list.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The entity issuing the Status List Credential.
+    /// * `status_purpose` - The purpose of the status (e.g., "revocation" or "message").
+    /// * `status_list` - The statuses of the credentials.
+    /// * `options` - Optional parameters, such as the id the credential is published at.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut status_list = BitstringStatusList::new(131_072, 1).unwrap();
+    /// status_list.set(94567, 1).unwrap();
+    ///
+    /// let status_list_credential = BitstringStatusListCredential::create(
+    ///     Issuer::String(issuer_bearer_did.did.uri.clone()),
+    ///     "revocation".to_string(),
+    ///     &status_list,
+    ///     Some(BitstringStatusListCredentialCreateOptions {
+    ///         id: Some("https://example.com/credentials/status/3".to_string()),
+    ///         ttl: Some(300_000),
+    ///         ..Default::default()
+    ///     }),
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    pub async fn create(
+        issuer: Issuer,
+        status_purpose: String,
+        status_list: &BitstringStatusList,
+        options: Option<BitstringStatusListCredentialCreateOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        if !STATUS_PURPOSES.contains(&status_purpose.as_str()) {
+            return Err(Web5Error::Parameter(format!(
+                "unsupported status purpose: {}",
+                status_purpose
+            )));
+        }
+
+        let mut properties = vec![
+            (
+                "type".to_string(),
+                JsonValue::String(BITSTRING_STATUS_LIST.to_string()),
+            ),
+            (
+                "statusPurpose".to_string(),
+                JsonValue::String(status_purpose),
+            ),
+            (
+                "encodedList".to_string(),
+                JsonValue::String(status_list.encode()?),
+            ),
+        ];
+        if let Some(ttl) = options.ttl {
+            properties.push(("ttl".to_string(), JsonValue::Number(ttl as f64)));
+        }
+
+        let credential_subject = CredentialSubject {
+            id: format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+            additional_properties: Some(JsonObject {
+                properties: properties.into_iter().collect(),
+            }),
+        };
+
+        let vc_options = VerifiableCredentialV2CreateOptions {
+            id: options.id,
+            r#type: Some(vec![BITSTRING_STATUS_LIST_CREDENTIAL_TYPE.to_string()]),
+            valid_from: options.valid_from,
+            valid_until: options.valid_until,
+            ..Default::default()
+        };
+
+        let verifiable_credential =
+            VerifiableCredentialV2::create(issuer, vec![credential_subject], Some(vc_options))
+                .await?;
+
+        Ok(Self {
+            base: verifiable_credential,
+        })
+    }
+
+    /// Constructs a Bitstring Status List Credential from a Verifiable Credential, such as one
+    /// fetched from the `statusListCredential` of a status entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The Verifiable Credential, whose type must include `BitstringStatusListCredential`.
+    pub fn from_base(base: VerifiableCredentialV2) -> Result<Self> {
+        if !base
+            .r#type
+            .contains(&BITSTRING_STATUS_LIST_CREDENTIAL_TYPE.to_string())
+        {
+            return Err(Web5Error::Parameter(format!(
+                "credential type must include {}",
+                BITSTRING_STATUS_LIST_CREDENTIAL_TYPE
+            )));
+        }
+
+        let status_list_credential = Self { base };
+        if status_list_credential.property("type")?
+            != &JsonValue::String(BITSTRING_STATUS_LIST.to_string())
+        {
+            return Err(Web5Error::Parameter(format!(
+                "credential subject type must be {}",
+                BITSTRING_STATUS_LIST
+            )));
+        }
+        Ok(status_list_credential)
+    }
+
+    /// The purposes of the status list. A list may serve several purposes, each entry of which
+    /// has the same status.
+    pub fn status_purposes(&self) -> Result<Vec<String>> {
+        match self.property("statusPurpose")? {
+            JsonValue::String(status_purpose) => Ok(vec![status_purpose.clone()]),
+            JsonValue::Array(status_purposes) => status_purposes
+                .iter()
+                .map(|status_purpose| match status_purpose {
+                    JsonValue::String(status_purpose) => Ok(status_purpose.clone()),
+                    _ => Err(Web5Error::Parameter(
+                        "no valid statusPurpose found".to_string(),
+                    )),
+                })
+                .collect(),
+            _ => Err(Web5Error::Parameter(
+                "no valid statusPurpose found".to_string(),
+            )),
+        }
+    }
+
+    /// The time, in milliseconds, that verifiers may cache the status list for, if given.
+    pub fn ttl(&self) -> Option<u64> {
+        match self.property("ttl") {
+            Ok(JsonValue::Number(ttl)) if *ttl >= 0.0 => Some(*ttl as u64),
+            _ => None,
+        }
+    }
+
+    /// Decodes the status list, with the status size of the entries to check.
+    pub fn status_list(&self, status_size: u32) -> Result<BitstringStatusList> {
+        match self.property("encodedList")? {
+            JsonValue::String(encoded_list) => {
+                BitstringStatusList::decode(encoded_list, status_size)
+            }
+            _ => Err(Web5Error::Parameter(
+                "no valid encodedList found".to_string(),
+            )),
+        }
+    }
+
+    /// Gets the status of a credential according to this Status List Credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_status` - The `BitstringStatusListEntry` of the credential to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CredentialStatusResult)` with the status value, and its message from the entry's
+    ///   `statusMessage` if given.
+    /// * `Err` if the credential status is invalid or incompatible.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let status = status_list_credential
+    ///     .get_status(verifiable_credential.credential_status.as_ref().unwrap())
+    ///     .unwrap();
+    /// if status.status_purpose == "revocation" && status.status == 1 {
+    ///     // revoked
+    /// }
+    /// ```
+    pub fn get_status(
+        &self,
+        credential_status: &CredentialStatus,
+    ) -> Result<CredentialStatusResult> {
+        if credential_status.r#type != BITSTRING_STATUS_LIST_ENTRY {
+            return Err(Web5Error::Parameter(format!(
+                "unsupported status type: {}",
+                credential_status.r#type
+            )));
+        }
+
+        if !self
+            .status_purposes()?
+            .contains(&credential_status.status_purpose)
+        {
+            return Err(Web5Error::Parameter("status purpose mismatch".to_string()));
+        }
+
+        let status_size = credential_status.status_size.unwrap_or(1);
+        validate_status_message(status_size, credential_status.status_message.as_ref())?;
+
+        let index = credential_status
+            .status_list_index
+            .parse::<usize>()
+            .map_err(|_| {
+                Web5Error::Parameter(format!(
+                    "invalid status list index: {}",
+                    credential_status.status_list_index
+                ))
+            })?;
+        let status = self.status_list(status_size)?.get(index)?;
+
+        let message = credential_status
+            .status_message
+            .iter()
+            .flatten()
+            .find(|status_message| parse_status_value(&status_message.status) == Some(status))
+            .map(|status_message| status_message.message.clone());
+
+        Ok(CredentialStatusResult {
+            status_purpose: credential_status.status_purpose.clone(),
+            status,
+            message,
+        })
+    }
+
+    fn property(&self, key: &str) -> Result<&JsonValue> {
+        self.base
+            .credential_subject
+            .first()
+            .and_then(|credential_subject| credential_subject.additional_properties.as_ref())
+            .and_then(|properties| properties.properties.get(key))
+            .ok_or_else(|| Web5Error::Parameter(format!("no valid {} found", key)))
+    }
+}
+
+/// Checks that a multi-bit status has a message for each of its values.
+pub(super) fn validate_status_message(
+    status_size: u32,
+    status_message: Option<&Vec<StatusMessage>>,
+) -> Result<()> {
+    if status_size > 1 {
+        let status_message = status_message.ok_or(Web5Error::Parameter(
+            "status message is required if status size is greater than 1".to_string(),
+        ))?;
+        if status_size < usize::BITS && status_message.len() != 1 << status_size {
+            return Err(Web5Error::Parameter(format!(
+                "status message must have {} entries for a status size of {}",
+                1u64 << status_size,
+                status_size
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parses a status value of a status message, a hexadecimal string such as `0x2`.
+fn parse_status_value(status: &str) -> Option<u64> {
+    let digits = status
+        .strip_prefix("0x")
+        .or_else(|| status.strip_prefix("0X"))?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUER_DID_URI: &str = "did:web:tbd.website";
+    const STATUS_LIST_CREDENTIAL_URL: &str = "https://example.com/credentials/status/3";
+
+    fn credential_status(index: &str, purpose: &str) -> CredentialStatus {
+        CredentialStatus {
+            id: format!("{}#{}", STATUS_LIST_CREDENTIAL_URL, index),
+            r#type: BITSTRING_STATUS_LIST_ENTRY.to_string(),
+            status_purpose: purpose.to_string(),
+            status_list_index: index.to_string(),
+            status_list_credential: STATUS_LIST_CREDENTIAL_URL.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn status_messages() -> Vec<StatusMessage> {
+        ["pending_review", "accepted", "rejected", "undefined"]
+            .iter()
+            .enumerate()
+            .map(|(status, message)| StatusMessage {
+                status: format!("0x{:x}", status),
+                message: message.to_string(),
+            })
+            .collect()
+    }
+
+    async fn status_list_credential(
+        status_purpose: &str,
+        status_list: &BitstringStatusList,
+    ) -> BitstringStatusListCredential {
+        BitstringStatusListCredential::create(
+            Issuer::from(ISSUER_DID_URI),
+            status_purpose.to_string(),
+            status_list,
+            Some(BitstringStatusListCredentialCreateOptions {
+                id: Some(STATUS_LIST_CREDENTIAL_URL.to_string()),
+                ttl: Some(300_000),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+    }
+
+    mod bitstring_status_list {
+        use super::*;
+
+        #[test]
+        fn test_minimum_length() {
+            let status_list = BitstringStatusList::new(100, 1).unwrap();
+            assert_eq!(status_list.length(), MINIMUM_BITSTRING_LENGTH);
+
+            let status_list = BitstringStatusList::new(100, 2).unwrap();
+            assert_eq!(status_list.length(), MINIMUM_BITSTRING_LENGTH / 2);
+
+            let status_list = BitstringStatusList::new(200_000, 1).unwrap();
+            assert_eq!(status_list.length(), 200_000);
+        }
+
+        #[test]
+        fn test_multi_bit_statuses() {
+            let mut status_list = BitstringStatusList::new(0, 3).unwrap();
+            status_list.set(0, 0b101).unwrap();
+            status_list.set(1, 0b111).unwrap();
+            status_list.set(2, 0b010).unwrap();
+            status_list.set(1, 0b001).unwrap();
+
+            assert_eq!(status_list.get(0).unwrap(), 0b101);
+            assert_eq!(status_list.get(1).unwrap(), 0b001);
+            assert_eq!(status_list.get(2).unwrap(), 0b010);
+            assert_eq!(status_list.get(3).unwrap(), 0);
+            // the statuses are packed most significant bit first: 101 001 01|0
+            assert_eq!(status_list.bitstring[0], 0b1010_0101);
+            assert_eq!(status_list.bitstring[1], 0b0000_0000);
+        }
+
+        #[test]
+        fn test_status_does_not_fit() {
+            let mut status_list = BitstringStatusList::new(0, 2).unwrap();
+            let result = status_list.set(0, 4);
+
+            match result {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "status 4 does not fit in 2 bits")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_index_out_of_range() {
+            let status_list = BitstringStatusList::new(0, 1).unwrap();
+            assert!(status_list.get(MINIMUM_BITSTRING_LENGTH - 1).is_ok());
+
+            match status_list.get(MINIMUM_BITSTRING_LENGTH) {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "status list index 131072 is out of range for a list of 131072 entries"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_invalid_status_size() {
+            assert!(BitstringStatusList::new(0, 0).is_err());
+            assert!(BitstringStatusList::new(0, 65).is_err());
+            assert!(BitstringStatusList::new(0, 64).is_ok());
+        }
+
+        #[test]
+        fn test_encode_decode() {
+            let mut status_list = BitstringStatusList::new(0, 2).unwrap();
+            status_list.set(94, 3).unwrap();
+            status_list.set(65_535, 2).unwrap();
+
+            let encoded_list = status_list.encode().unwrap();
+            assert!(encoded_list.starts_with('u'));
+
+            let decoded = BitstringStatusList::decode(&encoded_list, 2).unwrap();
+            assert_eq!(decoded, status_list);
+            assert_eq!(decoded.get(94).unwrap(), 3);
+            assert_eq!(decoded.get(65_535).unwrap(), 2);
+        }
+
+        #[test]
+        fn test_decode_spec_example() {
+            let status_list = BitstringStatusList::decode(
+                "uH4sIAAAAAAAAA-3BMQEAAADCoPVPbQwfoAAAAAAAAAAAAAAAAAAAAIC3AYbSVKsAQAAA",
+                1,
+            )
+            .unwrap();
+
+            assert_eq!(status_list.length(), MINIMUM_BITSTRING_LENGTH);
+            assert_eq!(status_list.get(94567).unwrap(), 0);
+        }
+
+        #[test]
+        fn test_decode_too_small() {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&[0u8; 1024]).unwrap();
+            let encoded_list = format!(
+                "u{}",
+                base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+            );
+
+            match BitstringStatusList::decode(&encoded_list, 1) {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "status list is 1024 bytes, smaller than the minimum of 16KB"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_decode_too_large() {
+            // 32MB of zeros, which compress to a few KB
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+            let zeros = vec![0u8; 1024 * 1024];
+            for _ in 0..32 {
+                encoder.write_all(&zeros).unwrap();
+            }
+            let encoded_list = format!(
+                "u{}",
+                base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(encoder.finish().unwrap())
+            );
+
+            match BitstringStatusList::decode(&encoded_list, 1) {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "status list is larger than the maximum of 16MB")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_new_too_large() {
+            assert!(BitstringStatusList::new(MAXIMUM_BITSTRING_LENGTH, 1).is_ok());
+            assert!(BitstringStatusList::new(MAXIMUM_BITSTRING_LENGTH + 1, 1).is_err());
+        }
+
+        #[test]
+        fn test_decode_requires_multibase() {
+            let encoded_list = BitstringStatusList::new(0, 1).unwrap().encode().unwrap();
+            assert!(BitstringStatusList::decode(&encoded_list[1..], 1).is_err());
+        }
+    }
+
+    mod create {
+        use super::*;
+        use crate::credentials::verifiable_credential_2_0::BASE_CONTEXT;
+
+        #[tokio::test]
+        async fn test_credential() {
+            let status_list = BitstringStatusList::new(0, 1).unwrap();
+            let status_list_credential = status_list_credential("revocation", &status_list).await;
+
+            let base = &status_list_credential.base;
+            assert_eq!(base.id, STATUS_LIST_CREDENTIAL_URL);
+            assert_eq!(base.context, vec![BASE_CONTEXT.to_string()]);
+            assert_eq!(
+                base.r#type,
+                vec![
+                    "VerifiableCredential".to_string(),
+                    BITSTRING_STATUS_LIST_CREDENTIAL_TYPE.to_string()
+                ]
+            );
+            assert_eq!(
+                status_list_credential.property("type").unwrap(),
+                &JsonValue::String(BITSTRING_STATUS_LIST.to_string())
+            );
+            assert_eq!(
+                status_list_credential.status_purposes().unwrap(),
+                vec!["revocation".to_string()]
+            );
+            assert_eq!(status_list_credential.ttl(), Some(300_000));
+            assert_eq!(status_list_credential.status_list(1).unwrap(), status_list);
+        }
+
+        #[tokio::test]
+        async fn test_unsupported_status_purpose() {
+            let result = BitstringStatusListCredential::create(
+                Issuer::from(ISSUER_DID_URI),
+                "expiration".to_string(),
+                &BitstringStatusList::new(0, 1).unwrap(),
+                None,
+            )
+            .await;
+
+            match result {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "unsupported status purpose: expiration")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod from_base {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_round_trip() {
+            let status_list = BitstringStatusList::new(0, 1).unwrap();
+            let base = status_list_credential("suspension", &status_list)
+                .await
+                .base;
+
+            let status_list_credential = BitstringStatusListCredential::from_base(base).unwrap();
+            assert_eq!(
+                status_list_credential.status_purposes().unwrap(),
+                vec!["suspension".to_string()]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_rejects_other_credential_type() {
+            let mut base =
+                status_list_credential("revocation", &BitstringStatusList::new(0, 1).unwrap())
+                    .await
+                    .base;
+            base.r#type = vec!["VerifiableCredential".to_string()];
+
+            match BitstringStatusListCredential::from_base(base) {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "credential type must include BitstringStatusListCredential"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod get_status {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_revocation() {
+            let mut status_list = BitstringStatusList::new(0, 1).unwrap();
+            status_list.set(94567, 1).unwrap();
+            let status_list_credential = status_list_credential("revocation", &status_list).await;
+
+            let status = status_list_credential
+                .get_status(&credential_status("94567", "revocation"))
+                .unwrap();
+            assert_eq!(
+                status,
+                CredentialStatusResult {
+                    status_purpose: "revocation".to_string(),
+                    status: 1,
+                    message: None,
+                }
+            );
+
+            let status = status_list_credential
+                .get_status(&credential_status("94568", "revocation"))
+                .unwrap();
+            assert_eq!(status.status, 0);
+        }
+
+        #[tokio::test]
+        async fn test_message() {
+            let mut status_list = BitstringStatusList::new(0, 2).unwrap();
+            status_list.set(7, 2).unwrap();
+            let status_list_credential = status_list_credential("message", &status_list).await;
+
+            let credential_status = CredentialStatus {
+                status_size: Some(2),
+                status_message: Some(status_messages()),
+                ..credential_status("7", "message")
+            };
+            let status = status_list_credential
+                .get_status(&credential_status)
+                .unwrap();
+            assert_eq!(status.status, 2);
+            assert_eq!(status.message.as_deref(), Some("rejected"));
+        }
+
+        #[tokio::test]
+        async fn test_status_message_required_for_multi_bit_status() {
+            let status_list = BitstringStatusList::new(0, 2).unwrap();
+            let status_list_credential = status_list_credential("message", &status_list).await;
+
+            let credential_status = CredentialStatus {
+                status_size: Some(2),
+                ..credential_status("7", "message")
+            };
+            assert!(status_list_credential
+                .get_status(&credential_status)
+                .is_err());
+
+            let credential_status = CredentialStatus {
+                status_size: Some(2),
+                status_message: Some(status_messages()[..3].to_vec()),
+                ..credential_status
+            };
+            match status_list_credential.get_status(&credential_status) {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "status message must have 4 entries for a status size of 2"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_status_purpose_mismatch() {
+            let status_list = BitstringStatusList::new(0, 1).unwrap();
+            let status_list_credential = status_list_credential("revocation", &status_list).await;
+
+            assert!(status_list_credential
+                .get_status(&credential_status("3", "suspension"))
+                .is_err());
+        }
+
+        #[tokio::test]
+        async fn test_multiple_status_purposes() {
+            let mut status_list = BitstringStatusList::new(0, 1).unwrap();
+            status_list.set(3, 1).unwrap();
+            let mut status_list_credential =
+                status_list_credential("revocation", &status_list).await;
+            status_list_credential.base.credential_subject[0]
+                .additional_properties
+                .as_mut()
+                .unwrap()
+                .properties
+                .insert(
+                    "statusPurpose".to_string(),
+                    JsonValue::Array(vec![
+                        JsonValue::String("revocation".to_string()),
+                        JsonValue::String("suspension".to_string()),
+                    ]),
+                );
+
+            let status = status_list_credential
+                .get_status(&credential_status("3", "suspension"))
+                .unwrap();
+            assert_eq!(status.status_purpose, "suspension");
+            assert_eq!(status.status, 1);
+        }
+
+        #[tokio::test]
+        async fn test_unsupported_status_type() {
+            let status_list = BitstringStatusList::new(0, 1).unwrap();
+            let status_list_credential = status_list_credential("revocation", &status_list).await;
+
+            let credential_status = CredentialStatus {
+                r#type: "StatusList2021Entry".to_string(),
+                ..credential_status("3", "revocation")
+            };
+            match status_list_credential.get_status(&credential_status) {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "unsupported status type: StatusList2021Entry")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+}
@@ -1,10 +1,15 @@
 use super::{
+    credential_schema::validate_against_credential_schema,
     credential_schema::validate_credential_schema,
     credential_subject::CredentialSubject,
     issuer::Issuer,
     verifiable_credential_1_1::{
         VerifiableCredential, VerifiableCredentialCreateOptions, BASE_CONTEXT, BASE_TYPE,
     },
+    verifiable_credential_2_0::{
+        VerifiableCredentialV2, VerifiableCredentialV2CreateOptions,
+        BASE_CONTEXT as BASE_CONTEXT_2_0,
+    },
 };
 use crate::{
     dids::did::Did,
@@ -23,7 +28,7 @@ pub async fn create_vc(
     validate_issuer(&issuer)?;
     validate_credential_subject(&credential_subject)?;
 
-    let context = build_context(options.context);
+    let context = build_context(options.context, BASE_CONTEXT);
     let r#type = build_type(options.r#type);
     let id = options
         .id
@@ -40,6 +45,7 @@ pub async fn create_vc(
         credential_subject,
         credential_schema: options.credential_schema,
         evidence: options.evidence,
+        proof: None,
     };
 
     validate_credential_schema(&verifiable_credential).await?;
@@ -47,6 +53,59 @@ pub async fn create_vc(
     Ok(verifiable_credential)
 }
 
+pub async fn create_vc_v2_0(
+    issuer: Issuer,
+    credential_subject: Vec<CredentialSubject>,
+    options: Option<VerifiableCredentialV2CreateOptions>,
+) -> Result<VerifiableCredentialV2> {
+    let options = options.unwrap_or_default();
+
+    validate_issuer(&issuer)?;
+    if credential_subject.is_empty() {
+        return Err(Web5Error::Parameter(
+            "at least one credential subject is required".into(),
+        ));
+    }
+    for credential_subject in &credential_subject {
+        validate_credential_subject(credential_subject)?;
+    }
+
+    let valid_from = options.valid_from.unwrap_or_else(SystemTime::now);
+    if let Some(valid_until) = options.valid_until {
+        if valid_until < valid_from {
+            return Err(Web5Error::Parameter(
+                "valid until must not be before valid from".into(),
+            ));
+        }
+    }
+
+    let verifiable_credential = VerifiableCredentialV2 {
+        context: build_context(options.context, BASE_CONTEXT_2_0),
+        id: options
+            .id
+            .unwrap_or_else(|| format!("urn:uuid:{}", Uuid::new_v4())),
+        r#type: build_type(options.r#type),
+        name: options.name,
+        description: options.description,
+        issuer,
+        credential_subject,
+        valid_from: Some(valid_from),
+        valid_until: options.valid_until,
+        credential_status: options.credential_status,
+        credential_schema: options.credential_schema,
+        evidence: options.evidence,
+        proof: None,
+    };
+
+    validate_against_credential_schema(
+        verifiable_credential.credential_schema.as_ref(),
+        &verifiable_credential,
+    )
+    .await?;
+
+    Ok(verifiable_credential)
+}
+
 fn validate_issuer(issuer: &Issuer) -> Result<()> {
     if issuer.to_string().is_empty() {
         return Err(Web5Error::Parameter("issuer id must not be empty".into()));
@@ -85,10 +144,10 @@ fn validate_credential_subject(credential_subject: &CredentialSubject) -> Result
     Ok(())
 }
 
-fn build_context(context: Option<Vec<String>>) -> Vec<String> {
-    let mut contexts = context.unwrap_or_else(|| vec![BASE_CONTEXT.to_string()]);
-    if !contexts.contains(&BASE_CONTEXT.to_string()) {
-        contexts.insert(0, BASE_CONTEXT.to_string());
+fn build_context(context: Option<Vec<String>>, base_context: &str) -> Vec<String> {
+    let mut contexts = context.unwrap_or_else(|| vec![base_context.to_string()]);
+    if !contexts.contains(&base_context.to_string()) {
+        contexts.insert(0, base_context.to_string());
     }
     contexts
 }
@@ -689,7 +748,7 @@ mod tests {
             .mock("GET", "/schemas/email.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&mock_json_schema(
+            .with_body(mock_json_schema(
                 url.clone(),
                 Some("this is not a valid $schema".to_string()), // here
             ))
@@ -725,7 +784,7 @@ mod tests {
             .mock("GET", "/schemas/email.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&mock_json_schema(
+            .with_body(mock_json_schema(
                 url.clone(),
                 Some("http://json-schema.org/draft-04/schema#".to_string()), // here
             ))
@@ -761,7 +820,7 @@ mod tests {
             .mock("GET", "/schemas/email.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&mock_json_schema(
+            .with_body(mock_json_schema(
                 url.clone(),
                 Some("http://json-schema.org/draft-06/schema#".to_string()), // here
             ))
@@ -797,7 +856,7 @@ mod tests {
             .mock("GET", "/schemas/email.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&mock_json_schema(url.clone(), None))
+            .with_body(mock_json_schema(url.clone(), None))
             .create();
 
         let result = create_vc(
@@ -832,7 +891,7 @@ mod tests {
             .mock("GET", "/schemas/email.json")
             .with_status(200)
             .with_header("content-type", "application/json")
-            .with_body(&mock_json_schema(url.clone(), None))
+            .with_body(mock_json_schema(url.clone(), None))
             .create();
 
         let mut additional_properties = JsonObject::new();
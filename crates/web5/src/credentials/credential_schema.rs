@@ -14,11 +14,36 @@ pub struct CredentialSchema {
 pub(crate) async fn validate_credential_schema(
     verifiable_credential: &VerifiableCredential,
 ) -> Result<()> {
-    let credential_schema = match &verifiable_credential.credential_schema {
+    validate_against_credential_schema(
+        verifiable_credential.credential_schema.as_ref(),
+        verifiable_credential,
+    )
+    .await
+}
+
+pub(crate) async fn validate_against_credential_schema<T: Serialize>(
+    credential_schema: Option<&CredentialSchema>,
+    credential: &T,
+) -> Result<()> {
+    let credential_schema = match credential_schema {
         None => return Ok(()),
         Some(c) => c,
     };
 
+    let violations = credential_schema_violations(credential_schema, credential).await?;
+    if !violations.is_empty() {
+        return Err(schema_violations_error(&violations));
+    }
+
+    Ok(())
+}
+
+/// Validates a credential against its credential schema, returning each violation of the schema.
+/// Fails if the schema can't be resolved or is unsupported.
+pub(crate) async fn credential_schema_violations<T: Serialize>(
+    credential_schema: &CredentialSchema,
+    credential: &T,
+) -> Result<Vec<String>> {
     if credential_schema.r#type != CREDENTIAL_SCHEMA_TYPE {
         return Err(Web5Error::Parameter(format!(
             "type must be {}",
@@ -51,17 +76,17 @@ pub(crate) async fn validate_credential_schema(
         )));
     }
 
-    let instance = serde_json::to_value(verifiable_credential)?;
-    let result = compiled_schema.validate(&instance);
-    if let Err(errors) = result {
-        let error_messages: Vec<String> = errors
+    let instance = serde_json::to_value(credential)?;
+    let violations = match compiled_schema.validate(&instance) {
+        Ok(()) => vec![],
+        Err(errors) => errors
             .map(|e| format!("{} at {}", e, e.instance_path))
-            .collect();
-        return Err(Web5Error::JsonSchema(format!(
-            "validation errors {}",
-            error_messages.join(", ")
-        )));
-    }
+            .collect(),
+    };
 
-    Ok(())
+    Ok(violations)
+}
+
+pub(crate) fn schema_violations_error(violations: &[String]) -> Web5Error {
+    Web5Error::JsonSchema(format!("validation errors {}", violations.join(", ")))
 }
@@ -0,0 +1,1078 @@
+use crate::{
+    credentials::VerificationError,
+    crypto::{
+        dsa::{ed25519::Ed25519Verifier, secp256r1::Secp256r1Verifier, Dsa, Verifier},
+        jwk::Jwk,
+    },
+    datetime::{deserialize_optional_rfc3339, serialize_optional_rfc3339},
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        controller::resolve_document,
+        data_model::document::{FindVerificationMethodOptions, VerificationRelationship},
+        did::Did,
+    },
+    errors::{Result, Web5Error},
+    jcs, json_ld, rdf,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The `type` of a proof per the [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) specification.
+pub const DATA_INTEGRITY_PROOF_TYPE: &str = "DataIntegrityProof";
+/// The context defining Data Integrity terms, which the 1.1 data model doesn't include.
+pub const DATA_INTEGRITY_CONTEXT: &str = "https://w3id.org/security/data-integrity/v2";
+
+/// A Data Integrity cryptosuite, which canonicalizes documents either with the
+/// [JSON Canonicalization Scheme](https://www.rfc-editor.org/rfc/rfc8785) or, for JSON-LD
+/// documents, with [RDF Dataset Canonicalization](https://www.w3.org/TR/rdf-canon/).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cryptosuite {
+    /// [`eddsa-jcs-2022`](https://www.w3.org/TR/vc-di-eddsa/#eddsa-jcs-2022), signing with an Ed25519 key.
+    EddsaJcs2022,
+    /// [`ecdsa-jcs-2019`](https://www.w3.org/TR/vc-di-ecdsa/#ecdsa-jcs-2019), signing with a P-256 key.
+    EcdsaJcs2019,
+    /// [`eddsa-rdfc-2022`](https://www.w3.org/TR/vc-di-eddsa/#eddsa-rdfc-2022), signing with an
+    /// Ed25519 key. Every term of the document must be defined by its contexts, which must be
+    /// bundled or registered with [`crate::json_ld::register_context`].
+    EddsaRdfc2022,
+}
+
+impl Cryptosuite {
+    /// The name of the cryptosuite, as found in a proof's `cryptosuite` property.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cryptosuite::EddsaJcs2022 => "eddsa-jcs-2022",
+            Cryptosuite::EcdsaJcs2019 => "ecdsa-jcs-2019",
+            Cryptosuite::EddsaRdfc2022 => "eddsa-rdfc-2022",
+        }
+    }
+
+    /// Returns the cryptosuite which signs with the given key by default.
+    fn for_key(jwk: &Jwk) -> Result<Self> {
+        match Dsa::from_str(&jwk.crv)? {
+            Dsa::Ed25519 => Ok(Cryptosuite::EddsaJcs2022),
+            Dsa::Secp256r1 => Ok(Cryptosuite::EcdsaJcs2019),
+            Dsa::Secp256k1 => Err(Web5Error::Parameter(format!(
+                "no data integrity cryptosuite supports {} keys",
+                jwk.crv
+            ))),
+        }
+    }
+
+    /// Whether the cryptosuite signs with the given key.
+    fn supports_key(&self, jwk: &Jwk) -> bool {
+        match Cryptosuite::for_key(jwk) {
+            Ok(Cryptosuite::EddsaJcs2022) => {
+                matches!(self, Cryptosuite::EddsaJcs2022 | Cryptosuite::EddsaRdfc2022)
+            }
+            Ok(cryptosuite) => *self == cryptosuite,
+            Err(_) => false,
+        }
+    }
+
+    /// Canonicalizes a proof configuration or unsecured document for hashing.
+    fn canonicalize(&self, value: &Value) -> Result<String> {
+        match self {
+            Cryptosuite::EddsaJcs2022 | Cryptosuite::EcdsaJcs2019 => jcs::canonicalize(value),
+            Cryptosuite::EddsaRdfc2022 => {
+                rdf::canonicalization::canonicalize(&json_ld::to_rdf(value)?)
+            }
+        }
+    }
+}
+
+impl FromStr for Cryptosuite {
+    type Err = Web5Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "eddsa-jcs-2022" => Ok(Cryptosuite::EddsaJcs2022),
+            "ecdsa-jcs-2019" => Ok(Cryptosuite::EcdsaJcs2019),
+            "eddsa-rdfc-2022" => Ok(Cryptosuite::EddsaRdfc2022),
+            _ => Err(Web5Error::Parameter(format!(
+                "unsupported cryptosuite: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// A proof embedded in a credential or presentation per the
+/// [Data Integrity](https://www.w3.org/TR/vc-data-integrity/#dataintegrityproof) specification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DataIntegrityProof {
+    /// The contexts of the secured document, included so that they are signed.
+    #[serde(rename = "@context", skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<String>>,
+
+    /// An optional identifier for the proof.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Always `DataIntegrityProof`.
+    #[serde(rename = "type")]
+    pub r#type: String,
+
+    /// The name of the cryptosuite which created the proof, such as `eddsa-jcs-2022`.
+    pub cryptosuite: String,
+
+    /// The id of the verification method whose key verifies the proof.
+    #[serde(rename = "verificationMethod")]
+    pub verification_method: String,
+
+    /// The verification relationship the verification method must have, `assertionMethod` for
+    /// credentials and `authentication` for presentations.
+    #[serde(rename = "proofPurpose")]
+    pub proof_purpose: VerificationRelationship,
+
+    /// The date and time the proof was created.
+    #[serde(
+        serialize_with = "serialize_optional_rfc3339",
+        deserialize_with = "deserialize_optional_rfc3339",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub created: Option<SystemTime>,
+
+    /// The date and time after which the proof is no longer valid.
+    #[serde(
+        serialize_with = "serialize_optional_rfc3339",
+        deserialize_with = "deserialize_optional_rfc3339",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub expires: Option<SystemTime>,
+
+    /// A challenge provided by the verifier, to prevent replay of a presentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<String>,
+
+    /// The domain the proof is restricted to, such as the verifier's origin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+
+    /// The signature, encoded as base58btc multibase.
+    #[serde(rename = "proofValue", skip_serializing_if = "Option::is_none")]
+    pub proof_value: Option<String>,
+}
+
+/// Represents the options available when adding a Data Integrity proof.
+#[derive(Debug, Clone, Default)]
+pub struct DataIntegrityProofOptions {
+    /// The cryptosuite to use. If not provided, it's chosen by the signing key: `eddsa-jcs-2022`
+    /// for Ed25519 keys and `ecdsa-jcs-2019` for P-256 keys. `eddsa-rdfc-2022` must be requested
+    /// explicitly.
+    pub cryptosuite: Option<Cryptosuite>,
+
+    /// The verification method to sign with. If not provided, the first verification method with
+    /// the proof purpose's relationship and a key supported by the cryptosuite is used.
+    pub verification_method_id: Option<String>,
+
+    /// An optional identifier for the proof.
+    pub id: Option<String>,
+
+    /// The creation date of the proof. If not provided, defaults to the current date and time.
+    pub created: Option<SystemTime>,
+
+    /// The optional date after which the proof is no longer valid.
+    pub expires: Option<SystemTime>,
+
+    /// A challenge provided by the verifier, for proofs of presentations.
+    pub challenge: Option<String>,
+
+    /// The domain the proof is restricted to, for proofs of presentations.
+    pub domain: Option<String>,
+}
+
+/// Represents the options available when verifying a Data Integrity proof.
+#[derive(Debug, Clone, Default)]
+pub struct DataIntegrityVerifyOptions {
+    /// The challenge the verifier provided. If provided, the proof's `challenge` must equal it,
+    /// which prevents a presentation's proof being replayed.
+    pub challenge: Option<String>,
+
+    /// The domain the verifier expects. If provided, the proof's `domain` must equal it.
+    pub domain: Option<String>,
+}
+
+/// Creates a proof of a document per the `DataIntegrityProof` "Add Proof" algorithm, using the
+/// transformation and hashing shared by the cryptosuites. Any existing `proof` of the document is
+/// not covered by the new proof.
+pub(crate) fn create_proof<T: Serialize>(
+    document: &T,
+    signer_did_uri: &str,
+    proof_purpose: VerificationRelationship,
+    bearer_did: &BearerDid,
+    options: DataIntegrityProofOptions,
+) -> Result<DataIntegrityProof> {
+    if Did::parse(signer_did_uri)?.uri != bearer_did.did.uri {
+        return Err(Web5Error::Parameter(format!(
+            "Bearer DID URI {} does not match {}",
+            bearer_did.did.uri, signer_did_uri
+        )));
+    }
+
+    let mut document = serde_json::to_value(document)?;
+    let unsecured = document
+        .as_object_mut()
+        .ok_or_else(|| Web5Error::Parameter("document must be a JSON object".to_string()))?;
+    unsecured.remove("proof");
+
+    let verification_method_id = match options.verification_method_id {
+        Some(verification_method_id) => verification_method_id,
+        None => bearer_did
+            .document
+            .verification_method
+            .iter()
+            .filter(|vm| {
+                bearer_did
+                    .document
+                    .has_verification_relationship(proof_purpose, &vm.id)
+            })
+            .find(|vm| match options.cryptosuite {
+                Some(cryptosuite) => cryptosuite.supports_key(&vm.public_key_jwk),
+                None => Cryptosuite::for_key(&vm.public_key_jwk).is_ok(),
+            })
+            .map(|vm| vm.id.clone())
+            .ok_or_else(|| {
+                Web5Error::NotFound(format!(
+                    "no {} verification method supports a data integrity cryptosuite",
+                    proof_purpose.property_name()
+                ))
+            })?,
+    };
+    let (verification_method, signer) = bearer_did.select_signer(&SignerOptions {
+        verification_method_id: Some(verification_method_id),
+        verification_relationship: Some(proof_purpose),
+        algorithms: None,
+    })?;
+
+    let cryptosuite = match options.cryptosuite {
+        Some(cryptosuite) => cryptosuite,
+        None => Cryptosuite::for_key(&verification_method.public_key_jwk)?,
+    };
+    if !cryptosuite.supports_key(&verification_method.public_key_jwk) {
+        return Err(Web5Error::Parameter(format!(
+            "verification method {} cannot sign with {}",
+            verification_method.id,
+            cryptosuite.name()
+        )));
+    }
+
+    let mut proof = DataIntegrityProof {
+        context: match cryptosuite {
+            Cryptosuite::EddsaRdfc2022 => None,
+            _ => unsecured.get("@context").map(context_values).transpose()?,
+        },
+        id: options.id,
+        r#type: DATA_INTEGRITY_PROOF_TYPE.to_string(),
+        cryptosuite: cryptosuite.name().to_string(),
+        verification_method: verification_method.id,
+        proof_purpose,
+        created: Some(whole_seconds(
+            options.created.unwrap_or_else(|| Utc::now().into()),
+        )),
+        expires: options.expires.map(whole_seconds),
+        challenge: options.challenge,
+        domain: options.domain,
+        proof_value: None,
+    };
+
+    let proof_configuration =
+        proof_configuration(cryptosuite, serde_json::to_value(&proof)?, &document);
+    let hash_data = hash_data(cryptosuite, &proof_configuration, &document)?;
+    let signature = signer.sign(&hash_data)?;
+    proof.proof_value = Some(format!("z{}", bs58::encode(signature).into_string()));
+
+    Ok(proof)
+}
+
+/// Verifies every proof of a secured document per the `DataIntegrityProof` "Verify Proof"
+/// algorithm, resolving each proof's verification method, which must belong to the signer's DID
+/// and have the expected proof purpose. Each proof must carry the expected challenge and domain,
+/// if given.
+///
+/// The document is verified as JSON, rather than as a deserialized struct, so that properties the
+/// struct doesn't model are covered, and values such as dates are verified exactly as signed.
+pub(crate) async fn verify_proofs(
+    secured_document: &Value,
+    signer_did_uri: &str,
+    proof_purpose: VerificationRelationship,
+    options: &DataIntegrityVerifyOptions,
+) -> Result<()> {
+    let mut document = secured_document.clone();
+    let unsecured = document
+        .as_object_mut()
+        .ok_or_else(|| Web5Error::Parameter("document must be a JSON object".to_string()))?;
+    let proofs = match unsecured.remove("proof") {
+        Some(Value::Array(proofs)) if !proofs.is_empty() => proofs,
+        Some(proof @ Value::Object(_)) => vec![proof],
+        _ => return Err(VerificationError::MissingClaim("proof".to_string()).into()),
+    };
+
+    let signer_did = Did::parse(signer_did_uri)?;
+    for proof in proofs {
+        verify_proof(proof, document.clone(), &signer_did, proof_purpose, options).await?;
+    }
+
+    Ok(())
+}
+
+async fn verify_proof(
+    mut proof_options: Value,
+    mut unsecured_document: Value,
+    signer_did: &Did,
+    proof_purpose: VerificationRelationship,
+    options: &DataIntegrityVerifyOptions,
+) -> Result<()> {
+    let proof: DataIntegrityProof = serde_json::from_value(proof_options.clone())?;
+    if proof.r#type != DATA_INTEGRITY_PROOF_TYPE {
+        return Err(data_integrity_error(format!(
+            "unsupported proof type {}",
+            proof.r#type
+        )));
+    }
+    let cryptosuite = Cryptosuite::from_str(&proof.cryptosuite)?;
+    if proof.proof_purpose != proof_purpose {
+        return Err(data_integrity_error(format!(
+            "proof purpose must be {}",
+            proof_purpose.property_name()
+        )));
+    }
+    if let Some(expires) = proof.expires {
+        if expires < SystemTime::from(Utc::now()) {
+            return Err(data_integrity_error("proof expired".to_string()));
+        }
+    }
+    for (name, expected, actual) in [
+        ("challenge", &options.challenge, &proof.challenge),
+        ("domain", &options.domain, &proof.domain),
+    ] {
+        match (expected, actual) {
+            (None, _) => {}
+            (Some(_), None) => {
+                return Err(data_integrity_error(format!("proof is missing {}", name)))
+            }
+            (Some(expected), Some(actual)) if expected != actual => {
+                return Err(data_integrity_error(format!(
+                    "proof {} does not match",
+                    name
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    // The proof's contexts must be a prefix of the document's, and are what the signer signed.
+    if let Some(proof_context) = &proof.context {
+        let document_context = unsecured_document
+            .get("@context")
+            .map(context_values)
+            .transpose()?
+            .unwrap_or_default();
+        if !document_context.starts_with(proof_context) {
+            return Err(data_integrity_error(
+                "document @context does not start with the proof @context".to_string(),
+            ));
+        }
+        unsecured_document["@context"] = serde_json::to_value(proof_context)?;
+    }
+
+    let verification_method_did = Did::parse(&proof.verification_method)?;
+    if verification_method_did.uri != signer_did.uri {
+        return Err(VerificationError::UnauthorizedSigner(format!(
+            "{} is not a verification method of {}",
+            proof.verification_method, signer_did.uri
+        ))
+        .into());
+    }
+    let did_document = resolve_document(&verification_method_did.uri).await?;
+    if !did_document.has_verification_relationship(proof_purpose, &proof.verification_method) {
+        return Err(data_integrity_error(format!(
+            "verification method {} is not listed under {}",
+            proof.verification_method,
+            proof_purpose.property_name()
+        )));
+    }
+    let public_jwk = did_document
+        .find_verification_method(FindVerificationMethodOptions {
+            verification_method_id: Some(proof.verification_method.clone()),
+        })?
+        .public_key_jwk;
+    if !cryptosuite.supports_key(&public_jwk) {
+        return Err(data_integrity_error(format!(
+            "verification method {} does not support {}",
+            proof.verification_method, proof.cryptosuite
+        )));
+    }
+    let verifier: Arc<dyn Verifier> = match cryptosuite {
+        Cryptosuite::EddsaJcs2022 | Cryptosuite::EddsaRdfc2022 => {
+            Arc::new(Ed25519Verifier::new(public_jwk))
+        }
+        Cryptosuite::EcdsaJcs2019 => Arc::new(Secp256r1Verifier::new(public_jwk)),
+    };
+
+    let signature = match proof
+        .proof_value
+        .as_deref()
+        .and_then(|v| v.strip_prefix('z'))
+    {
+        Some(encoded) => bs58::decode(encoded)
+            .into_vec()
+            .map_err(|e| data_integrity_error(format!("invalid proofValue: {}", e)))?,
+        _ => {
+            return Err(data_integrity_error(
+                "proofValue must be base58btc multibase".to_string(),
+            ))
+        }
+    };
+
+    if let Some(proof_options) = proof_options.as_object_mut() {
+        proof_options.remove("proofValue");
+    }
+    let proof_configuration = proof_configuration(cryptosuite, proof_options, &unsecured_document);
+    let hash_data = hash_data(cryptosuite, &proof_configuration, &unsecured_document)?;
+    verifier.verify(&hash_data, &signature)
+}
+
+/// Returns the proof options as signed. Proofs of the RDFC cryptosuite don't repeat the
+/// document's `@context`, but its terms are still defined by it.
+fn proof_configuration(
+    cryptosuite: Cryptosuite,
+    mut proof_options: Value,
+    unsecured_document: &Value,
+) -> Value {
+    if cryptosuite == Cryptosuite::EddsaRdfc2022 {
+        if let (Some(options), Some(context)) = (
+            proof_options.as_object_mut(),
+            unsecured_document.get("@context"),
+        ) {
+            options.entry("@context").or_insert_with(|| context.clone());
+        }
+    }
+    proof_options
+}
+
+/// The data signed by every cryptosuite: the SHA-256 hash of the canonical proof options,
+/// followed by the SHA-256 hash of the canonical unsecured document.
+fn hash_data(
+    cryptosuite: Cryptosuite,
+    proof_options: &Value,
+    unsecured_document: &Value,
+) -> Result<Vec<u8>> {
+    let mut hash_data =
+        Sha256::digest(cryptosuite.canonicalize(proof_options)?.as_bytes()).to_vec();
+    hash_data.extend_from_slice(&Sha256::digest(
+        cryptosuite.canonicalize(unsecured_document)?.as_bytes(),
+    ));
+    Ok(hash_data)
+}
+
+/// Reads an `@context`, which may be a single context or a list of them.
+fn context_values(context: &Value) -> Result<Vec<String>> {
+    match context {
+        Value::String(context) => Ok(vec![context.clone()]),
+        context => Ok(serde_json::from_value(context.clone())?),
+    }
+}
+
+/// Returns the document's contexts with [`DATA_INTEGRITY_CONTEXT`] appended, if the 1.1 base
+/// context doesn't already define Data Integrity terms.
+pub(crate) fn with_data_integrity_context(
+    mut context: Vec<String>,
+    base_context: &str,
+) -> Vec<String> {
+    if context.first().map(String::as_str) == Some(base_context)
+        && !context.iter().any(|c| c == DATA_INTEGRITY_CONTEXT)
+    {
+        context.push(DATA_INTEGRITY_CONTEXT.to_string());
+    }
+    context
+}
+
+/// Truncates a time to the precision it's serialized with, so that a proof's fields match what
+/// was signed.
+fn whole_seconds(time: SystemTime) -> SystemTime {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+fn data_integrity_error(message: String) -> Web5Error {
+    VerificationError::DataIntegrityProof(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        credentials::{
+            CredentialSubject, Issuer, VerifiableCredential, VerifiableCredentialV2,
+            VerifiablePresentation,
+        },
+        dids::methods::did_jwk::{DidJwk, DidJwkCreateOptions},
+    };
+    const SUBJECT_DID_URI: &str = "did:dht:qgmmpyjw5hwnqfgzn7wmrm33ady8gb8z9ideib6m9gj4ys6wny8y";
+
+    fn p256_did() -> BearerDid {
+        DidJwk::create(Some(DidJwkCreateOptions {
+            dsa: Some(Dsa::Secp256r1),
+            ..Default::default()
+        }))
+        .unwrap()
+    }
+
+    async fn credential(issuer: &BearerDid) -> VerifiableCredentialV2 {
+        VerifiableCredentialV2::create(
+            Issuer::from(issuer.did.uri.clone()),
+            vec![CredentialSubject::from(SUBJECT_DID_URI)],
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn proof_of(secured: &Value) -> DataIntegrityProof {
+        serde_json::from_value(secured["proof"].clone()).unwrap()
+    }
+
+    mod eddsa_jcs_2022 {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_add_and_verify_proof() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, None)
+                .unwrap();
+
+            let proof = vc.proof.clone().unwrap();
+            assert_eq!(proof.r#type, DATA_INTEGRITY_PROOF_TYPE);
+            assert_eq!(proof.cryptosuite, "eddsa-jcs-2022");
+            assert_eq!(
+                proof.proof_purpose,
+                VerificationRelationship::AssertionMethod
+            );
+            assert_eq!(proof.context, Some(vc.context.clone()));
+            assert!(proof.proof_value.unwrap().starts_with('z'));
+
+            vc.verify_data_integrity_proof(None).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_detects_tampering() {
+            let issuer = DidJwk::create(None).unwrap();
+            let mut vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, None)
+                .unwrap();
+            vc.id = "urn:uuid:tampered".to_string();
+
+            let result = vc.verify_data_integrity_proof(None).await;
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::Crypto("cryptographic verification failure".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn test_detects_tampered_proof_options() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, None)
+                .unwrap();
+
+            let mut secured = serde_json::to_value(&vc).unwrap();
+            secured["proof"]["created"] = Value::String("2020-01-01T00:00:00Z".to_string());
+            let result = verify_proofs(
+                &secured,
+                &issuer.did.uri,
+                VerificationRelationship::AssertionMethod,
+                &DataIntegrityVerifyOptions::default(),
+            )
+            .await;
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::Crypto("cryptographic verification failure".to_string())
+            );
+        }
+    }
+
+    mod ecdsa_jcs_2019 {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_add_and_verify_proof() {
+            let issuer = p256_did();
+            let vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, None)
+                .unwrap();
+
+            assert_eq!(vc.proof.as_ref().unwrap().cryptosuite, "ecdsa-jcs-2019");
+            vc.verify_data_integrity_proof(None).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_cryptosuite_must_match_key() {
+            let issuer = p256_did();
+            let result = credential(&issuer).await.add_data_integrity_proof(
+                &issuer,
+                Some(DataIntegrityProofOptions {
+                    cryptosuite: Some(Cryptosuite::EddsaJcs2022),
+                    ..Default::default()
+                }),
+            );
+            assert!(matches!(result, Err(Web5Error::NotFound(_))));
+        }
+    }
+
+    mod eddsa_rdfc_2022 {
+        use super::*;
+        use crate::{
+            crypto::dsa::{ed25519::Ed25519Signer, Signer},
+            json::JsonObject,
+            json_ld::register_context,
+        };
+        use serde_json::json;
+
+        fn rdfc_options() -> Option<DataIntegrityProofOptions> {
+            Some(DataIntegrityProofOptions {
+                cryptosuite: Some(Cryptosuite::EddsaRdfc2022),
+                ..Default::default()
+            })
+        }
+
+        #[test]
+        fn test_specification_vector() {
+            // the example in appendix A of the EdDSA cryptosuites specification
+            register_context(
+                "https://www.w3.org/ns/credentials/examples/v2",
+                r#"{ "@context": { "@vocab": "https://www.w3.org/ns/credentials/examples#" } }"#,
+            )
+            .unwrap();
+            let document = json!({
+                "@context": [
+                    "https://www.w3.org/ns/credentials/v2",
+                    "https://www.w3.org/ns/credentials/examples/v2"
+                ],
+                "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+                "type": ["VerifiableCredential", "AlumniCredential"],
+                "name": "Alumni Credential",
+                "description": "A minimum viable example of an Alumni Credential.",
+                "issuer": "https://vc.example/issuers/5678",
+                "validFrom": "2023-01-01T00:00:00Z",
+                "credentialSubject": {
+                    "id": "did:example:abcdefgh",
+                    "alumniOf": "The School of Examples"
+                }
+            });
+            let proof_options = proof_configuration(
+                Cryptosuite::EddsaRdfc2022,
+                json!({
+                    "type": "DataIntegrityProof",
+                    "cryptosuite": "eddsa-rdfc-2022",
+                    "created": "2023-02-24T23:36:38Z",
+                    "verificationMethod": "did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2#z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2",
+                    "proofPurpose": "assertionMethod"
+                }),
+                &document,
+            );
+
+            let hash_data =
+                hash_data(Cryptosuite::EddsaRdfc2022, &proof_options, &document).unwrap();
+            let hex: String = hash_data.iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(
+                hex,
+                "bea7b7acfbad0126b135104024a5f1733e705108f42d59668b05c0c50004c6b0\
+                 517744132ae165a5349155bef0bb0cf2258fff99dfe1dbd914b938d775a36017"
+            );
+
+            let signer = Ed25519Signer::new(Jwk {
+                alg: Some("Ed25519".to_string()),
+                kty: "OKP".to_string(),
+                crv: "Ed25519".to_string(),
+                x: "sA2Nk45_dz1RVlqtNqYj9TRPf10ZYPnPPo4SYg6igQ8".to_string(),
+                y: None,
+                d: Some("yW756hDF5BTEcXI6_53nLDX6W3D66X6IMuysfS4rjtY".to_string()),
+            });
+            let signature = signer.sign(&hash_data).unwrap();
+            assert_eq!(
+                format!("z{}", bs58::encode(signature).into_string()),
+                "z2YwC8z3ap7yx1nZYCg4L3j3ApHsF8kgPdSb5xoS1VR7vPG3F561B52hYnQF9iseabecm3ijx4K1FBTQsCZahKZme"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_add_and_verify_proof() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, rdfc_options())
+                .unwrap();
+
+            let proof = vc.proof.clone().unwrap();
+            assert_eq!(proof.cryptosuite, "eddsa-rdfc-2022");
+            assert_eq!(proof.context, None);
+            vc.verify_data_integrity_proof(None).await.unwrap();
+
+            let json = serde_json::to_string(&vc).unwrap();
+            let secured = VerifiableCredentialV2::from_secured_json(&json, true, None)
+                .await
+                .unwrap();
+            assert_eq!(secured.proof, Some(proof));
+        }
+
+        #[tokio::test]
+        async fn test_detects_tampering() {
+            let issuer = DidJwk::create(None).unwrap();
+            let mut vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, rdfc_options())
+                .unwrap();
+            vc.credential_subject[0].id = "did:example:tampered".to_string();
+
+            let result = vc.verify_data_integrity_proof(None).await;
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::Crypto("cryptographic verification failure".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn test_credential_1_1() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredential::create(
+                Issuer::from(issuer.did.uri.clone()),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                None,
+            )
+            .await
+            .unwrap()
+            .add_data_integrity_proof(&issuer, rdfc_options())
+            .unwrap();
+
+            vc.verify_data_integrity_proof(None).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_undefined_terms() {
+            let issuer = DidJwk::create(None).unwrap();
+            let mut additional_properties = JsonObject::new();
+            additional_properties
+                .insert("nickname", &"Al".to_string())
+                .unwrap();
+            let vc = VerifiableCredential::create(
+                Issuer::from(issuer.did.uri.clone()),
+                CredentialSubject {
+                    id: SUBJECT_DID_URI.to_string(),
+                    additional_properties: Some(additional_properties),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+            let result = vc.add_data_integrity_proof(&issuer, rdfc_options());
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::JsonLd("property nickname is not defined by the context".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn test_requires_ed25519_key() {
+            let issuer = p256_did();
+            let result = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, rdfc_options());
+            assert!(matches!(result, Err(Web5Error::NotFound(_))));
+        }
+    }
+
+    mod verify_proofs {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_missing_proof() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer).await;
+
+            let result = vc.verify_data_integrity_proof(None).await;
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::CredentialError(VerificationError::MissingClaim("proof".to_string()))
+            );
+        }
+
+        #[tokio::test]
+        async fn test_signer_must_be_issuer() {
+            let issuer = DidJwk::create(None).unwrap();
+            let stranger = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer).await;
+
+            let result = vc.add_data_integrity_proof(&stranger, None);
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+
+            let proof = create_proof(
+                &vc,
+                &stranger.did.uri,
+                VerificationRelationship::AssertionMethod,
+                &stranger,
+                DataIntegrityProofOptions::default(),
+            )
+            .unwrap();
+            let mut forged = vc.clone();
+            forged.proof = Some(proof);
+            let result = forged.verify_data_integrity_proof(None).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    VerificationError::UnauthorizedSigner(_)
+                ))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_proof_purpose() {
+            let holder = DidJwk::create(None).unwrap();
+            let vp = VerifiablePresentation::create(holder.did.uri.clone(), vec![], None)
+                .await
+                .unwrap()
+                .add_data_integrity_proof(&holder, None)
+                .unwrap();
+
+            let result = verify_proofs(
+                &serde_json::to_value(&vp).unwrap(),
+                &holder.did.uri,
+                VerificationRelationship::AssertionMethod,
+                &DataIntegrityVerifyOptions::default(),
+            )
+            .await;
+            assert_eq!(
+                result.unwrap_err(),
+                data_integrity_error("proof purpose must be assertionMethod".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn test_malformed_proof_value() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(&issuer, None)
+                .unwrap();
+
+            for proof_value in ["", "é", "u1234"] {
+                let mut malformed = vc.clone();
+                if let Some(proof) = malformed.proof.as_mut() {
+                    proof.proof_value = Some(proof_value.to_string());
+                }
+                let result = malformed.verify_data_integrity_proof(None).await;
+                assert_eq!(
+                    result.unwrap_err(),
+                    data_integrity_error("proofValue must be base58btc multibase".to_string())
+                );
+            }
+        }
+
+        #[tokio::test]
+        async fn test_expired_proof() {
+            let issuer = DidJwk::create(None).unwrap();
+            let now = SystemTime::now();
+            let vc = credential(&issuer)
+                .await
+                .add_data_integrity_proof(
+                    &issuer,
+                    Some(DataIntegrityProofOptions {
+                        created: Some(now - Duration::from_secs(120)),
+                        expires: Some(now - Duration::from_secs(60)),
+                        ..Default::default()
+                    }),
+                )
+                .unwrap();
+
+            let result = vc.verify_data_integrity_proof(None).await;
+            assert_eq!(
+                result.unwrap_err(),
+                data_integrity_error("proof expired".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn test_multiple_proofs() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer).await;
+            let first = create_proof(
+                &vc,
+                &issuer.did.uri,
+                VerificationRelationship::AssertionMethod,
+                &issuer,
+                DataIntegrityProofOptions::default(),
+            )
+            .unwrap();
+
+            let mut secured = serde_json::to_value(&vc).unwrap();
+            secured["proof"] = serde_json::to_value(vec![first.clone(), first]).unwrap();
+            verify_proofs(
+                &secured,
+                &issuer.did.uri,
+                VerificationRelationship::AssertionMethod,
+                &DataIntegrityVerifyOptions::default(),
+            )
+            .await
+            .unwrap();
+
+            secured["proof"][1]["verificationMethod"] = Value::String("did:jwk:e30#0".to_string());
+            assert!(verify_proofs(
+                &secured,
+                &issuer.did.uri,
+                VerificationRelationship::AssertionMethod,
+                &DataIntegrityVerifyOptions::default(),
+            )
+            .await
+            .is_err());
+        }
+    }
+
+    mod credential_1_1 {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_add_and_verify_proof() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredential::create(
+                Issuer::from(issuer.did.uri.clone()),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                None,
+            )
+            .await
+            .unwrap()
+            .add_data_integrity_proof(&issuer, None)
+            .unwrap();
+            assert!(vc.context.contains(&DATA_INTEGRITY_CONTEXT.to_string()));
+
+            let json = serde_json::to_string(&vc).unwrap();
+            let secured = VerifiableCredential::from_secured_json(&json, true, None)
+                .await
+                .unwrap();
+            assert_eq!(secured.proof, vc.proof);
+            assert_eq!(
+                proof_of(&serde_json::from_str(&json).unwrap()),
+                vc.proof.unwrap()
+            );
+        }
+    }
+
+    mod presentation {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_add_and_verify_proof() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = p256_did();
+            let vc_jwt = credential(&issuer)
+                .await
+                .sign_vc_jose(&issuer, &SignerOptions::default())
+                .unwrap();
+
+            let vp = VerifiablePresentation::create(holder.did.uri.clone(), vec![vc_jwt], None)
+                .await
+                .unwrap()
+                .add_data_integrity_proof(
+                    &holder,
+                    Some(DataIntegrityProofOptions {
+                        challenge: Some("a7f9c2".to_string()),
+                        domain: Some("verifier.example".to_string()),
+                        ..Default::default()
+                    }),
+                )
+                .unwrap();
+            let proof = vp.proof.clone().unwrap();
+            assert_eq!(
+                proof.proof_purpose,
+                VerificationRelationship::Authentication
+            );
+            assert_eq!(proof.challenge.as_deref(), Some("a7f9c2"));
+
+            let json = serde_json::to_string(&vp).unwrap();
+            let secured = VerifiablePresentation::from_secured_json(
+                &json,
+                true,
+                Some(DataIntegrityVerifyOptions {
+                    challenge: Some("a7f9c2".to_string()),
+                    domain: Some("verifier.example".to_string()),
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(secured.proof, Some(proof));
+            assert!(secured.additional_data.unwrap_or_default().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_challenge_and_domain() {
+            let holder = DidJwk::create(None).unwrap();
+            let vp = VerifiablePresentation::create(holder.did.uri.clone(), vec![], None)
+                .await
+                .unwrap();
+            let with_challenge = vp
+                .add_data_integrity_proof(
+                    &holder,
+                    Some(DataIntegrityProofOptions {
+                        challenge: Some("a7f9c2".to_string()),
+                        domain: Some("verifier.example".to_string()),
+                        ..Default::default()
+                    }),
+                )
+                .unwrap();
+            let without_challenge = vp.add_data_integrity_proof(&holder, None).unwrap();
+
+            let expected = DataIntegrityVerifyOptions {
+                challenge: Some("a7f9c2".to_string()),
+                domain: Some("verifier.example".to_string()),
+            };
+            let other_challenge = DataIntegrityVerifyOptions {
+                challenge: Some("b8e0d3".to_string()),
+                ..expected.clone()
+            };
+            let other_domain = DataIntegrityVerifyOptions {
+                domain: Some("attacker.example".to_string()),
+                ..expected.clone()
+            };
+
+            assert_eq!(
+                with_challenge
+                    .verify_data_integrity_proof(Some(other_challenge))
+                    .await
+                    .unwrap_err(),
+                data_integrity_error("proof challenge does not match".to_string())
+            );
+            assert_eq!(
+                with_challenge
+                    .verify_data_integrity_proof(Some(other_domain))
+                    .await
+                    .unwrap_err(),
+                data_integrity_error("proof domain does not match".to_string())
+            );
+            assert_eq!(
+                without_challenge
+                    .verify_data_integrity_proof(Some(expected))
+                    .await
+                    .unwrap_err(),
+                data_integrity_error("proof is missing challenge".to_string())
+            );
+        }
+    }
+}
@@ -1,63 +1,155 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use chrono::Utc;
 use super::{
     verifiable_credential_1_1::{VerifiableCredential, BASE_CONTEXT, BASE_TYPE},
+    verifiable_credential_2_0::{VerifiableCredentialV2, BASE_CONTEXT as BASE_CONTEXT_2_0},
     VerificationError,
 };
+use chrono::Utc;
+
+pub(crate) const VC_1_1_NOT_YET_VALID: &str = "issuance date in future";
+pub(crate) const VC_2_0_NOT_YET_VALID: &str = "credential not yet valid";
+pub(crate) const EXPIRED: &str = "credential expired";
 
 pub fn validate_vc_data_model(
     vc: &VerifiableCredential,
 ) -> std::result::Result<(), VerificationError> {
+    if let Some(error) = vc_required_field_errors(vc).into_iter().next() {
+        return Err(error);
+    }
+    validate_validity_period(
+        Some(vc.issuance_date),
+        vc.expiration_date,
+        VC_1_1_NOT_YET_VALID,
+        EXPIRED,
+        Utc::now().into(),
+        Duration::ZERO,
+    )
+}
+
+pub(crate) fn vc_required_field_errors(vc: &VerifiableCredential) -> Vec<VerificationError> {
+    let mut errors = vec![];
+
     // Required fields ["@context", "id", "type", "issuer", "issuanceDate", "credentialSubject"]
     if vc.id.is_empty() {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing id".to_string(),
         ));
     }
 
     if vc.context.is_empty() || vc.context[0] != BASE_CONTEXT {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing context".to_string(),
         ));
     }
 
     if vc.r#type.is_empty() || vc.r#type[0] != BASE_TYPE {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing type".to_string(),
         ));
     }
 
     if vc.issuer.to_string().is_empty() {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing issuer".to_string(),
         ));
     }
 
     if vc.credential_subject.id.is_empty() {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing credential subject".to_string(),
         ));
     }
 
-    let now: SystemTime = Utc::now().into();
-    if vc.issuance_date > now {
-        return Err(VerificationError::DataModelValidationError(
-            "issuance date in future".to_string(),
+    // TODO: Add validations to credential_status, credential_schema, and evidence once they are added to the VcDataModel
+    // https://github.com/TBD54566975/web5-rs/issues/112
+
+    errors
+}
+
+pub fn validate_vc_2_0_data_model(
+    vc: &VerifiableCredentialV2,
+) -> std::result::Result<(), VerificationError> {
+    if let Some(error) = vc_2_0_required_field_errors(vc).into_iter().next() {
+        return Err(error);
+    }
+    validate_validity_period(
+        vc.valid_from,
+        vc.valid_until,
+        VC_2_0_NOT_YET_VALID,
+        EXPIRED,
+        Utc::now().into(),
+        Duration::ZERO,
+    )
+}
+
+pub(crate) fn vc_2_0_required_field_errors(vc: &VerifiableCredentialV2) -> Vec<VerificationError> {
+    let mut errors = vec![];
+
+    // Required fields ["@context", "type", "issuer", "credentialSubject"], and "id" per the Web5 spec
+    if vc.id.is_empty() {
+        errors.push(VerificationError::DataModelValidationError(
+            "missing id".to_string(),
+        ));
+    }
+
+    if vc.context.is_empty() || vc.context[0] != BASE_CONTEXT_2_0 {
+        errors.push(VerificationError::DataModelValidationError(
+            "missing context".to_string(),
+        ));
+    }
+
+    if vc.r#type.is_empty() || vc.r#type[0] != BASE_TYPE {
+        errors.push(VerificationError::DataModelValidationError(
+            "missing type".to_string(),
+        ));
+    }
+
+    if vc.issuer.to_string().is_empty() {
+        errors.push(VerificationError::DataModelValidationError(
+            "missing issuer".to_string(),
         ));
     }
 
-    // Validate expiration date if it exists
-    if let Some(expiration_date) = &vc.expiration_date {
-        if expiration_date < &now {
+    if vc.credential_subject.is_empty()
+        || vc
+            .credential_subject
+            .iter()
+            .any(|credential_subject| credential_subject.id.is_empty())
+    {
+        errors.push(VerificationError::DataModelValidationError(
+            "missing credential subject".to_string(),
+        ));
+    }
+
+    errors
+}
+
+/// Checks that a credential or presentation is valid at `now`, tolerating `leeway` of clock skew
+/// between the issuer and the verifier.
+pub(crate) fn validate_validity_period(
+    valid_from: Option<SystemTime>,
+    valid_until: Option<SystemTime>,
+    not_yet_valid_message: &str,
+    expired_message: &str,
+    now: SystemTime,
+    leeway: Duration,
+) -> std::result::Result<(), VerificationError> {
+    if let Some(valid_from) = valid_from {
+        if valid_from > now + leeway {
             return Err(VerificationError::DataModelValidationError(
-                "credential expired".to_string(),
+                not_yet_valid_message.to_string(),
             ));
         }
     }
 
-    // TODO: Add validations to credential_status, credential_schema, and evidence once they are added to the VcDataModel
-    // https://github.com/TBD54566975/web5-rs/issues/112
+    if let Some(valid_until) = valid_until {
+        if valid_until + leeway < now {
+            return Err(VerificationError::DataModelValidationError(
+                expired_message.to_string(),
+            ));
+        }
+    }
 
     Ok(())
 }
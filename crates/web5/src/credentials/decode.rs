@@ -1,91 +1,199 @@
+use super::vc_jose_cose::{check_media_type, JwtSecuringMechanism, VC_JWT_MEDIA_TYPE};
 use super::{
-    credential_subject::CredentialSubject, issuer::Issuer,
-    jwt_payload_vc::JwtPayloadVerifiableCredential,
-    verifiable_credential_1_1::VerifiableCredential, VerificationError,
+    credential_subject::CredentialSubject,
+    issuer::Issuer,
+    jwt_payload_vc::{JwtPayloadVerifiableCredential, JwtPayloadVerifiableCredentialV2},
+    verifiable_credential_1_1::VerifiableCredential,
+    verifiable_credential_2_0::{
+        DataModelVersion, VerifiableCredentialV2, VersionedVerifiableCredential,
+    },
+    VerificationError,
 };
-use crate::errors::Result;
-use crate::{jose::Jwt, json::FromJsonValue};
+use crate::errors::{Result, Web5Error};
+use crate::{
+    dids::controller::verify_controller_authorization,
+    jose::{Jws, JwtClaims},
+    json::{FromJson, FromJsonValue, JsonValue},
+};
+use std::time::SystemTime;
 
 pub async fn decode(vc_jwt: &str, verify_signature: bool) -> Result<VerifiableCredential> {
-    let jwt = Jwt::from_compact_jws(vc_jwt, verify_signature).await?;
+    match decode_verified(vc_jwt, verify_signature, Some(DataModelVersion::V1_1)).await? {
+        VersionedVerifiableCredential::V1_1(vc) => Ok(vc),
+        VersionedVerifiableCredential::V2_0(_) => Err(Web5Error::Parameter(
+            "expected a data model 1.1 credential".to_string(),
+        )),
+    }
+}
+
+pub async fn decode_v2_0(vc_jwt: &str, verify_signature: bool) -> Result<VerifiableCredentialV2> {
+    match decode_verified(vc_jwt, verify_signature, Some(DataModelVersion::V2_0)).await? {
+        VersionedVerifiableCredential::V2_0(vc) => Ok(vc),
+        VersionedVerifiableCredential::V1_1(_) => Err(Web5Error::Parameter(
+            "expected a data model 2.0 credential".to_string(),
+        )),
+    }
+}
+
+/// Decodes a VC JWT, secured by either mechanism, as the data model version identified by the
+/// base context of the credential.
+pub async fn decode_versioned(
+    vc_jwt: &str,
+    verify_signature: bool,
+) -> Result<VersionedVerifiableCredential> {
+    decode_verified(vc_jwt, verify_signature, None).await
+}
+
+async fn decode_verified(
+    vc_jwt: &str,
+    verify_signature: bool,
+    version: Option<DataModelVersion>,
+) -> Result<VersionedVerifiableCredential> {
+    let jws = decode_jws(vc_jwt, verify_signature).await?;
+    let decoded = decode_credential(&jws, version)?;
+
+    // an empty issuer is reported by data model validation
+    if verify_signature && !decoded.issuer.is_empty() {
+        verify_controller_authorization(&decoded.issuer, &jws.kid).await?;
+    }
+
+    match decoded.claim_errors.into_iter().next() {
+        Some(claim_error) => Err(claim_error.into()),
+        None => Ok(decoded.credential),
+    }
+}
+
+async fn decode_jws(vc_jwt: &str, verify_signature: bool) -> Result<Jws> {
+    let jws = Jws::from_compact_jws(vc_jwt, verify_signature).await?;
+    check_media_type(&jws.header, VC_JWT_MEDIA_TYPE)?;
+    Ok(jws)
+}
+
+/// A credential decoded from the payload of a VC JWT, whose signer is not yet authorized.
+pub(crate) struct DecodedCredential {
+    pub credential: VersionedVerifiableCredential,
+    pub mechanism: JwtSecuringMechanism,
+    /// The issuer the signer must be authorized by: the `iss` claim of a VC-JWT, or else the
+    /// issuer of the credential.
+    pub issuer: String,
+    /// The inconsistencies between the registered claims of a VC-JWT and its `vc` claim, in the
+    /// order they were found. Always empty for VC-JOSE-COSE, which has no `vc` claim.
+    pub claim_errors: Vec<VerificationError>,
+}
 
-    let jti = jwt
-        .claims
+/// Decodes the credential of a JWS as the given data model version, or as the version identified
+/// by the base context of the credential if not given, without verifying the JWS.
+pub(crate) fn decode_credential(
+    jws: &Jws,
+    version: Option<DataModelVersion>,
+) -> Result<DecodedCredential> {
+    let mechanism = JwtSecuringMechanism::detect(&jws.header, &jws.payload);
+
+    let version = match version {
+        Some(version) => version,
+        None => {
+            let payload = serde_json::from_slice::<serde_json::Value>(&jws.payload)?;
+            let credential = match mechanism {
+                JwtSecuringMechanism::VcJoseCose => Some(&payload),
+                JwtSecuringMechanism::VcJwt => payload.get("vc"),
+            };
+            let context: Vec<String> = credential
+                .and_then(|credential| credential.get("@context"))
+                .and_then(|context| serde_json::from_value(context.clone()).ok())
+                .unwrap_or_default();
+            DataModelVersion::detect(&context).unwrap_or(DataModelVersion::V1_1)
+        }
+    };
+
+    match (version, mechanism) {
+        (DataModelVersion::V1_1, JwtSecuringMechanism::VcJoseCose) => {
+            let vc = VerifiableCredential::from_json_byte_array(&jws.payload)?;
+            Ok(DecodedCredential {
+                issuer: vc.issuer.to_string(),
+                credential: VersionedVerifiableCredential::V1_1(vc),
+                mechanism,
+                claim_errors: vec![],
+            })
+        }
+        (DataModelVersion::V2_0, JwtSecuringMechanism::VcJoseCose) => {
+            let vc = VerifiableCredentialV2::from_json_byte_array(&jws.payload)?;
+            Ok(DecodedCredential {
+                issuer: vc.issuer.to_string(),
+                credential: VersionedVerifiableCredential::V2_0(vc),
+                mechanism,
+                claim_errors: vec![],
+            })
+        }
+        (DataModelVersion::V1_1, JwtSecuringMechanism::VcJwt) => {
+            decode_1_1(&JwtClaims::from_json_byte_array(&jws.payload)?)
+        }
+        (DataModelVersion::V2_0, JwtSecuringMechanism::VcJwt) => {
+            decode_2_0(&JwtClaims::from_json_byte_array(&jws.payload)?)
+        }
+    }
+}
+
+fn vc_claim(claims: &JwtClaims) -> Result<&JsonValue> {
+    Ok(claims
+        .additional_properties
+        .as_ref()
+        .ok_or(VerificationError::MissingClaim("vc".to_string()))?
+        .get("vc")
+        .ok_or(VerificationError::MissingClaim("vc".to_string()))?)
+}
+
+fn decode_1_1(claims: &JwtClaims) -> Result<DecodedCredential> {
+    let jti = claims
         .jti
+        .clone()
         .ok_or(VerificationError::MissingClaim("jti".to_string()))?;
-    let iss = jwt
-        .claims
+    let iss = claims
         .iss
+        .clone()
         .ok_or(VerificationError::MissingClaim("issuer".to_string()))?;
-    let sub = jwt
-        .claims
+    let sub = claims
         .sub
+        .clone()
         .ok_or(VerificationError::MissingClaim("subject".to_string()))?;
-    let nbf = jwt
-        .claims
+    let nbf = claims
         .nbf
         .ok_or(VerificationError::MissingClaim("not_before".to_string()))?;
-    let exp = jwt.claims.exp;
-
-    let vc_payload = JwtPayloadVerifiableCredential::from_json_value(
-        jwt.claims
-            .additional_properties
-            .ok_or(VerificationError::MissingClaim("vc".to_string()))?
-            .get("vc")
-            .ok_or(VerificationError::MissingClaim("vc".to_string()))?,
-    )?;
-
-    if let Some(id) = vc_payload.id {
-        if id != jti {
-            return Err(VerificationError::ClaimMismatch("id".to_string()).into());
+    let exp = claims.exp;
+
+    let vc_payload = JwtPayloadVerifiableCredential::from_json_value(vc_claim(claims)?)?;
+
+    let mut claim_errors = vec![];
+
+    if let Some(id) = &vc_payload.id {
+        if *id != jti {
+            claim_errors.push(VerificationError::ClaimMismatch("id".to_string()));
         }
     }
 
     if let Some(issuer) = &vc_payload.issuer {
-        let vc_issuer = issuer.to_string();
-        if iss != vc_issuer {
-            return Err(VerificationError::ClaimMismatch("issuer".to_string()).into());
+        if iss != issuer.to_string() {
+            claim_errors.push(VerificationError::ClaimMismatch("issuer".to_string()));
         }
     }
 
     if let Some(credential_subject) = &vc_payload.credential_subject {
         if sub != credential_subject.id {
-            return Err(VerificationError::ClaimMismatch("subject".to_string()).into());
+            claim_errors.push(VerificationError::ClaimMismatch("subject".to_string()));
         }
     }
 
-    if let Some(vc_payload_expiration_date) = vc_payload.expiration_date {
-        match exp {
-            None => {
-                return Err(VerificationError::MisconfiguredExpirationDate(
-                    "VC has expiration date but no exp in registered claims".to_string(),
-                )
-                .into());
-            }
-            Some(exp) => {
-                let difference = vc_payload_expiration_date
-                    .duration_since(exp)
-                    .unwrap_or_else(|_| exp.duration_since(vc_payload_expiration_date).unwrap());
-
-                if difference.as_secs() > 0 {
-                    return Err(
-                        VerificationError::ClaimMismatch("expiration_date".to_string()).into(),
-                    );
-                }
-            }
-        }
-    }
+    claim_errors.extend(check_expiration(vc_payload.expiration_date, exp));
 
-    let vc_issuer = vc_payload.issuer.unwrap_or(Issuer::String(iss.to_string()));
+    let vc_issuer = vc_payload.issuer.unwrap_or(Issuer::String(iss.clone()));
 
     let vc_credential_subject = vc_payload.credential_subject.unwrap_or(CredentialSubject {
-        id: sub.to_string(),
+        id: sub,
         additional_properties: None,
     });
 
-    Ok(VerifiableCredential {
+    let vc = VerifiableCredential {
         context: vc_payload.context,
-        id: jti.to_string(),
+        id: jti,
         r#type: vc_payload.r#type,
         issuer: vc_issuer,
         issuance_date: nbf,
@@ -94,5 +202,107 @@ pub async fn decode(vc_jwt: &str, verify_signature: bool) -> Result<VerifiableCr
         credential_subject: vc_credential_subject,
         credential_schema: vc_payload.credential_schema,
         evidence: vc_payload.evidence,
+        proof: None,
+    };
+
+    Ok(DecodedCredential {
+        credential: VersionedVerifiableCredential::V1_1(vc),
+        mechanism: JwtSecuringMechanism::VcJwt,
+        issuer: iss,
+        claim_errors,
+    })
+}
+
+fn decode_2_0(claims: &JwtClaims) -> Result<DecodedCredential> {
+    let jti = claims
+        .jti
+        .clone()
+        .ok_or(VerificationError::MissingClaim("jti".to_string()))?;
+    let iss = claims
+        .iss
+        .clone()
+        .ok_or(VerificationError::MissingClaim("issuer".to_string()))?;
+
+    let vc_payload = JwtPayloadVerifiableCredentialV2::from_json_value(vc_claim(claims)?)?;
+
+    let mut claim_errors = vec![];
+
+    if let Some(id) = &vc_payload.id {
+        if *id != jti {
+            claim_errors.push(VerificationError::ClaimMismatch("id".to_string()));
+        }
+    }
+
+    if let Some(issuer) = &vc_payload.issuer {
+        if iss != issuer.to_string() {
+            claim_errors.push(VerificationError::ClaimMismatch("issuer".to_string()));
+        }
+    }
+
+    // the subject claim is only set for credentials with a single subject
+    let mut credential_subject = vc_payload.credential_subject;
+    match (&claims.sub, credential_subject.as_slice()) {
+        (Some(sub), []) => credential_subject.push(CredentialSubject::from(sub.as_str())),
+        (Some(sub), [single]) if *sub == single.id => {}
+        (Some(_), _) => {
+            claim_errors.push(VerificationError::ClaimMismatch("subject".to_string()));
+        }
+        (None, []) => {
+            return Err(VerificationError::MissingClaim("subject".to_string()).into());
+        }
+        (None, _) => {}
+    }
+
+    if let (Some(valid_from), Some(nbf)) = (vc_payload.valid_from, claims.nbf) {
+        if !same_second(valid_from, nbf) {
+            claim_errors.push(VerificationError::ClaimMismatch("valid_from".to_string()));
+        }
+    }
+
+    claim_errors.extend(check_expiration(vc_payload.valid_until, claims.exp));
+
+    let vc = VerifiableCredentialV2 {
+        context: vc_payload.context,
+        id: jti,
+        r#type: vc_payload.r#type,
+        name: vc_payload.name,
+        description: vc_payload.description,
+        issuer: vc_payload.issuer.unwrap_or(Issuer::String(iss.clone())),
+        credential_subject,
+        valid_from: vc_payload.valid_from.or(claims.nbf),
+        valid_until: vc_payload.valid_until.or(claims.exp),
+        credential_status: vc_payload.credential_status,
+        credential_schema: vc_payload.credential_schema,
+        evidence: vc_payload.evidence,
+        proof: None,
+    };
+
+    Ok(DecodedCredential {
+        credential: VersionedVerifiableCredential::V2_0(vc),
+        mechanism: JwtSecuringMechanism::VcJwt,
+        issuer: iss,
+        claim_errors,
     })
 }
+
+fn check_expiration(
+    vc_expiration: Option<SystemTime>,
+    exp: Option<SystemTime>,
+) -> Option<VerificationError> {
+    match (vc_expiration, exp) {
+        (Some(_), None) => Some(VerificationError::MisconfiguredExpirationDate(
+            "VC has expiration date but no exp in registered claims".to_string(),
+        )),
+        (Some(vc_expiration), Some(exp)) if !same_second(vc_expiration, exp) => Some(
+            VerificationError::ClaimMismatch("expiration_date".to_string()),
+        ),
+        _ => None,
+    }
+}
+
+fn same_second(a: SystemTime, b: SystemTime) -> bool {
+    let difference = a
+        .duration_since(b)
+        .unwrap_or_else(|_| b.duration_since(a).unwrap());
+    difference.as_secs() == 0
+}
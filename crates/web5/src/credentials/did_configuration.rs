@@ -0,0 +1,601 @@
+use super::verifiable_credential_1_1::{VerifiableCredential, VerifiableCredentialCreateOptions};
+use super::{CredentialSubject, Issuer, VerificationError};
+use crate::dids::{
+    bearer_did::BearerDid,
+    data_model::{
+        document::Document,
+        service::{ServiceEndpoint, ServiceEndpointEntry},
+    },
+    did::Did,
+    resolution::resolution_result::ResolutionResult,
+};
+use crate::errors::{Result, Web5Error};
+use crate::jose::Jwt;
+use crate::json::{FromJson, JsonObject, JsonValue, ToJson};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use url::Url;
+
+pub const DID_CONFIGURATION_CONTEXT: &str =
+    "https://identity.foundation/.well-known/did-configuration/v1";
+pub const DOMAIN_LINKAGE_CREDENTIAL_TYPE: &str = "DomainLinkageCredential";
+pub const LINKED_DOMAINS_SERVICE_TYPE: &str = "LinkedDomains";
+pub const DID_CONFIGURATION_PATH: &str = "/.well-known/did-configuration.json";
+
+/// Represents a Domain Linkage Credential as defined by the
+/// [DIF Well-Known DID Configuration](https://identity.foundation/.well-known/resources/did-configuration/)
+/// specification, in which a DID asserts control over a web origin.
+pub struct DomainLinkageCredential {
+    /// The base Verifiable Credential associated with the Domain Linkage Credential.
+    pub base: VerifiableCredential,
+}
+
+impl DomainLinkageCredential {
+    /// Creates a new Domain Linkage Credential in which the DID is both issuer and subject.
+    ///
+    /// # Arguments
+    ///
+    /// * `did_uri` - The DID being linked to the origin.
+    /// * `origin` - The web origin (e.g. `https://example.com`) being linked to the DID.
+    /// * `expiration_date` - When the linkage expires, which the specification requires.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let bearer_did = DidDht::create(None).await.unwrap();
+    ///
+    /// let domain_linkage_credential = DomainLinkageCredential::create(
+    ///     &bearer_did.did.uri,
+    ///     "https://example.com",
+    ///     SystemTime::now() + Duration::from_secs(365 * 24 * 60 * 60),
+    /// ).await.unwrap();
+    ///
+    /// let vc_jwt = domain_linkage_credential.sign(&bearer_did, None).unwrap();
+    /// ```
+    pub async fn create(did_uri: &str, origin: &str, expiration_date: SystemTime) -> Result<Self> {
+        let origin = parse_origin(origin)?;
+
+        let credential_subject = CredentialSubject {
+            id: did_uri.to_string(),
+            additional_properties: Some(JsonObject {
+                properties: [("origin".to_string(), JsonValue::String(origin))]
+                    .into_iter()
+                    .collect(),
+            }),
+        };
+
+        let vc_options = VerifiableCredentialCreateOptions {
+            context: Some(vec![DID_CONFIGURATION_CONTEXT.to_string()]),
+            r#type: Some(vec![DOMAIN_LINKAGE_CREDENTIAL_TYPE.to_string()]),
+            expiration_date: Some(expiration_date),
+            ..Default::default()
+        };
+
+        let verifiable_credential = VerifiableCredential::create(
+            Issuer::from(did_uri),
+            credential_subject,
+            Some(vc_options),
+        )
+        .await?;
+
+        Ok(Self {
+            base: verifiable_credential,
+        })
+    }
+
+    /// Signs the Domain Linkage Credential with the linked DID, producing the JWT to list in the
+    /// origin's DID Configuration resource.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The linked DID.
+    /// * `verification_method_id` - Optional identifier of the Verification Method to sign with.
+    pub fn sign(
+        &self,
+        bearer_did: &BearerDid,
+        verification_method_id: Option<String>,
+    ) -> Result<String> {
+        self.base.sign(bearer_did, verification_method_id)
+    }
+}
+
+/// Represents the DID Configuration resource served by an origin at
+/// `/.well-known/did-configuration.json`, listing the DIDs linked to it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DidConfiguration {
+    #[serde(rename = "@context")]
+    pub context: String,
+    /// Domain Linkage Credentials, serialized as JWTs.
+    pub linked_dids: Vec<String>,
+}
+
+impl FromJson for DidConfiguration {}
+impl ToJson for DidConfiguration {}
+
+impl DidConfiguration {
+    /// Creates a DID Configuration resource from signed Domain Linkage Credential JWTs.
+    pub fn new(linked_dids: Vec<String>) -> Self {
+        Self {
+            context: DID_CONFIGURATION_CONTEXT.to_string(),
+            linked_dids,
+        }
+    }
+
+    /// Fetches the DID Configuration resource served by the given origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The web origin (e.g. `https://example.com`) to fetch from.
+    pub async fn fetch(origin: &str) -> Result<Self> {
+        let url = format!("{}{}", parse_origin(origin)?, DID_CONFIGURATION_PATH);
+        let response = http_std::fetch(&url, None).await?;
+
+        if !(200..300).contains(&response.status_code) {
+            return Err(Web5Error::Network(format!(
+                "failed to fetch {} status code {}",
+                url, response.status_code
+            )));
+        }
+
+        DidConfiguration::from_json_byte_array(&response.body)
+    }
+
+    /// Verifies that this DID Configuration resource links the origin to the DID, by way of a
+    /// valid Domain Linkage Credential issued by the DID.
+    ///
+    /// # Arguments
+    ///
+    /// * `did_uri` - The DID expected to be linked.
+    /// * `origin` - The origin this DID Configuration resource was served from.
+    ///
+    /// Entries of `linked_dids` which aren't JWTs are skipped, and reported in the error if no
+    /// other entry links the DID.
+    pub async fn verify_linked_did(&self, did_uri: &str, origin: &str) -> Result<()> {
+        let origin = parse_origin(origin)?;
+
+        let mut last_error = None;
+        let mut malformed = vec![];
+        for (i, linked_did) in self.linked_dids.iter().enumerate() {
+            let jwt = match Jwt::from_compact_jws(linked_did, false).await {
+                Ok(jwt) => jwt,
+                Err(e) => {
+                    malformed.push(format!("linked_dids[{}] is malformed: {}", i, e));
+                    continue;
+                }
+            };
+            if jwt.claims.iss.as_deref() != Some(did_uri) {
+                continue;
+            }
+
+            match verify_domain_linkage_credential(linked_did, &jwt.kid, did_uri, &origin).await {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            let mut message = format!(
+                "{} does not list a domain linkage credential for {}",
+                origin, did_uri
+            );
+            for report in &malformed {
+                message.push_str(&format!("; {}", report));
+            }
+            VerificationError::DomainLinkage(message).into()
+        }))
+    }
+
+    /// Verifies the origins a DID claims to be linked to, checking both directions of the linkage:
+    /// each origin listed in the DID's `LinkedDomains` services must serve a DID Configuration
+    /// resource holding a valid Domain Linkage Credential issued by the DID.
+    ///
+    /// # Arguments
+    ///
+    /// * `did_uri` - The DID whose linked domains to verify.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<String>>` - The verified origins, or an error if the DID lists no linked
+    ///   domains or any of them fails verification.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let origins = DidConfiguration::verify_linked_domains(&bearer_did.did.uri).await?;
+    /// ```
+    pub async fn verify_linked_domains(did_uri: &str) -> Result<Vec<String>> {
+        let resolution_result = ResolutionResult::resolve(did_uri).await;
+        if let Some(e) = resolution_result.resolution_metadata.error {
+            return Err(e.into());
+        }
+        let document = resolution_result.document.ok_or_else(|| {
+            Web5Error::NotFound(format!("failed to resolve did document for {}", did_uri))
+        })?;
+
+        let origins = linked_domain_origins(&document)?;
+        if origins.is_empty() {
+            return Err(VerificationError::DomainLinkage(format!(
+                "{} has no {} service",
+                did_uri, LINKED_DOMAINS_SERVICE_TYPE
+            ))
+            .into());
+        }
+
+        for origin in &origins {
+            let did_configuration = DidConfiguration::fetch(origin).await?;
+            did_configuration.verify_linked_did(did_uri, origin).await?;
+        }
+
+        Ok(origins)
+    }
+}
+
+async fn verify_domain_linkage_credential(
+    vc_jwt: &str,
+    kid: &str,
+    did_uri: &str,
+    origin: &str,
+) -> Result<()> {
+    if Did::parse(kid)?.uri != did_uri {
+        return Err(VerificationError::DomainLinkage(format!(
+            "kid {} does not belong to {}",
+            kid, did_uri
+        ))
+        .into());
+    }
+
+    let vc = VerifiableCredential::from_vc_jwt(vc_jwt, true).await?;
+
+    if !vc.context.iter().any(|c| c == DID_CONFIGURATION_CONTEXT)
+        || !vc
+            .r#type
+            .iter()
+            .any(|t| t == DOMAIN_LINKAGE_CREDENTIAL_TYPE)
+    {
+        return Err(VerificationError::DomainLinkage(
+            "credential is not a domain linkage credential".to_string(),
+        )
+        .into());
+    }
+
+    if vc.issuer.to_string() != did_uri || vc.credential_subject.id != did_uri {
+        return Err(VerificationError::DomainLinkage(format!(
+            "credential issuer and subject must both be {}",
+            did_uri
+        ))
+        .into());
+    }
+
+    if vc.expiration_date.is_none() {
+        return Err(VerificationError::DomainLinkage(
+            "credential must have an expiration date".to_string(),
+        )
+        .into());
+    }
+
+    let credential_origin = vc
+        .credential_subject
+        .additional_properties
+        .as_ref()
+        .and_then(|properties| properties.get::<String>("origin").ok().flatten())
+        .ok_or_else(|| VerificationError::MissingClaim("credentialSubject.origin".to_string()))?;
+    if parse_origin(&credential_origin)? != origin {
+        return Err(VerificationError::DomainLinkage(format!(
+            "credential origin {} does not match {}",
+            credential_origin, origin
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Collects the origins listed by the document's `LinkedDomains` services, whose endpoint is an
+/// origin, a set of origins, or a map with an `origins` member.
+fn linked_domain_origins(document: &Document) -> Result<Vec<String>> {
+    let mut origins = vec![];
+    for service in document.service.iter().flatten() {
+        if service.r#type != LINKED_DOMAINS_SERVICE_TYPE {
+            continue;
+        }
+
+        let entries = match &service.service_endpoint {
+            ServiceEndpoint::Map(map) => match map.get_value("origins") {
+                Some(JsonValue::Array(values)) => values
+                    .iter()
+                    .filter_map(|value| match value {
+                        JsonValue::String(origin) => {
+                            Some(ServiceEndpointEntry::Uri(origin.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+                _ => vec![],
+            },
+            endpoint => endpoint.entries(),
+        };
+
+        for entry in entries {
+            if let ServiceEndpointEntry::Uri(origin) = entry {
+                let origin = parse_origin(&origin)?;
+                if !origins.contains(&origin) {
+                    origins.push(origin);
+                }
+            }
+        }
+    }
+    Ok(origins)
+}
+
+#[cfg(test)]
+thread_local! {
+    // the mock servers tests run against only serve http
+    static ALLOW_HTTP_ORIGINS: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+fn is_allowed_scheme(scheme: &str) -> bool {
+    #[cfg(test)]
+    if scheme == "http" && ALLOW_HTTP_ORIGINS.with(|allow| allow.get()) {
+        return true;
+    }
+    scheme == "https"
+}
+
+fn parse_origin(origin: &str) -> Result<String> {
+    let url = Url::parse(origin)
+        .map_err(|e| Web5Error::Parameter(format!("invalid origin {}: {}", origin, e)))?;
+    if !is_allowed_scheme(url.scheme()) {
+        return Err(Web5Error::Parameter(format!(
+            "origin {} must use https",
+            origin
+        )));
+    }
+    if url.path() != "/" || url.query().is_some() || url.fragment().is_some() {
+        return Err(Web5Error::Parameter(format!(
+            "origin {} must not have a path, query or fragment",
+            origin
+        )));
+    }
+    Ok(url.origin().ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::{
+        data_model::service::Service,
+        methods::did_web::{DidWeb, DidWebCreateOptions},
+    };
+    use mockito::{Server, ServerGuard};
+    use std::time::Duration;
+
+    fn expiration_date() -> SystemTime {
+        SystemTime::now() + Duration::from_secs(60 * 60)
+    }
+
+    fn linked_did(mock_server: &ServerGuard, origin: &str) -> BearerDid {
+        ALLOW_HTTP_ORIGINS.with(|allow| allow.set(true));
+        DidWeb::create(
+            &mock_server.url(),
+            Some(DidWebCreateOptions {
+                service: Some(vec![Service {
+                    id: "#linked-domain".to_string(),
+                    r#type: LINKED_DOMAINS_SERVICE_TYPE.to_string(),
+                    service_endpoint: origin.into(),
+                    additional_properties: None,
+                }]),
+                ..Default::default()
+            }),
+        )
+        .unwrap()
+    }
+
+    mod create {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_and_sign() {
+            let mock_server = Server::new_async().await;
+            let bearer_did = linked_did(&mock_server, "https://example.com");
+
+            let domain_linkage_credential = DomainLinkageCredential::create(
+                &bearer_did.did.uri,
+                "https://example.com/",
+                expiration_date(),
+            )
+            .await
+            .unwrap();
+
+            let vc = &domain_linkage_credential.base;
+            assert_eq!(vc.issuer.to_string(), bearer_did.did.uri);
+            assert_eq!(vc.credential_subject.id, bearer_did.did.uri);
+            assert!(vc
+                .r#type
+                .contains(&DOMAIN_LINKAGE_CREDENTIAL_TYPE.to_string()));
+            assert_eq!(
+                vc.credential_subject
+                    .additional_properties
+                    .as_ref()
+                    .unwrap()
+                    .get::<String>("origin")
+                    .unwrap(),
+                Some("https://example.com".to_string())
+            );
+
+            assert!(domain_linkage_credential.sign(&bearer_did, None).is_ok());
+        }
+
+        #[tokio::test]
+        async fn test_origin_must_not_have_path() {
+            let result = DomainLinkageCredential::create(
+                "did:web:example.com",
+                "https://example.com/some/path",
+                expiration_date(),
+            )
+            .await;
+
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[tokio::test]
+        async fn test_origin_must_use_https() {
+            let result = DomainLinkageCredential::create(
+                "did:web:example.com",
+                "http://example.com",
+                expiration_date(),
+            )
+            .await;
+
+            match result {
+                Err(Web5Error::Parameter(msg)) => {
+                    assert_eq!(msg, "origin http://example.com must use https")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod verify_linked_domains {
+        use super::*;
+
+        async fn serve(
+            mock_server: &mut ServerGuard,
+            bearer_did: &BearerDid,
+            linked_dids: Vec<String>,
+        ) {
+            mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(bearer_did.document.to_json_string().unwrap())
+                .create_async()
+                .await;
+            mock_server
+                .mock("GET", DID_CONFIGURATION_PATH)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(DidConfiguration::new(linked_dids).to_json_string().unwrap())
+                .create_async()
+                .await;
+        }
+
+        #[tokio::test]
+        async fn test_verifies_both_directions() {
+            let mut mock_server = Server::new_async().await;
+            let origin = mock_server.url();
+            let bearer_did = linked_did(&mock_server, &origin);
+
+            let vc_jwt =
+                DomainLinkageCredential::create(&bearer_did.did.uri, &origin, expiration_date())
+                    .await
+                    .unwrap()
+                    .sign(&bearer_did, None)
+                    .unwrap();
+            serve(&mut mock_server, &bearer_did, vec![vc_jwt]).await;
+
+            let origins = DidConfiguration::verify_linked_domains(&bearer_did.did.uri)
+                .await
+                .unwrap();
+            assert_eq!(origins, vec![parse_origin(&origin).unwrap()]);
+        }
+
+        #[tokio::test]
+        async fn test_origin_does_not_list_did() {
+            let mut mock_server = Server::new_async().await;
+            let origin = mock_server.url();
+            let bearer_did = linked_did(&mock_server, &origin);
+
+            serve(&mut mock_server, &bearer_did, vec![]).await;
+
+            let result = DidConfiguration::verify_linked_domains(&bearer_did.did.uri).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    VerificationError::DomainLinkage(_)
+                ))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_skips_malformed_entries() {
+            let mut mock_server = Server::new_async().await;
+            let origin = mock_server.url();
+            let bearer_did = linked_did(&mock_server, &origin);
+
+            let vc_jwt =
+                DomainLinkageCredential::create(&bearer_did.did.uri, &origin, expiration_date())
+                    .await
+                    .unwrap()
+                    .sign(&bearer_did, None)
+                    .unwrap();
+            serve(
+                &mut mock_server,
+                &bearer_did,
+                vec!["not a jwt".to_string(), vc_jwt],
+            )
+            .await;
+
+            let origins = DidConfiguration::verify_linked_domains(&bearer_did.did.uri)
+                .await
+                .unwrap();
+            assert_eq!(origins, vec![parse_origin(&origin).unwrap()]);
+        }
+
+        #[tokio::test]
+        async fn test_reports_malformed_entries() {
+            let mut mock_server = Server::new_async().await;
+            let origin = mock_server.url();
+            let bearer_did = linked_did(&mock_server, &origin);
+
+            serve(&mut mock_server, &bearer_did, vec!["not a jwt".to_string()]).await;
+
+            let result = DidConfiguration::verify_linked_domains(&bearer_did.did.uri).await;
+            match result {
+                Err(Web5Error::CredentialError(VerificationError::DomainLinkage(msg))) => {
+                    assert!(msg.contains("linked_dids[0] is malformed"))
+                }
+                _ => panic!("expected domain linkage error but got {:?}", result),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_credential_for_other_origin() {
+            let mut mock_server = Server::new_async().await;
+            let origin = mock_server.url();
+            let bearer_did = linked_did(&mock_server, &origin);
+
+            let vc_jwt = DomainLinkageCredential::create(
+                &bearer_did.did.uri,
+                "https://example.com",
+                expiration_date(),
+            )
+            .await
+            .unwrap()
+            .sign(&bearer_did, None)
+            .unwrap();
+            serve(&mut mock_server, &bearer_did, vec![vc_jwt]).await;
+
+            let result = DidConfiguration::verify_linked_domains(&bearer_did.did.uri).await;
+            match result {
+                Err(Web5Error::CredentialError(VerificationError::DomainLinkage(msg))) => {
+                    assert!(msg.contains("does not match"))
+                }
+                _ => panic!("expected domain linkage error but got {:?}", result),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_did_without_linked_domains() {
+            let mut mock_server = Server::new_async().await;
+            let bearer_did = DidWeb::create(&mock_server.url(), None).unwrap();
+
+            serve(&mut mock_server, &bearer_did, vec![]).await;
+
+            let result = DidConfiguration::verify_linked_domains(&bearer_did.did.uri).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    VerificationError::DomainLinkage(_)
+                ))
+            ));
+        }
+    }
+}
@@ -2,6 +2,9 @@ use super::{
     credential_schema::CredentialSchema, credential_subject::CredentialSubject, issuer::Issuer,
 };
 use crate::credentials::verifiable_credential_1_1::CredentialStatus;
+use crate::credentials::verifiable_credential_2_0::{
+    deserialize_credential_subjects, serialize_credential_subjects, VerifiableCredentialV2,
+};
 use crate::errors::{Result, Web5Error};
 use crate::json::{json_value_type_name, FromJsonValue, JsonValue, ToJsonValue};
 use crate::{
@@ -70,3 +73,91 @@ impl ToJsonValue for JwtPayloadVerifiableCredential {
         map.to_json_value()
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JwtPayloadVerifiableCredentialV2 {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub r#type: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<Issuer>,
+    #[serde(
+        rename = "credentialSubject",
+        serialize_with = "serialize_credential_subjects",
+        deserialize_with = "deserialize_credential_subjects",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub credential_subject: Vec<CredentialSubject>,
+    #[serde(
+        rename = "validFrom",
+        serialize_with = "serialize_optional_rfc3339",
+        deserialize_with = "deserialize_optional_rfc3339",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub valid_from: Option<SystemTime>,
+    #[serde(
+        rename = "validUntil",
+        serialize_with = "serialize_optional_rfc3339",
+        deserialize_with = "deserialize_optional_rfc3339",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub valid_until: Option<SystemTime>,
+    #[serde(rename = "credentialStatus", skip_serializing_if = "Option::is_none")]
+    pub credential_status: Option<CredentialStatus>,
+    #[serde(rename = "credentialSchema", skip_serializing_if = "Option::is_none")]
+    pub credential_schema: Option<CredentialSchema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<Vec<JsonObject>>,
+}
+
+impl From<VerifiableCredentialV2> for JwtPayloadVerifiableCredentialV2 {
+    fn from(vc: VerifiableCredentialV2) -> Self {
+        Self {
+            context: vc.context,
+            id: Some(vc.id),
+            r#type: vc.r#type,
+            name: vc.name,
+            description: vc.description,
+            issuer: Some(vc.issuer),
+            credential_subject: vc.credential_subject,
+            valid_from: vc.valid_from,
+            valid_until: vc.valid_until,
+            credential_status: vc.credential_status,
+            credential_schema: vc.credential_schema,
+            evidence: vc.evidence,
+        }
+    }
+}
+
+impl FromJsonValue for JwtPayloadVerifiableCredentialV2 {
+    fn from_json_value(value: &JsonValue) -> Result<Self> {
+        if let JsonValue::Object(ref obj) = *value {
+            let json_value = serde_json::to_value(obj)?;
+            let value = serde_json::from_value::<Self>(json_value)?;
+            Ok(value)
+        } else {
+            Err(Web5Error::Json(format!(
+                "expected object, but found {}",
+                json_value_type_name(value)
+            )))
+        }
+    }
+}
+
+impl ToJsonValue for JwtPayloadVerifiableCredentialV2 {
+    fn to_json_value(&self) -> Result<JsonValue> {
+        let json_string = serde_json::to_string(self)?;
+        let map = serde_json::from_str::<HashMap<String, JsonValue>>(&json_string)?;
+        map.to_json_value()
+    }
+}
@@ -1,24 +1,64 @@
+mod bitstring_status_list;
+pub use bitstring_status_list::{
+    BitstringStatusList, BitstringStatusListCredential, BitstringStatusListCredentialCreateOptions,
+    BITSTRING_STATUS_LIST, BITSTRING_STATUS_LIST_CREDENTIAL_TYPE, BITSTRING_STATUS_LIST_ENTRY,
+    STATUS_PURPOSES,
+};
 mod create;
 mod credential_schema;
 mod credential_subject;
+mod data_integrity;
+pub use data_integrity::{
+    Cryptosuite, DataIntegrityProof, DataIntegrityProofOptions, DataIntegrityVerifyOptions,
+    DATA_INTEGRITY_CONTEXT, DATA_INTEGRITY_PROOF_TYPE,
+};
 mod data_model_validation;
 mod decode;
+mod did_configuration;
+pub use did_configuration::{
+    DidConfiguration, DomainLinkageCredential, DID_CONFIGURATION_CONTEXT, DID_CONFIGURATION_PATH,
+    DOMAIN_LINKAGE_CREDENTIAL_TYPE, LINKED_DOMAINS_SERVICE_TYPE,
+};
 mod issuer;
 mod jwt_payload_vc;
 pub mod presentation_definition;
+mod sd_jwt_vc;
+pub use sd_jwt_vc::SD_JWT_VC_MEDIA_TYPE;
 mod sign;
 mod status_list_credential;
 pub use status_list_credential::{
-    StatusListCredential, STATUS_LIST_2021, STATUS_LIST_2021_ENTRY, STATUS_LIST_CREDENTIAL_CONTEXT,
-    STATUS_LIST_CREDENTIAL_TYPE,
+    CredentialStatusResult, StatusListCredential, STATUS_LIST_2021, STATUS_LIST_2021_ENTRY,
+    STATUS_LIST_CREDENTIAL_CONTEXT, STATUS_LIST_CREDENTIAL_TYPE,
+};
+mod status_list_manager;
+pub use status_list_manager::{
+    StatusListManager, StatusListManagerCreateOptions, StatusListManagerIssueOptions,
+};
+mod vc_jose_cose;
+pub use vc_jose_cose::{
+    JwtSecuringMechanism, VC_CONTENT_TYPE, VC_JWT_MEDIA_TYPE, VP_CONTENT_TYPE, VP_JWT_MEDIA_TYPE,
 };
 mod verifiable_credential_1_1;
+mod verifiable_credential_2_0;
 mod verifiable_presentation_1_1;
+mod verification_policy;
+pub use verification_policy::{
+    VerificationCheck, VerificationCheckResult, VerificationOutcome, VerificationPolicy,
+    VerificationReport,
+};
+mod verify;
+pub use verify::{clear_status_list_cache, StatusListCache};
 
 pub use verifiable_credential_1_1::CredentialStatus;
+pub use verifiable_credential_1_1::StatusMessage;
 pub use verifiable_credential_1_1::VerifiableCredential;
 pub use verifiable_credential_1_1::VerifiableCredentialCreateOptions;
 
+pub use verifiable_credential_2_0::DataModelVersion;
+pub use verifiable_credential_2_0::VerifiableCredentialV2;
+pub use verifiable_credential_2_0::VerifiableCredentialV2CreateOptions;
+pub use verifiable_credential_2_0::VersionedVerifiableCredential;
+
 pub use verifiable_presentation_1_1::VerifiablePresentation;
 pub use verifiable_presentation_1_1::VerifiablePresentationCreateOptions;
 
@@ -36,8 +76,32 @@ pub enum VerificationError {
     MisconfiguredExpirationDate(String),
     #[error("credential expired")]
     CredentialExpired,
+    #[error("credential revoked")]
+    CredentialRevoked,
+    #[error("credential suspended")]
+    CredentialSuspended,
     #[error("data model validation error: {0}")]
     DataModelValidationError(String),
     #[error("missing kid jose header")]
     MissingKid,
+    #[error("domain linkage error: {0}")]
+    DomainLinkage(String),
+    #[error("authentication error: {0}")]
+    Authentication(String),
+    #[error("unauthorized signer: {0}")]
+    UnauthorizedSigner(String),
+    #[error("data integrity proof error: {0}")]
+    DataIntegrityProof(String),
+    #[error("selective disclosure error: {0}")]
+    SelectiveDisclosure(String),
+    #[error("key binding error: {0}")]
+    KeyBinding(String),
+    #[error("untrusted issuer: {0}")]
+    UntrustedIssuer(String),
+    #[error("missing required type: {0}")]
+    MissingType(String),
+    #[error("disallowed algorithm: {0}")]
+    DisallowedAlgorithm(String),
+    #[error("missing credential schema")]
+    MissingCredentialSchema,
 }
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::credentials::verifiable_credential_1_1::VerifiableCredential;
+use crate::credentials::verification_policy::VerificationPolicy;
 use jsonpath_rust::{
     JsonPathFinder,
     JsonPathValue::{NewValue, NoValue, Slice},
@@ -179,10 +180,32 @@ impl PresentationDefinition {
     ///
     /// A `Result` containing a vector of VCs that fulfill the input descriptors. If no VCs match, returns an empty vector.
     pub async fn select_credentials(&self, vc_jwts: &Vec<String>) -> Result<Vec<String>> {
+        self.select_credentials_with_policy(vc_jwts, &VerificationPolicy::default())
+            .await
+    }
+
+    /// Selects Verifiable Credentials (VCs) that match the input descriptors of the presentation
+    /// definition, and are verified under the given policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwts` - A reference to a vector of VC JWTs to validate.
+    /// * `policy` - The policy VCs must be verified under to be selected.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a vector of VCs that fulfill the input descriptors. If no VCs match, returns an empty vector.
+    pub async fn select_credentials_with_policy(
+        &self,
+        vc_jwts: &Vec<String>,
+        policy: &VerificationPolicy,
+    ) -> Result<Vec<String>> {
         let mut matches: HashSet<String> = HashSet::new();
 
         for input_descriptor in &self.input_descriptors {
-            let matching_vc_jwts = input_descriptor.select_credentials(vc_jwts).await?;
+            let matching_vc_jwts = input_descriptor
+                .select_credentials_with_policy(vc_jwts, policy)
+                .await?;
             if matching_vc_jwts.is_empty() {
                 return Ok(vec![]);
             }
@@ -206,6 +229,28 @@ impl PresentationDefinition {
     pub async fn create_presentation_from_credentials(
         &self,
         vc_jwts: &Vec<String>,
+    ) -> Result<PresentationResult> {
+        self.create_presentation_from_credentials_with_policy(
+            vc_jwts,
+            &VerificationPolicy::default(),
+        )
+        .await
+    }
+
+    /// Creates a Presentation Submission in which the list of Verifiable Credentials JWTs (VCs),
+    /// verified under the given policy, fulfills the given Presentation Definition.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwts` - Iterable of VCs in JWT format to validate.
+    /// * `policy` - The policy VCs must be verified under to be submitted.
+    ///
+    /// # Returns
+    /// A `PresentationResult` which holds the `PresentationSubmission` and a `Vec<String>` which has the vc_jwts that were used
+    pub async fn create_presentation_from_credentials_with_policy(
+        &self,
+        vc_jwts: &Vec<String>,
+        policy: &VerificationPolicy,
     ) -> Result<PresentationResult> {
         // Check if there are submission requirements (not supported in this implementation)
         if self.submission_requirements.is_some() {
@@ -215,7 +260,7 @@ impl PresentationDefinition {
         }
 
         // Select the appropriate credentials that match the presentation definition
-        let selected_credentials = self.select_credentials(vc_jwts).await?;
+        let selected_credentials = self.select_credentials_with_policy(vc_jwts, policy).await?;
 
         if selected_credentials.is_empty() {
             return Err(PexError::IllegalState(
@@ -230,7 +275,7 @@ impl PresentationDefinition {
             let mut matching_vcs = Vec::new();
             for vc_jwt in &selected_credentials {
                 if let Ok(result) = input_descriptor
-                    .select_credentials(&vec![vc_jwt.to_string()])
+                    .select_credentials_with_policy(&vec![vc_jwt.to_string()], policy)
                     .await
                 {
                     if !result.is_empty() {
@@ -304,6 +349,15 @@ fn get_value_at_json_path(json: &str, path: &str) -> Option<Value> {
 
 impl InputDescriptor {
     pub async fn select_credentials(&self, vc_jwts: &Vec<String>) -> Result<Vec<String>> {
+        self.select_credentials_with_policy(vc_jwts, &VerificationPolicy::default())
+            .await
+    }
+
+    pub async fn select_credentials_with_policy(
+        &self,
+        vc_jwts: &Vec<String>,
+        policy: &VerificationPolicy,
+    ) -> Result<Vec<String>> {
         let mut tokenized_fields: Vec<TokenizedField> = vec![];
         let mut json_schema_builder = JsonSchemaBuilder::new();
 
@@ -345,8 +399,8 @@ impl InputDescriptor {
         for vc_jwt in vc_jwts {
             let mut selection_candidate: Map<String, Value> = Map::new();
 
-            let vc = match VerifiableCredential::from_vc_jwt(vc_jwt, true).await {
-                Ok(vc) => vc,
+            let vc = match VerifiableCredential::from_vc_jwt_with_policy(vc_jwt, policy).await {
+                Ok((vc, _)) => vc,
                 Err(_) => {
                     continue;
                 }
@@ -0,0 +1,417 @@
+use super::{
+    credential_subject::CredentialSubject,
+    issuer::Issuer,
+    sign::check_issuer,
+    verifiable_credential_1_1::{VerifiableCredential, BASE_CONTEXT, BASE_TYPE},
+    VerificationError,
+};
+use crate::{
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        controller::verify_controller_authorization,
+        data_model::document::VerificationRelationship,
+    },
+    errors::{Result, Web5Error},
+    jose::JwtClaims,
+    json::{JsonObject, JsonValue},
+    sd_jwt::{SdJwt, SdJwtCreateOptions, SdJwtVerifyOptions},
+};
+use chrono::Utc;
+use std::collections::HashMap;
+
+/// The `typ` of a Verifiable Credential secured per
+/// [SD-JWT VC](https://datatracker.ietf.org/doc/draft-ietf-oauth-sd-jwt-vc/).
+pub const SD_JWT_VC_MEDIA_TYPE: &str = "dc+sd-jwt";
+/// The `typ` of SD-JWT VCs issued before the media type was renamed.
+const LEGACY_SD_JWT_VC_MEDIA_TYPE: &str = "vc+sd-jwt";
+
+/// Claims registered by SD-JWT VC, which the credential subject's claims cannot be named.
+const REGISTERED_CLAIMS: &[&str] = &[
+    "iss",
+    "sub",
+    "aud",
+    "iat",
+    "nbf",
+    "exp",
+    "jti",
+    "vct",
+    "vct#integrity",
+    "cnf",
+    "status",
+];
+
+/// Issues the credential as an SD-JWT VC. The subject's claims are the top-level claims of the
+/// SD-JWT, so the disclosable claims are JSON Pointers into the credential subject.
+pub(crate) fn sign_sd_jwt_vc(
+    vc: &VerifiableCredential,
+    bearer_did: &BearerDid,
+    options: SdJwtCreateOptions,
+) -> Result<String> {
+    check_issuer(
+        &vc.issuer,
+        bearer_did,
+        &SignerOptions {
+            verification_method_id: options.verification_method_id.clone(),
+            verification_relationship: Some(VerificationRelationship::AssertionMethod),
+            algorithms: None,
+        },
+    )?;
+
+    if options
+        .disclosable_claims
+        .iter()
+        .any(|pointer| pointer == "/id" || pointer.starts_with("/id/"))
+    {
+        return Err(Web5Error::Parameter(
+            "the credential subject id cannot be selectively disclosed".to_string(),
+        ));
+    }
+
+    let mut additional_properties: HashMap<String, JsonValue> = vc
+        .credential_subject
+        .additional_properties
+        .clone()
+        .map(|subject| subject.properties)
+        .unwrap_or_default();
+    if let Some(name) = REGISTERED_CLAIMS
+        .iter()
+        .find(|name| additional_properties.contains_key(**name))
+    {
+        return Err(Web5Error::Parameter(format!(
+            "credential subject claim {} is registered by sd-jwt vc",
+            name
+        )));
+    }
+    // the most specific type, as the base type is the first
+    let vct = vc.r#type.last().cloned().unwrap_or(BASE_TYPE.to_string());
+    additional_properties.insert("vct".to_string(), JsonValue::String(vct));
+
+    let claims = JwtClaims {
+        aud: None,
+        iss: Some(vc.issuer.to_string()),
+        jti: Some(vc.id.clone()),
+        sub: Some(vc.credential_subject.id.clone()),
+        nbf: Some(vc.issuance_date),
+        iat: Some(Utc::now().into()),
+        exp: vc.expiration_date,
+        additional_properties: Some(additional_properties),
+    };
+
+    let sd_jwt = SdJwt::create(
+        &claims,
+        bearer_did,
+        &SdJwtCreateOptions {
+            typ: Some(SD_JWT_VC_MEDIA_TYPE.to_string()),
+            ..options
+        },
+    )?;
+    Ok(sd_jwt.serialize())
+}
+
+/// Verifies an SD-JWT VC, and constructs the credential of the disclosed claims.
+pub(crate) async fn decode_sd_jwt_vc(
+    sd_jwt_vc: &str,
+    options: &SdJwtVerifyOptions,
+) -> Result<VerifiableCredential> {
+    let sd_jwt = SdJwt::verify(sd_jwt_vc, options).await?;
+
+    match sd_jwt.issuer_jwt.header.typ.as_deref() {
+        Some(SD_JWT_VC_MEDIA_TYPE | LEGACY_SD_JWT_VC_MEDIA_TYPE) => {}
+        typ => {
+            return Err(Web5Error::Parameter(format!(
+                "expected typ {} but found {}",
+                SD_JWT_VC_MEDIA_TYPE,
+                typ.unwrap_or_default()
+            )))
+        }
+    }
+
+    let claims = sd_jwt.disclosed_claims()?;
+    let iss = claims
+        .iss
+        .ok_or(VerificationError::MissingClaim("iss".to_string()))?;
+    // an empty issuer is reported by data model validation
+    if !iss.is_empty() {
+        verify_controller_authorization(&iss, &sd_jwt.issuer_jwt.kid).await?;
+    }
+
+    let mut additional_properties = claims.additional_properties.unwrap_or_default();
+    let vct = match additional_properties.remove("vct") {
+        Some(JsonValue::String(vct)) => vct,
+        _ => return Err(VerificationError::MissingClaim("vct".to_string()).into()),
+    };
+    for name in REGISTERED_CLAIMS {
+        additional_properties.remove(*name);
+    }
+
+    let mut r#type = vec![BASE_TYPE.to_string()];
+    if vct != BASE_TYPE {
+        r#type.push(vct);
+    }
+
+    Ok(VerifiableCredential {
+        context: vec![BASE_CONTEXT.to_string()],
+        id: claims
+            .jti
+            .ok_or(VerificationError::MissingClaim("jti".to_string()))?,
+        r#type,
+        issuer: Issuer::String(iss),
+        credential_subject: CredentialSubject {
+            id: claims
+                .sub
+                .ok_or(VerificationError::MissingClaim("sub".to_string()))?,
+            additional_properties: if additional_properties.is_empty() {
+                None
+            } else {
+                Some(JsonObject {
+                    properties: additional_properties,
+                })
+            },
+        },
+        issuance_date: claims
+            .nbf
+            .or(claims.iat)
+            .ok_or(VerificationError::MissingClaim("nbf".to_string()))?,
+        expiration_date: claims.exp,
+        credential_status: None,
+        credential_schema: None,
+        evidence: None,
+        proof: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        credentials::VerifiableCredentialCreateOptions, dids::methods::did_jwk::DidJwk,
+        json::ToJsonValue,
+    };
+
+    const AUDIENCE: &str = "https://verifier.example.com";
+    const NONCE: &str = "1234567890";
+
+    async fn kyc_credential(issuer: &BearerDid, holder: &BearerDid) -> VerifiableCredential {
+        let mut claims = JsonObject::new();
+        claims.insert("givenName", &"Alice".to_string()).unwrap();
+        claims
+            .insert("dateOfBirth", &"1990-01-01".to_string())
+            .unwrap();
+        claims.insert("country", &"US".to_string()).unwrap();
+
+        VerifiableCredential::create(
+            Issuer::String(issuer.did.uri.clone()),
+            CredentialSubject {
+                id: holder.did.uri.clone(),
+                additional_properties: Some(claims),
+            },
+            Some(VerifiableCredentialCreateOptions {
+                r#type: Some(vec!["KycCredential".to_string()]),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn options(holder: &BearerDid) -> SdJwtCreateOptions {
+        SdJwtCreateOptions {
+            disclosable_claims: vec!["/dateOfBirth".to_string(), "/country".to_string()],
+            holder_jwk: Some(
+                holder.document.verification_method[0]
+                    .public_key_jwk
+                    .clone(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn verify_options() -> SdJwtVerifyOptions {
+        SdJwtVerifyOptions {
+            require_key_binding: true,
+            audience: Some(AUDIENCE.to_string()),
+            nonce: Some(NONCE.to_string()),
+        }
+    }
+
+    mod sign_sd_jwt_vc {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_claims() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let vc = kyc_credential(&issuer, &holder).await;
+
+            let sd_jwt_vc = vc.sign_sd_jwt_vc(&issuer, options(&holder)).unwrap();
+            let sd_jwt = SdJwt::from_serialized(&sd_jwt_vc, true).await.unwrap();
+
+            assert_eq!(
+                sd_jwt.issuer_jwt.header.typ.as_deref(),
+                Some(SD_JWT_VC_MEDIA_TYPE)
+            );
+            let claims = &sd_jwt.issuer_jwt.claims;
+            assert_eq!(claims.iss, Some(issuer.did.uri.clone()));
+            assert_eq!(claims.sub, Some(holder.did.uri.clone()));
+            assert_eq!(claims.jti, Some(vc.id.clone()));
+            let additional_properties = claims.additional_properties.clone().unwrap();
+            assert_eq!(
+                additional_properties["vct"],
+                JsonValue::String("KycCredential".to_string())
+            );
+            assert_eq!(
+                additional_properties["givenName"],
+                JsonValue::String("Alice".to_string())
+            );
+            assert!(!additional_properties.contains_key("dateOfBirth"));
+            assert_eq!(sd_jwt.disclosures.len(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_subject_id_cannot_be_disclosable() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let vc = kyc_credential(&issuer, &holder).await;
+
+            let result = vc.sign_sd_jwt_vc(
+                &issuer,
+                SdJwtCreateOptions {
+                    disclosable_claims: vec!["/id".to_string()],
+                    ..Default::default()
+                },
+            );
+            match result {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "the credential subject id cannot be selectively disclosed"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_registered_claim_name() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let mut vc = kyc_credential(&issuer, &holder).await;
+            vc.credential_subject
+                .additional_properties
+                .as_mut()
+                .unwrap()
+                .properties
+                .insert(
+                    "exp".to_string(),
+                    "soon".to_string().to_json_value().unwrap(),
+                );
+
+            match vc.sign_sd_jwt_vc(&issuer, options(&holder)) {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "credential subject claim exp is registered by sd-jwt vc"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_issuer_mismatch() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let vc = kyc_credential(&issuer, &holder).await;
+
+            let result = vc.sign_sd_jwt_vc(&holder, options(&holder));
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+    }
+
+    mod from_sd_jwt_vc {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_selective_disclosure() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let vc = kyc_credential(&issuer, &holder).await;
+            let sd_jwt_vc = vc.sign_sd_jwt_vc(&issuer, options(&holder)).unwrap();
+
+            let presentation = SdJwt::from_serialized(&sd_jwt_vc, true)
+                .await
+                .unwrap()
+                .disclose(&["/country".to_string()])
+                .unwrap()
+                .add_key_binding(&holder, AUDIENCE, NONCE)
+                .unwrap()
+                .serialize();
+
+            let verified = VerifiableCredential::from_sd_jwt_vc(&presentation, &verify_options())
+                .await
+                .unwrap();
+            assert_eq!(verified.id, vc.id);
+            assert_eq!(verified.r#type, vc.r#type);
+            assert_eq!(verified.issuer.to_string(), issuer.did.uri);
+            assert_eq!(verified.credential_subject.id, holder.did.uri);
+            assert_eq!(
+                verified
+                    .issuance_date
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs(),
+                vc.issuance_date
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+            );
+
+            let properties = verified
+                .credential_subject
+                .additional_properties
+                .unwrap()
+                .properties;
+            assert_eq!(properties.len(), 2);
+            assert_eq!(
+                properties["givenName"],
+                JsonValue::String("Alice".to_string())
+            );
+            assert_eq!(properties["country"], JsonValue::String("US".to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_requires_key_binding() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let vc = kyc_credential(&issuer, &holder).await;
+            let sd_jwt_vc = vc.sign_sd_jwt_vc(&issuer, options(&holder)).unwrap();
+
+            let result = VerifiableCredential::from_sd_jwt_vc(&sd_jwt_vc, &verify_options()).await;
+            match result {
+                Err(Web5Error::CredentialError(VerificationError::KeyBinding(message))) => {
+                    assert_eq!(message, "key binding jwt is required")
+                }
+                _ => panic!("expected key binding error"),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_rejects_other_typ() {
+            let issuer = DidJwk::create(None).unwrap();
+            let claims: JwtClaims = serde_json::from_value(serde_json::json!({
+                "iss": issuer.did.uri,
+                "vct": "KycCredential"
+            }))
+            .unwrap();
+            let sd_jwt = SdJwt::create(&claims, &issuer, &SdJwtCreateOptions::default()).unwrap();
+
+            let result = VerifiableCredential::from_sd_jwt_vc(
+                &sd_jwt.serialize(),
+                &SdJwtVerifyOptions::default(),
+            )
+            .await;
+            match result {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "expected typ dc+sd-jwt but found sd+jwt")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+}
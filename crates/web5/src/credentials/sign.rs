@@ -1,26 +1,66 @@
 use super::{
-    jwt_payload_vc::JwtPayloadVerifiableCredential, verifiable_credential_1_1::VerifiableCredential,
+    issuer::Issuer,
+    jwt_payload_vc::{JwtPayloadVerifiableCredential, JwtPayloadVerifiableCredentialV2},
+    verifiable_credential_1_1::VerifiableCredential,
+    verifiable_credential_2_0::VerifiableCredentialV2,
 };
 use crate::{
-    dids::bearer_did::BearerDid,
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        controller::signs_as_controller,
+        data_model::document::VerificationRelationship,
+    },
     errors::{Result, Web5Error},
     jose::{Jwt, JwtClaims},
     json::{JsonValue, ToJsonValue},
 };
-use std::{collections::HashMap};
 use chrono::Utc;
+use std::collections::HashMap;
 
 pub fn sign_with_did(
     vc: &VerifiableCredential,
     bearer_did: &BearerDid,
     verification_method_id: Option<String>,
 ) -> Result<String> {
-    if !vc.issuer.to_string().starts_with(&bearer_did.did.uri) {
+    sign_with_did_options(vc, bearer_did, &assertion_method(verification_method_id))
+}
+
+pub fn sign_vc_2_0_with_did(
+    vc: &VerifiableCredentialV2,
+    bearer_did: &BearerDid,
+    verification_method_id: Option<String>,
+) -> Result<String> {
+    sign_vc_2_0_with_did_options(vc, bearer_did, &assertion_method(verification_method_id))
+}
+
+fn assertion_method(verification_method_id: Option<String>) -> SignerOptions {
+    SignerOptions {
+        verification_method_id,
+        verification_relationship: Some(VerificationRelationship::AssertionMethod),
+        algorithms: None,
+    }
+}
+
+pub(super) fn check_issuer(
+    issuer: &Issuer,
+    bearer_did: &BearerDid,
+    options: &SignerOptions,
+) -> Result<()> {
+    if !issuer.to_string().starts_with(&bearer_did.did.uri) && !signs_as_controller(options) {
         return Err(Web5Error::Parameter(format!(
             "Bearer DID URI {} does not match issuer {}",
-            bearer_did.did.uri, vc.issuer
+            bearer_did.did.uri, issuer
         )));
     }
+    Ok(())
+}
+
+pub fn sign_with_did_options(
+    vc: &VerifiableCredential,
+    bearer_did: &BearerDid,
+    options: &SignerOptions,
+) -> Result<String> {
+    check_issuer(&vc.issuer, bearer_did, options)?;
 
     let vc_claim = JwtPayloadVerifiableCredential {
         context: vc.context.clone(),
@@ -49,7 +89,40 @@ pub fn sign_with_did(
         additional_properties: Some(additional_properties),
     };
 
-    let jwt = Jwt::from_claims(&claims, bearer_did, verification_method_id)?;
+    let jwt = Jwt::from_claims_with_options(&claims, bearer_did, options)?;
+    Ok(jwt.compact_jws)
+}
+
+pub fn sign_vc_2_0_with_did_options(
+    vc: &VerifiableCredentialV2,
+    bearer_did: &BearerDid,
+    options: &SignerOptions,
+) -> Result<String> {
+    check_issuer(&vc.issuer, bearer_did, options)?;
+
+    let vc_claim = JwtPayloadVerifiableCredentialV2::from(vc.clone());
+
+    let mut additional_properties: HashMap<String, JsonValue> = HashMap::new();
+    additional_properties.insert("vc".to_string(), vc_claim.to_json_value()?);
+
+    // the registered subject claim can only identify a credential with a single subject
+    let sub = match vc.credential_subject.as_slice() {
+        [credential_subject] => Some(credential_subject.id.clone()),
+        _ => None,
+    };
+
+    let claims = JwtClaims {
+        aud: None,
+        iss: Some(vc.issuer.to_string()),
+        jti: Some(vc.id.clone()),
+        sub,
+        nbf: vc.valid_from,
+        iat: Some(Utc::now().into()),
+        exp: vc.valid_until,
+        additional_properties: Some(additional_properties),
+    };
+
+    let jwt = Jwt::from_claims_with_options(&claims, bearer_did, options)?;
     Ok(jwt.compact_jws)
 }
 
@@ -69,6 +142,79 @@ mod tests {
 
         use super::*;
 
+        #[tokio::test]
+        async fn test_controller_can_sign_for_issuer() {
+            let controller = DidJwk::create(None).unwrap();
+            let mut server = mockito::Server::new_async().await;
+            let issuer = crate::dids::methods::did_web::DidWeb::create(
+                &server.url(),
+                Some(crate::dids::methods::did_web::DidWebCreateOptions {
+                    controller: Some(vec![controller.did.uri.clone()]),
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+            server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_body(crate::json::ToJson::to_json_string(&issuer.document).unwrap())
+                .create_async()
+                .await;
+
+            let vc = VerifiableCredential::create(
+                Issuer::String(issuer.did.uri.clone()),
+                credential_subject(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+            let vc_jwt = sign_with_did_options(
+                &vc,
+                &controller,
+                &SignerOptions {
+                    verification_relationship: Some(VerificationRelationship::CapabilityInvocation),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            let vc_from_vc_jwt = VerifiableCredential::from_vc_jwt(&vc_jwt, true)
+                .await
+                .expect("should accept the controller's signature");
+            assert_eq!(vc_from_vc_jwt.issuer.to_string(), issuer.did.uri);
+        }
+
+        #[tokio::test]
+        async fn test_rejects_signature_by_non_controller() {
+            let issuer = DidJwk::create(None).unwrap();
+            let stranger = DidJwk::create(None).unwrap();
+
+            let vc = VerifiableCredential::create(
+                Issuer::String(issuer.did.uri.clone()),
+                credential_subject(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+            let vc_jwt = sign_with_did_options(
+                &vc,
+                &stranger,
+                &SignerOptions {
+                    verification_relationship: Some(VerificationRelationship::CapabilityInvocation),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            let result = VerifiableCredential::from_vc_jwt(&vc_jwt, true).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    crate::credentials::VerificationError::UnauthorizedSigner(_)
+                ))
+            ));
+        }
+
         #[tokio::test]
         async fn test_can_sign_then_verify() {
             let bearer_did = DidJwk::create(None).unwrap();
@@ -169,5 +315,33 @@ mod tests {
                 _ => panic!("expected Web5Error::Parameter but got {:?}", result),
             }
         }
+
+        #[tokio::test]
+        async fn test_sign_with_authentication_relationship() {
+            let mut bearer_did = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredential::create(
+                Issuer::String(bearer_did.did.uri.clone()),
+                credential_subject(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+
+            // only the authentication relationship remains
+            bearer_did.document.assertion_method = None;
+
+            let vc_jwt = sign_with_did_options(
+                &vc,
+                &bearer_did,
+                &SignerOptions {
+                    verification_relationship: Some(VerificationRelationship::Authentication),
+                    ..Default::default()
+                },
+            )
+            .expect("should sign with authentication key");
+
+            let kid = Jwt::from_compact_jws(&vc_jwt, false).await.unwrap().kid;
+            assert_eq!(bearer_did.document.verification_method[0].id, kid);
+        }
     }
 }
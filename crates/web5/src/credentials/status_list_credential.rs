@@ -1,4 +1,6 @@
-use super::verifiable_credential_1_1::{VerifiableCredential, VerifiableCredentialCreateOptions};
+use super::verifiable_credential_1_1::{
+    CredentialStatus, VerifiableCredential, VerifiableCredentialCreateOptions,
+};
 use crate::credentials::{CredentialSubject, Issuer};
 use crate::errors::{Result, Web5Error};
 use crate::json::{JsonObject, JsonValue};
@@ -12,6 +14,21 @@ pub const STATUS_LIST_2021: &str = "StatusList2021";
 
 pub const STATUS_LIST_2021_ENTRY: &str = "StatusList2021Entry";
 
+/// The status of a credential in a status list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CredentialStatusResult {
+    /// The purpose of the status list, such as "revocation", "suspension" or "message".
+    pub status_purpose: String,
+
+    /// The status value. For the "revocation" and "suspension" purposes, 1 means the credential
+    /// is revoked or suspended, and 0 that it is not.
+    pub status: u64,
+
+    /// The message for the status value, given by the `statusMessage` of the credential's
+    /// status entry, if any.
+    pub message: Option<String>,
+}
+
 /// Represents a Status List Credential, which is used to maintain the revocation or suspension status of multiple Verifiable Credentials.
 /// A Status List Credential is a special type of Verifiable Credential that tracks the status of other credentials.
 pub struct StatusListCredential {
@@ -47,6 +64,7 @@ impl StatusListCredential {
     ///             status_purpose: "revocation".to_string(),
     ///             status_list_index: "3".to_string(),
     ///             status_list_credential: "https://example.com/status/1".to_string(),
+    ///             ..Default::default()
     ///         }),
     ///         ..Default::default()
     ///     }),
@@ -112,7 +130,8 @@ impl StatusListCredential {
         })
     }
 
-    /// Checks if a given credential is disabled according to this Status List Credential.
+    /// Checks if a given credential is disabled according to this Status List Credential, that
+    /// is, if [`Self::get_status`] of its credential status is nonzero.
     ///
     /// # Arguments
     ///
@@ -141,6 +160,7 @@ impl StatusListCredential {
     ///             status_purpose: "revocation".to_string(),
     ///             status_list_index: "3".to_string(),
     ///             status_list_credential: "https://example.com/status/1".to_string(),
+    ///             ..Default::default()
     ///         }),
     ///         ..Default::default()
     ///     }),
@@ -161,11 +181,36 @@ impl StatusListCredential {
             Web5Error::Parameter("no credential status found in credential".to_string())
         })?;
 
+        Ok(self.get_status(status)?.status != 0)
+    }
+
+    /// Gets the status of a credential according to this Status List Credential.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_status` - The `StatusList2021Entry` of the credential to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CredentialStatusResult)` with a status of 1 if the credential is revoked or
+    ///   suspended, and 0 otherwise.
+    /// * `Err` if the credential status is invalid or incompatible.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let status = status_list_credential
+    ///     .get_status(verifiable_credential.credential_status.as_ref().unwrap())
+    ///     .unwrap();
+    /// ```
+    pub fn get_status(
+        &self,
+        credential_status: &CredentialStatus,
+    ) -> Result<CredentialStatusResult> {
         // Check if the status type matches
-        if status.r#type != STATUS_LIST_2021_ENTRY {
+        if credential_status.r#type != STATUS_LIST_2021_ENTRY {
             return Err(Web5Error::Parameter(format!(
                 "unsupported status type: {}",
-                status.r#type
+                credential_status.r#type
             )));
         }
 
@@ -175,17 +220,20 @@ impl StatusListCredential {
             "statusPurpose",
         )?;
 
-        if status_purpose != status.status_purpose {
+        if status_purpose != credential_status.status_purpose {
             return Err(Web5Error::Parameter("status purpose mismatch".to_string()));
         }
 
         // Get the bit index
-        let index = status.status_list_index.parse::<usize>().map_err(|_| {
-            Web5Error::Parameter(format!(
-                "invalid status list index: {}",
-                status.status_list_index
-            ))
-        })?;
+        let index = credential_status
+            .status_list_index
+            .parse::<usize>()
+            .map_err(|_| {
+                Web5Error::Parameter(format!(
+                    "invalid status list index: {}",
+                    credential_status.status_list_index
+                ))
+            })?;
 
         let encoded_list = Self::get_additional_property(
             &self.base.credential_subject.additional_properties,
@@ -193,7 +241,13 @@ impl StatusListCredential {
         )?;
 
         // Check the bit in the encoded list
-        Self::get_bit(encoded_list, index)
+        let status = Self::get_bit(encoded_list, index)?;
+
+        Ok(CredentialStatusResult {
+            status_purpose: status_purpose.to_string(),
+            status: status as u64,
+            message: None,
+        })
     }
 
     /// Extracts status list indexes from a vector of verifiable credentials that match the specified status purpose.
@@ -332,9 +386,7 @@ impl StatusListCredential {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::credentials::verifiable_credential_1_1::{
-        CredentialStatus, BASE_CONTEXT, BASE_TYPE,
-    };
+    use crate::credentials::verifiable_credential_1_1::{BASE_CONTEXT, BASE_TYPE};
 
     const ISSUER_DID_URI: &str = "did:web:tbd.website";
     const SUBJECT_DID_URI: &str = "did:dht:qgmmpyjw5hwnqfgzn7wmrm33ady8gb8z9ideib6m9gj4ys6wny8y";
@@ -354,6 +406,7 @@ mod tests {
             status_purpose: purpose.to_string(),
             status_list_index: index.to_string(),
             status_list_credential: "https://example.com/status/1".to_string(),
+            ..Default::default()
         };
 
         VerifiableCredential::create(
@@ -424,10 +477,7 @@ mod tests {
             additional_properties.properties.get("type").unwrap(),
             &JsonValue::String(STATUS_LIST_2021.to_string())
         );
-        assert!(additional_properties
-            .properties
-            .get("encodedList")
-            .is_some());
+        assert!(additional_properties.properties.contains_key("encodedList"));
     }
 
     #[test]
@@ -436,16 +486,10 @@ mod tests {
 
         let bitstring = StatusListCredential::bitstring_generation(bit_indices).unwrap();
 
-        assert_eq!(StatusListCredential::get_bit(&bitstring, 3).unwrap(), true);
-        assert_eq!(
-            StatusListCredential::get_bit(&bitstring, 1023).unwrap(),
-            true
-        );
-        assert_eq!(StatusListCredential::get_bit(&bitstring, 0).unwrap(), false);
-        assert_eq!(
-            StatusListCredential::get_bit(&bitstring, 1024).unwrap(),
-            false
-        );
+        assert!(StatusListCredential::get_bit(&bitstring, 3).unwrap());
+        assert!(StatusListCredential::get_bit(&bitstring, 1023).unwrap());
+        assert!(!StatusListCredential::get_bit(&bitstring, 0).unwrap());
+        assert!(!StatusListCredential::get_bit(&bitstring, 1024).unwrap());
 
         let result = StatusListCredential::get_bit(&bitstring, 16 * 1024 * 8 + 1);
         assert!(result.is_err());
@@ -503,6 +547,34 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_status() -> Result<()> {
+        let status_purpose = "revocation".to_string();
+        let credentials_to_disable = Some(vec![create_test_credential("3", &status_purpose).await]);
+        let status_list_credential =
+            StatusListCredential::create(issuer(), status_purpose.clone(), credentials_to_disable)
+                .await?;
+
+        let disabled_credential = create_test_credential("3", &status_purpose).await;
+        let status = status_list_credential
+            .get_status(disabled_credential.credential_status.as_ref().unwrap())?;
+        assert_eq!(
+            status,
+            CredentialStatusResult {
+                status_purpose: status_purpose.clone(),
+                status: 1,
+                message: None,
+            }
+        );
+
+        let enabled_credential = create_test_credential("4", &status_purpose).await;
+        let status = status_list_credential
+            .get_status(enabled_credential.credential_status.as_ref().unwrap())?;
+        assert_eq!(status.status, 0);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_full_flow() {
         let status_purpose = "revocation".to_string();
@@ -522,18 +594,9 @@ mod tests {
         .unwrap();
 
         // Test various bit positions
-        assert_eq!(
-            StatusListCredential::get_bit(encoded_list, 0).unwrap(),
-            false
-        );
-        assert_eq!(
-            StatusListCredential::get_bit(encoded_list, 100).unwrap(),
-            false
-        );
-        assert_eq!(
-            StatusListCredential::get_bit(encoded_list, 1000).unwrap(),
-            false
-        );
+        assert!(!StatusListCredential::get_bit(encoded_list, 0).unwrap());
+        assert!(!StatusListCredential::get_bit(encoded_list, 100).unwrap());
+        assert!(!StatusListCredential::get_bit(encoded_list, 1000).unwrap());
 
         let vc1_options = Some(VerifiableCredentialCreateOptions {
             credential_status: Some(CredentialStatus {
@@ -542,6 +605,7 @@ mod tests {
                 status_purpose: "revocation".to_string(),
                 status_list_index: "3".to_string(),
                 status_list_credential: "https://example.com/status/1".to_string(),
+                ..Default::default()
             }),
             ..Default::default()
         });
@@ -557,6 +621,7 @@ mod tests {
                 status_purpose: "revocation".to_string(),
                 status_list_index: "1023".to_string(),
                 status_list_credential: "https://example.com/status/1".to_string(),
+                ..Default::default()
             }),
             ..Default::default()
         });
@@ -585,27 +650,12 @@ mod tests {
         .unwrap();
 
         // Test the bits corresponding to the disabled credentials
-        assert_eq!(
-            StatusListCredential::get_bit(updated_encoded_list, 3).unwrap(),
-            true
-        );
-        assert_eq!(
-            StatusListCredential::get_bit(updated_encoded_list, 1023).unwrap(),
-            true
-        );
+        assert!(StatusListCredential::get_bit(updated_encoded_list, 3).unwrap());
+        assert!(StatusListCredential::get_bit(updated_encoded_list, 1023).unwrap());
 
         // Test other bits are still false
-        assert_eq!(
-            StatusListCredential::get_bit(updated_encoded_list, 0).unwrap(),
-            false
-        );
-        assert_eq!(
-            StatusListCredential::get_bit(updated_encoded_list, 100).unwrap(),
-            false
-        );
-        assert_eq!(
-            StatusListCredential::get_bit(updated_encoded_list, 1000).unwrap(),
-            false
-        );
+        assert!(!StatusListCredential::get_bit(updated_encoded_list, 0).unwrap());
+        assert!(!StatusListCredential::get_bit(updated_encoded_list, 100).unwrap());
+        assert!(!StatusListCredential::get_bit(updated_encoded_list, 1000).unwrap());
     }
 }
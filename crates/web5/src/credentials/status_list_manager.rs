@@ -0,0 +1,544 @@
+use super::bitstring_status_list::{
+    validate_status_message, BitstringStatusList, BitstringStatusListCredential,
+    BitstringStatusListCredentialCreateOptions, BITSTRING_STATUS_LIST_ENTRY, STATUS_PURPOSES,
+};
+use super::verifiable_credential_1_1::{CredentialStatus, StatusMessage};
+use crate::credentials::Issuer;
+use crate::dids::bearer_did::BearerDid;
+use crate::errors::{Result, Web5Error};
+use crate::json::{FromJson, ToJson};
+use rand::Rng;
+use serde::{ser::Error, Deserialize, Serialize, Serializer};
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime};
+
+/// Represents the options available when creating a Status List Manager.
+#[derive(Debug, Default, Clone)]
+pub struct StatusListManagerCreateOptions {
+    /// The number of entries of the list. The list is padded to at least 16KB, so it may hold
+    /// more entries than requested. If not provided, the list is the minimum size.
+    pub length: Option<usize>,
+
+    /// The number of bits of each status. If not provided, defaults to 1.
+    pub status_size: Option<u32>,
+
+    /// The message of each status value, required if `status_size` is greater than 1.
+    pub status_message: Option<Vec<StatusMessage>>,
+}
+
+/// Represents the options available when issuing a Bitstring Status List Credential from a
+/// Status List Manager.
+#[derive(Debug, Default, Clone)]
+pub struct StatusListManagerIssueOptions {
+    /// How long the credential is valid for from the time it's issued. If not provided, the
+    /// credential does not expire.
+    pub validity: Option<Duration>,
+
+    /// The time, in milliseconds, that verifiers may cache the status list for.
+    pub ttl: Option<u64>,
+
+    /// The id of the verification method to sign with. If not provided, the first assertion
+    /// method of the issuer is used.
+    pub verification_method_id: Option<String>,
+}
+
+/// Manages a [Bitstring Status List](https://www.w3.org/TR/vc-bitstring-status-list/) over the
+/// lifetime of the credentials it tracks.
+///
+/// The manager allocates a random unused index to each new credential, so the position of a
+/// credential in the list reveals nothing about when it was issued, and sets or clears the
+/// status at an index as credentials are revoked or reinstated. After each change, the issuer
+/// re-issues the status list credential for hosting at the `statusListCredential` URL.
+///
+/// The manager serializes to JSON, to persist it between changes.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "PersistedStatusList")]
+pub struct StatusListManager {
+    status_list_credential: String,
+    status_purpose: String,
+    status_message: Option<Vec<StatusMessage>>,
+    status_list: BitstringStatusList,
+    allocated_indexes: BTreeSet<usize>,
+}
+
+impl FromJson for StatusListManager {}
+impl ToJson for StatusListManager {}
+
+impl StatusListManager {
+    /// Creates a Status List Manager with all statuses cleared and no indexes allocated.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_list_credential` - The URL the status list credential is hosted at.
+    /// * `status_purpose` - The purpose of the status (e.g., "revocation" or "suspension").
+    /// * `options` - Optional parameters, such as the size of the list and of each status.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut status_list_manager = StatusListManager::new(
+    ///     "https://example.com/credentials/status/3".to_string(),
+    ///     "revocation".to_string(),
+    ///     None,
+    /// )
+    /// .unwrap();
+    ///
+    /// let credential_status = status_list_manager.allocate().unwrap();
+    /// let verifiable_credential = VerifiableCredential::create(
+    ///     issuer,
+    ///     credential_subject,
+    ///     Some(VerifiableCredentialCreateOptions {
+    ///         credential_status: Some(credential_status),
+    ///         ..Default::default()
+    ///     }),
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    pub fn new(
+        status_list_credential: String,
+        status_purpose: String,
+        options: Option<StatusListManagerCreateOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        if !STATUS_PURPOSES.contains(&status_purpose.as_str()) {
+            return Err(Web5Error::Parameter(format!(
+                "unsupported status purpose: {}",
+                status_purpose
+            )));
+        }
+
+        let status_size = options.status_size.unwrap_or(1);
+        validate_status_message(status_size, options.status_message.as_ref())?;
+
+        Ok(Self {
+            status_list_credential,
+            status_purpose,
+            status_message: options.status_message,
+            status_list: BitstringStatusList::new(options.length.unwrap_or(0), status_size)?,
+            allocated_indexes: BTreeSet::new(),
+        })
+    }
+
+    /// Allocates a random unused index of the list to a new credential.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CredentialStatus)` - The `BitstringStatusListEntry` to set as the credential's status.
+    /// * `Err` if every index of the list is allocated.
+    pub fn allocate(&mut self) -> Result<CredentialStatus> {
+        let length = self.status_list.length();
+        if self.allocated_indexes.len() >= length {
+            return Err(Web5Error::Parameter(format!(
+                "all {} indexes of the status list are allocated",
+                length
+            )));
+        }
+
+        let mut rng = rand::thread_rng();
+        let index = loop {
+            let index = rng.gen_range(0..length);
+            if !self.allocated_indexes.contains(&index) {
+                break index;
+            }
+        };
+        self.allocated_indexes.insert(index);
+
+        Ok(self.credential_status(index))
+    }
+
+    /// The status entry of an allocated index.
+    pub fn credential_status(&self, index: usize) -> CredentialStatus {
+        let status_size = self.status_list.status_size();
+        CredentialStatus {
+            id: format!("{}#{}", self.status_list_credential, index),
+            r#type: BITSTRING_STATUS_LIST_ENTRY.to_string(),
+            status_purpose: self.status_purpose.clone(),
+            status_list_index: index.to_string(),
+            status_list_credential: self.status_list_credential.clone(),
+            status_size: (status_size > 1).then_some(status_size),
+            status_message: self.status_message.clone(),
+        }
+    }
+
+    /// Gets the status at an allocated index.
+    pub fn get_status(&self, index: usize) -> Result<u64> {
+        self.check_allocated(index)?;
+        self.status_list.get(index)
+    }
+
+    /// Sets the status at an allocated index, such as 1 to revoke or suspend the credential.
+    pub fn set_status(&mut self, index: usize, status: u64) -> Result<()> {
+        self.check_allocated(index)?;
+        self.status_list.set(index, status)
+    }
+
+    /// Clears the status at an allocated index, such as to reinstate a suspended credential.
+    pub fn clear_status(&mut self, index: usize) -> Result<()> {
+        self.set_status(index, 0)
+    }
+
+    /// The `encodedList` of the current statuses.
+    pub fn encoded_list(&self) -> Result<String> {
+        self.status_list.encode()
+    }
+
+    /// Issues a Bitstring Status List Credential of the current statuses, with a new id and
+    /// validity, to host at the `statusListCredential` URL in place of the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The issuer of the status list, which signs the credential.
+    /// * `options` - Optional parameters, such as how long the credential is valid for.
+    ///
+    /// # Returns
+    ///
+    /// The signed credential, serialized as a compact JWS.
+    ///
+    /// # Example
+    /// ```ignore
+    /// status_list_manager.set_status(index, 1).unwrap();
+    /// let status_list_credential_jwt = status_list_manager
+    ///     .issue(
+    ///         &issuer_bearer_did,
+    ///         Some(StatusListManagerIssueOptions {
+    ///             validity: Some(Duration::from_secs(24 * 60 * 60)),
+    ///             ..Default::default()
+    ///         }),
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub async fn issue(
+        &self,
+        bearer_did: &BearerDid,
+        options: Option<StatusListManagerIssueOptions>,
+    ) -> Result<String> {
+        let options = options.unwrap_or_default();
+
+        let valid_from = SystemTime::now();
+        let status_list_credential = BitstringStatusListCredential::create(
+            Issuer::from(bearer_did.did.uri.clone()),
+            self.status_purpose.clone(),
+            &self.status_list,
+            Some(BitstringStatusListCredentialCreateOptions {
+                id: None,
+                ttl: options.ttl,
+                valid_from: Some(valid_from),
+                valid_until: options.validity.map(|validity| valid_from + validity),
+            }),
+        )
+        .await?;
+
+        status_list_credential
+            .base
+            .sign(bearer_did, options.verification_method_id)
+    }
+
+    fn check_allocated(&self, index: usize) -> Result<()> {
+        if !self.allocated_indexes.contains(&index) {
+            return Err(Web5Error::Parameter(format!(
+                "status list index {} is not allocated",
+                index
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedStatusList {
+    status_list_credential: String,
+    status_purpose: String,
+    status_size: u32,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    status_message: Option<Vec<StatusMessage>>,
+    encoded_list: String,
+    allocated_indexes: Vec<usize>,
+}
+
+impl Serialize for StatusListManager {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        PersistedStatusList {
+            status_list_credential: self.status_list_credential.clone(),
+            status_purpose: self.status_purpose.clone(),
+            status_size: self.status_list.status_size(),
+            status_message: self.status_message.clone(),
+            encoded_list: self.status_list.encode().map_err(S::Error::custom)?,
+            allocated_indexes: self.allocated_indexes.iter().copied().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl TryFrom<PersistedStatusList> for StatusListManager {
+    type Error = Web5Error;
+
+    fn try_from(persisted: PersistedStatusList) -> Result<Self> {
+        let status_list =
+            BitstringStatusList::decode(&persisted.encoded_list, persisted.status_size)?;
+        if let Some(index) = persisted
+            .allocated_indexes
+            .iter()
+            .find(|index| **index >= status_list.length())
+        {
+            return Err(Web5Error::Parameter(format!(
+                "allocated index {} is out of range for a list of {} entries",
+                index,
+                status_list.length()
+            )));
+        }
+
+        Ok(Self {
+            status_list_credential: persisted.status_list_credential,
+            status_purpose: persisted.status_purpose,
+            status_message: persisted.status_message,
+            status_list,
+            allocated_indexes: persisted.allocated_indexes.into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::VerifiableCredentialV2;
+    use crate::dids::methods::did_jwk::DidJwk;
+
+    const STATUS_LIST_CREDENTIAL_URL: &str = "https://example.com/credentials/status/3";
+
+    fn status_list_manager() -> StatusListManager {
+        StatusListManager::new(
+            STATUS_LIST_CREDENTIAL_URL.to_string(),
+            "revocation".to_string(),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn index(credential_status: &CredentialStatus) -> usize {
+        credential_status.status_list_index.parse().unwrap()
+    }
+
+    mod new {
+        use super::*;
+
+        #[test]
+        fn test_unsupported_status_purpose() {
+            let result = StatusListManager::new(
+                STATUS_LIST_CREDENTIAL_URL.to_string(),
+                "expiration".to_string(),
+                None,
+            );
+
+            match result {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "unsupported status purpose: expiration")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_status_message_required() {
+            let result = StatusListManager::new(
+                STATUS_LIST_CREDENTIAL_URL.to_string(),
+                "message".to_string(),
+                Some(StatusListManagerCreateOptions {
+                    status_size: Some(2),
+                    ..Default::default()
+                }),
+            );
+
+            match result {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "status message is required if status size is greater than 1"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod allocate {
+        use super::*;
+
+        #[test]
+        fn test_credential_status() {
+            let mut status_list_manager = status_list_manager();
+            let credential_status = status_list_manager.allocate().unwrap();
+
+            assert_eq!(
+                credential_status.id,
+                format!(
+                    "{}#{}",
+                    STATUS_LIST_CREDENTIAL_URL, credential_status.status_list_index
+                )
+            );
+            assert_eq!(credential_status.r#type, BITSTRING_STATUS_LIST_ENTRY);
+            assert_eq!(credential_status.status_purpose, "revocation");
+            assert_eq!(
+                credential_status.status_list_credential,
+                STATUS_LIST_CREDENTIAL_URL
+            );
+            assert_eq!(credential_status.status_size, None);
+            assert_eq!(
+                status_list_manager
+                    .get_status(index(&credential_status))
+                    .unwrap(),
+                0
+            );
+        }
+
+        #[test]
+        fn test_unique_indexes() {
+            let mut status_list_manager = status_list_manager();
+            let indexes = (0..1000)
+                .map(|_| index(&status_list_manager.allocate().unwrap()))
+                .collect::<BTreeSet<_>>();
+
+            assert_eq!(indexes.len(), 1000);
+            // random rather than sequential
+            assert_ne!(indexes, (0..1000).collect());
+        }
+
+        #[test]
+        fn test_full() {
+            let mut status_list_manager = status_list_manager();
+            status_list_manager.allocated_indexes = (0..status_list_manager.status_list.length())
+                .skip(1)
+                .collect();
+
+            let credential_status = status_list_manager.allocate().unwrap();
+            assert_eq!(index(&credential_status), 0);
+
+            match status_list_manager.allocate() {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "all 131072 indexes of the status list are allocated"
+                ),
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod set_status {
+        use super::*;
+
+        #[test]
+        fn test_revoke_and_reinstate() {
+            let mut status_list_manager = status_list_manager();
+            let index = index(&status_list_manager.allocate().unwrap());
+
+            status_list_manager.set_status(index, 1).unwrap();
+            assert_eq!(status_list_manager.get_status(index).unwrap(), 1);
+
+            let status_list =
+                BitstringStatusList::decode(&status_list_manager.encoded_list().unwrap(), 1)
+                    .unwrap();
+            assert_eq!(status_list.get(index).unwrap(), 1);
+
+            status_list_manager.clear_status(index).unwrap();
+            assert_eq!(status_list_manager.get_status(index).unwrap(), 0);
+        }
+
+        #[test]
+        fn test_unallocated_index() {
+            let mut status_list_manager = status_list_manager();
+
+            match status_list_manager.set_status(5, 1) {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "status list index 5 is not allocated")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod issue {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_signed_status_list_credential() {
+            let issuer = DidJwk::create(None).unwrap();
+            let mut status_list_manager = status_list_manager();
+            let credential_status = status_list_manager.allocate().unwrap();
+            status_list_manager
+                .set_status(index(&credential_status), 1)
+                .unwrap();
+
+            let options = Some(StatusListManagerIssueOptions {
+                validity: Some(Duration::from_secs(24 * 60 * 60)),
+                ttl: Some(300_000),
+                ..Default::default()
+            });
+            let first = status_list_manager
+                .issue(&issuer, options.clone())
+                .await
+                .unwrap();
+            let second = status_list_manager.issue(&issuer, options).await.unwrap();
+
+            let first = VerifiableCredentialV2::from_vc_jwt(&first, true)
+                .await
+                .unwrap();
+            let second = VerifiableCredentialV2::from_vc_jwt(&second, true)
+                .await
+                .unwrap();
+            assert_ne!(first.id, second.id);
+            assert_eq!(
+                first.valid_until.unwrap(),
+                first.valid_from.unwrap() + Duration::from_secs(24 * 60 * 60)
+            );
+
+            let status_list_credential = BitstringStatusListCredential::from_base(first).unwrap();
+            assert_eq!(
+                status_list_credential.base.issuer.to_string(),
+                issuer.did.uri
+            );
+            assert_eq!(status_list_credential.ttl(), Some(300_000));
+            let status = status_list_credential
+                .get_status(&credential_status)
+                .unwrap();
+            assert_eq!(status.status, 1);
+        }
+    }
+
+    mod to_json_string {
+        use super::*;
+
+        #[test]
+        fn test_round_trip() {
+            let mut status_list_manager = StatusListManager::new(
+                STATUS_LIST_CREDENTIAL_URL.to_string(),
+                "message".to_string(),
+                Some(StatusListManagerCreateOptions {
+                    length: Some(200_000),
+                    status_size: Some(1),
+                    status_message: Some(vec![StatusMessage {
+                        status: "0x1".to_string(),
+                        message: "withdrawn".to_string(),
+                    }]),
+                }),
+            )
+            .unwrap();
+            let index = index(&status_list_manager.allocate().unwrap());
+            status_list_manager.set_status(index, 1).unwrap();
+            status_list_manager.allocate().unwrap();
+
+            let json = status_list_manager.to_json_string().unwrap();
+            let restored = StatusListManager::from_json_string(&json).unwrap();
+            assert_eq!(restored, status_list_manager);
+            assert_eq!(restored.get_status(index).unwrap(), 1);
+        }
+
+        #[test]
+        fn test_allocated_index_out_of_range() {
+            let mut json = serde_json::to_value(status_list_manager()).unwrap();
+            json["allocatedIndexes"] = serde_json::json!([131072]);
+
+            let result = StatusListManager::from_json_string(&json.to_string());
+            assert!(result.is_err());
+        }
+    }
+}
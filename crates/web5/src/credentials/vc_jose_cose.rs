@@ -0,0 +1,285 @@
+use crate::{
+    dids::{bearer_did::BearerDid, bearer_did::SignerOptions, controller::signs_as_controller},
+    errors::{Result, Web5Error},
+    jose::{JoseHeader, Jws},
+};
+use serde::Serialize;
+
+/// The `typ` of a Verifiable Credential secured per [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/).
+pub const VC_JWT_MEDIA_TYPE: &str = "vc+jwt";
+/// The `typ` of a Verifiable Presentation secured per [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/).
+pub const VP_JWT_MEDIA_TYPE: &str = "vp+jwt";
+/// The `cty` of a Verifiable Credential secured per VC-JOSE-COSE.
+pub const VC_CONTENT_TYPE: &str = "vc";
+/// The `cty` of a Verifiable Presentation secured per VC-JOSE-COSE.
+pub const VP_CONTENT_TYPE: &str = "vp";
+
+/// How a Verifiable Credential or Presentation is secured as a JWT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JwtSecuringMechanism {
+    /// [VC-JWT](https://www.w3.org/TR/vc-data-model/#json-web-token) as in the 1.1 data model:
+    /// the credential is wrapped in a `vc` (or `vp`) claim, with `typ: JWT`.
+    VcJwt,
+    /// [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/): the credential itself is the JWS
+    /// payload, with `typ: vc+jwt` (or `vp+jwt`) and `cty: vc` (or `vp`).
+    VcJoseCose,
+}
+
+impl JwtSecuringMechanism {
+    /// Detects which mechanism secures a token, from its `typ` header, or, if the `typ` is
+    /// missing or generic, from whether the payload is a credential rather than a claims set.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The protected header of the JWS.
+    /// * `payload` - The decoded payload of the JWS.
+    pub fn detect(header: &JoseHeader, payload: &[u8]) -> Self {
+        match header.typ.as_deref().map(media_type) {
+            Some(VC_JWT_MEDIA_TYPE) | Some(VP_JWT_MEDIA_TYPE) => Self::VcJoseCose,
+            _ => match serde_json::from_slice::<serde_json::Value>(payload) {
+                Ok(payload) if payload.get("@context").is_some() => Self::VcJoseCose,
+                _ => Self::VcJwt,
+            },
+        }
+    }
+}
+
+/// Strips the optional `application/` prefix of a media type.
+fn media_type(typ: &str) -> &str {
+    typ.strip_prefix("application/").unwrap_or(typ)
+}
+
+/// Returns an error if a token's `typ` is a VC-JOSE-COSE media type other than the expected one,
+/// such as a presentation passed where a credential is expected.
+pub(crate) fn check_media_type(header: &JoseHeader, expected: &str) -> Result<()> {
+    match header.typ.as_deref().map(media_type) {
+        Some(typ @ (VC_JWT_MEDIA_TYPE | VP_JWT_MEDIA_TYPE)) if typ != expected => Err(
+            Web5Error::Parameter(format!("expected typ {} but found {}", expected, typ)),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Signs a credential or presentation as the payload of a JWS per VC-JOSE-COSE.
+///
+/// # Arguments
+///
+/// * `document` - The credential or presentation to secure.
+/// * `signer_did_uri` - The issuer of the credential, or holder of the presentation.
+/// * `media_type` - Either [`VC_JWT_MEDIA_TYPE`] or [`VP_JWT_MEDIA_TYPE`].
+/// * `content_type` - Either [`VC_CONTENT_TYPE`] or [`VP_CONTENT_TYPE`].
+pub(crate) fn sign_enveloped<T: Serialize>(
+    document: &T,
+    signer_did_uri: &str,
+    media_type: &str,
+    content_type: &str,
+    bearer_did: &BearerDid,
+    options: &SignerOptions,
+) -> Result<String> {
+    if !signer_did_uri.starts_with(&bearer_did.did.uri) && !signs_as_controller(options) {
+        return Err(Web5Error::Parameter(format!(
+            "Bearer DID URI {} does not match {}",
+            bearer_did.did.uri, signer_did_uri
+        )));
+    }
+
+    let payload = serde_json::to_vec(document)?;
+    let header = JoseHeader {
+        typ: Some(media_type.to_string()),
+        alg: None,
+        kid: None,
+        cty: Some(content_type.to_string()),
+        additional_properties: None,
+    };
+
+    let jws = Jws::from_payload_with_header(&payload, header, bearer_did, options)?;
+    Ok(jws.compact_jws)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        credentials::{
+            CredentialSubject, Issuer, VerifiableCredential, VerifiableCredentialV2,
+            VerifiablePresentation, VersionedVerifiableCredential,
+        },
+        dids::methods::did_jwk::DidJwk,
+    };
+    use base64::Engine;
+
+    const SUBJECT_DID_URI: &str = "did:dht:qgmmpyjw5hwnqfgzn7wmrm33ady8gb8z9ideib6m9gj4ys6wny8y";
+
+    fn header(compact_jws: &str) -> JoseHeader {
+        let header_part = compact_jws.split('.').next().unwrap();
+        let header = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(header_part)
+            .unwrap();
+        serde_json::from_slice(&header).unwrap()
+    }
+
+    async fn credential(issuer: &BearerDid) -> VerifiableCredentialV2 {
+        VerifiableCredentialV2::create(
+            Issuer::from(issuer.did.uri.clone()),
+            vec![CredentialSubject::from(SUBJECT_DID_URI)],
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    mod detect {
+        use super::*;
+
+        fn jose_header(typ: Option<&str>) -> JoseHeader {
+            JoseHeader {
+                typ: typ.map(String::from),
+                alg: None,
+                kid: None,
+                cty: None,
+                additional_properties: None,
+            }
+        }
+
+        #[test]
+        fn test_media_types() {
+            for typ in ["vc+jwt", "vp+jwt", "application/vc+jwt"] {
+                assert_eq!(
+                    JwtSecuringMechanism::detect(&jose_header(Some(typ)), b"{}"),
+                    JwtSecuringMechanism::VcJoseCose
+                );
+            }
+        }
+
+        #[test]
+        fn test_payload() {
+            assert_eq!(
+                JwtSecuringMechanism::detect(&jose_header(Some("JWT")), br#"{"vc":{}}"#),
+                JwtSecuringMechanism::VcJwt
+            );
+            assert_eq!(
+                JwtSecuringMechanism::detect(&jose_header(None), br#"{"@context":[]}"#),
+                JwtSecuringMechanism::VcJoseCose
+            );
+        }
+    }
+
+    mod credential {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_sign_and_verify() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer).await;
+
+            let vc_jwt = vc.sign_vc_jose(&issuer, &SignerOptions::default()).unwrap();
+            let header = header(&vc_jwt);
+            assert_eq!(header.typ.as_deref(), Some(VC_JWT_MEDIA_TYPE));
+            assert_eq!(header.cty.as_deref(), Some(VC_CONTENT_TYPE));
+
+            let jws = Jws::from_compact_jws(&vc_jwt, true).await.unwrap();
+            assert_eq!(jws.payload, serde_json::to_vec(&vc).unwrap());
+
+            let decoded = VerifiableCredentialV2::from_vc_jwt(&vc_jwt, true)
+                .await
+                .unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&vc).unwrap()
+            );
+
+            let versioned = VersionedVerifiableCredential::from_vc_jwt(&vc_jwt, true)
+                .await
+                .unwrap();
+            assert!(matches!(versioned, VersionedVerifiableCredential::V2_0(_)));
+        }
+
+        #[tokio::test]
+        async fn test_verifies_issuer() {
+            let issuer = DidJwk::create(None).unwrap();
+            let stranger = DidJwk::create(None).unwrap();
+            let vc = credential(&issuer).await;
+
+            let result = vc.sign_vc_jose(&stranger, &SignerOptions::default());
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+
+            let mut forged = vc.clone();
+            forged.issuer = Issuer::from(issuer.did.uri.clone());
+            let vc_jwt = sign_enveloped(
+                &forged,
+                &stranger.did.uri,
+                VC_JWT_MEDIA_TYPE,
+                VC_CONTENT_TYPE,
+                &stranger,
+                &SignerOptions::default(),
+            )
+            .unwrap();
+            let result = VerifiableCredentialV2::from_vc_jwt(&vc_jwt, true).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    crate::credentials::VerificationError::UnauthorizedSigner(_)
+                ))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_rejects_presentation_media_type() {
+            let holder = DidJwk::create(None).unwrap();
+            let vp = VerifiablePresentation::create(holder.did.uri.clone(), vec![], None)
+                .await
+                .unwrap();
+            let vp_jwt = vp.sign_vp_jose(&holder, &SignerOptions::default()).unwrap();
+
+            let result = VerifiableCredentialV2::from_vc_jwt(&vp_jwt, false).await;
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[tokio::test]
+        async fn test_vc_jwt_still_supported() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredential::create(
+                Issuer::from(issuer.did.uri.clone()),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                None,
+            )
+            .await
+            .unwrap();
+            let vc_jwt = vc.sign(&issuer, None).unwrap();
+            assert_eq!(header(&vc_jwt).typ.as_deref(), Some("JWT"));
+
+            let decoded = VerifiableCredential::from_vc_jwt(&vc_jwt, true)
+                .await
+                .unwrap();
+            assert_eq!(decoded.id, vc.id);
+        }
+    }
+
+    mod presentation {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_sign_and_verify() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let vc_jwt = credential(&issuer)
+                .await
+                .sign_vc_jose(&issuer, &SignerOptions::default())
+                .unwrap();
+
+            let vp = VerifiablePresentation::create(holder.did.uri.clone(), vec![vc_jwt], None)
+                .await
+                .unwrap();
+            let vp_jwt = vp.sign_vp_jose(&holder, &SignerOptions::default()).unwrap();
+            let header = header(&vp_jwt);
+            assert_eq!(header.typ.as_deref(), Some(VP_JWT_MEDIA_TYPE));
+            assert_eq!(header.cty.as_deref(), Some(VP_CONTENT_TYPE));
+
+            let decoded = VerifiablePresentation::from_vp_jwt(&vp_jwt, true)
+                .await
+                .unwrap();
+            assert_eq!(decoded.id, vp.id);
+            assert_eq!(decoded.verifiable_credential, vp.verifiable_credential);
+        }
+    }
+}
@@ -1,19 +1,29 @@
 use super::credential_schema::validate_credential_schema;
 use super::credential_schema::CredentialSchema;
+use super::data_integrity::{
+    create_proof, verify_proofs, with_data_integrity_context, DataIntegrityProof,
+    DataIntegrityProofOptions, DataIntegrityVerifyOptions,
+};
 use super::data_model_validation::validate_vc_data_model;
 use super::decode::decode;
+use super::sd_jwt_vc::{decode_sd_jwt_vc, sign_sd_jwt_vc};
+use super::verifiable_credential_2_0::{DataModelVersion, VersionedVerifiableCredential};
+use super::verification_policy::{VerificationPolicy, VerificationReport};
 use super::CredentialSubject;
 use super::Issuer;
 use crate::datetime::{
     deserialize_optional_rfc3339, deserialize_rfc3339, serialize_optional_rfc3339,
     serialize_rfc3339,
 };
-use crate::dids::bearer_did::BearerDid;
-use crate::errors::Result;
+use crate::dids::bearer_did::{BearerDid, SignerOptions};
+use crate::dids::data_model::document::VerificationRelationship;
+use crate::errors::{Result, Web5Error};
 use crate::json::JsonObject;
 use crate::json::{FromJson, ToJson};
+use crate::sd_jwt::{SdJwtCreateOptions, SdJwtVerifyOptions};
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::time::SystemTime;
 
 pub const BASE_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
@@ -71,6 +81,10 @@ pub struct VerifiableCredential {
     /// An array of evidence supporting the claims made in the credential.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub evidence: Option<Vec<JsonObject>>,
+
+    /// An embedded [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proof securing the credential, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<DataIntegrityProof>,
 }
 
 impl FromJson for VerifiableCredential {}
@@ -133,6 +147,33 @@ pub struct CredentialStatus {
     /// The unique identifier for the Verifiable Credential that lists the status of the credential.
     #[serde(rename = "statusListCredential")]
     pub status_list_credential: String,
+
+    /// The number of bits of the credential's status in a Bitstring Status List. Defaults to 1.
+    #[serde(
+        rename = "statusSize",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub status_size: Option<u32>,
+
+    /// The messages of the credential's possible status values in a Bitstring Status List,
+    /// required if `status_size` is greater than 1.
+    #[serde(
+        rename = "statusMessage",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub status_message: Option<Vec<StatusMessage>>,
+}
+
+/// A message describing a status value of a Bitstring Status List entry.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct StatusMessage {
+    /// The status value as a hexadecimal string, such as `0x2`.
+    pub status: String,
+
+    /// The message describing the status value.
+    pub message: String,
 }
 
 impl VerifiableCredential {
@@ -180,16 +221,59 @@ impl VerifiableCredential {
     ///     VerifiableCredential::from_vc_jwt(vc_jwt, true).unwrap();
     /// ```
     pub async fn from_vc_jwt(vc_jwt: &str, verify: bool) -> Result<Self> {
-        let verifiable_credential = decode(vc_jwt, verify).await?;
-
-        if verify {
-            validate_vc_data_model(&verifiable_credential)?;
-            validate_credential_schema(&verifiable_credential).await?;
+        if !verify {
+            return decode(vc_jwt, false).await;
         }
 
+        let (verifiable_credential, _) =
+            Self::from_vc_jwt_with_policy(vc_jwt, &VerificationPolicy::default()).await?;
         Ok(verifiable_credential)
     }
 
+    /// Constructs and verifies a Verifiable Credential from a VC JWT, as [`Self::from_vc_jwt`]
+    /// does, against the requirements of a verification policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwt` - The Verifiable Credential in JWT format, serialized as a compact JWS.
+    /// * `policy` - The requirements, such as the trusted issuers and whether to check the
+    ///   credential isn't revoked.
+    ///
+    /// # Returns
+    ///
+    /// The credential, and a report of the checks made in verifying it.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (verifiable_credential, report) = VerifiableCredential::from_vc_jwt_with_policy(
+    ///     vc_jwt,
+    ///     &VerificationPolicy {
+    ///         leeway: Some(Duration::from_secs(60)),
+    ///         allowed_issuer_did_methods: Some(vec!["dht".to_string()]),
+    ///         check_status: true,
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    pub async fn from_vc_jwt_with_policy(
+        vc_jwt: &str,
+        policy: &VerificationPolicy,
+    ) -> Result<(Self, VerificationReport)> {
+        match policy
+            .verify_vc_jwt_fail_fast(vc_jwt, Some(DataModelVersion::V1_1))
+            .await?
+        {
+            (VersionedVerifiableCredential::V1_1(verifiable_credential), report) => {
+                Ok((verifiable_credential, report))
+            }
+            (VersionedVerifiableCredential::V2_0(_), _) => Err(Web5Error::Parameter(
+                "expected a data model 1.1 credential".to_string(),
+            )),
+        }
+    }
+
     /// Signs the Verifiable Credential using the specified Bearer DID and optional verification method.
     ///
     /// # Arguments
@@ -226,6 +310,208 @@ impl VerifiableCredential {
     ) -> Result<String> {
         super::sign::sign_with_did(self, bearer_did, verification_method_id)
     }
+
+    /// Signs the Verifiable Credential using the specified Bearer DID, selecting the
+    /// verification method by relationship, algorithm preference and/or key id.
+    ///
+    /// A controller of the issuer may sign by selecting one of its `capabilityInvocation` or
+    /// `capabilityDelegation` verification methods. Verifiers accept the signature if the
+    /// issuer's DID document lists the signer as a controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the credential.
+    /// * `options` - How to select the verification method to sign with.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the signed JWT, serialized as a compact JWS, of the Verifiable Credential.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let vc_jwt = verifiable_credential
+    ///     .sign_with_options(
+    ///         &issuer_bearer_did,
+    ///         &SignerOptions {
+    ///             verification_relationship: Some(VerificationRelationship::AssertionMethod),
+    ///             algorithms: Some(vec!["Ed25519".to_string()]),
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn sign_with_options(
+        &self,
+        bearer_did: &BearerDid,
+        options: &SignerOptions,
+    ) -> Result<String> {
+        super::sign::sign_with_did_options(self, bearer_did, options)
+    }
+
+    /// Adds a [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proof to the Verifiable
+    /// Credential, signed by an `assertionMethod` verification method of the issuer
+    /// with the `eddsa-jcs-2022` or `ecdsa-jcs-2019` cryptosuite. This secures the credential as
+    /// JSON, as an alternative to securing it as a JWT.
+    ///
+    /// The Data Integrity context is appended to the credential's contexts, as the 1.1 base
+    /// context doesn't define the proof's terms.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the proof.
+    /// * `options` - Optional parameters for the proof, such as the cryptosuite or verification method.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the credential with its `proof` set, replacing any existing proof.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let secured = verifiable_credential
+    ///     .add_data_integrity_proof(&issuer_bearer_did, None)
+    ///     .unwrap();
+    /// let json = serde_json::to_string(&secured).unwrap();
+    /// ```
+    pub fn add_data_integrity_proof(
+        &self,
+        bearer_did: &BearerDid,
+        options: Option<DataIntegrityProofOptions>,
+    ) -> Result<Self> {
+        let mut verifiable_credential = self.clone();
+        verifiable_credential.context =
+            with_data_integrity_context(verifiable_credential.context, BASE_CONTEXT);
+        let proof = create_proof(
+            &verifiable_credential,
+            &self.issuer.to_string(),
+            VerificationRelationship::AssertionMethod,
+            bearer_did,
+            options.unwrap_or_default(),
+        )?;
+        verifiable_credential.proof = Some(proof);
+        Ok(verifiable_credential)
+    }
+
+    /// Verifies the Data Integrity proof of the Verifiable Credential, resolving the
+    /// issuer's DID document to find the verification method.
+    ///
+    /// Use [`Self::from_secured_json`] to verify a credential received as JSON, as the proof covers
+    /// the JSON exactly as signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The challenge and domain the proof must carry, if any.
+    pub async fn verify_data_integrity_proof(
+        &self,
+        options: Option<DataIntegrityVerifyOptions>,
+    ) -> Result<()> {
+        verify_proofs(
+            &serde_json::to_value(self)?,
+            &self.issuer.to_string(),
+            VerificationRelationship::AssertionMethod,
+            &options.unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Constructs a Verifiable Credential secured with a Data Integrity proof from its JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The Verifiable Credential, including its `proof`, serialized as JSON.
+    /// * `verify` - If true, verifies the proof against the JSON as received, and validates the VC Data Model and the JSON Schema if present.
+    /// * `options` - The challenge and domain the proof must carry, if any.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let verifiable_credential = VerifiableCredential::from_secured_json(&json, true, None).await.unwrap();
+    /// ```
+    pub async fn from_secured_json(
+        json: &str,
+        verify: bool,
+        options: Option<DataIntegrityVerifyOptions>,
+    ) -> Result<Self> {
+        let secured_document: Value = serde_json::from_str(json)?;
+        let verifiable_credential: Self = serde_json::from_value(secured_document.clone())?;
+
+        if verify {
+            verify_proofs(
+                &secured_document,
+                &verifiable_credential.issuer.to_string(),
+                VerificationRelationship::AssertionMethod,
+                &options.unwrap_or_default(),
+            )
+            .await?;
+            validate_vc_data_model(&verifiable_credential)?;
+            validate_credential_schema(&verifiable_credential).await?;
+        }
+
+        Ok(verifiable_credential)
+    }
+
+    /// Issues the Verifiable Credential as an [SD-JWT VC](https://datatracker.ietf.org/doc/draft-ietf-oauth-sd-jwt-vc/)
+    /// with `typ: dc+sd-jwt`, so the holder can reveal only some of the subject's claims.
+    ///
+    /// The subject's claims become the top-level claims of the SD-JWT and its most specific type
+    /// becomes the `vct` claim. The credential's status, schema and evidence are not carried.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the credential.
+    /// * `options` - The selectively disclosable claims, as JSON Pointers into the credential
+    ///   subject, and optionally decoy digests and the holder's key to bind to.
+    ///
+    /// # Returns
+    ///
+    /// The SD-JWT VC, serialized with all of its disclosures.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let sd_jwt_vc = verifiable_credential
+    ///     .sign_sd_jwt_vc(
+    ///         &issuer_bearer_did,
+    ///         SdJwtCreateOptions {
+    ///             disclosable_claims: vec!["/dateOfBirth".to_string(), "/address/country".to_string()],
+    ///             holder_jwk: Some(holder_bearer_did.document.verification_method[0].public_key_jwk.clone()),
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn sign_sd_jwt_vc(
+        &self,
+        bearer_did: &BearerDid,
+        options: SdJwtCreateOptions,
+    ) -> Result<String> {
+        sign_sd_jwt_vc(self, bearer_did, options)
+    }
+
+    /// Constructs a Verifiable Credential from a presented SD-JWT VC, with only the disclosed
+    /// claims in its credential subject. The issuer's signature and the disclosures are always
+    /// verified, and the Key Binding JWT per `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sd_jwt_vc` - The SD-JWT VC, with the disclosures the holder selected.
+    /// * `options` - Whether a Key Binding JWT is required, and its expected audience and nonce.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let verifiable_credential = VerifiableCredential::from_sd_jwt_vc(
+    ///     &presentation,
+    ///     &SdJwtVerifyOptions {
+    ///         require_key_binding: true,
+    ///         audience: Some("https://verifier.example.com".to_string()),
+    ///         nonce: Some(nonce),
+    ///     },
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    pub async fn from_sd_jwt_vc(sd_jwt_vc: &str, options: &SdJwtVerifyOptions) -> Result<Self> {
+        let verifiable_credential = decode_sd_jwt_vc(sd_jwt_vc, options).await?;
+        validate_vc_data_model(&verifiable_credential)?;
+        Ok(verifiable_credential)
+    }
 }
 
 #[cfg(test)]
@@ -411,7 +697,7 @@ mod tests {
         async fn test_decode_issuer_string() {
             let vc_jwt_with_issuer_as_string = r#"eyJ0eXAiOiJKV1QiLCJhbGciOiJFZERTQSIsImtpZCI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSnlkMmhYU1VOWWNsSjNiMFphUm1SMU0wbHNOaTFCTkdVdGRqazNRbE14UmtaUmFWRTRhV05tV2t0ckluMCMwIn0.eyJ2YyI6eyJAY29udGV4dCI6WyJodHRwczovL3d3dy53My5vcmcvMjAxOC9jcmVkZW50aWFscy92MSJdLCJpZCI6InVybjp1dWlkOjc0NTY5ZmIzLWMyZTktNGZiMy1hOThkLWY3NGFjNzVjYTg5NSIsInR5cGUiOlsiVmVyaWZpYWJsZUNyZWRlbnRpYWwiXSwiaXNzdWVyIjoiZGlkOmp3azpleUpoYkdjaU9pSkZaREkxTlRFNUlpd2lhM1I1SWpvaVQwdFFJaXdpWTNKMklqb2lSV1F5TlRVeE9TSXNJbmdpT2lKeWQyaFhTVU5ZY2xKM2IwWmFSbVIxTTBsc05pMUJOR1V0ZGprM1FsTXhSa1pSYVZFNGFXTm1Xa3RySW4wIiwiaXNzdWFuY2VEYXRlIjoiMjAyNC0wOC0yOFQxNjozNjoyOS4zNDc4ODArMDA6MDAiLCJleHBpcmF0aW9uRGF0ZSI6bnVsbCwiY3JlZGVudGlhbFN1YmplY3QiOnsiaWQiOiJkaWQ6ZGh0OnFnbW1weWp3NWh3bnFmZ3puN3dtcm0zM2FkeThnYjh6OWlkZWliNm05Z2o0eXM2d255OHkifX0sImlzcyI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSnlkMmhYU1VOWWNsSjNiMFphUm1SMU0wbHNOaTFCTkdVdGRqazNRbE14UmtaUmFWRTRhV05tV2t0ckluMCIsImp0aSI6InVybjp1dWlkOjc0NTY5ZmIzLWMyZTktNGZiMy1hOThkLWY3NGFjNzVjYTg5NSIsInN1YiI6ImRpZDpkaHQ6cWdtbXB5anc1aHducWZnem43d21ybTMzYWR5OGdiOHo5aWRlaWI2bTlnajR5czZ3bnk4eSIsIm5iZiI6MTcyNDg2Mjk4OSwiaWF0IjoxNzI0ODYyOTg5fQ.0DSZ2XbPtjtrtxNKo3tImoByb1-jlQxZQN11lsngaFSe4lhy4mYmaxGAby4wIl-c_cLEkgBULfF3Qa_dlNSTCw"#;
 
-            let vc = VerifiableCredential::from_vc_jwt(&vc_jwt_with_issuer_as_string, false)
+            let vc = VerifiableCredential::from_vc_jwt(vc_jwt_with_issuer_as_string, false)
                 .await
                 .expect("should be valid vc jwt");
             match vc.issuer {
@@ -429,7 +715,7 @@ mod tests {
         async fn test_decode_issuer_object() {
             let vc_jwt_with_issuer_object = r#"eyJ0eXAiOiJKV1QiLCJhbGciOiJFZERTQSIsImtpZCI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSTFVazF5YVVNMVZsaHVielpTVkRoTVdWVnJibnBKWm5OamFUUXlZbXhCYVdsTFdrcENaR2huVm5WQkluMCMwIn0.eyJ2YyI6eyJAY29udGV4dCI6WyJodHRwczovL3d3dy53My5vcmcvMjAxOC9jcmVkZW50aWFscy92MSJdLCJpZCI6InVybjp1dWlkOjcwNWM0MTZiLTU1ODYtNDUzMS1hMmRmLWI3YzdhNTMxMGY5NiIsInR5cGUiOlsiVmVyaWZpYWJsZUNyZWRlbnRpYWwiXSwiaXNzdWVyIjp7ImlkIjoiZGlkOmp3azpleUpoYkdjaU9pSkZaREkxTlRFNUlpd2lhM1I1SWpvaVQwdFFJaXdpWTNKMklqb2lSV1F5TlRVeE9TSXNJbmdpT2lJMVVrMXlhVU0xVmxodWJ6WlNWRGhNV1ZWcmJucEpabk5qYVRReVlteEJhV2xMV2twQ1pHaG5WblZCSW4wIiwibmFtZSI6InNvbWUgbmFtZSJ9LCJpc3N1YW5jZURhdGUiOiIyMDI0LTA4LTI4VDE2OjQwOjExLjUwNDIyMCswMDowMCIsImV4cGlyYXRpb25EYXRlIjpudWxsLCJjcmVkZW50aWFsU3ViamVjdCI6eyJpZCI6ImRpZDpkaHQ6cWdtbXB5anc1aHducWZnem43d21ybTMzYWR5OGdiOHo5aWRlaWI2bTlnajR5czZ3bnk4eSJ9fSwiaXNzIjoiZGlkOmp3azpleUpoYkdjaU9pSkZaREkxTlRFNUlpd2lhM1I1SWpvaVQwdFFJaXdpWTNKMklqb2lSV1F5TlRVeE9TSXNJbmdpT2lJMVVrMXlhVU0xVmxodWJ6WlNWRGhNV1ZWcmJucEpabk5qYVRReVlteEJhV2xMV2twQ1pHaG5WblZCSW4wIiwianRpIjoidXJuOnV1aWQ6NzA1YzQxNmItNTU4Ni00NTMxLWEyZGYtYjdjN2E1MzEwZjk2Iiwic3ViIjoiZGlkOmRodDpxZ21tcHlqdzVod25xZmd6bjd3bXJtMzNhZHk4Z2I4ejlpZGVpYjZtOWdqNHlzNndueTh5IiwibmJmIjoxNzI0ODYzMjExLCJpYXQiOjE3MjQ4NjMyMTF9.Mv-wlUcnj0w-OWuoMBCciaQXrAogXL3qqgZnthTRI9f55S5PidYiSapWFxFqc4SzxTVSpe64H2vF7kfGU-QpBw"#;
 
-            let vc = VerifiableCredential::from_vc_jwt(&vc_jwt_with_issuer_object, false)
+            let vc = VerifiableCredential::from_vc_jwt(vc_jwt_with_issuer_object, false)
                 .await
                 .expect("should be valid vc jwt");
             match vc.issuer {
@@ -447,7 +733,7 @@ mod tests {
         #[tokio::test]
         async fn test_decode_evidence() {
             let vc_jwt_with_evidence = r#"eyJ0eXAiOiJKV1QiLCJhbGciOiJFZERTQSIsImtpZCI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSmljbll0T1VKSGRUVlhNVFV4VkVKVk9GY3hVVkozU1dwSWRXVmlVVGc1TlRCQ2VuRTFjR1ZxV25wSkluMCMwIn0.eyJ2YyI6eyJAY29udGV4dCI6WyJodHRwczovL3d3dy53My5vcmcvMjAxOC9jcmVkZW50aWFscy92MSJdLCJpZCI6InVybjp1dWlkOjkxYWM1NzBmLTRjMDMtNDIxZi1iZGY4LWQ3Y2YyNzQ1YzVmNSIsInR5cGUiOlsiVmVyaWZpYWJsZUNyZWRlbnRpYWwiXSwiaXNzdWVyIjoiZGlkOmp3azpleUpoYkdjaU9pSkZaREkxTlRFNUlpd2lhM1I1SWpvaVQwdFFJaXdpWTNKMklqb2lSV1F5TlRVeE9TSXNJbmdpT2lKaWNuWXRPVUpIZFRWWE1UVXhWRUpWT0ZjeFVWSjNTV3BJZFdWaVVUZzVOVEJDZW5FMWNHVnFXbnBKSW4wIiwiaXNzdWFuY2VEYXRlIjoiMjAyNC0wOC0zMFQxMDowMToyNC4yNTgzNjYrMDA6MDAiLCJleHBpcmF0aW9uRGF0ZSI6bnVsbCwiY3JlZGVudGlhbFN1YmplY3QiOnsiaWQiOiJkaWQ6ZGh0OnFnbW1weWp3NWh3bnFmZ3puN3dtcm0zM2FkeThnYjh6OWlkZWliNm05Z2o0eXM2d255OHkifSwiZXZpZGVuY2UiOlt7IkEgS2V5IjoiQSBWYWx1ZSJ9XX0sImlzcyI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSmljbll0T1VKSGRUVlhNVFV4VkVKVk9GY3hVVkozU1dwSWRXVmlVVGc1TlRCQ2VuRTFjR1ZxV25wSkluMCIsImp0aSI6InVybjp1dWlkOjkxYWM1NzBmLTRjMDMtNDIxZi1iZGY4LWQ3Y2YyNzQ1YzVmNSIsInN1YiI6ImRpZDpkaHQ6cWdtbXB5anc1aHducWZnem43d21ybTMzYWR5OGdiOHo5aWRlaWI2bTlnajR5czZ3bnk4eSIsIm5iZiI6MTcyNTAxMjA4NCwiaWF0IjoxNzI1MDEyMDg0fQ.M7t4Ox08v-rC-naPSfIqlE1KKhZ1nrx_QA2HbuW38AkgxnSZOYEpXEG1UTAzh6mdwKZin9jwoGrj29u24K1ABA"#;
-            let vc = VerifiableCredential::from_vc_jwt(&vc_jwt_with_evidence, false)
+            let vc = VerifiableCredential::from_vc_jwt(vc_jwt_with_evidence, false)
                 .await
                 .expect("should be valid vc jwt");
 
@@ -460,7 +746,7 @@ mod tests {
         #[tokio::test]
         async fn test_decode_credential_schema() {
             let vc_jwt_with_credential_schema = r#"eyJ0eXAiOiJKV1QiLCJhbGciOiJFZERTQSIsImtpZCI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSTFUMXB2TVd4bU9VcHlOeTFZTkdGWU4yRmxka2N5WVU5MGEwWmxlSFZuYzBwcVZVbEtWVU5UYVVkUkluMCMwIn0.eyJ2YyI6eyJAY29udGV4dCI6WyJodHRwczovL3d3dy53My5vcmcvMjAxOC9jcmVkZW50aWFscy92MSJdLCJpZCI6InVybjp1dWlkOjNhMDU2NjE1LWNlZDMtNGQ4Zi05ODRhLTUwMzQ2Y2FlNDQ2ZiIsInR5cGUiOlsiVmVyaWZpYWJsZUNyZWRlbnRpYWwiXSwiaXNzdWVyIjoiZGlkOmp3azpleUpoYkdjaU9pSkZaREkxTlRFNUlpd2lhM1I1SWpvaVQwdFFJaXdpWTNKMklqb2lSV1F5TlRVeE9TSXNJbmdpT2lJMVQxcHZNV3htT1VweU55MVlOR0ZZTjJGbGRrY3lZVTkwYTBabGVIVm5jMHBxVlVsS1ZVTlRhVWRSSW4wIiwiaXNzdWFuY2VEYXRlIjoiMjAyNC0wOC0zMFQxNDo1OToyMi4xMDMzODUrMDA6MDAiLCJleHBpcmF0aW9uRGF0ZSI6bnVsbCwiY3JlZGVudGlhbFN1YmplY3QiOnsiaWQiOiJkaWQ6ZGh0OnFnbW1weWp3NWh3bnFmZ3puN3dtcm0zM2FkeThnYjh6OWlkZWliNm05Z2o0eXM2d255OHkifSwiY3JlZGVudGlhbFNjaGVtYSI6eyJpZCI6Imh0dHBzOi8vZXhhbXBsZS5jb20vc2NoZW1hcy9lbWFpbC5qc29uIiwidHlwZSI6Ikpzb25TY2hlbWEifX0sImlzcyI6ImRpZDpqd2s6ZXlKaGJHY2lPaUpGWkRJMU5URTVJaXdpYTNSNUlqb2lUMHRRSWl3aVkzSjJJam9pUldReU5UVXhPU0lzSW5naU9pSTFUMXB2TVd4bU9VcHlOeTFZTkdGWU4yRmxka2N5WVU5MGEwWmxlSFZuYzBwcVZVbEtWVU5UYVVkUkluMCIsImp0aSI6InVybjp1dWlkOjNhMDU2NjE1LWNlZDMtNGQ4Zi05ODRhLTUwMzQ2Y2FlNDQ2ZiIsInN1YiI6ImRpZDpkaHQ6cWdtbXB5anc1aHducWZnem43d21ybTMzYWR5OGdiOHo5aWRlaWI2bTlnajR5czZ3bnk4eSIsIm5iZiI6MTcyNTAyOTk2MiwiaWF0IjoxNzI1MDI5OTYyfQ.ZQkusfYLJSpfLVF9OuWrrhw8NdcBnjlalMFZbsfAxJp8i74KH47RkMsVVPadLPuKwbozgcDRCKPsokrl33TuCw"#;
-            let vc = VerifiableCredential::from_vc_jwt(&vc_jwt_with_credential_schema, false)
+            let vc = VerifiableCredential::from_vc_jwt(vc_jwt_with_credential_schema, false)
                 .await
                 .expect("should be valid vc jwt");
 
@@ -888,7 +1174,7 @@ mod tests {
                 .mock("GET", "/schemas/email.json")
                 .with_status(200)
                 .with_header("content-type", "application/json")
-                .with_body(&mock_json_schema(
+                .with_body(mock_json_schema(
                     "http://localhost:40003/schemas/email.json".to_string(),
                     Some("this is not a valid $schema".to_string()), // here
                 ))
@@ -919,7 +1205,7 @@ mod tests {
                 .mock("GET", "/schemas/email.json")
                 .with_status(200)
                 .with_header("content-type", "application/json")
-                .with_body(&mock_json_schema(
+                .with_body(mock_json_schema(
                     "http://localhost:40004/schemas/email.json".to_string(),
                     Some("http://json-schema.org/draft-04/schema#".to_string()), // here
                 ))
@@ -950,7 +1236,7 @@ mod tests {
                 .mock("GET", "/schemas/email.json")
                 .with_status(200)
                 .with_header("content-type", "application/json")
-                .with_body(&mock_json_schema(
+                .with_body(mock_json_schema(
                     "http://localhost:40005/schemas/email.json".to_string(),
                     Some("http://json-schema.org/draft-06/schema#".to_string()), // here
                 ))
@@ -981,7 +1267,7 @@ mod tests {
                 .mock("GET", "/schemas/email.json")
                 .with_status(200)
                 .with_header("content-type", "application/json")
-                .with_body(&mock_json_schema(
+                .with_body(mock_json_schema(
                     "http://localhost/schemas/email.json".to_string(),
                     None,
                 ))
@@ -1013,7 +1299,7 @@ mod tests {
                 .mock("GET", "/schemas/email.json")
                 .with_status(200)
                 .with_header("content-type", "application/json")
-                .with_body(&mock_json_schema(
+                .with_body(mock_json_schema(
                     "http://localhost:40007/schemas/email.json".to_string(),
                     None,
                 ))
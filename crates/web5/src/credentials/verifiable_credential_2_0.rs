@@ -0,0 +1,851 @@
+use super::credential_schema::{validate_against_credential_schema, CredentialSchema};
+use super::data_integrity::{
+    create_proof, verify_proofs, DataIntegrityProof, DataIntegrityProofOptions,
+    DataIntegrityVerifyOptions,
+};
+use super::data_model_validation::validate_vc_2_0_data_model;
+use super::decode::{decode_v2_0, decode_versioned};
+use super::vc_jose_cose::{sign_enveloped, VC_CONTENT_TYPE, VC_JWT_MEDIA_TYPE};
+use super::verifiable_credential_1_1::{
+    CredentialStatus, VerifiableCredential, BASE_CONTEXT as BASE_CONTEXT_1_1,
+};
+use super::verification_policy::{VerificationPolicy, VerificationReport};
+use super::CredentialSubject;
+use super::Issuer;
+use crate::datetime::{deserialize_optional_rfc3339, serialize_optional_rfc3339};
+use crate::dids::bearer_did::{BearerDid, SignerOptions};
+use crate::dids::data_model::document::VerificationRelationship;
+use crate::errors::{Result, Web5Error};
+use crate::json::JsonObject;
+use crate::json::{FromJson, ToJson};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::time::SystemTime;
+
+pub const BASE_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+
+/// Represents a Verifiable Credential according to the [W3C Verifiable Credentials Data Model v2.0](https://www.w3.org/TR/vc-data-model-2.0/).
+/// A Verifiable Credential is a tamper-evident credential that has authorship that can be cryptographically verified.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VerifiableCredentialV2 {
+    /// A list of contexts used to define the semantic meaning of the data contained in the Verifiable Credential.
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+
+    /// The unique identifier for the Verifiable Credential.
+    pub id: String,
+
+    /// The type(s) of the Verifiable Credential.
+    #[serde(rename = "type")]
+    pub r#type: Vec<String>,
+
+    /// An optional human-readable name of the credential.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// An optional human-readable description of the credential.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The entity (either a string or an object) that issued the credential.
+    pub issuer: Issuer,
+
+    /// The subjects of the credential, each containing claims about an entity being described
+    /// by the credential. A single subject is serialized as an object, and several as an array.
+    #[serde(
+        rename = "credentialSubject",
+        serialize_with = "serialize_credential_subjects",
+        deserialize_with = "deserialize_credential_subjects"
+    )]
+    pub credential_subject: Vec<CredentialSubject>,
+
+    /// The optional date and time from which the credential is valid.
+    #[serde(
+        rename = "validFrom",
+        serialize_with = "serialize_optional_rfc3339",
+        deserialize_with = "deserialize_optional_rfc3339",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub valid_from: Option<SystemTime>,
+
+    /// The optional date and time after which the credential is no longer valid.
+    #[serde(
+        rename = "validUntil",
+        serialize_with = "serialize_optional_rfc3339",
+        deserialize_with = "deserialize_optional_rfc3339",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub valid_until: Option<SystemTime>,
+
+    /// The credential status information, if applicable (e.g., revoked or suspended).
+    #[serde(rename = "credentialStatus", skip_serializing_if = "Option::is_none")]
+    pub credential_status: Option<CredentialStatus>,
+
+    /// The credential schema, used to validate the data structure of the credential.
+    #[serde(rename = "credentialSchema", skip_serializing_if = "Option::is_none")]
+    pub credential_schema: Option<CredentialSchema>,
+
+    /// An array of evidence supporting the claims made in the credential.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<Vec<JsonObject>>,
+
+    /// An embedded [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proof securing the credential, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<DataIntegrityProof>,
+}
+
+impl FromJson for VerifiableCredentialV2 {}
+impl ToJson for VerifiableCredentialV2 {}
+
+/// Represents the options available when creating a Verifiable Credential 2.0.
+/// These options allow customization of various attributes of the credential during its creation.
+#[derive(Default, Clone)]
+pub struct VerifiableCredentialV2CreateOptions {
+    /// The unique identifier for the Verifiable Credential. This is optional.
+    /// If not provided then the default value will be of format urn:uuid:{uuid}.
+    pub id: Option<String>,
+
+    /// The context(s) for the Verifiable Credential, which define the meaning of terms within the credential.
+    /// The base context `<https://www.w3.org/ns/credentials/v2>` is always the first value whereafter values provided here will be appended onto.
+    /// If the base context is also provided here then it will be de-duplicated against the base context referred to above.
+    pub context: Option<Vec<String>>,
+
+    /// The type(s) of the Verifiable Credential.
+    /// The base type VerifiableCredential will always be the first value whereafter values provided here will be appeneded onto.
+    /// If the base type is also provided here then it will be de-duplicated against the base type referred to above.
+    pub r#type: Option<Vec<String>>,
+
+    /// An optional human-readable name of the credential.
+    pub name: Option<String>,
+
+    /// An optional human-readable description of the credential.
+    pub description: Option<String>,
+
+    /// The date from which the credential is valid. If not provided, defaults to the current date and time.
+    pub valid_from: Option<SystemTime>,
+
+    /// The optional date after which the credential is no longer valid.
+    pub valid_until: Option<SystemTime>,
+
+    /// The optional credential status, which may indicate revocation or suspension information.
+    pub credential_status: Option<CredentialStatus>,
+
+    /// The credential schema, used to validate the data structure of the credential. This is optional.
+    /// JSON Schema validation is performed if the value is provided, and creation will fail if validation fails.
+    pub credential_schema: Option<CredentialSchema>,
+
+    /// An optional array of evidence supporting the claims made in the credential.
+    pub evidence: Option<Vec<JsonObject>>,
+}
+
+/// The version of the W3C Verifiable Credentials Data Model a credential conforms to, as
+/// identified by its base context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataModelVersion {
+    V1_1,
+    V2_0,
+}
+
+impl DataModelVersion {
+    /// Detects the data model version from the first value of a credential's `@context`.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<DataModelVersion>` - The version, or `None` if the first context is neither
+    ///   the 1.1 nor the 2.0 base context.
+    pub fn detect(context: &[String]) -> Option<Self> {
+        match context.first().map(String::as_str) {
+            Some(BASE_CONTEXT_1_1) => Some(Self::V1_1),
+            Some(BASE_CONTEXT) => Some(Self::V2_0),
+            _ => None,
+        }
+    }
+}
+
+/// A Verifiable Credential of either data model version, as decoded from a VC JWT whose version
+/// isn't known in advance.
+#[derive(Debug, Clone)]
+pub enum VersionedVerifiableCredential {
+    V1_1(VerifiableCredential),
+    V2_0(VerifiableCredentialV2),
+}
+
+impl VersionedVerifiableCredential {
+    /// Constructs a Verifiable Credential of either version from a VC JWT, detecting the version
+    /// from the base context of the `vc` claim. Credentials without a recognized base context are
+    /// decoded as 1.1, and so fail data model validation if `verify` is true.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwt` - The Verifiable Credential in JWT format, serialized as a compact JWS.
+    /// * `verify` - If true, verifies the signature, the data model of the detected version, and the JSON Schema if present.
+    ///
+    /// # Example
+    /// ```ignore
+    /// match VersionedVerifiableCredential::from_vc_jwt(vc_jwt, true).await? {
+    ///     VersionedVerifiableCredential::V1_1(vc) => println!("1.1 issued at {:?}", vc.issuance_date),
+    ///     VersionedVerifiableCredential::V2_0(vc) => println!("2.0 valid from {:?}", vc.valid_from),
+    /// }
+    /// ```
+    pub async fn from_vc_jwt(vc_jwt: &str, verify: bool) -> Result<Self> {
+        if !verify {
+            return decode_versioned(vc_jwt, false).await;
+        }
+
+        let (verifiable_credential, _) =
+            Self::from_vc_jwt_with_policy(vc_jwt, &VerificationPolicy::default()).await?;
+        Ok(verifiable_credential)
+    }
+
+    /// Constructs and verifies a Verifiable Credential from a VC JWT, as [`Self::from_vc_jwt`]
+    /// does, against the requirements of a verification policy.
+    ///
+    /// # Returns
+    ///
+    /// The credential, and a report of the checks made in verifying it.
+    pub async fn from_vc_jwt_with_policy(
+        vc_jwt: &str,
+        policy: &VerificationPolicy,
+    ) -> Result<(Self, VerificationReport)> {
+        policy.verify_vc_jwt_fail_fast(vc_jwt, None).await
+    }
+
+    /// Returns the data model version of the credential.
+    pub fn version(&self) -> DataModelVersion {
+        match self {
+            Self::V1_1(_) => DataModelVersion::V1_1,
+            Self::V2_0(_) => DataModelVersion::V2_0,
+        }
+    }
+
+    /// Returns the credential in the 2.0 data model, converting a 1.1 credential.
+    pub fn into_v2_0(self) -> VerifiableCredentialV2 {
+        match self {
+            Self::V1_1(vc) => vc.into(),
+            Self::V2_0(vc) => vc,
+        }
+    }
+}
+
+impl VerifiableCredentialV2 {
+    /// Creates a new Verifiable Credential 2.0 with the specified issuer, subjects, and optional creation options.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The entity issuing the credential. The `issuer` must be a valid DID.
+    /// * `credential_subject` - One or more subjects of the credential containing claims. Each subject must be a valid DID.
+    /// * `options` - Optional parameters for creating the credential, such as name, validity period or schema.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let issuer_bearer_did = DidJwk::create(None).unwrap();
+    ///
+    /// let verifiable_credential = VerifiableCredentialV2::create(
+    ///     Issuer::String(issuer_bearer_did.did.uri.clone()),
+    ///     vec![CredentialSubject::from(alice_did_uri), CredentialSubject::from(bob_did_uri)],
+    ///     Some(VerifiableCredentialV2CreateOptions {
+    ///         name: Some("Joint Account Credential".to_string()),
+    ///         ..Default::default()
+    ///     }),
+    /// ).await.unwrap();
+    /// ```
+    pub async fn create(
+        issuer: Issuer,
+        credential_subject: Vec<CredentialSubject>,
+        options: Option<VerifiableCredentialV2CreateOptions>,
+    ) -> Result<Self> {
+        super::create::create_vc_v2_0(issuer, credential_subject, options).await
+    }
+
+    /// Constructs a Verifiable Credential 2.0 from a VC JWT (JSON Web Token).
+    ///
+    /// Both a VC-JWT with a `vc` claim and a `vc+jwt` secured per VC-JOSE-COSE are accepted. Use
+    /// [`VersionedVerifiableCredential::from_vc_jwt`] if the data model version isn't known in advance.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwt` - The Verifiable Credential in JWT format, serialized as a compact JWS.
+    /// * `verify` - If true, verifies the integrity of the JWT by performing cryptographic verification against the signature, validating the VC Data Model, and validates the JSON Schema if present.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let verifiable_credential = VerifiableCredentialV2::from_vc_jwt(vc_jwt, true).await.unwrap();
+    /// ```
+    pub async fn from_vc_jwt(vc_jwt: &str, verify: bool) -> Result<Self> {
+        if !verify {
+            return decode_v2_0(vc_jwt, false).await;
+        }
+
+        let (verifiable_credential, _) =
+            Self::from_vc_jwt_with_policy(vc_jwt, &VerificationPolicy::default()).await?;
+        Ok(verifiable_credential)
+    }
+
+    /// Constructs and verifies a Verifiable Credential from a VC JWT, as [`Self::from_vc_jwt`]
+    /// does, against the requirements of a verification policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwt` - The Verifiable Credential in JWT format, serialized as a compact JWS.
+    /// * `policy` - The requirements, such as the trusted issuers and whether to check the
+    ///   credential isn't revoked.
+    ///
+    /// # Returns
+    ///
+    /// The credential, and a report of the checks made in verifying it.
+    pub async fn from_vc_jwt_with_policy(
+        vc_jwt: &str,
+        policy: &VerificationPolicy,
+    ) -> Result<(Self, VerificationReport)> {
+        match policy
+            .verify_vc_jwt_fail_fast(vc_jwt, Some(DataModelVersion::V2_0))
+            .await?
+        {
+            (VersionedVerifiableCredential::V2_0(verifiable_credential), report) => {
+                Ok((verifiable_credential, report))
+            }
+            (VersionedVerifiableCredential::V1_1(_), _) => Err(Web5Error::Parameter(
+                "expected a data model 2.0 credential".to_string(),
+            )),
+        }
+    }
+
+    /// Signs the Verifiable Credential using the specified Bearer DID and optional verification method.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the credential.
+    /// * `verification_method_id` - Optional identifier of the Verification Method for which to sign with.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the signed JWT, serialized as a compact JWS, of the Verifiable Credential.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let vc_jwt = verifiable_credential
+    ///     .sign(&issuer_bearer_did, None)
+    ///     .unwrap();
+    /// ```
+    pub fn sign(
+        &self,
+        bearer_did: &BearerDid,
+        verification_method_id: Option<String>,
+    ) -> Result<String> {
+        super::sign::sign_vc_2_0_with_did(self, bearer_did, verification_method_id)
+    }
+
+    /// Signs the Verifiable Credential using the specified Bearer DID, selecting the
+    /// verification method by relationship, algorithm preference and/or key id.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the credential.
+    /// * `options` - How to select the verification method to sign with.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the signed JWT, serialized as a compact JWS, of the Verifiable Credential.
+    pub fn sign_with_options(
+        &self,
+        bearer_did: &BearerDid,
+        options: &SignerOptions,
+    ) -> Result<String> {
+        super::sign::sign_vc_2_0_with_did_options(self, bearer_did, options)
+    }
+
+    /// Secures the Verifiable Credential per [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/),
+    /// signing the credential itself as the JWS payload with `typ: vc+jwt` and `cty: vc`, rather
+    /// than wrapping it in a `vc` claim as [`Self::sign`] does.
+    ///
+    /// [`Self::from_vc_jwt`] detects and verifies either securing mechanism.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the credential.
+    /// * `options` - How to select the verification method to sign with.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the signed credential, serialized as a compact JWS.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let vc_jwt = verifiable_credential
+    ///     .sign_vc_jose(&issuer_bearer_did, &SignerOptions::default())
+    ///     .unwrap();
+    /// ```
+    pub fn sign_vc_jose(&self, bearer_did: &BearerDid, options: &SignerOptions) -> Result<String> {
+        sign_enveloped(
+            self,
+            &self.issuer.to_string(),
+            VC_JWT_MEDIA_TYPE,
+            VC_CONTENT_TYPE,
+            bearer_did,
+            options,
+        )
+    }
+
+    /// Adds a [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proof to the Verifiable
+    /// Credential, signed by an `assertionMethod` verification method of the issuer
+    /// with the `eddsa-jcs-2022` or `ecdsa-jcs-2019` cryptosuite. This secures the credential as
+    /// JSON, as an alternative to securing it as a JWT.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the proof.
+    /// * `options` - Optional parameters for the proof, such as the cryptosuite or verification method.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the credential with its `proof` set, replacing any existing proof.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let secured = verifiable_credential
+    ///     .add_data_integrity_proof(&issuer_bearer_did, None)
+    ///     .unwrap();
+    /// let json = serde_json::to_string(&secured).unwrap();
+    /// ```
+    pub fn add_data_integrity_proof(
+        &self,
+        bearer_did: &BearerDid,
+        options: Option<DataIntegrityProofOptions>,
+    ) -> Result<Self> {
+        let mut verifiable_credential = self.clone();
+        let proof = create_proof(
+            &verifiable_credential,
+            &self.issuer.to_string(),
+            VerificationRelationship::AssertionMethod,
+            bearer_did,
+            options.unwrap_or_default(),
+        )?;
+        verifiable_credential.proof = Some(proof);
+        Ok(verifiable_credential)
+    }
+
+    /// Verifies the Data Integrity proof of the Verifiable Credential, resolving the
+    /// issuer's DID document to find the verification method.
+    ///
+    /// Use [`Self::from_secured_json`] to verify a credential received as JSON, as the proof covers
+    /// the JSON exactly as signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The challenge and domain the proof must carry, if any.
+    pub async fn verify_data_integrity_proof(
+        &self,
+        options: Option<DataIntegrityVerifyOptions>,
+    ) -> Result<()> {
+        verify_proofs(
+            &serde_json::to_value(self)?,
+            &self.issuer.to_string(),
+            VerificationRelationship::AssertionMethod,
+            &options.unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Constructs a Verifiable Credential secured with a Data Integrity proof from its JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The Verifiable Credential, including its `proof`, serialized as JSON.
+    /// * `verify` - If true, verifies the proof against the JSON as received, and validates the VC Data Model 2.0 and the JSON Schema if present.
+    /// * `options` - The challenge and domain the proof must carry, if any.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let verifiable_credential = VerifiableCredential::from_secured_json(&json, true, None).await.unwrap();
+    /// ```
+    pub async fn from_secured_json(
+        json: &str,
+        verify: bool,
+        options: Option<DataIntegrityVerifyOptions>,
+    ) -> Result<Self> {
+        let secured_document: Value = serde_json::from_str(json)?;
+        let verifiable_credential: Self = serde_json::from_value(secured_document.clone())?;
+
+        if verify {
+            verify_proofs(
+                &secured_document,
+                &verifiable_credential.issuer.to_string(),
+                VerificationRelationship::AssertionMethod,
+                &options.unwrap_or_default(),
+            )
+            .await?;
+            verifiable_credential.validate().await?;
+        }
+
+        Ok(verifiable_credential)
+    }
+
+    async fn validate(&self) -> Result<()> {
+        validate_vc_2_0_data_model(self)?;
+        validate_against_credential_schema(self.credential_schema.as_ref(), self).await
+    }
+}
+
+impl From<VerifiableCredential> for VerifiableCredentialV2 {
+    /// Converts a 1.1 credential by replacing the base context, and mapping `issuanceDate` to
+    /// `validFrom` and `expirationDate` to `validUntil`.
+    fn from(vc: VerifiableCredential) -> Self {
+        Self {
+            context: replace_base_context(vc.context, BASE_CONTEXT_1_1, BASE_CONTEXT),
+            id: vc.id,
+            r#type: vc.r#type,
+            name: None,
+            description: None,
+            issuer: vc.issuer,
+            credential_subject: vec![vc.credential_subject],
+            valid_from: Some(vc.issuance_date),
+            valid_until: vc.expiration_date,
+            credential_status: vc.credential_status,
+            credential_schema: vc.credential_schema,
+            evidence: vc.evidence,
+            proof: None,
+        }
+    }
+}
+
+impl TryFrom<VerifiableCredentialV2> for VerifiableCredential {
+    type Error = Web5Error;
+
+    /// Converts a 2.0 credential to 1.1, which is only lossless if the credential has exactly one
+    /// subject, a `validFrom` date, and neither a name nor a description.
+    fn try_from(vc: VerifiableCredentialV2) -> Result<Self> {
+        if vc.name.is_some() || vc.description.is_some() {
+            return Err(Web5Error::Parameter(
+                "name and description are not supported by the 1.1 data model".to_string(),
+            ));
+        }
+        let issuance_date = vc.valid_from.ok_or_else(|| {
+            Web5Error::Parameter("validFrom is required to convert to issuanceDate".to_string())
+        })?;
+        let mut credential_subject = vc.credential_subject;
+        if credential_subject.len() != 1 {
+            return Err(Web5Error::Parameter(
+                "the 1.1 data model supports exactly one credential subject".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            context: replace_base_context(vc.context, BASE_CONTEXT, BASE_CONTEXT_1_1),
+            id: vc.id,
+            r#type: vc.r#type,
+            issuer: vc.issuer,
+            credential_subject: credential_subject.remove(0),
+            issuance_date,
+            expiration_date: vc.valid_until,
+            credential_status: vc.credential_status,
+            credential_schema: vc.credential_schema,
+            evidence: vc.evidence,
+            proof: None,
+        })
+    }
+}
+
+fn replace_base_context(context: Vec<String>, from: &str, to: &str) -> Vec<String> {
+    let mut context: Vec<String> = context
+        .into_iter()
+        .filter(|c| c != from && c != to)
+        .collect();
+    context.insert(0, to.to_string());
+    context
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+pub(crate) fn serialize_credential_subjects<S>(
+    credential_subjects: &[CredentialSubject],
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match credential_subjects {
+        [credential_subject] => credential_subject.serialize(serializer),
+        _ => credential_subjects.serialize(serializer),
+    }
+}
+
+pub(crate) fn deserialize_credential_subjects<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<CredentialSubject>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(credential_subject) => vec![credential_subject],
+        OneOrMany::Many(credential_subjects) => credential_subjects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::VerificationError;
+    use crate::dids::methods::did_jwk::DidJwk;
+    use std::time::Duration;
+
+    const SUBJECT_DID_URI: &str = "did:dht:qgmmpyjw5hwnqfgzn7wmrm33ady8gb8z9ideib6m9gj4ys6wny8y";
+    const OTHER_SUBJECT_DID_URI: &str =
+        "did:dht:ng4hmqtrgujox4agpf8okxihnyy1zqnq97qfeq15x8oar7yepzhy";
+
+    mod create {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_defaults() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredentialV2::create(
+                Issuer::from(issuer.did.uri.clone()),
+                vec![CredentialSubject::from(SUBJECT_DID_URI)],
+                None,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(vc.context, vec![BASE_CONTEXT]);
+            assert_eq!(vc.r#type, vec!["VerifiableCredential"]);
+            assert!(vc.id.starts_with("urn:uuid:"));
+            assert!(vc.valid_from.is_some());
+
+            let json = serde_json::to_value(&vc).unwrap();
+            assert!(json["credentialSubject"].is_object());
+            assert!(json["validFrom"].is_string());
+            assert!(json.get("issuanceDate").is_none());
+        }
+
+        #[tokio::test]
+        async fn test_requires_a_subject() {
+            let issuer = DidJwk::create(None).unwrap();
+            let result =
+                VerifiableCredentialV2::create(Issuer::from(issuer.did.uri.clone()), vec![], None)
+                    .await;
+
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[tokio::test]
+        async fn test_rejects_valid_until_before_valid_from() {
+            let issuer = DidJwk::create(None).unwrap();
+            let now = SystemTime::now();
+            let result = VerifiableCredentialV2::create(
+                Issuer::from(issuer.did.uri.clone()),
+                vec![CredentialSubject::from(SUBJECT_DID_URI)],
+                Some(VerifiableCredentialV2CreateOptions {
+                    valid_from: Some(now),
+                    valid_until: Some(now - Duration::from_secs(60)),
+                    ..Default::default()
+                }),
+            )
+            .await;
+
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+    }
+
+    mod from_vc_jwt {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_round_trip_with_multiple_subjects() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredentialV2::create(
+                Issuer::from(issuer.did.uri.clone()),
+                vec![
+                    CredentialSubject::from(SUBJECT_DID_URI),
+                    CredentialSubject::from(OTHER_SUBJECT_DID_URI),
+                ],
+                Some(VerifiableCredentialV2CreateOptions {
+                    r#type: Some(vec!["JointAccountCredential".to_string()]),
+                    name: Some("Joint Account".to_string()),
+                    description: Some("Owners of a joint account".to_string()),
+                    valid_until: Some(SystemTime::now() + Duration::from_secs(3600)),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+            let vc_jwt = vc.sign(&issuer, None).unwrap();
+            let decoded = VerifiableCredentialV2::from_vc_jwt(&vc_jwt, true)
+                .await
+                .unwrap();
+
+            let subject_ids: Vec<String> = decoded
+                .credential_subject
+                .iter()
+                .map(|credential_subject| credential_subject.id.clone())
+                .collect();
+            assert_eq!(subject_ids, vec![SUBJECT_DID_URI, OTHER_SUBJECT_DID_URI]);
+            assert_eq!(decoded.name, vc.name);
+            assert_eq!(decoded.description, vc.description);
+            assert_eq!(decoded.id, vc.id);
+            assert!(decoded.valid_until.is_some());
+        }
+
+        #[tokio::test]
+        async fn test_expired() {
+            let issuer = DidJwk::create(None).unwrap();
+            let now = SystemTime::now();
+            let vc = VerifiableCredentialV2::create(
+                Issuer::from(issuer.did.uri.clone()),
+                vec![CredentialSubject::from(SUBJECT_DID_URI)],
+                Some(VerifiableCredentialV2CreateOptions {
+                    valid_from: Some(now - Duration::from_secs(7200)),
+                    valid_until: Some(now - Duration::from_secs(3600)),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+            let vc_jwt = vc.sign(&issuer, None).unwrap();
+            let result = VerifiableCredentialV2::from_vc_jwt(&vc_jwt, true).await;
+
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::CredentialError(VerificationError::DataModelValidationError(
+                    "credential expired".to_string()
+                ))
+            );
+        }
+
+        #[tokio::test]
+        async fn test_rejects_1_1_credential() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc = VerifiableCredential::create(
+                Issuer::from(issuer.did.uri.clone()),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                None,
+            )
+            .await
+            .unwrap();
+
+            let vc_jwt = vc.sign(&issuer, None).unwrap();
+            let result = VerifiableCredentialV2::from_vc_jwt(&vc_jwt, true).await;
+
+            assert_eq!(
+                result.unwrap_err(),
+                Web5Error::CredentialError(VerificationError::DataModelValidationError(
+                    "missing context".to_string()
+                ))
+            );
+        }
+    }
+
+    mod versioned {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_detects_version() {
+            let issuer = DidJwk::create(None).unwrap();
+            let vc_1_1 = VerifiableCredential::create(
+                Issuer::from(issuer.did.uri.clone()),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                None,
+            )
+            .await
+            .unwrap();
+            let vc_2_0 = VerifiableCredentialV2::create(
+                Issuer::from(issuer.did.uri.clone()),
+                vec![CredentialSubject::from(SUBJECT_DID_URI)],
+                None,
+            )
+            .await
+            .unwrap();
+
+            let decoded = VersionedVerifiableCredential::from_vc_jwt(
+                &vc_1_1.sign(&issuer, None).unwrap(),
+                true,
+            )
+            .await
+            .unwrap();
+            assert_eq!(decoded.version(), DataModelVersion::V1_1);
+
+            let decoded = VersionedVerifiableCredential::from_vc_jwt(
+                &vc_2_0.sign(&issuer, None).unwrap(),
+                true,
+            )
+            .await
+            .unwrap();
+            assert_eq!(decoded.version(), DataModelVersion::V2_0);
+            assert_eq!(decoded.into_v2_0().id, vc_2_0.id);
+        }
+
+        #[test]
+        fn test_detect_unknown_context() {
+            assert_eq!(
+                DataModelVersion::detect(&["https://example.com/context".to_string()]),
+                None
+            );
+            assert_eq!(DataModelVersion::detect(&[]), None);
+        }
+    }
+
+    mod conversion {
+        use super::*;
+
+        async fn vc_1_1() -> VerifiableCredential {
+            VerifiableCredential::create(
+                Issuer::from("did:web:tbd.website"),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                Some(crate::credentials::VerifiableCredentialCreateOptions {
+                    context: Some(vec!["https://example.com/context".to_string()]),
+                    expiration_date: Some(SystemTime::now() + Duration::from_secs(3600)),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_round_trip_is_lossless() {
+            let vc_1_1 = vc_1_1().await;
+
+            let vc_2_0 = VerifiableCredentialV2::from(vc_1_1.clone());
+            assert_eq!(
+                vc_2_0.context,
+                vec![BASE_CONTEXT, "https://example.com/context"]
+            );
+            assert_eq!(vc_2_0.valid_from, Some(vc_1_1.issuance_date));
+            assert_eq!(vc_2_0.valid_until, vc_1_1.expiration_date);
+
+            let round_tripped = VerifiableCredential::try_from(vc_2_0).unwrap();
+            assert_eq!(
+                serde_json::to_value(&round_tripped).unwrap(),
+                serde_json::to_value(&vc_1_1).unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_lossy_conversions_fail() {
+            let vc_2_0 = VerifiableCredentialV2::from(vc_1_1().await);
+
+            let mut named = vc_2_0.clone();
+            named.name = Some("Name".to_string());
+            assert!(VerifiableCredential::try_from(named).is_err());
+
+            let mut multiple_subjects = vc_2_0.clone();
+            multiple_subjects
+                .credential_subject
+                .push(CredentialSubject::from(OTHER_SUBJECT_DID_URI));
+            assert!(VerifiableCredential::try_from(multiple_subjects).is_err());
+
+            let mut no_valid_from = vc_2_0;
+            no_valid_from.valid_from = None;
+            assert!(VerifiableCredential::try_from(no_valid_from).is_err());
+        }
+    }
+}
@@ -1,19 +1,29 @@
-use crate::credentials::verifiable_credential_1_1::VerifiableCredential;
+use crate::credentials::data_integrity::{
+    create_proof, verify_proofs, with_data_integrity_context, DataIntegrityProof,
+    DataIntegrityProofOptions, DataIntegrityVerifyOptions,
+};
+use crate::credentials::vc_jose_cose::{
+    check_media_type, sign_enveloped, JwtSecuringMechanism, VP_CONTENT_TYPE, VP_JWT_MEDIA_TYPE,
+};
+use crate::credentials::verifiable_credential_2_0::VersionedVerifiableCredential;
+use crate::credentials::verification_policy::{VerificationPolicy, VerificationReport};
 use crate::credentials::VerificationError;
 use crate::datetime::{
     deserialize_optional_rfc3339, deserialize_rfc3339, serialize_optional_rfc3339,
     serialize_rfc3339,
 };
-use crate::dids::bearer_did::BearerDid;
+use crate::dids::bearer_did::{BearerDid, SignerOptions};
+use crate::dids::controller::{signs_as_controller, verify_controller_authorization};
+use crate::dids::data_model::document::VerificationRelationship;
 use crate::dids::did::Did;
 use crate::errors::{Result, Web5Error};
-use crate::jose::{Jwt, JwtClaims};
-use crate::json::{json_value_type_name, FromJsonValue, JsonValue, ToJsonValue};
+use crate::jose::{Jws, Jwt, JwtClaims};
+use crate::json::{json_value_type_name, FromJson, FromJsonValue, JsonValue, ToJson, ToJsonValue};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::time::SystemTime;
-use chrono::Utc;
 use uuid::Uuid;
 
 pub const BASE_PRESENTATION_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
@@ -60,11 +70,18 @@ pub struct VerifiablePresentation {
     #[serde(rename = "verifiableCredential")]
     pub verifiable_credential: Vec<String>,
 
+    /// An embedded [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proof securing the presentation, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<DataIntegrityProof>,
+
     /// Additional data that may be included in the presentation, represented as a key-value map.
     #[serde(flatten)]
     pub additional_data: Option<HashMap<String, Value>>,
 }
 
+impl FromJson for VerifiablePresentation {}
+impl ToJson for VerifiablePresentation {}
+
 /// Represents the options available when creating a Verifiable Presentation.
 /// These options allow customization of various attributes of the presentation during its creation.
 #[derive(Default, Clone)]
@@ -188,7 +205,7 @@ impl VerifiablePresentation {
 
         // Verify vcjwts
         for vc_jwt in vc_jwts.clone() {
-            VerifiableCredential::from_vc_jwt(&vc_jwt, true).await?;
+            VersionedVerifiableCredential::from_vc_jwt(&vc_jwt, true).await?;
         }
 
         let context = build_vp_context(options.context);
@@ -205,19 +222,21 @@ impl VerifiablePresentation {
             issuance_date: options.issuance_date.unwrap_or_else(SystemTime::now),
             expiration_date: options.expiration_date,
             verifiable_credential: vc_jwts,
+            proof: None,
             additional_data: options.additional_data,
         };
 
         Ok(verifiable_presentation)
     }
 
-    /// Constructs a Verifiable Presentation from a VP JWT (JSON Web Token).
+    /// Constructs a Verifiable Presentation from a VP JWT (JSON Web Token), either with a `vp`
+    /// claim or secured as a `vp+jwt` per VC-JOSE-COSE.
     ///
     /// # Arguments
     ///
     /// * `vp_jwt` - The Verifiable Presentation in JWT format, serialized as a compact JWS.
     /// * `verify` - If true, verifies the integrity of the JWT by performing cryptographic verification
-    ///              against the signature and validating the Data Model.
+    ///   against the signature and validating the Data Model.
     ///
     /// # Example
     /// ```ignore
@@ -227,15 +246,48 @@ impl VerifiablePresentation {
     /// let verifiable_presentation = VerifiablePresentation::from_vp_jwt(vp_jwt, true).await.unwrap();
     /// ```
     pub async fn from_vp_jwt(vp_jwt: &str, verify: bool) -> Result<Self> {
-        let verifiable_presentation = decode_vp_jwt(vp_jwt, verify).await?;
-
-        if verify {
-            validate_vp_data_model(&verifiable_presentation).await?;
+        if !verify {
+            return decode_vp_jwt(vp_jwt, false).await;
         }
 
+        let (verifiable_presentation, _) =
+            Self::from_vp_jwt_with_policy(vp_jwt, &VerificationPolicy::default()).await?;
         Ok(verifiable_presentation)
     }
 
+    /// Constructs and verifies a Verifiable Presentation from a VP JWT, as [`Self::from_vp_jwt`]
+    /// does, verifying the presentation and each of its credentials against the requirements
+    /// of a verification policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `vp_jwt` - The Verifiable Presentation in JWT format, serialized as a compact JWS.
+    /// * `policy` - The requirements, such as the trusted issuers of the credentials.
+    ///
+    /// # Returns
+    ///
+    /// The presentation, and a report of the checks made in verifying it, with the reports of
+    /// its credentials.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let (verifiable_presentation, report) = VerifiablePresentation::from_vp_jwt_with_policy(
+    ///     vp_jwt,
+    ///     &VerificationPolicy {
+    ///         required_types: Some(vec!["EmploymentCredential".to_string()]),
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    pub async fn from_vp_jwt_with_policy(
+        vp_jwt: &str,
+        policy: &VerificationPolicy,
+    ) -> Result<(Self, VerificationReport)> {
+        policy.verify_vp_jwt_fail_fast(vp_jwt).await
+    }
+
     /// Signs the Verifiable Presentation using the specified Bearer DID and optional verification method.
     ///
     /// # Arguments
@@ -270,6 +322,170 @@ impl VerifiablePresentation {
     ) -> Result<String> {
         sign_presentation_with_did(self, bearer_did, verification_method_id)
     }
+
+    /// Signs the Verifiable Presentation using the specified Bearer DID, selecting the
+    /// verification method by relationship, algorithm preference and/or key id.
+    ///
+    /// This allows a presentation to be signed with an `authentication` key rather than an
+    /// `assertionMethod` key.
+    ///
+    /// A controller of the holder may sign by selecting one of its `capabilityInvocation` or
+    /// `capabilityDelegation` verification methods. Verifiers accept the signature if the
+    /// holder's DID document lists the signer as a controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the presentation.
+    /// * `options` - How to select the verification method to sign with.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the signed JWT, serialized as a compact JWS, of the Verifiable Presentation.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let vp_jwt = verifiable_presentation
+    ///     .sign_with_options(
+    ///         &holder_bearer_did,
+    ///         &SignerOptions {
+    ///             verification_relationship: Some(VerificationRelationship::Authentication),
+    ///             ..Default::default()
+    ///         },
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn sign_with_options(
+        &self,
+        bearer_did: &BearerDid,
+        options: &SignerOptions,
+    ) -> Result<String> {
+        sign_presentation_with_options(self, bearer_did, options)
+    }
+
+    /// Secures the Verifiable Presentation per [VC-JOSE-COSE](https://www.w3.org/TR/vc-jose-cose/),
+    /// signing the presentation itself as the JWS payload with `typ: vp+jwt` and `cty: vp`, rather
+    /// than wrapping it in a `vp` claim as [`Self::sign`] does.
+    ///
+    /// [`Self::from_vp_jwt`] detects and verifies either securing mechanism.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the presentation.
+    /// * `options` - How to select the verification method to sign with.
+    ///
+    /// # Returns
+    ///
+    /// A string representing the signed presentation, serialized as a compact JWS.
+    pub fn sign_vp_jose(&self, bearer_did: &BearerDid, options: &SignerOptions) -> Result<String> {
+        sign_enveloped(
+            self,
+            &self.holder,
+            VP_JWT_MEDIA_TYPE,
+            VP_CONTENT_TYPE,
+            bearer_did,
+            options,
+        )
+    }
+
+    /// Adds a [Data Integrity](https://www.w3.org/TR/vc-data-integrity/) proof to the Verifiable
+    /// Presentation, signed by an `authentication` verification method of the holder
+    /// with the `eddsa-jcs-2022` or `ecdsa-jcs-2019` cryptosuite. This secures the presentation as
+    /// JSON, as an alternative to securing it as a JWT.
+    ///
+    /// Set the verifier's `challenge` and `domain` in the options, and have the verifier expect
+    /// them via `DataIntegrityVerifyOptions`, to prevent the proof being replayed. The Data
+    /// Integrity context is appended to the presentation's contexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID used to sign the proof.
+    /// * `options` - Optional parameters for the proof, such as the cryptosuite or verification method.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the presentation with its `proof` set, replacing any existing proof.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let secured = verifiable_presentation
+    ///     .add_data_integrity_proof(&holder_bearer_did, None)
+    ///     .unwrap();
+    /// let json = serde_json::to_string(&secured).unwrap();
+    /// ```
+    pub fn add_data_integrity_proof(
+        &self,
+        bearer_did: &BearerDid,
+        options: Option<DataIntegrityProofOptions>,
+    ) -> Result<Self> {
+        let mut verifiable_presentation = self.clone();
+        verifiable_presentation.context =
+            with_data_integrity_context(verifiable_presentation.context, BASE_PRESENTATION_CONTEXT);
+        let proof = create_proof(
+            &verifiable_presentation,
+            &self.holder.to_string(),
+            VerificationRelationship::Authentication,
+            bearer_did,
+            options.unwrap_or_default(),
+        )?;
+        verifiable_presentation.proof = Some(proof);
+        Ok(verifiable_presentation)
+    }
+
+    /// Verifies the Data Integrity proof of the Verifiable Presentation, resolving the
+    /// holder's DID document to find the verification method.
+    ///
+    /// Use [`Self::from_secured_json`] to verify a presentation received as JSON, as the proof covers
+    /// the JSON exactly as signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The challenge and domain the proof must carry, if any.
+    pub async fn verify_data_integrity_proof(
+        &self,
+        options: Option<DataIntegrityVerifyOptions>,
+    ) -> Result<()> {
+        verify_proofs(
+            &serde_json::to_value(self)?,
+            &self.holder.to_string(),
+            VerificationRelationship::Authentication,
+            &options.unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Constructs a Verifiable Presentation secured with a Data Integrity proof from its JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The Verifiable Presentation, including its `proof`, serialized as JSON.
+    /// * `verify` - If true, verifies the proof against the JSON as received, and validates the VP Data Model and its Verifiable Credentials.
+    /// * `options` - The challenge and domain the proof must carry, if any.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let verifiable_presentation = VerifiablePresentation::from_secured_json(&json, true, None).await.unwrap();
+    /// ```
+    pub async fn from_secured_json(
+        json: &str,
+        verify: bool,
+        options: Option<DataIntegrityVerifyOptions>,
+    ) -> Result<Self> {
+        let secured_document: Value = serde_json::from_str(json)?;
+        let verifiable_presentation: Self = serde_json::from_value(secured_document.clone())?;
+
+        if verify {
+            verify_proofs(
+                &secured_document,
+                &verifiable_presentation.holder.to_string(),
+                VerificationRelationship::Authentication,
+                &options.unwrap_or_default(),
+            )
+            .await?;
+            validate_vp_data_model(&verifiable_presentation).await?;
+        }
+
+        Ok(verifiable_presentation)
+    }
 }
 
 pub fn sign_presentation_with_did(
@@ -277,7 +493,23 @@ pub fn sign_presentation_with_did(
     bearer_did: &BearerDid,
     verification_method_id: Option<String>,
 ) -> Result<String> {
-    if !vp.holder.starts_with(&bearer_did.did.uri) {
+    sign_presentation_with_options(
+        vp,
+        bearer_did,
+        &SignerOptions {
+            verification_method_id,
+            verification_relationship: Some(VerificationRelationship::AssertionMethod),
+            algorithms: None,
+        },
+    )
+}
+
+pub fn sign_presentation_with_options(
+    vp: &VerifiablePresentation,
+    bearer_did: &BearerDid,
+    options: &SignerOptions,
+) -> Result<String> {
+    if !vp.holder.starts_with(&bearer_did.did.uri) && !signs_as_controller(options) {
         return Err(Web5Error::Parameter(format!(
             "Bearer DID URI {} does not match holder {}",
             bearer_did.did.uri, vp.holder
@@ -309,7 +541,7 @@ pub fn sign_presentation_with_did(
         additional_properties: Some(additional_properties),
     };
 
-    let jwt = Jwt::from_claims(&claims, bearer_did, verification_method_id)?;
+    let jwt = Jwt::from_claims_with_options(&claims, bearer_did, options)?;
     Ok(jwt.compact_jws)
 }
 
@@ -330,117 +562,142 @@ fn build_vp_type(r#type: Option<Vec<String>>) -> Vec<String> {
 }
 
 pub async fn decode_vp_jwt(vp_jwt: &str, verify_signature: bool) -> Result<VerifiablePresentation> {
-    let jwt = Jwt::from_compact_jws(vp_jwt, verify_signature).await?;
+    let jws = Jws::from_compact_jws(vp_jwt, verify_signature).await?;
+    check_media_type(&jws.header, VP_JWT_MEDIA_TYPE)?;
+
+    let decoded = decode_presentation(&jws)?;
+    // an empty holder is reported by data model validation
+    if verify_signature && !decoded.holder.is_empty() {
+        verify_controller_authorization(&decoded.holder, &jws.kid).await?;
+    }
+
+    match decoded.claim_errors.into_iter().next() {
+        Some(claim_error) => Err(claim_error.into()),
+        None => Ok(decoded.presentation),
+    }
+}
+
+/// A presentation decoded from the payload of a VP JWT, whose signer is not yet authorized.
+pub(crate) struct DecodedPresentation {
+    pub presentation: VerifiablePresentation,
+    pub mechanism: JwtSecuringMechanism,
+    /// The holder the signer must be authorized by: the `iss` claim of a VP-JWT, or else the
+    /// holder of the presentation.
+    pub holder: String,
+    /// The inconsistencies between the registered claims of a VP-JWT and its `vp` claim, in the
+    /// order they were found. Always empty for VC-JOSE-COSE, which has no `vp` claim.
+    pub claim_errors: Vec<VerificationError>,
+}
+
+/// Decodes the presentation of a JWS without verifying the JWS.
+pub(crate) fn decode_presentation(jws: &Jws) -> Result<DecodedPresentation> {
+    let mechanism = JwtSecuringMechanism::detect(&jws.header, &jws.payload);
+    if mechanism == JwtSecuringMechanism::VcJoseCose {
+        let presentation = VerifiablePresentation::from_json_byte_array(&jws.payload)?;
+        return Ok(DecodedPresentation {
+            holder: presentation.holder.clone(),
+            presentation,
+            mechanism,
+            claim_errors: vec![],
+        });
+    }
+
+    let claims = JwtClaims::from_json_byte_array(&jws.payload)?;
 
-    let jti = jwt
-        .claims
+    let jti = claims
         .jti
         .ok_or(VerificationError::MissingClaim("jti".to_string()))?;
-    let iss = jwt
-        .claims
+    let iss = claims
         .iss
         .ok_or(VerificationError::MissingClaim("issuer".to_string()))?;
-    let nbf = jwt
-        .claims
+    let nbf = claims
         .nbf
         .ok_or(VerificationError::MissingClaim("not_before".to_string()))?;
-    let exp = jwt.claims.exp;
+    let exp = claims.exp;
 
     let vp_payload = JwtPayloadVerifiablePresentation::from_json_value(
-        jwt.claims
+        claims
             .additional_properties
             .ok_or(VerificationError::MissingClaim("vp".to_string()))?
             .get("vp")
             .ok_or(VerificationError::MissingClaim("vp".to_string()))?,
     )?;
 
-    if let Some(id) = vp_payload.id {
-        if id != jti {
-            return Err(VerificationError::ClaimMismatch("id".to_string()).into());
+    let mut claim_errors = vec![];
+
+    if let Some(id) = &vp_payload.id {
+        if *id != jti {
+            claim_errors.push(VerificationError::ClaimMismatch("id".to_string()));
         }
     }
 
-    let vp_issuer = vp_payload.holder.clone();
-    if let Some(holder) = vp_payload.holder {
-        if iss != holder {
-            return Err(VerificationError::ClaimMismatch("holder".to_string()).into());
+    if let Some(holder) = &vp_payload.holder {
+        if iss != *holder {
+            claim_errors.push(VerificationError::ClaimMismatch("holder".to_string()));
         }
     }
 
-    let verifiable_presentation = VerifiablePresentation {
+    let presentation = VerifiablePresentation {
         context: vp_payload.context,
-        id: jti.to_string(),
+        id: jti,
         r#type: vp_payload.r#type,
-        holder: vp_issuer.unwrap_or_default(),
+        holder: vp_payload.holder.unwrap_or_default(),
         issuance_date: nbf,
         expiration_date: exp,
         verifiable_credential: vp_payload.verifiable_credential,
+        proof: None,
         additional_data: vp_payload.additional_data,
     };
 
-    Ok(verifiable_presentation)
+    Ok(DecodedPresentation {
+        presentation,
+        mechanism,
+        holder: iss,
+        claim_errors,
+    })
 }
 
 pub async fn validate_vp_data_model(
     vp: &VerifiablePresentation,
 ) -> std::result::Result<(), VerificationError> {
+    VerificationPolicy::default().verify_presentation(vp).await
+}
+
+pub(crate) fn vp_required_field_errors(vp: &VerifiablePresentation) -> Vec<VerificationError> {
+    let mut errors = vec![];
+
     // Required fields ["@context", "id", "type", "holder", "verifiableCredential"]
     if vp.id.is_empty() {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing id".to_string(),
         ));
     }
 
     if vp.context.is_empty() || vp.context[0] != BASE_PRESENTATION_CONTEXT {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing or invalid context".to_string(),
         ));
     }
 
     if vp.r#type.is_empty() || vp.r#type[0] != BASE_PRESENTATION_TYPE {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing or invalid type".to_string(),
         ));
     }
 
     if vp.holder.is_empty() {
-        return Err(VerificationError::DataModelValidationError(
+        errors.push(VerificationError::DataModelValidationError(
             "missing holder".to_string(),
         ));
     }
 
-    let now: SystemTime = Utc::now().into();
-    if vp.issuance_date > now {
-        return Err(VerificationError::DataModelValidationError(
-            "issuance date in future".to_string(),
-        ));
-    }
-
-    // Validate expiration date if it exists
-    if let Some(expiration_date) = &vp.expiration_date {
-        if expiration_date < &now {
-            return Err(VerificationError::DataModelValidationError(
-                "presentation expired".to_string(),
-            ));
-        }
-    }
-
-    // Verify vc_jwts
-    for vc_jwt in vp.verifiable_credential.clone() {
-        VerifiableCredential::from_vc_jwt(&vc_jwt, true)
-            .await
-            .map_err(|e| {
-                VerificationError::DataModelValidationError(format!("invalid vc_jwt: {}", e))
-            })?;
-    }
-
-    Ok(())
+    errors
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::credentials::{CredentialSubject, Issuer};
+    use crate::credentials::{CredentialSubject, Issuer, VerifiableCredential};
     use crate::dids::methods::did_jwk::DidJwk;
 
     fn setup_vc_issuer_and_holder() -> (BearerDid, String, BearerDid, String) {
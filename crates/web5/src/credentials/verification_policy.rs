@@ -0,0 +1,1679 @@
+use super::credential_schema::{credential_schema_violations, schema_violations_error};
+use super::data_model_validation::{
+    validate_validity_period, vc_2_0_required_field_errors, vc_required_field_errors, EXPIRED,
+    VC_1_1_NOT_YET_VALID, VC_2_0_NOT_YET_VALID,
+};
+use super::decode::decode_credential;
+use super::vc_jose_cose::{
+    check_media_type, JwtSecuringMechanism, VC_JWT_MEDIA_TYPE, VP_JWT_MEDIA_TYPE,
+};
+use super::verifiable_credential_1_1::CredentialStatus;
+use super::verifiable_credential_2_0::{DataModelVersion, VersionedVerifiableCredential};
+use super::verifiable_presentation_1_1::{
+    decode_presentation, vp_required_field_errors, VerifiablePresentation,
+};
+use super::verify::{check_credential_status, default_status_list_cache, StatusListCache};
+use super::{CredentialSchema, VerificationError};
+use crate::crypto::dsa::Dsa;
+use crate::dids::controller::verify_controller_authorization;
+use crate::dids::data_model::document::{Document, FindVerificationMethodOptions};
+use crate::dids::did::Did;
+use crate::errors::{Result, Web5Error};
+use crate::jose::Jws;
+use crate::json::ToJson;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const VP_NOT_YET_VALID: &str = "issuance date in future";
+const VP_EXPIRED: &str = "presentation expired";
+
+// the codes of checks that passed or were skipped; failed checks are coded by their error
+const PASSED: &str = "passed";
+const NOT_REQUIRED: &str = "not_required";
+const NOT_APPLICABLE: &str = "not_applicable";
+const PREREQUISITE_FAILED: &str = "prerequisite_failed";
+
+const VC_CHECKS: [VerificationCheck; 11] = [
+    VerificationCheck::Format,
+    VerificationCheck::KidResolution,
+    VerificationCheck::Algorithm,
+    VerificationCheck::Signature,
+    VerificationCheck::Claims,
+    VerificationCheck::DataModel,
+    VerificationCheck::ValidityPeriod,
+    VerificationCheck::Issuer,
+    VerificationCheck::Type,
+    VerificationCheck::Schema,
+    VerificationCheck::Status,
+];
+
+const VP_CHECKS: [VerificationCheck; 8] = [
+    VerificationCheck::Format,
+    VerificationCheck::KidResolution,
+    VerificationCheck::Algorithm,
+    VerificationCheck::Signature,
+    VerificationCheck::Claims,
+    VerificationCheck::DataModel,
+    VerificationCheck::ValidityPeriod,
+    VerificationCheck::Credentials,
+];
+
+/// Represents the requirements a Verifiable Credential or Presentation must meet to be verified,
+/// beyond a valid signature and data model.
+///
+/// The default policy verifies as `from_vc_jwt` and `from_vp_jwt` do: at the current time without
+/// leeway, trusting any issuer, and validating the credential schema only if one is given.
+#[derive(Debug, Default, Clone)]
+pub struct VerificationPolicy {
+    /// The time at which to verify, such as the time a presentation was received. If not
+    /// provided, defaults to the current time.
+    pub current_time: Option<SystemTime>,
+
+    /// The tolerated clock skew between the issuer and the verifier when checking validity
+    /// periods. If not provided, defaults to none.
+    pub leeway: Option<Duration>,
+
+    /// If true, expired credentials and presentations are accepted, such as for auditing past
+    /// verifications. The report notes the expiry.
+    pub allow_expired: bool,
+
+    /// The issuers to trust. A credential passes if its issuer is in this list, or uses one of
+    /// `allowed_issuer_did_methods`. If neither is provided, any issuer is trusted.
+    pub allowed_issuers: Option<Vec<String>>,
+
+    /// The DID methods of the issuers to trust, such as `dht` or `web`.
+    pub allowed_issuer_did_methods: Option<Vec<String>>,
+
+    /// The types every credential must have, such as `EmploymentCredential`.
+    pub required_types: Option<Vec<String>>,
+
+    /// The JWS algorithms to accept, such as `Ed25519`. The `alg` header must also be that of the
+    /// signing key. If not provided, any supported algorithm is accepted.
+    pub allowed_algorithms: Option<Vec<String>>,
+
+    /// If true, fetches the status list credential of each credential's `credentialStatus` over
+    /// https, and fails if the credential is revoked or suspended.
+    pub check_status: bool,
+
+    /// If true, credentials must have a `credentialSchema`.
+    pub require_schema: bool,
+
+    /// The cache of status list credentials fetched by `check_status`. If not provided, a cache
+    /// shared by all policies without one is used.
+    pub status_list_cache: Option<Arc<StatusListCache>>,
+}
+
+/// A check made in verifying a Verifiable Credential or Presentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationCheck {
+    /// The token is a compact JWS of the expected media type.
+    Format,
+    /// The DID of the verification method identified by the `kid` header resolves.
+    KidResolution,
+    /// The JWS algorithm is that of the verification method's key, and is allowed by the policy.
+    Algorithm,
+    /// The signature is valid, and the signer may sign on behalf of the issuer or holder.
+    Signature,
+    /// The registered claims of the JWT are consistent with the credential or presentation.
+    Claims,
+    /// The properties required by the data model are present.
+    DataModel,
+    /// The validity period includes the time of verification.
+    ValidityPeriod,
+    /// The issuer is trusted by the policy.
+    Issuer,
+    /// The credential has the types required by the policy.
+    Type,
+    /// The credential conforms to its credential schema.
+    Schema,
+    /// The credential is neither revoked nor suspended.
+    Status,
+    /// The credentials of the presentation are verified.
+    Credentials,
+}
+
+/// Whether a check passed, failed, or was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationOutcome {
+    Passed,
+    Failed,
+    /// The check wasn't required by the policy, didn't apply, or couldn't be made because a
+    /// check it depends on failed.
+    Skipped,
+}
+
+/// The outcome of a check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationCheckResult {
+    pub check: VerificationCheck,
+    pub outcome: VerificationOutcome,
+    /// A machine-readable code for the outcome: `passed`; for a skipped check `not_required`,
+    /// `not_applicable` or `prerequisite_failed`; and for a failed check the reason, such as
+    /// `claim_mismatch` or `expired`.
+    pub code: String,
+    /// What the check established, or why it failed or was skipped.
+    pub detail: String,
+}
+
+/// Reports the checks made in verifying a Verifiable Credential or Presentation, in the order
+/// they were made. A check that found several problems, such as several claim mismatches, has a
+/// failed result for each.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VerificationReport {
+    pub checks: Vec<VerificationCheckResult>,
+    /// The reports of the credentials of a presentation, in order.
+    pub credentials: Vec<VerificationReport>,
+}
+
+impl ToJson for VerificationReport {}
+
+impl VerificationReport {
+    /// The result of the check, or its first result if it found several problems.
+    pub fn check(&self, check: VerificationCheck) -> Option<&VerificationCheckResult> {
+        self.checks.iter().find(|result| result.check == check)
+    }
+
+    /// The failed results, not including those of the credentials of a presentation.
+    pub fn failures(&self) -> Vec<&VerificationCheckResult> {
+        self.checks
+            .iter()
+            .filter(|result| result.outcome == VerificationOutcome::Failed)
+            .collect()
+    }
+
+    /// Whether no check failed, including those of the credentials of a presentation.
+    pub fn is_verified(&self) -> bool {
+        self.failures().is_empty() && self.credentials.iter().all(Self::is_verified)
+    }
+}
+
+/// Records the results of checks and the error of the first failure. When failing fast, records
+/// nothing after the first failure.
+struct Checks {
+    report: VerificationReport,
+    error: Option<Web5Error>,
+    fail_fast: bool,
+}
+
+impl Checks {
+    fn new(fail_fast: bool) -> Self {
+        Self {
+            report: VerificationReport::default(),
+            error: None,
+            fail_fast,
+        }
+    }
+
+    fn halted(&self) -> bool {
+        self.fail_fast && self.error.is_some()
+    }
+
+    fn passed(&self, check: VerificationCheck) -> bool {
+        self.report
+            .check(check)
+            .is_some_and(|result| result.outcome == VerificationOutcome::Passed)
+    }
+
+    fn record(
+        &mut self,
+        check: VerificationCheck,
+        outcome: VerificationOutcome,
+        code: &str,
+        detail: String,
+    ) {
+        if self.halted() {
+            return;
+        }
+
+        self.report.checks.push(VerificationCheckResult {
+            check,
+            outcome,
+            code: code.to_string(),
+            detail,
+        });
+    }
+
+    fn pass(&mut self, check: VerificationCheck, detail: impl Into<String>) {
+        self.record(check, VerificationOutcome::Passed, PASSED, detail.into());
+    }
+
+    fn skip(&mut self, check: VerificationCheck, code: &str, detail: impl Into<String>) {
+        self.record(check, VerificationOutcome::Skipped, code, detail.into());
+    }
+
+    fn skip_all(&mut self, checks: &[VerificationCheck], detail: &str) {
+        for check in checks {
+            self.skip(*check, PREREQUISITE_FAILED, detail);
+        }
+    }
+
+    fn fail(&mut self, check: VerificationCheck, error: impl Into<Web5Error>) {
+        let error = error.into();
+        self.fail_with_code(check, error_code(&error), error);
+    }
+
+    fn fail_with_code(
+        &mut self,
+        check: VerificationCheck,
+        code: &str,
+        error: impl Into<Web5Error>,
+    ) {
+        let error = error.into();
+        self.record(check, VerificationOutcome::Failed, code, error.to_string());
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    fn finish<T>(self, value: Option<T>) -> Verification<T> {
+        Verification {
+            value,
+            report: self.report,
+            error: self.error,
+        }
+    }
+}
+
+/// The outcome of verifying a credential or presentation: what was decoded of it, the report,
+/// and the error of the first failed check.
+struct Verification<T> {
+    value: Option<T>,
+    report: VerificationReport,
+    error: Option<Web5Error>,
+}
+
+impl<T> Verification<T> {
+    fn into_result(self) -> Result<(T, VerificationReport)> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        match self.value {
+            Some(value) => Ok((value, self.report)),
+            None => Err(Web5Error::Unknown(
+                "verification neither decoded nor failed".to_string(),
+            )),
+        }
+    }
+}
+
+impl VerificationPolicy {
+    /// Verifies a VC JWT, making every check rather than stopping at the first that fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_jwt` - The Verifiable Credential in JWT format, serialized as a compact JWS.
+    ///
+    /// # Returns
+    ///
+    /// A report with the result of each check. Checks that can't be made, such as the data
+    /// model of a credential that can't be decoded, are skipped.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let report = VerificationPolicy::default().verify_vc_jwt(vc_jwt).await;
+    /// for failure in report.failures() {
+    ///     println!("{:?} failed: {} ({})", failure.check, failure.detail, failure.code);
+    /// }
+    /// ```
+    pub async fn verify_vc_jwt(&self, vc_jwt: &str) -> VerificationReport {
+        self.verification_of_vc_jwt(vc_jwt, None, false)
+            .await
+            .report
+    }
+
+    /// Verifies a VP JWT and each of its credentials, making every check rather than stopping at
+    /// the first that fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `vp_jwt` - The Verifiable Presentation in JWT format, serialized as a compact JWS.
+    ///
+    /// # Returns
+    ///
+    /// A report with the result of each check, and the reports of the credentials.
+    pub async fn verify_vp_jwt(&self, vp_jwt: &str) -> VerificationReport {
+        self.verification_of_vp_jwt(vp_jwt, false).await.report
+    }
+
+    /// Verifies a VC JWT, decoding it as the given data model version, or as the version of its
+    /// base context if not given. Fails with the error of the first check that fails.
+    pub(crate) async fn verify_vc_jwt_fail_fast(
+        &self,
+        vc_jwt: &str,
+        version: Option<DataModelVersion>,
+    ) -> Result<(VersionedVerifiableCredential, VerificationReport)> {
+        self.verification_of_vc_jwt(vc_jwt, version, true)
+            .await
+            .into_result()
+    }
+
+    /// Verifies a VP JWT, and the credentials of the presentation. Fails with the error of the
+    /// first check that fails.
+    pub(crate) async fn verify_vp_jwt_fail_fast(
+        &self,
+        vp_jwt: &str,
+    ) -> Result<(VerifiablePresentation, VerificationReport)> {
+        self.verification_of_vp_jwt(vp_jwt, true)
+            .await
+            .into_result()
+    }
+
+    /// Verifies the data model and validity period of a presentation whose signature or proof is
+    /// verified, and its credentials.
+    pub(crate) async fn verify_presentation(
+        &self,
+        verifiable_presentation: &VerifiablePresentation,
+    ) -> std::result::Result<(), VerificationError> {
+        let mut checks = Checks::new(true);
+        self.check_presentation(verifiable_presentation, &mut checks)
+            .await;
+
+        match checks.error {
+            None => Ok(()),
+            Some(Web5Error::CredentialError(error)) => Err(error),
+            Some(error) => Err(VerificationError::DataModelValidationError(
+                error.to_string(),
+            )),
+        }
+    }
+
+    async fn verification_of_vc_jwt(
+        &self,
+        vc_jwt: &str,
+        version: Option<DataModelVersion>,
+        fail_fast: bool,
+    ) -> Verification<VersionedVerifiableCredential> {
+        let mut checks = Checks::new(fail_fast);
+        let credential = self.check_vc_jwt(vc_jwt, version, &mut checks).await;
+        checks.finish(credential)
+    }
+
+    async fn verification_of_vp_jwt(
+        &self,
+        vp_jwt: &str,
+        fail_fast: bool,
+    ) -> Verification<VerifiablePresentation> {
+        let mut checks = Checks::new(fail_fast);
+        let presentation = self.check_vp_jwt(vp_jwt, &mut checks).await;
+        checks.finish(presentation)
+    }
+
+    async fn check_vc_jwt(
+        &self,
+        vc_jwt: &str,
+        version: Option<DataModelVersion>,
+        checks: &mut Checks,
+    ) -> Option<VersionedVerifiableCredential> {
+        let jws = match check_format(vc_jwt, VC_JWT_MEDIA_TYPE, checks) {
+            Some(jws) => jws,
+            None => {
+                checks.skip_all(&VC_CHECKS[1..], "the token is malformed");
+                return None;
+            }
+        };
+
+        let document = check_kid_resolution(&jws, checks).await;
+        self.check_algorithm(&jws, document.as_ref(), checks);
+
+        let decoded = decode_credential(&jws, version);
+        let issuer = decoded.as_ref().ok().map(|decoded| decoded.issuer.as_str());
+        check_signature(&jws, document.as_ref(), issuer, checks).await;
+
+        let decoded = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                checks.fail(VerificationCheck::Claims, e);
+                checks.skip_all(&VC_CHECKS[5..], "the credential can't be decoded");
+                return None;
+            }
+        };
+        check_claims(decoded.mechanism, decoded.claim_errors, checks);
+
+        match &decoded.credential {
+            VersionedVerifiableCredential::V1_1(vc) => {
+                check_data_model(vc_required_field_errors(vc), "data model 1.1", checks);
+                self.check_validity_period(
+                    Some(vc.issuance_date),
+                    vc.expiration_date,
+                    VC_1_1_NOT_YET_VALID,
+                    EXPIRED,
+                    checks,
+                );
+                self.check_credential(
+                    &vc.issuer.to_string(),
+                    &vc.r#type,
+                    vc.credential_schema.as_ref(),
+                    vc.credential_status.as_ref(),
+                    vc,
+                    checks,
+                )
+                .await;
+            }
+            VersionedVerifiableCredential::V2_0(vc) => {
+                check_data_model(vc_2_0_required_field_errors(vc), "data model 2.0", checks);
+                self.check_validity_period(
+                    vc.valid_from,
+                    vc.valid_until,
+                    VC_2_0_NOT_YET_VALID,
+                    EXPIRED,
+                    checks,
+                );
+                self.check_credential(
+                    &vc.issuer.to_string(),
+                    &vc.r#type,
+                    vc.credential_schema.as_ref(),
+                    vc.credential_status.as_ref(),
+                    vc,
+                    checks,
+                )
+                .await;
+            }
+        }
+
+        Some(decoded.credential)
+    }
+
+    async fn check_vp_jwt(
+        &self,
+        vp_jwt: &str,
+        checks: &mut Checks,
+    ) -> Option<VerifiablePresentation> {
+        let jws = match check_format(vp_jwt, VP_JWT_MEDIA_TYPE, checks) {
+            Some(jws) => jws,
+            None => {
+                checks.skip_all(&VP_CHECKS[1..], "the token is malformed");
+                return None;
+            }
+        };
+
+        let document = check_kid_resolution(&jws, checks).await;
+        self.check_algorithm(&jws, document.as_ref(), checks);
+
+        let decoded = decode_presentation(&jws);
+        let holder = decoded.as_ref().ok().map(|decoded| decoded.holder.as_str());
+        check_signature(&jws, document.as_ref(), holder, checks).await;
+
+        let decoded = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                checks.fail(VerificationCheck::Claims, e);
+                checks.skip_all(&VP_CHECKS[5..], "the presentation can't be decoded");
+                return None;
+            }
+        };
+        check_claims(decoded.mechanism, decoded.claim_errors, checks);
+
+        self.check_presentation(&decoded.presentation, checks).await;
+
+        Some(decoded.presentation)
+    }
+
+    async fn check_presentation(
+        &self,
+        verifiable_presentation: &VerifiablePresentation,
+        checks: &mut Checks,
+    ) {
+        check_data_model(
+            vp_required_field_errors(verifiable_presentation),
+            "data model 1.1",
+            checks,
+        );
+        self.check_validity_period(
+            Some(verifiable_presentation.issuance_date),
+            verifiable_presentation.expiration_date,
+            VP_NOT_YET_VALID,
+            VP_EXPIRED,
+            checks,
+        );
+
+        let mut verified = 0;
+        for vc_jwt in &verifiable_presentation.verifiable_credential {
+            if checks.halted() {
+                return;
+            }
+
+            let verification = self
+                .verification_of_vc_jwt(vc_jwt, None, checks.fail_fast)
+                .await;
+            checks.report.credentials.push(verification.report);
+            match verification.error {
+                None => verified += 1,
+                Some(e) => checks.fail_with_code(
+                    VerificationCheck::Credentials,
+                    "invalid_credential",
+                    VerificationError::DataModelValidationError(format!("invalid vc_jwt: {}", e)),
+                ),
+            }
+        }
+
+        if verified == verifiable_presentation.verifiable_credential.len() {
+            checks.pass(
+                VerificationCheck::Credentials,
+                format!("{} credentials verified", verified),
+            );
+        }
+    }
+
+    /// Checks the `alg` header is that of the key of the signing verification method, as the key
+    /// chooses the verifier, and that it's allowed.
+    fn check_algorithm(&self, jws: &Jws, document: Option<&Document>, checks: &mut Checks) {
+        let allowed_algorithms = match &self.allowed_algorithms {
+            Some(allowed_algorithms) => allowed_algorithms,
+            None => {
+                checks.skip(
+                    VerificationCheck::Algorithm,
+                    NOT_REQUIRED,
+                    "any supported algorithm is allowed",
+                );
+                return;
+            }
+        };
+
+        let public_key_jwk = match document.and_then(|document| {
+            document
+                .find_verification_method(FindVerificationMethodOptions {
+                    verification_method_id: Some(jws.kid.clone()),
+                })
+                .ok()
+        }) {
+            Some(verification_method) => verification_method.public_key_jwk,
+            None => {
+                checks.skip(
+                    VerificationCheck::Algorithm,
+                    PREREQUISITE_FAILED,
+                    "the kid didn't resolve to a verification method",
+                );
+                return;
+            }
+        };
+
+        let alg = jws.header.alg.clone().unwrap_or_default();
+        let key_dsa = Dsa::from_str(&public_key_jwk.crv).ok();
+        if key_dsa.is_none() || Dsa::from_str(&alg).ok() != key_dsa {
+            checks.fail(
+                VerificationCheck::Algorithm,
+                VerificationError::DisallowedAlgorithm(format!(
+                    "{} does not match the {} key of {}",
+                    alg, public_key_jwk.crv, jws.kid
+                )),
+            );
+            return;
+        }
+
+        if allowed_algorithms.contains(&alg) {
+            checks.pass(VerificationCheck::Algorithm, format!("{} is allowed", alg));
+        } else {
+            checks.fail(
+                VerificationCheck::Algorithm,
+                VerificationError::DisallowedAlgorithm(alg),
+            );
+        }
+    }
+
+    fn check_validity_period(
+        &self,
+        valid_from: Option<SystemTime>,
+        valid_until: Option<SystemTime>,
+        not_yet_valid_message: &str,
+        expired_message: &str,
+        checks: &mut Checks,
+    ) {
+        let now = self.current_time.unwrap_or_else(|| Utc::now().into());
+        let leeway = self.leeway.unwrap_or_default();
+
+        if let Err(e) = validate_validity_period(
+            valid_from,
+            None,
+            not_yet_valid_message,
+            expired_message,
+            now,
+            leeway,
+        ) {
+            checks.fail_with_code(VerificationCheck::ValidityPeriod, "not_yet_valid", e);
+            return;
+        }
+
+        match valid_until.filter(|valid_until| *valid_until + leeway < now) {
+            None => checks.pass(
+                VerificationCheck::ValidityPeriod,
+                format!("valid at {}", rfc3339(now)),
+            ),
+            Some(expired_at) if self.allow_expired => checks.pass(
+                VerificationCheck::ValidityPeriod,
+                format!(
+                    "expired at {}, which the policy allows",
+                    rfc3339(expired_at)
+                ),
+            ),
+            Some(_) => checks.fail_with_code(
+                VerificationCheck::ValidityPeriod,
+                "expired",
+                VerificationError::DataModelValidationError(expired_message.to_string()),
+            ),
+        }
+    }
+
+    async fn check_credential<T: Serialize>(
+        &self,
+        issuer: &str,
+        types: &[String],
+        credential_schema: Option<&CredentialSchema>,
+        credential_status: Option<&CredentialStatus>,
+        credential: &T,
+        checks: &mut Checks,
+    ) {
+        self.check_issuer(issuer, checks);
+        self.check_types(types, checks);
+
+        // both fetch documents named by the credential, which aren't worth fetching for a
+        // credential that isn't known to be signed by its issuer
+        if !checks.passed(VerificationCheck::Signature) {
+            checks.skip_all(
+                &[VerificationCheck::Schema, VerificationCheck::Status],
+                "the signature isn't verified",
+            );
+            return;
+        }
+
+        self.check_schema(credential_schema, credential, checks)
+            .await;
+        self.check_status(issuer, credential_status, checks).await;
+    }
+
+    fn check_issuer(&self, issuer: &str, checks: &mut Checks) {
+        if self.allowed_issuers.is_none() && self.allowed_issuer_did_methods.is_none() {
+            checks.skip(
+                VerificationCheck::Issuer,
+                NOT_REQUIRED,
+                "any issuer is trusted",
+            );
+            return;
+        }
+
+        if let Some(allowed_issuers) = &self.allowed_issuers {
+            if allowed_issuers.iter().any(|allowed| allowed == issuer) {
+                checks.pass(VerificationCheck::Issuer, format!("{} is allowed", issuer));
+                return;
+            }
+        }
+
+        if let (Some(allowed_issuer_did_methods), Ok(did)) =
+            (&self.allowed_issuer_did_methods, Did::parse(issuer))
+        {
+            if allowed_issuer_did_methods.contains(&did.method) {
+                checks.pass(
+                    VerificationCheck::Issuer,
+                    format!("did:{} is allowed", did.method),
+                );
+                return;
+            }
+        }
+
+        checks.fail(
+            VerificationCheck::Issuer,
+            VerificationError::UntrustedIssuer(issuer.to_string()),
+        );
+    }
+
+    fn check_types(&self, types: &[String], checks: &mut Checks) {
+        let required_types = match &self.required_types {
+            Some(required_types) => required_types,
+            None => {
+                checks.skip(
+                    VerificationCheck::Type,
+                    NOT_REQUIRED,
+                    "no types are required",
+                );
+                return;
+            }
+        };
+
+        let missing_types: Vec<&String> = required_types
+            .iter()
+            .filter(|required_type| !types.contains(required_type))
+            .collect();
+        if missing_types.is_empty() {
+            checks.pass(
+                VerificationCheck::Type,
+                format!("has types {}", required_types.join(", ")),
+            );
+        }
+        for missing_type in missing_types {
+            checks.fail(
+                VerificationCheck::Type,
+                VerificationError::MissingType(missing_type.clone()),
+            );
+        }
+    }
+
+    async fn check_schema<T: Serialize>(
+        &self,
+        credential_schema: Option<&CredentialSchema>,
+        credential: &T,
+        checks: &mut Checks,
+    ) {
+        let credential_schema = match credential_schema {
+            Some(credential_schema) => credential_schema,
+            None if self.require_schema => {
+                checks.fail(
+                    VerificationCheck::Schema,
+                    VerificationError::MissingCredentialSchema,
+                );
+                return;
+            }
+            None => {
+                checks.skip(
+                    VerificationCheck::Schema,
+                    NOT_APPLICABLE,
+                    "no credential schema",
+                );
+                return;
+            }
+        };
+
+        if checks.halted() {
+            return;
+        }
+
+        match credential_schema_violations(credential_schema, credential).await {
+            Ok(violations) if violations.is_empty() => checks.pass(
+                VerificationCheck::Schema,
+                format!("conforms to {}", credential_schema.id),
+            ),
+            Ok(violations) => {
+                // each violation is reported, but fails as validation joining them does
+                for violation in &violations {
+                    checks.record(
+                        VerificationCheck::Schema,
+                        VerificationOutcome::Failed,
+                        "schema_violation",
+                        violation.clone(),
+                    );
+                }
+                if checks.error.is_none() {
+                    checks.error = Some(schema_violations_error(&violations));
+                }
+            }
+            Err(e) => checks.fail(VerificationCheck::Schema, e),
+        }
+    }
+
+    async fn check_status(
+        &self,
+        issuer: &str,
+        credential_status: Option<&CredentialStatus>,
+        checks: &mut Checks,
+    ) {
+        if !self.check_status {
+            checks.skip(
+                VerificationCheck::Status,
+                NOT_REQUIRED,
+                "status is not checked",
+            );
+            return;
+        }
+
+        let credential_status = match credential_status {
+            Some(credential_status) => credential_status,
+            None => {
+                checks.skip(
+                    VerificationCheck::Status,
+                    NOT_APPLICABLE,
+                    "no credential status",
+                );
+                return;
+            }
+        };
+
+        if checks.halted() {
+            return;
+        }
+
+        let status_list_cache = self
+            .status_list_cache
+            .clone()
+            .unwrap_or_else(default_status_list_cache);
+        match check_credential_status(issuer, Some(credential_status), &status_list_cache).await {
+            Ok(()) => checks.pass(
+                VerificationCheck::Status,
+                format!(
+                    "not revoked or suspended in {}",
+                    credential_status.status_list_credential
+                ),
+            ),
+            Err(e) => checks.fail(VerificationCheck::Status, e),
+        }
+    }
+}
+
+fn check_format(compact_jws: &str, media_type: &str, checks: &mut Checks) -> Option<Jws> {
+    let jws = Jws::parse(compact_jws).and_then(|jws| {
+        check_media_type(&jws.header, media_type)?;
+        Ok(jws)
+    });
+
+    match jws {
+        Ok(jws) => {
+            checks.pass(VerificationCheck::Format, "compact JWS");
+            Some(jws)
+        }
+        Err(e) => {
+            checks.fail_with_code(VerificationCheck::Format, "malformed", e);
+            None
+        }
+    }
+}
+
+async fn check_kid_resolution(jws: &Jws, checks: &mut Checks) -> Option<Document> {
+    if checks.halted() {
+        return None;
+    }
+
+    match jws.resolve_kid().await {
+        Ok(document) => {
+            checks.pass(
+                VerificationCheck::KidResolution,
+                format!("resolved {}", jws.kid),
+            );
+            Some(document)
+        }
+        Err(e) => {
+            checks.fail(VerificationCheck::KidResolution, e);
+            None
+        }
+    }
+}
+
+/// Checks the signature against the resolved document, and that the signer may sign on behalf of
+/// the issuer or holder, if it could be decoded.
+async fn check_signature(
+    jws: &Jws,
+    document: Option<&Document>,
+    issuer: Option<&str>,
+    checks: &mut Checks,
+) {
+    let document = match document {
+        Some(document) => document,
+        None => {
+            checks.skip(
+                VerificationCheck::Signature,
+                PREREQUISITE_FAILED,
+                "the kid didn't resolve",
+            );
+            return;
+        }
+    };
+
+    if let Err(e) = jws.verify_with_document(document) {
+        checks.fail_with_code(VerificationCheck::Signature, "invalid_signature", e);
+        return;
+    }
+
+    let issuer = match issuer {
+        Some(issuer) => issuer,
+        None => {
+            checks.skip(
+                VerificationCheck::Signature,
+                PREREQUISITE_FAILED,
+                "the signer can't be authorized without the claims",
+            );
+            return;
+        }
+    };
+
+    // an empty issuer is reported by data model validation
+    if !issuer.is_empty() && !checks.halted() {
+        if let Err(e) = verify_controller_authorization(issuer, &jws.kid).await {
+            checks.fail(VerificationCheck::Signature, e);
+            return;
+        }
+    }
+
+    checks.pass(
+        VerificationCheck::Signature,
+        format!("signed by {}", jws.kid),
+    );
+}
+
+fn check_claims(
+    mechanism: JwtSecuringMechanism,
+    claim_errors: Vec<VerificationError>,
+    checks: &mut Checks,
+) {
+    if mechanism == JwtSecuringMechanism::VcJoseCose {
+        checks.skip(
+            VerificationCheck::Claims,
+            NOT_APPLICABLE,
+            "secured per VC-JOSE-COSE, without registered claims",
+        );
+    } else if claim_errors.is_empty() {
+        checks.pass(
+            VerificationCheck::Claims,
+            "registered claims are consistent",
+        );
+    }
+
+    for claim_error in claim_errors {
+        checks.fail(VerificationCheck::Claims, claim_error);
+    }
+}
+
+fn check_data_model(errors: Vec<VerificationError>, data_model: &str, checks: &mut Checks) {
+    if errors.is_empty() {
+        checks.pass(VerificationCheck::DataModel, data_model);
+    }
+
+    for error in errors {
+        checks.fail(VerificationCheck::DataModel, error);
+    }
+}
+
+/// The machine-readable code of a failed check.
+fn error_code(error: &Web5Error) -> &'static str {
+    match error {
+        Web5Error::CredentialError(error) => match error {
+            VerificationError::MissingClaim(_) => "missing_claim",
+            VerificationError::ClaimMismatch(_) => "claim_mismatch",
+            VerificationError::MisconfiguredExpirationDate(_) => "misconfigured_expiration_date",
+            VerificationError::CredentialExpired => "expired",
+            VerificationError::CredentialRevoked => "revoked",
+            VerificationError::CredentialSuspended => "suspended",
+            VerificationError::DataModelValidationError(_) => "invalid_data_model",
+            VerificationError::MissingKid => "missing_kid",
+            VerificationError::DomainLinkage(_) => "domain_linkage",
+            VerificationError::Authentication(_) => "authentication",
+            VerificationError::UnauthorizedSigner(_) => "unauthorized_signer",
+            VerificationError::DataIntegrityProof(_) => "invalid_proof",
+            VerificationError::SelectiveDisclosure(_) => "selective_disclosure",
+            VerificationError::KeyBinding(_) => "key_binding",
+            VerificationError::UntrustedIssuer(_) => "untrusted_issuer",
+            VerificationError::MissingType(_) => "missing_type",
+            VerificationError::DisallowedAlgorithm(_) => "disallowed_algorithm",
+            VerificationError::MissingCredentialSchema => "missing_schema",
+        },
+        Web5Error::Resolution(_) => "resolution_failed",
+        Web5Error::Network(_) | Web5Error::Http(_) => "network_error",
+        Web5Error::Json(_) => "invalid_json",
+        Web5Error::JsonSchema(_) => "invalid_schema",
+        Web5Error::Crypto(_) => "invalid_signature",
+        _ => "error",
+    }
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::presentation_definition::PresentationDefinition;
+    use crate::credentials::verifiable_credential_1_1::BASE_CONTEXT;
+    use crate::credentials::VerifiablePresentation;
+    use crate::credentials::{
+        CredentialSubject, Issuer, VerifiableCredential, VerifiableCredentialCreateOptions,
+    };
+    use crate::dids::{bearer_did::BearerDid, methods::did_jwk::DidJwk};
+    use base64::Engine;
+    use serde_json::json;
+
+    const SUBJECT_DID_URI: &str = "did:dht:qgmmpyjw5hwnqfgzn7wmrm33ady8gb8z9ideib6m9gj4ys6wny8y";
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    async fn vc_jwt(issuer: &BearerDid, options: VerifiableCredentialCreateOptions) -> String {
+        VerifiableCredential::create(
+            Issuer::from(issuer.did.uri.clone()),
+            CredentialSubject::from(SUBJECT_DID_URI),
+            Some(options),
+        )
+        .await
+        .unwrap()
+        .sign(issuer, None)
+        .unwrap()
+    }
+
+    async fn expired_vc_jwt(issuer: &BearerDid) -> String {
+        let now = SystemTime::now();
+        vc_jwt(
+            issuer,
+            VerifiableCredentialCreateOptions {
+                issuance_date: Some(now - 2 * HOUR),
+                expiration_date: Some(now - HOUR),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_default_policy_report() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+
+        let (_, report) =
+            VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &VerificationPolicy::default())
+                .await
+                .unwrap();
+
+        let checks: Vec<VerificationCheck> = report.checks.iter().map(|r| r.check).collect();
+        assert_eq!(VC_CHECKS.to_vec(), checks);
+        assert!(report.is_verified());
+        assert!(report
+            .check(VerificationCheck::Signature)
+            .unwrap()
+            .detail
+            .contains(&issuer.did.uri));
+
+        let issuer_check = report.check(VerificationCheck::Issuer).unwrap();
+        assert_eq!(VerificationOutcome::Skipped, issuer_check.outcome);
+        assert_eq!(NOT_REQUIRED, issuer_check.code);
+    }
+
+    #[tokio::test]
+    async fn test_expired() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = expired_vc_jwt(&issuer).await;
+
+        let result =
+            VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &VerificationPolicy::default())
+                .await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::DataModelValidationError(msg))) => {
+                assert_eq!(EXPIRED, msg)
+            }
+            _ => panic!("expected credential expired error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allow_expired() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = expired_vc_jwt(&issuer).await;
+        let policy = VerificationPolicy {
+            allow_expired: true,
+            ..Default::default()
+        };
+
+        let (_, report) = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy)
+            .await
+            .unwrap();
+
+        assert!(report
+            .check(VerificationCheck::ValidityPeriod)
+            .unwrap()
+            .detail
+            .ends_with("which the policy allows"));
+    }
+
+    #[tokio::test]
+    async fn test_leeway() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = expired_vc_jwt(&issuer).await;
+        let policy = VerificationPolicy {
+            leeway: Some(2 * HOUR),
+            ..Default::default()
+        };
+
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_current_time() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = expired_vc_jwt(&issuer).await;
+
+        let policy = VerificationPolicy {
+            current_time: Some(SystemTime::now() - HOUR - HOUR / 2),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        assert!(result.is_ok());
+
+        let policy = VerificationPolicy {
+            current_time: Some(SystemTime::now() - 3 * HOUR),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::DataModelValidationError(msg))) => {
+                assert_eq!(VC_1_1_NOT_YET_VALID, msg)
+            }
+            _ => panic!("expected not yet valid error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allowed_issuers() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+
+        let policy = VerificationPolicy {
+            allowed_issuers: Some(vec![issuer.did.uri.clone()]),
+            ..Default::default()
+        };
+        let (_, report) = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy)
+            .await
+            .unwrap();
+        assert!(report.check(VerificationCheck::Issuer).is_some());
+
+        let policy = VerificationPolicy {
+            allowed_issuers: Some(vec![SUBJECT_DID_URI.to_string()]),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::UntrustedIssuer(untrusted))) => {
+                assert_eq!(issuer.did.uri, untrusted)
+            }
+            _ => panic!("expected untrusted issuer error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allowed_issuer_did_methods() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+
+        let policy = VerificationPolicy {
+            allowed_issuer_did_methods: Some(vec!["jwk".to_string()]),
+            ..Default::default()
+        };
+        let (_, report) = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy)
+            .await
+            .unwrap();
+        assert_eq!(
+            "did:jwk is allowed",
+            report.check(VerificationCheck::Issuer).unwrap().detail
+        );
+
+        let policy = VerificationPolicy {
+            allowed_issuer_did_methods: Some(vec!["dht".to_string()]),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::UntrustedIssuer(_))) => {}
+            _ => panic!("expected untrusted issuer error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_required_types() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(
+            &issuer,
+            VerifiableCredentialCreateOptions {
+                r#type: Some(vec!["EmploymentCredential".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let policy = VerificationPolicy {
+            required_types: Some(vec!["EmploymentCredential".to_string()]),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        assert!(result.is_ok());
+
+        let policy = VerificationPolicy {
+            required_types: Some(vec!["EducationCredential".to_string()]),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::MissingType(missing_type))) => {
+                assert_eq!("EducationCredential", missing_type)
+            }
+            _ => panic!("expected missing type error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allowed_algorithms() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+
+        let policy = VerificationPolicy {
+            allowed_algorithms: Some(vec!["Ed25519".to_string()]),
+            ..Default::default()
+        };
+        let (_, report) = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy)
+            .await
+            .unwrap();
+        assert_eq!(
+            VerificationOutcome::Passed,
+            report.check(VerificationCheck::Algorithm).unwrap().outcome
+        );
+
+        let policy = VerificationPolicy {
+            allowed_algorithms: Some(vec!["ES256K".to_string()]),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::DisallowedAlgorithm(alg))) => {
+                assert_eq!("Ed25519", alg)
+            }
+            _ => panic!("expected disallowed algorithm error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_algorithm_must_match_key() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+
+        // re-signed by the Ed25519 key, but claiming ES256K
+        let parts = vc_jwt.split('.').collect::<Vec<&str>>();
+        let mut header: serde_json::Value = serde_json::from_slice(
+            &base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(parts[0])
+                .unwrap(),
+        )
+        .unwrap();
+        header["alg"] = json!("ES256K");
+        let header_part =
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(header.to_string());
+        let message = format!("{}.{}", header_part, parts[1]);
+        let (_, signer) = issuer.select_signer(&Default::default()).unwrap();
+        let signature = signer.sign(message.as_bytes()).unwrap();
+        let vc_jwt = format!(
+            "{}.{}",
+            message,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(signature)
+        );
+
+        // the signature verifies, as the verifier is chosen by the key
+        let (_, report) =
+            VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &VerificationPolicy::default())
+                .await
+                .unwrap();
+        assert!(report.is_verified());
+
+        let policy = VerificationPolicy {
+            allowed_algorithms: Some(vec!["ES256K".to_string()]),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::DisallowedAlgorithm(message))) => {
+                assert!(message.starts_with("ES256K does not match the Ed25519 key"))
+            }
+            _ => panic!("expected disallowed algorithm error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_require_schema() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+        let policy = VerificationPolicy {
+            require_schema: true,
+            ..Default::default()
+        };
+
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::MissingCredentialSchema)) => {}
+            _ => panic!(
+                "expected missing credential schema error but got {:?}",
+                result
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_presentation_report() {
+        let holder = DidJwk::create(None).unwrap();
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwts = vec![
+            vc_jwt(&issuer, Default::default()).await,
+            vc_jwt(&issuer, Default::default()).await,
+        ];
+        let vp_jwt = VerifiablePresentation::create(holder.did.uri.clone(), vc_jwts, None)
+            .await
+            .unwrap()
+            .sign(&holder, None)
+            .unwrap();
+        let policy = VerificationPolicy {
+            allowed_issuers: Some(vec![issuer.did.uri.clone()]),
+            ..Default::default()
+        };
+
+        let (_, report) = VerifiablePresentation::from_vp_jwt_with_policy(&vp_jwt, &policy)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            "2 credentials verified",
+            report.check(VerificationCheck::Credentials).unwrap().detail
+        );
+        assert_eq!(2, report.credentials.len());
+        assert!(report.credentials[0]
+            .check(VerificationCheck::Issuer)
+            .is_some());
+
+        let policy = VerificationPolicy {
+            allowed_issuers: Some(vec![holder.did.uri.clone()]),
+            ..Default::default()
+        };
+        let result = VerifiablePresentation::from_vp_jwt_with_policy(&vp_jwt, &policy).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_select_credentials_with_policy() {
+        let trusted_issuer = DidJwk::create(None).unwrap();
+        let untrusted_issuer = DidJwk::create(None).unwrap();
+        let trusted_vc_jwt = vc_jwt(&trusted_issuer, Default::default()).await;
+        let vc_jwts = vec![
+            vc_jwt(&untrusted_issuer, Default::default()).await,
+            trusted_vc_jwt.clone(),
+        ];
+        let presentation_definition: PresentationDefinition =
+            serde_json::from_value(serde_json::json!({
+                "id": "presentation-definition",
+                "input_descriptors": [{
+                    "id": "subject",
+                    "constraints": {
+                        "fields": [{ "path": ["$.credentialSubject.id"] }]
+                    }
+                }]
+            }))
+            .unwrap();
+        let policy = VerificationPolicy {
+            allowed_issuers: Some(vec![trusted_issuer.did.uri.clone()]),
+            ..Default::default()
+        };
+
+        let selected = presentation_definition
+            .select_credentials_with_policy(&vc_jwts, &policy)
+            .await
+            .unwrap();
+        assert_eq!(vec![trusted_vc_jwt], selected);
+
+        let selected = presentation_definition
+            .select_credentials(&vc_jwts)
+            .await
+            .unwrap();
+        assert_eq!(2, selected.len());
+    }
+
+    fn outcomes(
+        report: &VerificationReport,
+    ) -> Vec<(VerificationCheck, VerificationOutcome, &str)> {
+        report
+            .checks
+            .iter()
+            .map(|result| (result.check, result.outcome, result.code.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_report_makes_every_check() {
+        let issuer = DidJwk::create(None).unwrap();
+        let now = Utc::now();
+        let issuance_date = now - chrono::Duration::hours(2);
+        let expiration_date = now - chrono::Duration::hours(1);
+        let payload = json!({
+            "iss": issuer.did.uri,
+            "jti": "urn:uuid:jti",
+            "sub": SUBJECT_DID_URI,
+            "nbf": issuance_date.timestamp(),
+            "exp": expiration_date.timestamp(),
+            "vc": {
+                "@context": [BASE_CONTEXT],
+                "id": "urn:uuid:id",
+                "type": ["VerifiableCredential"],
+                "issuer": issuer.did.uri,
+                "issuanceDate": rfc3339(issuance_date.into()),
+                "expirationDate": rfc3339(expiration_date.into()),
+                "credentialSubject": { "id": "did:example:someone-else" }
+            }
+        });
+        let vc_jwt = Jws::from_payload(payload.to_string().as_bytes(), &issuer, None)
+            .unwrap()
+            .compact_jws;
+        let policy = VerificationPolicy {
+            allowed_issuer_did_methods: Some(vec!["dht".to_string()]),
+            ..Default::default()
+        };
+
+        let report = policy.verify_vc_jwt(&vc_jwt).await;
+
+        assert_eq!(
+            vec![
+                (
+                    VerificationCheck::Format,
+                    VerificationOutcome::Passed,
+                    PASSED
+                ),
+                (
+                    VerificationCheck::KidResolution,
+                    VerificationOutcome::Passed,
+                    PASSED
+                ),
+                (
+                    VerificationCheck::Algorithm,
+                    VerificationOutcome::Skipped,
+                    NOT_REQUIRED
+                ),
+                (
+                    VerificationCheck::Signature,
+                    VerificationOutcome::Passed,
+                    PASSED
+                ),
+                (
+                    VerificationCheck::Claims,
+                    VerificationOutcome::Failed,
+                    "claim_mismatch"
+                ),
+                (
+                    VerificationCheck::Claims,
+                    VerificationOutcome::Failed,
+                    "claim_mismatch"
+                ),
+                (
+                    VerificationCheck::DataModel,
+                    VerificationOutcome::Passed,
+                    PASSED
+                ),
+                (
+                    VerificationCheck::ValidityPeriod,
+                    VerificationOutcome::Failed,
+                    "expired"
+                ),
+                (
+                    VerificationCheck::Issuer,
+                    VerificationOutcome::Failed,
+                    "untrusted_issuer"
+                ),
+                (
+                    VerificationCheck::Type,
+                    VerificationOutcome::Skipped,
+                    NOT_REQUIRED
+                ),
+                (
+                    VerificationCheck::Schema,
+                    VerificationOutcome::Skipped,
+                    NOT_APPLICABLE
+                ),
+                (
+                    VerificationCheck::Status,
+                    VerificationOutcome::Skipped,
+                    NOT_REQUIRED
+                ),
+            ],
+            outcomes(&report)
+        );
+        assert_eq!(4, report.failures().len());
+        assert!(!report.is_verified());
+
+        // the fail-fast API fails with the first
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::ClaimMismatch(claim))) => {
+                assert_eq!("id", claim)
+            }
+            _ => panic!("expected claim mismatch error but got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_report_malformed() {
+        let report = VerificationPolicy::default()
+            .verify_vc_jwt("not a compact jws")
+            .await;
+
+        let format = report.check(VerificationCheck::Format).unwrap();
+        assert_eq!(VerificationOutcome::Failed, format.outcome);
+        assert_eq!("malformed", format.code);
+        assert_eq!(VC_CHECKS.len(), report.checks.len());
+        assert!(report.checks[1..].iter().all(|result| {
+            result.outcome == VerificationOutcome::Skipped && result.code == PREREQUISITE_FAILED
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_report_unresolvable_kid() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = vc_jwt(&issuer, Default::default()).await;
+        let mut parts: Vec<&str> = vc_jwt.split('.').collect();
+        let header = base64::Engine::encode(
+            &base64::engine::general_purpose::URL_SAFE_NO_PAD,
+            json!({ "alg": "Ed25519", "typ": "JWT", "kid": "did:jwk:invalid#0" }).to_string(),
+        );
+        parts[0] = &header;
+
+        let report = VerificationPolicy::default()
+            .verify_vc_jwt(&parts.join("."))
+            .await;
+
+        let kid_resolution = report.check(VerificationCheck::KidResolution).unwrap();
+        assert_eq!(VerificationOutcome::Failed, kid_resolution.outcome);
+        assert_eq!("resolution_failed", kid_resolution.code);
+        let signature = report.check(VerificationCheck::Signature).unwrap();
+        assert_eq!(VerificationOutcome::Skipped, signature.outcome);
+        assert_eq!(PREREQUISITE_FAILED, signature.code);
+        // the checks of the content are still made
+        assert_eq!(
+            VerificationOutcome::Passed,
+            report.check(VerificationCheck::DataModel).unwrap().outcome
+        );
+    }
+
+    #[tokio::test]
+    async fn test_report_skips_fetches_without_signature() {
+        let issuer = DidJwk::create(None).unwrap();
+        let now = Utc::now();
+        let payload = json!({
+            "iss": issuer.did.uri,
+            "jti": "urn:uuid:id",
+            "sub": SUBJECT_DID_URI,
+            "nbf": now.timestamp(),
+            "vc": {
+                "@context": [BASE_CONTEXT],
+                "id": "urn:uuid:id",
+                "type": ["VerifiableCredential"],
+                "issuer": issuer.did.uri,
+                "issuanceDate": rfc3339(now.into()),
+                "credentialSubject": { "id": SUBJECT_DID_URI },
+                "credentialSchema": {
+                    "id": "https://schema.example.com/credential",
+                    "type": "JsonSchema"
+                },
+                "credentialStatus": {
+                    "id": "https://status.example.com/list#94567",
+                    "type": "BitstringStatusListEntry",
+                    "statusPurpose": "revocation",
+                    "statusListIndex": "94567",
+                    "statusListCredential": "https://status.example.com/list"
+                }
+            }
+        });
+        let vc_jwt = Jws::from_payload(payload.to_string().as_bytes(), &issuer, None)
+            .unwrap()
+            .compact_jws;
+        let (signing_input, _) = vc_jwt.rsplit_once('.').unwrap();
+        let forged = format!("{}.{}", signing_input, "A".repeat(86));
+        let policy = VerificationPolicy {
+            check_status: true,
+            ..Default::default()
+        };
+
+        let report = policy.verify_vc_jwt(&forged).await;
+
+        let signature = report.check(VerificationCheck::Signature).unwrap();
+        assert_eq!(VerificationOutcome::Failed, signature.outcome);
+        for check in [VerificationCheck::Schema, VerificationCheck::Status] {
+            let result = report.check(check).unwrap();
+            assert_eq!(VerificationOutcome::Skipped, result.outcome);
+            assert_eq!(PREREQUISITE_FAILED, result.code);
+        }
+        // the checks made without fetching are still made
+        assert_eq!(
+            VerificationOutcome::Passed,
+            report.check(VerificationCheck::DataModel).unwrap().outcome
+        );
+    }
+
+    #[tokio::test]
+    async fn test_report_presentation_with_failed_credential() {
+        let holder = DidJwk::create(None).unwrap();
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwts = vec![vc_jwt(&issuer, Default::default()).await];
+        let mut verifiable_presentation =
+            VerifiablePresentation::create(holder.did.uri.clone(), vc_jwts, None)
+                .await
+                .unwrap();
+        // creation rejects an expired credential, but a holder may present one regardless
+        verifiable_presentation
+            .verifiable_credential
+            .push(expired_vc_jwt(&issuer).await);
+        let vp_jwt = verifiable_presentation.sign(&holder, None).unwrap();
+
+        let report = VerificationPolicy::default().verify_vp_jwt(&vp_jwt).await;
+
+        assert!(!report.is_verified());
+        assert_eq!(2, report.credentials.len());
+        assert!(report.credentials[0].is_verified());
+        assert_eq!(
+            "expired",
+            report.credentials[1]
+                .check(VerificationCheck::ValidityPeriod)
+                .unwrap()
+                .code
+        );
+        let failures = report.failures();
+        assert_eq!(1, failures.len());
+        assert_eq!(VerificationCheck::Credentials, failures[0].check);
+        assert_eq!("invalid_credential", failures[0].code);
+    }
+
+    #[tokio::test]
+    async fn test_report_to_json() {
+        let issuer = DidJwk::create(None).unwrap();
+        let vc_jwt = expired_vc_jwt(&issuer).await;
+
+        let report = VerificationPolicy::default().verify_vc_jwt(&vc_jwt).await;
+        let json: serde_json::Value =
+            serde_json::from_str(&report.to_json_string().unwrap()).unwrap();
+
+        let validity_period = json["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|result| result["check"] == "validity_period")
+            .unwrap();
+        assert_eq!("failed", validity_period["outcome"]);
+        assert_eq!("expired", validity_period["code"]);
+    }
+}
@@ -0,0 +1,620 @@
+use super::bitstring_status_list::{BitstringStatusListCredential, BITSTRING_STATUS_LIST_ENTRY};
+use super::credential_schema::{validate_against_credential_schema, validate_credential_schema};
+use super::data_model_validation::{validate_vc_2_0_data_model, validate_vc_data_model};
+use super::decode::decode_versioned;
+use super::status_list_credential::{StatusListCredential, STATUS_LIST_2021_ENTRY};
+use super::verifiable_credential_1_1::CredentialStatus;
+use super::verifiable_credential_2_0::VersionedVerifiableCredential;
+use super::VerificationError;
+use crate::errors::{Result, Web5Error};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+use url::Url;
+
+/// How long a status list credential is cached for if it doesn't give a `ttl`, per the
+/// Bitstring Status List specification.
+const DEFAULT_STATUS_LIST_TTL: Duration = Duration::from_millis(300_000);
+
+/// The largest status list credential to fetch, in bytes, enough for the largest status list
+/// [`BitstringStatusList::decode`](super::bitstring_status_list::BitstringStatusList::decode)
+/// accepts.
+const MAX_STATUS_LIST_CREDENTIAL_SIZE: usize = 32 * 1024 * 1024;
+
+/// How many status list credentials a cache holds by default.
+const DEFAULT_STATUS_LIST_CACHE_CAPACITY: usize = 100;
+
+#[derive(Debug)]
+struct CachedStatusList {
+    status_list_credential: VersionedVerifiableCredential,
+    expires_at: SystemTime,
+}
+
+/// Caches the status list credentials fetched by status checks, each until its `ttl` passes or
+/// it expires.
+///
+/// Once the cache holds `capacity` status lists, expired entries are evicted, and then the entry
+/// expiring soonest.
+///
+/// # Example
+///
+/// ```ignore
+/// let policy = VerificationPolicy {
+///     check_status: true,
+///     status_list_cache: Some(Arc::new(StatusListCache::new(10))),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug)]
+pub struct StatusListCache {
+    entries: RwLock<HashMap<String, CachedStatusList>>,
+    capacity: usize,
+}
+
+impl Default for StatusListCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATUS_LIST_CACHE_CAPACITY)
+    }
+}
+
+impl StatusListCache {
+    /// Creates an empty cache holding at most `capacity` status list credentials.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Clears the cached status list credentials, so the next check of each list fetches it
+    /// again.
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.clear();
+        }
+    }
+
+    fn get(&self, url: &str, now: SystemTime) -> Option<VersionedVerifiableCredential> {
+        let entries = self.entries.read().ok()?;
+        entries
+            .get(url)
+            .filter(|cached| cached.expires_at > now)
+            .map(|cached| cached.status_list_credential.clone())
+    }
+
+    fn insert(
+        &self,
+        url: &str,
+        status_list_credential: VersionedVerifiableCredential,
+        now: SystemTime,
+        expires_at: SystemTime,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = match self.entries.write() {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+
+        if !entries.contains_key(url) && entries.len() >= self.capacity {
+            entries.retain(|_, cached| cached.expires_at > now);
+            while entries.len() >= self.capacity {
+                let soonest = entries
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.expires_at)
+                    .map(|(url, _)| url.clone());
+                match soonest {
+                    Some(soonest) => entries.remove(&soonest),
+                    None => break,
+                };
+            }
+        }
+
+        entries.insert(
+            url.to_string(),
+            CachedStatusList {
+                status_list_credential,
+                expires_at,
+            },
+        );
+    }
+}
+
+lazy_static! {
+    static ref DEFAULT_STATUS_LIST_CACHE: Arc<StatusListCache> =
+        Arc::new(StatusListCache::default());
+}
+
+/// Returns the cache shared by the policies which don't provide their own.
+pub(crate) fn default_status_list_cache() -> Arc<StatusListCache> {
+    DEFAULT_STATUS_LIST_CACHE.clone()
+}
+
+/// Clears the status list credentials cached by the status checks of policies which don't
+/// provide their own cache, so the next check of each list fetches it again.
+pub fn clear_status_list_cache() {
+    DEFAULT_STATUS_LIST_CACHE.clear();
+}
+
+/// Checks the status of a credential against its status list credential, which must be issued
+/// by the credential's issuer.
+///
+/// A credential without a status passes, as does any status other than a set revocation or
+/// suspension status.
+pub(crate) async fn check_credential_status(
+    issuer: &str,
+    credential_status: Option<&CredentialStatus>,
+    status_list_cache: &StatusListCache,
+) -> Result<()> {
+    let credential_status = match credential_status {
+        Some(credential_status) => credential_status,
+        None => return Ok(()),
+    };
+
+    let status_list_credential =
+        fetch_status_list_credential(&credential_status.status_list_credential, status_list_cache)
+            .await?;
+
+    let status_list_issuer = match &status_list_credential {
+        VersionedVerifiableCredential::V1_1(vc) => vc.issuer.to_string(),
+        VersionedVerifiableCredential::V2_0(vc) => vc.issuer.to_string(),
+    };
+    if status_list_issuer != issuer {
+        return Err(VerificationError::ClaimMismatch(format!(
+            "status list credential issuer {} does not match credential issuer {}",
+            status_list_issuer, issuer
+        ))
+        .into());
+    }
+
+    let result = match (credential_status.r#type.as_str(), status_list_credential) {
+        (STATUS_LIST_2021_ENTRY, VersionedVerifiableCredential::V1_1(vc)) => {
+            StatusListCredential { base: vc }.get_status(credential_status)?
+        }
+        (STATUS_LIST_2021_ENTRY, VersionedVerifiableCredential::V2_0(_)) => {
+            return Err(Web5Error::Parameter(
+                "status list 2021 credential must use data model 1.1".to_string(),
+            ))
+        }
+        (BITSTRING_STATUS_LIST_ENTRY, status_list_credential) => {
+            BitstringStatusListCredential::from_base(status_list_credential.into_v2_0())?
+                .get_status(credential_status)?
+        }
+        (r#type, _) => {
+            return Err(Web5Error::Parameter(format!(
+                "unsupported status type: {}",
+                r#type
+            )))
+        }
+    };
+
+    match (result.status_purpose.as_str(), result.status) {
+        (_, 0) => Ok(()),
+        ("revocation", _) => Err(VerificationError::CredentialRevoked.into()),
+        ("suspension", _) => Err(VerificationError::CredentialSuspended.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    // the mock servers tests run against only serve http
+    static ALLOW_HTTP_STATUS_LISTS: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+fn is_allowed_scheme(scheme: &str) -> bool {
+    #[cfg(test)]
+    if scheme == "http" && ALLOW_HTTP_STATUS_LISTS.with(|allow| allow.get()) {
+        return true;
+    }
+    scheme == "https"
+}
+
+/// Fetches and verifies a status list credential, served as a VC JWT over https, or returns it
+/// from the cache until its `ttl` passes or it expires.
+async fn fetch_status_list_credential(
+    url: &str,
+    status_list_cache: &StatusListCache,
+) -> Result<VersionedVerifiableCredential> {
+    if !Url::parse(url).is_ok_and(|url| is_allowed_scheme(url.scheme())) {
+        return Err(Web5Error::Parameter(format!(
+            "status list credential {} is not an https url",
+            url
+        )));
+    }
+
+    let now = SystemTime::now();
+    if let Some(status_list_credential) = status_list_cache.get(url, now) {
+        return Ok(status_list_credential);
+    }
+
+    let response = http_std::fetch(
+        url,
+        Some(http_std::FetchOptions {
+            max_response_size: Some(MAX_STATUS_LIST_CREDENTIAL_SIZE),
+            ..Default::default()
+        }),
+    )
+    .await?;
+    if !(200..300).contains(&response.status_code) {
+        return Err(Web5Error::Network(format!(
+            "failed to fetch {} status code {}",
+            url, response.status_code
+        )));
+    }
+
+    let vc_jwt = String::from_utf8(response.body).map_err(|_| {
+        Web5Error::Parameter(format!("status list credential {} is not a JWT", url))
+    })?;
+    // verified as from_vc_jwt does, but without a policy, whose status check this is
+    let status_list_credential = decode_versioned(vc_jwt.trim(), true).await?;
+    match &status_list_credential {
+        VersionedVerifiableCredential::V1_1(vc) => {
+            validate_vc_data_model(vc)?;
+            validate_credential_schema(vc).await?;
+        }
+        VersionedVerifiableCredential::V2_0(vc) => {
+            validate_vc_2_0_data_model(vc)?;
+            validate_against_credential_schema(vc.credential_schema.as_ref(), vc).await?;
+        }
+    }
+
+    let base = status_list_credential.clone().into_v2_0();
+    let valid_until = base.valid_until;
+    let ttl = BitstringStatusListCredential { base }
+        .ttl()
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_STATUS_LIST_TTL);
+    let expires_at = match valid_until {
+        Some(valid_until) => valid_until.min(now + ttl),
+        None => now + ttl,
+    };
+
+    status_list_cache.insert(url, status_list_credential.clone(), now, expires_at);
+
+    Ok(status_list_credential)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::{
+        CredentialSubject, Issuer, StatusListManager, StatusListManagerIssueOptions,
+        VerifiableCredential, VerifiableCredentialCreateOptions, VerificationPolicy,
+    };
+    use crate::dids::{bearer_did::BearerDid, methods::did_jwk::DidJwk};
+    use mockito::{Mock, Server, ServerGuard};
+
+    const SUBJECT_DID_URI: &str = "did:dht:qgmmpyjw5hwnqfgzn7wmrm33ady8gb8z9ideib6m9gj4ys6wny8y";
+
+    // mock servers are pooled, so each test serves its list at a unique path to avoid hitting
+    // the cache of another
+    fn status_list_path() -> String {
+        format!("/credentials/status/{}", uuid::Uuid::new_v4())
+    }
+
+    async fn vc_jwt(issuer: &BearerDid, credential_status: CredentialStatus) -> String {
+        VerifiableCredential::create(
+            Issuer::from(issuer.did.uri.clone()),
+            CredentialSubject::from(SUBJECT_DID_URI),
+            Some(VerifiableCredentialCreateOptions {
+                credential_status: Some(credential_status),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .sign(issuer, None)
+        .unwrap()
+    }
+
+    async fn serve(
+        mock_server: &mut ServerGuard,
+        path: &str,
+        status_list_credential_jwt: String,
+    ) -> Mock {
+        mock_server
+            .mock("GET", path)
+            .with_status(200)
+            .with_body(status_list_credential_jwt)
+            .create_async()
+            .await
+    }
+
+    fn status_list_manager(
+        mock_server: &ServerGuard,
+        path: &str,
+        status_purpose: &str,
+    ) -> StatusListManager {
+        StatusListManager::new(
+            format!("{}{}", mock_server.url(), path),
+            status_purpose.to_string(),
+            None,
+        )
+        .unwrap()
+    }
+
+    fn status_policy() -> VerificationPolicy {
+        ALLOW_HTTP_STATUS_LISTS.with(|allow| allow.set(true));
+        VerificationPolicy {
+            check_status: true,
+            status_list_cache: Some(Arc::new(StatusListCache::default())),
+            ..Default::default()
+        }
+    }
+
+    fn index(credential_status: &CredentialStatus) -> usize {
+        credential_status.status_list_index.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_active() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        serve(
+            &mut mock_server,
+            &path,
+            status_list_manager.issue(&issuer, None).await.unwrap(),
+        )
+        .await;
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_revoked() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        status_list_manager
+            .set_status(index(&credential_status), 1)
+            .unwrap();
+        serve(
+            &mut mock_server,
+            &path,
+            status_list_manager.issue(&issuer, None).await.unwrap(),
+        )
+        .await;
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::CredentialRevoked)) => {}
+            _ => panic!("expected credential revoked error"),
+        }
+
+        // status is only checked on request
+        let result = VerifiableCredential::from_vc_jwt(&vc_jwt, true).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_suspended() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "suspension");
+        let credential_status = status_list_manager.allocate().unwrap();
+        status_list_manager
+            .set_status(index(&credential_status), 1)
+            .unwrap();
+        serve(
+            &mut mock_server,
+            &path,
+            status_list_manager.issue(&issuer, None).await.unwrap(),
+        )
+        .await;
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::CredentialSuspended)) => {}
+            _ => panic!("expected credential suspended error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_status_list_2021() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let credential_status = CredentialStatus {
+            id: format!("{}{}#94567", mock_server.url(), path),
+            r#type: STATUS_LIST_2021_ENTRY.to_string(),
+            status_purpose: "revocation".to_string(),
+            status_list_index: "94567".to_string(),
+            status_list_credential: format!("{}{}", mock_server.url(), path),
+            ..Default::default()
+        };
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+
+        let revoked_credential = VerifiableCredential::from_vc_jwt(&vc_jwt, true)
+            .await
+            .unwrap();
+        let status_list_credential = StatusListCredential::create(
+            Issuer::from(issuer.did.uri.clone()),
+            "revocation".to_string(),
+            Some(vec![revoked_credential]),
+        )
+        .await
+        .unwrap();
+        serve(
+            &mut mock_server,
+            &path,
+            status_list_credential.base.sign(&issuer, None).unwrap(),
+        )
+        .await;
+
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::CredentialRevoked)) => {}
+            _ => panic!("expected credential revoked error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_issuer_mismatch() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let other_issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        serve(
+            &mut mock_server,
+            &path,
+            status_list_manager
+                .issue(&other_issuer, None)
+                .await
+                .unwrap(),
+        )
+        .await;
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::ClaimMismatch(message))) => {
+                assert!(message.starts_with("status list credential issuer"))
+            }
+            _ => panic!("expected claim mismatch error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_status_list_signature() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        let status_list_credential_jwt = status_list_manager.issue(&issuer, None).await.unwrap();
+        let (signing_input, _) = status_list_credential_jwt.rsplit_once('.').unwrap();
+        serve(
+            &mut mock_server,
+            &path,
+            format!("{}.{}", signing_input, "A".repeat(86)),
+        )
+        .await;
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_until_ttl() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        let mock = serve(
+            &mut mock_server,
+            &path,
+            status_list_manager
+                .issue(
+                    &issuer,
+                    Some(StatusListManagerIssueOptions {
+                        ttl: Some(60_000),
+                        ..Default::default()
+                    }),
+                )
+                .await
+                .unwrap(),
+        )
+        .await
+        .expect(1);
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let policy = status_policy();
+        for _ in 0..2 {
+            VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy)
+                .await
+                .unwrap();
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_failure() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        let _ = mock_server
+            .mock("GET", path.as_str())
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &status_policy()).await;
+        match result {
+            Err(Web5Error::Network(message)) => assert!(message.ends_with("status code 404")),
+            _ => panic!("expected network error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requires_https() {
+        let mut mock_server = Server::new_async().await;
+        let path = status_list_path();
+        let issuer = DidJwk::create(None).unwrap();
+        let mut status_list_manager = status_list_manager(&mock_server, &path, "revocation");
+        let credential_status = status_list_manager.allocate().unwrap();
+        let mock = serve(
+            &mut mock_server,
+            &path,
+            status_list_manager.issue(&issuer, None).await.unwrap(),
+        )
+        .await
+        .expect(0);
+
+        let vc_jwt = vc_jwt(&issuer, credential_status).await;
+        let policy = VerificationPolicy {
+            check_status: true,
+            status_list_cache: Some(Arc::new(StatusListCache::default())),
+            ..Default::default()
+        };
+        let result = VerifiableCredential::from_vc_jwt_with_policy(&vc_jwt, &policy).await;
+        match result {
+            Err(Web5Error::Parameter(message)) => assert!(message.ends_with("is not an https url")),
+            _ => panic!("expected parameter error"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_soonest_expiring_at_capacity() {
+        let credential = VersionedVerifiableCredential::V1_1(
+            VerifiableCredential::create(
+                Issuer::from(SUBJECT_DID_URI),
+                CredentialSubject::from(SUBJECT_DID_URI),
+                None,
+            )
+            .await
+            .unwrap(),
+        );
+        let now = SystemTime::now();
+        let cache = StatusListCache::new(2);
+        cache.insert("a", credential.clone(), now, now + Duration::from_secs(10));
+        cache.insert("b", credential.clone(), now, now + Duration::from_secs(20));
+        cache.insert("c", credential.clone(), now, now + Duration::from_secs(30));
+
+        assert!(cache.get("a", now).is_none());
+        assert!(cache.get("b", now).is_some());
+        assert!(cache.get("c", now).is_some());
+    }
+}
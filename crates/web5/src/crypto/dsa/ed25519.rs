@@ -296,7 +296,7 @@ mod tests {
             let mut jwk = Ed25519Generator::generate();
 
             // Set an invalid private key (wrong length)
-            jwk.d = Some(URL_SAFE_NO_PAD.encode(&[0u8; SECRET_KEY_LENGTH - 1]));
+            jwk.d = Some(URL_SAFE_NO_PAD.encode([0u8; SECRET_KEY_LENGTH - 1]));
 
             let signer = Ed25519Signer::new(jwk);
             let message = b"Test message";
@@ -410,7 +410,7 @@ mod tests {
         #[test]
         fn test_with_invalid_public_key() {
             let (mut public_jwk, private_jwk) = generate_keys();
-            public_jwk.x = URL_SAFE_NO_PAD.encode(&[0u8; PUBLIC_KEY_LENGTH - 1]);
+            public_jwk.x = URL_SAFE_NO_PAD.encode([0u8; PUBLIC_KEY_LENGTH - 1]);
 
             let signer = Ed25519Signer::new(private_jwk);
             let verifier = Ed25519Verifier::new(public_jwk);
@@ -1,12 +1,18 @@
-use crate::errors::{Result, Web5Error};
+use crate::{
+    crypto::jwk::Jwk,
+    errors::{Result, Web5Error},
+};
+use std::{str::FromStr, sync::Arc};
 
 pub mod ed25519;
 pub mod secp256k1;
+pub mod secp256r1;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Dsa {
     Ed25519,
     Secp256k1,
+    Secp256r1,
 }
 
 impl std::str::FromStr for Dsa {
@@ -18,6 +24,9 @@ impl std::str::FromStr for Dsa {
             "eddsa" => Ok(Dsa::Ed25519),
             "secp256k1" => Ok(Dsa::Secp256k1),
             "es256k" => Ok(Dsa::Secp256k1),
+            "secp256r1" => Ok(Dsa::Secp256r1),
+            "p-256" => Ok(Dsa::Secp256r1),
+            "es256" => Ok(Dsa::Secp256r1),
             _ => Err(Web5Error::Parameter(format!("unsupported dsa {}", input))),
         }
     }
@@ -30,3 +39,17 @@ pub trait Signer: Send + Sync {
 pub trait Verifier: Send + Sync {
     fn verify(&self, payload: &[u8], signature: &[u8]) -> Result<()>;
 }
+
+/// Returns a verifier for the public key, chosen by its curve.
+///
+/// # Errors
+///
+/// Returns `Web5Error::Parameter` if the curve isn't supported.
+pub(crate) fn verifier_for_public_jwk(public_jwk: Jwk) -> Result<Arc<dyn Verifier>> {
+    let verifier: Arc<dyn Verifier> = match Dsa::from_str(&public_jwk.crv)? {
+        Dsa::Ed25519 => Arc::new(ed25519::Ed25519Verifier::new(public_jwk)),
+        Dsa::Secp256k1 => Arc::new(secp256k1::Secp256k1Verifier::new(public_jwk)),
+        Dsa::Secp256r1 => Arc::new(secp256r1::Secp256r1Verifier::new(public_jwk)),
+    };
+    Ok(verifier)
+}
@@ -32,7 +32,7 @@ impl Secp256k1Generator {
             crv: "secp256k1".to_string(),
             x: general_purpose::URL_SAFE_NO_PAD.encode(x_bytes),
             y: Some(general_purpose::URL_SAFE_NO_PAD.encode(y_bytes)),
-            d: Some(general_purpose::URL_SAFE_NO_PAD.encode(signing_key.to_bytes().as_slice())),
+            d: Some(general_purpose::URL_SAFE_NO_PAD.encode(signing_key.to_bytes())),
         }
     }
 }
@@ -285,7 +285,7 @@ mod tests {
             let mut jwk = Secp256k1Generator::generate();
 
             // Set an invalid private key (wrong length)
-            jwk.d = Some(general_purpose::URL_SAFE_NO_PAD.encode(&[0u8; 31])); // One byte too short
+            jwk.d = Some(general_purpose::URL_SAFE_NO_PAD.encode([0u8; 31])); // One byte too short
 
             let signer = Secp256k1Signer::new(jwk);
             let message = b"Test message";
@@ -398,7 +398,7 @@ mod tests {
         #[test]
         fn test_with_invalid_public_key() {
             let (mut public_jwk, private_jwk) = generate_keys();
-            public_jwk.x = general_purpose::URL_SAFE_NO_PAD.encode(&[0u8; 31]); // Invalid length
+            public_jwk.x = general_purpose::URL_SAFE_NO_PAD.encode([0u8; 31]); // Invalid length
 
             let signer = Secp256k1Signer::new(private_jwk);
             let verifier = Secp256k1Verifier::new(public_jwk);
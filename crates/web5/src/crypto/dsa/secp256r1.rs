@@ -0,0 +1,166 @@
+use super::Signer;
+use super::Verifier;
+use crate::crypto::jwk::Jwk;
+use crate::errors::Result;
+use crate::errors::Web5Error;
+use base64::{engine::general_purpose, Engine as _};
+use p256::ecdsa::signature::{Signer as P256Signer, Verifier as P256Verifier};
+use p256::ecdsa::Signature;
+
+/// A key generator for secp256r1 (NIST P-256), used to create JWKs with P-256 key pairs.
+pub struct Secp256r1Generator;
+
+impl Secp256r1Generator {
+    /// Generates a new P-256 key pair and returns it as a JWK.
+    ///
+    /// # Returns
+    /// A `Jwk` containing the generated P-256 key pair.
+    pub fn generate() -> Jwk {
+        let signing_key = p256::ecdsa::SigningKey::random(&mut rand::thread_rng());
+        let verifying_key = signing_key.verifying_key();
+        let serialized_pub_key = verifying_key.to_encoded_point(false);
+        let bytes = serialized_pub_key.as_bytes();
+        let x_bytes = &bytes[1..33];
+        let y_bytes = &bytes[33..65];
+
+        Jwk {
+            alg: Some("ES256".to_string()),
+            kty: "EC".to_string(),
+            crv: "P-256".to_string(),
+            x: general_purpose::URL_SAFE_NO_PAD.encode(x_bytes),
+            y: Some(general_purpose::URL_SAFE_NO_PAD.encode(y_bytes)),
+            d: Some(general_purpose::URL_SAFE_NO_PAD.encode(signing_key.to_bytes())),
+        }
+    }
+}
+
+/// A signer for P-256 keys, producing 64 byte `r || s` signatures over the SHA-256 digest of the payload.
+#[derive(Clone)]
+pub struct Secp256r1Signer {
+    private_jwk: Jwk,
+}
+
+impl Secp256r1Signer {
+    /// Creates a new `Secp256r1Signer` from a private JWK.
+    pub fn new(private_jwk: Jwk) -> Self {
+        Self { private_jwk }
+    }
+}
+
+impl Signer for Secp256r1Signer {
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let d = self.private_jwk.d.as_ref().ok_or(Web5Error::Crypto(
+            "private key material must be set".to_string(),
+        ))?;
+
+        let decoded_d = general_purpose::URL_SAFE_NO_PAD.decode(d)?;
+
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&decoded_d)
+            .map_err(|_| Web5Error::Crypto("invalid private key".to_string()))?;
+
+        let signature: Signature = signing_key.sign(payload);
+
+        Ok(signature.to_vec())
+    }
+}
+
+/// A verifier for P-256 keys.
+#[derive(Clone)]
+pub struct Secp256r1Verifier {
+    public_jwk: Jwk,
+}
+
+impl Secp256r1Verifier {
+    /// Creates a new `Secp256r1Verifier` from a public JWK.
+    pub fn new(public_jwk: Jwk) -> Self {
+        Self { public_jwk }
+    }
+}
+
+impl Verifier for Secp256r1Verifier {
+    fn verify(&self, payload: &[u8], signature: &[u8]) -> Result<()> {
+        if let Some(d) = &self.public_jwk.d {
+            if !d.is_empty() {
+                return Err(Web5Error::Crypto(
+                    "provided verification key cannot contain private key material".to_string(),
+                ));
+            }
+        }
+
+        let decoded_x = general_purpose::URL_SAFE_NO_PAD.decode(&self.public_jwk.x)?;
+        let decoded_y = general_purpose::URL_SAFE_NO_PAD.decode(
+            self.public_jwk
+                .y
+                .as_ref()
+                .ok_or(Web5Error::Parameter("missing y".to_string()))?,
+        )?;
+        let mut public_key_bytes = vec![0x04]; // uncompressed
+        public_key_bytes.extend_from_slice(&decoded_x);
+        public_key_bytes.extend_from_slice(&decoded_y);
+
+        let verifying_key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key_bytes)
+            .map_err(|_| Web5Error::Crypto("unable to instantiate verifying key".to_string()))?;
+
+        let signature = Signature::from_slice(signature)
+            .map_err(|_| Web5Error::Crypto("invalid signature".to_string()))?;
+
+        verifying_key
+            .verify(payload, &signature)
+            .map_err(|_| Web5Error::Crypto("cryptographic verification failure".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_keys() -> (Jwk, Jwk) {
+        let private_jwk = Secp256r1Generator::generate();
+        let mut public_jwk = private_jwk.clone();
+        public_jwk.d = None;
+        (public_jwk, private_jwk)
+    }
+
+    #[test]
+    fn test_generate() {
+        let jwk = Secp256r1Generator::generate();
+        assert_eq!(jwk.alg, Some("ES256".to_string()));
+        assert_eq!(jwk.kty, "EC");
+        assert_eq!(jwk.crv, "P-256");
+        assert_eq!(
+            general_purpose::URL_SAFE_NO_PAD
+                .decode(jwk.d.unwrap())
+                .unwrap()
+                .len(),
+            32
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (public_jwk, private_jwk) = generate_keys();
+        let signature = Secp256r1Signer::new(private_jwk)
+            .sign(b"Test message")
+            .unwrap();
+        assert_eq!(signature.len(), 64);
+
+        let verifier = Secp256r1Verifier::new(public_jwk);
+        assert!(verifier.verify(b"Test message", &signature).is_ok());
+        assert_eq!(
+            verifier.verify(b"Other message", &signature).unwrap_err(),
+            Web5Error::Crypto("cryptographic verification failure".to_string())
+        );
+    }
+
+    #[test]
+    fn test_verify_with_private_key() {
+        let (_, private_jwk) = generate_keys();
+        let result = Secp256r1Verifier::new(private_jwk).verify(b"Test message", &[0u8; 64]);
+        assert_eq!(
+            result.unwrap_err(),
+            Web5Error::Crypto(
+                "provided verification key cannot contain private key material".to_string()
+            )
+        );
+    }
+}
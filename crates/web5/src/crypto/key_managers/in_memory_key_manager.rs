@@ -1,8 +1,12 @@
 use super::{KeyExporter, KeyManager};
 use crate::{
     crypto::{
-        dsa::{ed25519::Ed25519Signer, secp256k1::Secp256k1Signer, Dsa, Signer},
+        dsa::{
+            ed25519::Ed25519Signer, secp256k1::Secp256k1Signer, secp256r1::Secp256r1Signer, Dsa,
+            Signer,
+        },
         jwk::Jwk,
+        x25519::{KeyAgreement, X25519KeyAgreement},
     },
     errors::{Result, Web5Error},
 };
@@ -132,9 +136,51 @@ impl KeyManager for InMemoryKeyManager {
         let signer: Arc<dyn Signer> = match Dsa::from_str(&public_jwk.crv)? {
             Dsa::Ed25519 => Arc::new(Ed25519Signer::new(private_jwk.clone())),
             Dsa::Secp256k1 => Arc::new(Secp256k1Signer::new(private_jwk.clone())),
+            Dsa::Secp256r1 => Arc::new(Secp256r1Signer::new(private_jwk.clone())),
         };
         Ok(signer)
     }
+
+    /// Retrieves a key agreement for a given public X25519 JWK.
+    ///
+    /// This method looks up the private JWK associated with the given public JWK
+    /// and returns a key agreement for deriving shared secrets.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_jwk` - The public JWK for which to retrieve the key agreement.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Arc<dyn KeyAgreement>>` - The key agreement associated with the public JWK.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the public JWK is not found, is not a public key, or is not an X25519 key.
+    fn get_key_agreement(&self, public_jwk: Jwk) -> Result<Arc<dyn KeyAgreement>> {
+        if !public_jwk.is_public_key() {
+            return Err(Web5Error::Parameter(
+                "public_jwk must be a public key".to_string(),
+            ));
+        }
+        if public_jwk.crv != "X25519" {
+            return Err(Web5Error::Parameter(format!(
+                "unsupported key agreement curve {}",
+                public_jwk.crv
+            )));
+        }
+
+        let map_lock = self.map.read()?;
+        let thumbprint = public_jwk.compute_thumbprint()?;
+        let private_jwk = map_lock
+            .get(&thumbprint)
+            .ok_or(Web5Error::NotFound(format!(
+                "key agreement not found for public_jwk with thumbprint {}",
+                thumbprint
+            )))?;
+
+        Ok(Arc::new(X25519KeyAgreement::new(private_jwk.clone())))
+    }
 }
 
 impl KeyExporter for InMemoryKeyManager {
@@ -245,6 +291,33 @@ mod tests {
         }
     }
 
+    mod get_key_agreement {
+        use super::*;
+        use crate::crypto::x25519::X25519Generator;
+
+        #[test]
+        fn test_found() {
+            let key_manager = InMemoryKeyManager::new();
+            let public_jwk = key_manager
+                .import_private_jwk(X25519Generator::generate())
+                .unwrap();
+
+            let result = key_manager.get_key_agreement(public_jwk);
+            assert!(result.is_ok());
+        }
+
+        #[test]
+        fn test_unsupported_curve() {
+            let key_manager = InMemoryKeyManager::new();
+            let public_jwk = key_manager
+                .import_private_jwk(Ed25519Generator::generate())
+                .unwrap();
+
+            let result = key_manager.get_key_agreement(public_jwk);
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+    }
+
     mod export_private_jwks {
         use super::*;
 
@@ -1,6 +1,6 @@
 use crate::{
-    crypto::{dsa::Signer, jwk::Jwk},
-    errors::Result,
+    crypto::{dsa::Signer, jwk::Jwk, x25519::KeyAgreement},
+    errors::{Result, Web5Error},
 };
 use std::sync::Arc;
 
@@ -27,6 +27,23 @@ pub trait KeyManager: Send + Sync {
     /// # Returns
     /// A cryptographic signer associated with the public key.
     fn get_signer(&self, public_jwk: Jwk) -> Result<Arc<dyn Signer>>;
+
+    /// Retrieves a key agreement for a given public JWK.
+    ///
+    /// By default, key agreement is not supported. Implementations which hold key agreement keys
+    /// (e.g. X25519) must override this method.
+    ///
+    /// # Arguments
+    /// * `public_jwk` - The public JWK for which to retrieve the key agreement.
+    ///
+    /// # Returns
+    /// A key agreement associated with the public key.
+    fn get_key_agreement(&self, public_jwk: Jwk) -> Result<Arc<dyn KeyAgreement>> {
+        Err(Web5Error::Crypto(format!(
+            "key agreement is not supported for {} keys by this key manager",
+            public_jwk.crv
+        )))
+    }
 }
 
 /// A trait for exporting private key material.
@@ -1,4 +1,5 @@
 pub mod dsa;
 pub mod jwk;
 pub mod key_managers;
+pub(crate) mod pbes2;
 pub mod x25519;
@@ -0,0 +1,213 @@
+use crate::{
+    errors::{Result, Web5Error},
+    json::{FromJson, ToJson},
+};
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use aes_kw::KekAes256;
+use base64::{engine::general_purpose, Engine as _};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+
+pub(crate) const PBES2_ALG: &str = "PBES2-HS512+A256KW";
+pub(crate) const A256GCM_ENC: &str = "A256GCM";
+
+/// The PBKDF2 iteration count used when encrypting, per the OWASP recommendation for
+/// PBKDF2-HMAC-SHA512.
+pub(crate) const PBES2_ITERATIONS: u32 = 210_000;
+/// The lowest PBKDF2 iteration count accepted when decrypting.
+pub(crate) const MIN_ITERATIONS: u32 = 1_000;
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LENGTH: usize = 16;
+const KEY_LENGTH: usize = 32;
+const IV_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Pbes2Header {
+    alg: String,
+    enc: String,
+    p2s: String,
+    p2c: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cty: Option<String>,
+}
+
+impl FromJson for Pbes2Header {}
+impl ToJson for Pbes2Header {}
+
+/// Encrypts the plaintext under a password as a compact JWE, using `PBES2-HS512+A256KW` key
+/// management and `A256GCM` content encryption ([RFC 7518 §4.8](https://www.rfc-editor.org/rfc/rfc7518#section-4.8)).
+pub(crate) fn encrypt(
+    plaintext: &[u8],
+    password: &str,
+    content_type: Option<&str>,
+    iterations: u32,
+) -> Result<String> {
+    if password.is_empty() {
+        return Err(Web5Error::Parameter("password cannot be empty".to_string()));
+    }
+
+    let mut p2s = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut p2s);
+    let header = Pbes2Header {
+        alg: PBES2_ALG.to_string(),
+        enc: A256GCM_ENC.to_string(),
+        p2s: general_purpose::URL_SAFE_NO_PAD.encode(p2s),
+        p2c: iterations,
+        cty: content_type.map(|c| c.to_string()),
+    };
+    let header_part = general_purpose::URL_SAFE_NO_PAD.encode(header.to_json_string()?);
+
+    let kek = derive_key_encryption_key(password, &p2s, iterations);
+    let mut cek = [0u8; KEY_LENGTH];
+    OsRng.fill_bytes(&mut cek);
+    let encrypted_key = kek
+        .wrap_vec(&cek)
+        .map_err(|e| Web5Error::Crypto(format!("failed to wrap content encryption key {}", e)))?;
+
+    let mut iv = [0u8; IV_LENGTH];
+    OsRng.fill_bytes(&mut iv);
+    let cipher = Aes256Gcm::new_from_slice(&cek)
+        .map_err(|e| Web5Error::Crypto(format!("invalid content encryption key {}", e)))?;
+    let mut ciphertext = cipher
+        .encrypt(
+            &Nonce::from(iv),
+            Payload {
+                msg: plaintext,
+                aad: header_part.as_bytes(),
+            },
+        )
+        .map_err(|e| Web5Error::Crypto(format!("failed to encrypt {}", e)))?;
+    let tag = ciphertext.split_off(ciphertext.len() - TAG_LENGTH);
+
+    Ok([
+        header_part,
+        general_purpose::URL_SAFE_NO_PAD.encode(encrypted_key),
+        general_purpose::URL_SAFE_NO_PAD.encode(iv),
+        general_purpose::URL_SAFE_NO_PAD.encode(ciphertext),
+        general_purpose::URL_SAFE_NO_PAD.encode(tag),
+    ]
+    .join("."))
+}
+
+/// Decrypts a compact JWE produced by [`encrypt`].
+pub(crate) fn decrypt(compact_jwe: &str, password: &str) -> Result<Vec<u8>> {
+    let parts = compact_jwe.split('.').collect::<Vec<&str>>();
+    if parts.len() != 5 {
+        return Err(Web5Error::Parameter(
+            "compact jwe has wrong number of parts".to_string(),
+        ));
+    }
+
+    let decode = |part: &str, name: &str| {
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(part)
+            .map_err(|e| Web5Error::Encoding(format!("failed to base64 decode {} {}", name, e)))
+    };
+
+    let header = Pbes2Header::from_json_byte_array(&decode(parts[0], "header")?)?;
+    if header.alg != PBES2_ALG || header.enc != A256GCM_ENC {
+        return Err(Web5Error::Parameter(format!(
+            "unsupported jwe alg {} and enc {}",
+            header.alg, header.enc
+        )));
+    }
+    if !(MIN_ITERATIONS..=MAX_ITERATIONS).contains(&header.p2c) {
+        return Err(Web5Error::Parameter(format!(
+            "jwe p2c {} is out of range",
+            header.p2c
+        )));
+    }
+
+    let p2s = decode(&header.p2s, "p2s")?;
+    let encrypted_key = decode(parts[1], "encrypted key")?;
+    let iv: [u8; IV_LENGTH] = decode(parts[2], "iv")?
+        .try_into()
+        .map_err(|_| Web5Error::Parameter("jwe iv has wrong length".to_string()))?;
+    let mut ciphertext = decode(parts[3], "ciphertext")?;
+    let tag = decode(parts[4], "tag")?;
+    if tag.len() != TAG_LENGTH {
+        return Err(Web5Error::Parameter("jwe tag has wrong length".to_string()));
+    }
+
+    let kek = derive_key_encryption_key(password, &p2s, header.p2c);
+    let cek = kek.unwrap_vec(&encrypted_key).map_err(|_| {
+        Web5Error::Crypto("failed to decrypt, the password may be wrong".to_string())
+    })?;
+
+    let cipher = Aes256Gcm::new_from_slice(&cek)
+        .map_err(|e| Web5Error::Crypto(format!("invalid content encryption key {}", e)))?;
+    ciphertext.extend_from_slice(&tag);
+    cipher
+        .decrypt(
+            &Nonce::from(iv),
+            Payload {
+                msg: &ciphertext,
+                aad: parts[0].as_bytes(),
+            },
+        )
+        .map_err(|_| Web5Error::Crypto("failed to decrypt, the jwe may be corrupt".to_string()))
+}
+
+fn derive_key_encryption_key(password: &str, p2s: &[u8], iterations: u32) -> KekAes256 {
+    // the salt is the concatenation of the alg, a zero octet, and the p2s value
+    let mut salt = PBES2_ALG.as_bytes().to_vec();
+    salt.push(0);
+    salt.extend_from_slice(p2s);
+
+    let mut kek = [0u8; KEY_LENGTH];
+    pbkdf2::pbkdf2_hmac::<Sha512>(password.as_bytes(), &salt, iterations, &mut kek);
+    KekAes256::from(kek)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let jwe = encrypt(b"hello world", "correct horse", None, MIN_ITERATIONS).unwrap();
+        assert_eq!(jwe.split('.').count(), 5);
+
+        let plaintext = decrypt(&jwe, "correct horse").unwrap();
+        assert_eq!(plaintext, b"hello world");
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let jwe = encrypt(b"hello world", "correct horse", None, MIN_ITERATIONS).unwrap();
+
+        let result = decrypt(&jwe, "battery staple");
+        assert!(matches!(result, Err(Web5Error::Crypto(_))));
+    }
+
+    #[test]
+    fn test_tampered_header() {
+        let jwe = encrypt(b"hello world", "correct horse", None, MIN_ITERATIONS).unwrap();
+        let header = Pbes2Header {
+            cty: Some("text/plain".to_string()),
+            ..Pbes2Header::from_json_byte_array(
+                &general_purpose::URL_SAFE_NO_PAD
+                    .decode(jwe.split('.').next().unwrap())
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+        let mut parts = jwe.split('.').map(String::from).collect::<Vec<String>>();
+        parts[0] = general_purpose::URL_SAFE_NO_PAD.encode(header.to_json_string().unwrap());
+
+        let result = decrypt(&parts.join("."), "correct horse");
+        assert!(matches!(result, Err(Web5Error::Crypto(_))));
+    }
+
+    #[test]
+    fn test_empty_password() {
+        let result = encrypt(b"hello world", "", None, MIN_ITERATIONS);
+        assert!(matches!(result, Err(Web5Error::Parameter(_))));
+    }
+}
@@ -4,6 +4,75 @@ use x25519_dalek::{PublicKey, StaticSecret};
 use crate::{crypto::jwk::Jwk, errors::Result, errors::Web5Error};
 
 const PUBLIC_KEY_LENGTH: usize = 32;
+const PRIVATE_KEY_LENGTH: usize = 32;
+
+/// A trait for performing Diffie-Hellman key agreement with a private key.
+pub trait KeyAgreement: Send + Sync {
+    /// Computes the shared secret between the private key and another party's public key.
+    ///
+    /// # Arguments
+    /// * `public_jwk` - The other party's public key.
+    ///
+    /// # Returns
+    /// The raw shared secret, or an error if the public key is invalid for the curve.
+    fn agree(&self, public_jwk: &Jwk) -> Result<Vec<u8>>;
+}
+
+/// Performs X25519 key agreement with a private JWK.
+#[derive(Clone)]
+pub struct X25519KeyAgreement {
+    private_jwk: Jwk,
+}
+
+impl X25519KeyAgreement {
+    pub fn new(private_jwk: Jwk) -> Self {
+        Self { private_jwk }
+    }
+}
+
+impl KeyAgreement for X25519KeyAgreement {
+    /// Computes the X25519 shared secret.
+    ///
+    /// Public keys of small order, which would yield an all-zero shared secret, are rejected.
+    fn agree(&self, public_jwk: &Jwk) -> Result<Vec<u8>> {
+        let d = self.private_jwk.d.as_ref().ok_or(Web5Error::Crypto(
+            "private key material must be set".to_string(),
+        ))?;
+        let private_key_bytes: [u8; PRIVATE_KEY_LENGTH] = general_purpose::URL_SAFE_NO_PAD
+            .decode(d)?
+            .try_into()
+            .map_err(|_| {
+                Web5Error::Crypto(format!(
+                    "private key has incorrect length {}",
+                    PRIVATE_KEY_LENGTH
+                ))
+            })?;
+
+        if public_jwk.crv != "X25519" {
+            return Err(Web5Error::Crypto(format!(
+                "cannot perform X25519 key agreement with {} key",
+                public_jwk.crv
+            )));
+        }
+        let public_key_bytes: [u8; PUBLIC_KEY_LENGTH] = public_jwk_extract_bytes(public_jwk)?
+            .try_into()
+            .map_err(|_| {
+                Web5Error::Crypto(format!(
+                    "public key has incorrect length {}",
+                    PUBLIC_KEY_LENGTH
+                ))
+            })?;
+
+        let shared_secret = StaticSecret::from(private_key_bytes)
+            .diffie_hellman(&PublicKey::from(public_key_bytes));
+        if !shared_secret.was_contributory() {
+            return Err(Web5Error::Crypto(
+                "public key is of small order".to_string(),
+            ));
+        }
+        Ok(shared_secret.as_bytes().to_vec())
+    }
+}
 
 pub struct X25519Generator;
 
@@ -94,6 +163,52 @@ pub(crate) fn public_jwk_from_bytes(public_key_bytes: &[u8]) -> Result<Jwk> {
 mod tests {
     use super::*;
 
+    mod agree {
+        use super::*;
+
+        fn public_jwk(private_jwk: &Jwk) -> Jwk {
+            Jwk {
+                d: None,
+                ..private_jwk.clone()
+            }
+        }
+
+        #[test]
+        fn test_both_parties_derive_same_secret() {
+            let alice = X25519Generator::generate();
+            let bob = X25519Generator::generate();
+
+            let alice_secret = X25519KeyAgreement::new(alice.clone())
+                .agree(&public_jwk(&bob))
+                .unwrap();
+            let bob_secret = X25519KeyAgreement::new(bob)
+                .agree(&public_jwk(&alice))
+                .unwrap();
+            assert_eq!(alice_secret, bob_secret);
+        }
+
+        #[test]
+        fn test_rejects_small_order_public_key() {
+            let alice = X25519Generator::generate();
+            let small_order = public_jwk_from_bytes(&[0u8; PUBLIC_KEY_LENGTH]).unwrap();
+
+            let result = X25519KeyAgreement::new(alice).agree(&small_order);
+            assert!(matches!(result, Err(Web5Error::Crypto(_))));
+        }
+
+        #[test]
+        fn test_rejects_other_curves() {
+            let alice = X25519Generator::generate();
+            let ed25519 = Jwk {
+                crv: "Ed25519".to_string(),
+                ..public_jwk(&X25519Generator::generate())
+            };
+
+            let result = X25519KeyAgreement::new(alice).agree(&ed25519);
+            assert!(matches!(result, Err(Web5Error::Crypto(_))));
+        }
+    }
+
     mod generate {
         use super::*;
 
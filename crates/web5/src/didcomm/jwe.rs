@@ -0,0 +1,471 @@
+use crate::{
+    crypto::{
+        jwk::Jwk,
+        x25519::{KeyAgreement, X25519Generator, X25519KeyAgreement},
+    },
+    errors::{Result, Web5Error},
+    json::{FromJson, ToJson},
+};
+use aes::Aes256;
+use aes_kw::KekAes256;
+use base64::{engine::general_purpose, Engine as _};
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+pub(crate) const ECDH_ES_A256KW: &str = "ECDH-ES+A256KW";
+pub(crate) const ECDH_1PU_A256KW: &str = "ECDH-1PU+A256KW";
+pub(crate) const A256CBC_HS512: &str = "A256CBC-HS512";
+
+const CEK_LENGTH: usize = 64;
+const IV_LENGTH: usize = 16;
+const TAG_LENGTH: usize = 32;
+const KEK_BITS: u32 = 256;
+
+type Aes256CbcEncryptor = cbc::Encryptor<Aes256>;
+type Aes256CbcDecryptor = cbc::Decryptor<Aes256>;
+
+/// The protected header of a DIDComm encrypted message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct JweHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    pub alg: String,
+    pub enc: String,
+    pub epk: Jwk,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apu: Option<String>,
+    pub apv: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skid: Option<String>,
+}
+
+impl FromJson for JweHeader {}
+impl ToJson for JweHeader {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct JweRecipientHeader {
+    pub kid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct JweRecipient {
+    pub header: JweRecipientHeader,
+    pub encrypted_key: String,
+}
+
+/// A JWE in the general JSON serialization, encrypted to one or more recipients with
+/// `ECDH-ES+A256KW` (anoncrypt) or `ECDH-1PU+A256KW` (authcrypt) key wrapping, and
+/// `A256CBC-HS512` content encryption.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Jwe {
+    pub protected: String,
+    pub recipients: Vec<JweRecipient>,
+    pub iv: String,
+    pub ciphertext: String,
+    pub tag: String,
+}
+
+impl FromJson for Jwe {}
+impl ToJson for Jwe {}
+
+/// The sender's static key, for authenticated encryption.
+pub(crate) struct JweSender<'a> {
+    pub kid: String,
+    pub key_agreement: &'a dyn KeyAgreement,
+}
+
+impl Jwe {
+    /// Encrypts the plaintext to each of the recipients' public keys, keyed by their `kid`.
+    pub fn encrypt(
+        plaintext: &[u8],
+        typ: &str,
+        recipients: &[(String, Jwk)],
+        sender: Option<JweSender>,
+    ) -> Result<Self> {
+        if recipients.is_empty() {
+            return Err(Web5Error::Parameter(
+                "jwe must have at least one recipient".to_string(),
+            ));
+        }
+
+        let ephemeral_private_jwk = X25519Generator::generate();
+        let ephemeral = X25519KeyAgreement::new(ephemeral_private_jwk.clone());
+        let epk = Jwk {
+            alg: None,
+            d: None,
+            ..ephemeral_private_jwk
+        };
+
+        let kids = recipients
+            .iter()
+            .map(|(kid, _)| kid.as_str())
+            .collect::<Vec<_>>();
+        let header = JweHeader {
+            typ: Some(typ.to_string()),
+            alg: match sender {
+                Some(_) => ECDH_1PU_A256KW,
+                None => ECDH_ES_A256KW,
+            }
+            .to_string(),
+            enc: A256CBC_HS512.to_string(),
+            epk,
+            apu: sender
+                .as_ref()
+                .map(|s| general_purpose::URL_SAFE_NO_PAD.encode(&s.kid)),
+            apv: compute_apv(&kids),
+            skid: sender.as_ref().map(|s| s.kid.clone()),
+        };
+        let protected = general_purpose::URL_SAFE_NO_PAD.encode(header.to_json_string()?);
+
+        let mut cek = [0u8; CEK_LENGTH];
+        OsRng.fill_bytes(&mut cek);
+        let mut iv = [0u8; IV_LENGTH];
+        OsRng.fill_bytes(&mut iv);
+        let (ciphertext, tag) = encrypt_content(&cek, &iv, protected.as_bytes(), plaintext)?;
+
+        let apu = decode_optional(&header.apu, "apu")?;
+        let apv = decode(&header.apv, "apv")?;
+        let mut jwe_recipients = vec![];
+        for (kid, public_jwk) in recipients {
+            let mut z = ephemeral.agree(public_jwk)?;
+            if let Some(sender) = &sender {
+                z.extend(sender.key_agreement.agree(public_jwk)?);
+            }
+            let cc_tag = match sender {
+                Some(_) => tag.as_slice(),
+                None => &[],
+            };
+
+            let kek = KekAes256::from(concat_kdf(&z, &header.alg, &apu, &apv, cc_tag));
+            let encrypted_key = kek.wrap_vec(&cek).map_err(|e| {
+                Web5Error::Crypto(format!("failed to wrap content encryption key {}", e))
+            })?;
+            jwe_recipients.push(JweRecipient {
+                header: JweRecipientHeader { kid: kid.clone() },
+                encrypted_key: general_purpose::URL_SAFE_NO_PAD.encode(encrypted_key),
+            });
+        }
+
+        Ok(Self {
+            protected,
+            recipients: jwe_recipients,
+            iv: general_purpose::URL_SAFE_NO_PAD.encode(iv),
+            ciphertext: general_purpose::URL_SAFE_NO_PAD.encode(ciphertext),
+            tag: general_purpose::URL_SAFE_NO_PAD.encode(tag),
+        })
+    }
+
+    pub fn header(&self) -> Result<JweHeader> {
+        JweHeader::from_json_byte_array(&decode(&self.protected, "protected header")?)
+    }
+
+    /// Decrypts the JWE as the recipient `kid`. The sender's public key is required if, and only
+    /// if, the JWE was encrypted with `ECDH-1PU+A256KW`.
+    pub fn decrypt(
+        &self,
+        kid: &str,
+        key_agreement: &dyn KeyAgreement,
+        sender_public_jwk: Option<&Jwk>,
+    ) -> Result<Vec<u8>> {
+        let header = self.header()?;
+        if header.enc != A256CBC_HS512 {
+            return Err(Web5Error::Parameter(format!(
+                "unsupported jwe enc {}",
+                header.enc
+            )));
+        }
+
+        let kids = self
+            .recipients
+            .iter()
+            .map(|r| r.header.kid.as_str())
+            .collect::<Vec<_>>();
+        if header.apv != compute_apv(&kids) {
+            return Err(Web5Error::Crypto(
+                "jwe apv does not match its recipients".to_string(),
+            ));
+        }
+
+        let recipient = self
+            .recipients
+            .iter()
+            .find(|r| r.header.kid == kid)
+            .ok_or_else(|| Web5Error::NotFound(format!("jwe has no recipient {}", kid)))?;
+
+        let tag = decode(&self.tag, "tag")?;
+        let mut z = key_agreement.agree(&header.epk)?;
+        let cc_tag: &[u8] = match (header.alg.as_str(), sender_public_jwk) {
+            (ECDH_ES_A256KW, None) => &[],
+            (ECDH_1PU_A256KW, Some(sender_public_jwk)) => {
+                let skid = header.skid.as_deref().unwrap_or_default();
+                if header.apu != Some(general_purpose::URL_SAFE_NO_PAD.encode(skid)) {
+                    return Err(Web5Error::Crypto(
+                        "jwe apu does not match its skid".to_string(),
+                    ));
+                }
+                z.extend(key_agreement.agree(sender_public_jwk)?);
+                &tag
+            }
+            (ECDH_1PU_A256KW, None) => {
+                return Err(Web5Error::Parameter(
+                    "sender public key is required to decrypt an ECDH-1PU jwe".to_string(),
+                ))
+            }
+            (alg, _) => return Err(Web5Error::Parameter(format!("unsupported jwe alg {}", alg))),
+        };
+
+        let kek = KekAes256::from(concat_kdf(
+            &z,
+            &header.alg,
+            &decode_optional(&header.apu, "apu")?,
+            &decode(&header.apv, "apv")?,
+            cc_tag,
+        ));
+        let cek = kek
+            .unwrap_vec(&decode(&recipient.encrypted_key, "encrypted key")?)
+            .map_err(|_| {
+                Web5Error::Crypto("failed to unwrap content encryption key".to_string())
+            })?;
+        if cek.len() != CEK_LENGTH {
+            return Err(Web5Error::Crypto(
+                "content encryption key has wrong length".to_string(),
+            ));
+        }
+
+        decrypt_content(
+            &cek,
+            &decode(&self.iv, "iv")?,
+            self.protected.as_bytes(),
+            &decode(&self.ciphertext, "ciphertext")?,
+            &tag,
+        )
+    }
+}
+
+/// The agreement PartyVInfo: the hash of the recipients' sorted key ids, joined with `.`.
+fn compute_apv(kids: &[&str]) -> String {
+    let mut kids = kids.to_vec();
+    kids.sort();
+    general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(kids.join(".")))
+}
+
+/// The Concat KDF of [NIST SP 800-56A](https://doi.org/10.6028/NIST.SP.800-56Ar3), as profiled by
+/// [RFC 7518 §4.6.2](https://www.rfc-editor.org/rfc/rfc7518#section-4.6.2). For ECDH-1PU, the
+/// content encryption tag is appended to the SuppPubInfo.
+fn concat_kdf(z: &[u8], alg: &str, apu: &[u8], apv: &[u8], cc_tag: &[u8]) -> [u8; 32] {
+    let mut other_info = vec![];
+    for field in [alg.as_bytes(), apu, apv] {
+        other_info.extend((field.len() as u32).to_be_bytes());
+        other_info.extend(field);
+    }
+    other_info.extend(KEK_BITS.to_be_bytes());
+    if !cc_tag.is_empty() {
+        other_info.extend((cc_tag.len() as u32).to_be_bytes());
+        other_info.extend(cc_tag);
+    }
+
+    // a single round of SHA-256 yields the full 256 bit key encryption key
+    let mut hasher = Sha256::new();
+    hasher.update(1u32.to_be_bytes());
+    hasher.update(z);
+    hasher.update(other_info);
+    hasher.finalize().into()
+}
+
+/// Encrypts with `A256CBC-HS512` ([RFC 7518 §5.2](https://www.rfc-editor.org/rfc/rfc7518#section-5.2)),
+/// returning the ciphertext and authentication tag.
+fn encrypt_content(
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let (mac_key, enc_key) = cek.split_at(CEK_LENGTH / 2);
+    let ciphertext = Aes256CbcEncryptor::new_from_slices(enc_key, iv)
+        .map_err(|e| Web5Error::Crypto(format!("invalid content encryption key {}", e)))?
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+    let tag = compute_tag(mac_key, iv, aad, &ciphertext)?;
+    Ok((ciphertext, tag))
+}
+
+fn decrypt_content(
+    cek: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    ciphertext: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>> {
+    let (mac_key, enc_key) = cek.split_at(CEK_LENGTH / 2);
+    if tag.len() != TAG_LENGTH {
+        return Err(Web5Error::Crypto("jwe tag has wrong length".to_string()));
+    }
+    content_mac(mac_key, iv, aad, ciphertext)?
+        .verify_truncated_left(tag)
+        .map_err(|_| Web5Error::Crypto("jwe authentication tag is invalid".to_string()))?;
+
+    Aes256CbcDecryptor::new_from_slices(enc_key, iv)
+        .map_err(|e| Web5Error::Crypto(format!("invalid content encryption key {}", e)))?
+        .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+        .map_err(|_| Web5Error::Crypto("failed to decrypt jwe ciphertext".to_string()))
+}
+
+fn compute_tag(mac_key: &[u8], iv: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let mac = content_mac(mac_key, iv, aad, ciphertext)?;
+    Ok(mac.finalize().into_bytes()[..TAG_LENGTH].to_vec())
+}
+
+fn content_mac(mac_key: &[u8], iv: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Hmac<Sha512>> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(mac_key)
+        .map_err(|e| Web5Error::Crypto(format!("invalid mac key {}", e)))?;
+    mac.update(aad);
+    mac.update(iv);
+    mac.update(ciphertext);
+    mac.update(&((aad.len() as u64) * 8).to_be_bytes());
+    Ok(mac)
+}
+
+fn decode(value: &str, name: &str) -> Result<Vec<u8>> {
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|e| Web5Error::Encoding(format!("failed to base64 decode jwe {} {}", name, e)))
+}
+
+fn decode_optional(value: &Option<String>, name: &str) -> Result<Vec<u8>> {
+    match value {
+        Some(value) => decode(value, name),
+        None => Ok(vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYP: &str = "application/didcomm-encrypted+json";
+
+    fn key_pair(kid: &str) -> (String, Jwk, X25519KeyAgreement) {
+        let private_jwk = X25519Generator::generate();
+        let public_jwk = Jwk {
+            d: None,
+            ..private_jwk.clone()
+        };
+        (
+            kid.to_string(),
+            public_jwk,
+            X25519KeyAgreement::new(private_jwk),
+        )
+    }
+
+    #[test]
+    fn test_anoncrypt_round_trip() {
+        let (bob_kid, bob_public_jwk, bob) = key_pair("did:example:bob#key-1");
+        let (carol_kid, carol_public_jwk, carol) = key_pair("did:example:carol#key-1");
+
+        let jwe = Jwe::encrypt(
+            b"hello world",
+            TYP,
+            &[
+                (bob_kid.clone(), bob_public_jwk),
+                (carol_kid.clone(), carol_public_jwk),
+            ],
+            None,
+        )
+        .unwrap();
+        assert_eq!(jwe.header().unwrap().alg, ECDH_ES_A256KW);
+
+        assert_eq!(jwe.decrypt(&bob_kid, &bob, None).unwrap(), b"hello world");
+        assert_eq!(
+            jwe.decrypt(&carol_kid, &carol, None).unwrap(),
+            b"hello world"
+        );
+    }
+
+    #[test]
+    fn test_authcrypt_round_trip() {
+        let (alice_kid, alice_public_jwk, alice) = key_pair("did:example:alice#key-1");
+        let (bob_kid, bob_public_jwk, bob) = key_pair("did:example:bob#key-1");
+
+        let jwe = Jwe::encrypt(
+            b"hello world",
+            TYP,
+            &[(bob_kid.clone(), bob_public_jwk)],
+            Some(JweSender {
+                kid: alice_kid.clone(),
+                key_agreement: &alice,
+            }),
+        )
+        .unwrap();
+        let header = jwe.header().unwrap();
+        assert_eq!(header.alg, ECDH_1PU_A256KW);
+        assert_eq!(header.skid, Some(alice_kid));
+
+        let plaintext = jwe
+            .decrypt(&bob_kid, &bob, Some(&alice_public_jwk))
+            .unwrap();
+        assert_eq!(plaintext, b"hello world");
+    }
+
+    #[test]
+    fn test_authcrypt_wrong_sender() {
+        let (alice_kid, _, alice) = key_pair("did:example:alice#key-1");
+        let (_, mallory_public_jwk, _) = key_pair("did:example:mallory#key-1");
+        let (bob_kid, bob_public_jwk, bob) = key_pair("did:example:bob#key-1");
+
+        let jwe = Jwe::encrypt(
+            b"hello world",
+            TYP,
+            &[(bob_kid.clone(), bob_public_jwk)],
+            Some(JweSender {
+                kid: alice_kid,
+                key_agreement: &alice,
+            }),
+        )
+        .unwrap();
+
+        let result = jwe.decrypt(&bob_kid, &bob, Some(&mallory_public_jwk));
+        assert!(matches!(result, Err(Web5Error::Crypto(_))));
+    }
+
+    #[test]
+    fn test_tampered_ciphertext() {
+        let (bob_kid, bob_public_jwk, bob) = key_pair("did:example:bob#key-1");
+
+        let mut jwe = Jwe::encrypt(
+            b"hello world",
+            TYP,
+            &[(bob_kid.clone(), bob_public_jwk)],
+            None,
+        )
+        .unwrap();
+        let mut ciphertext = decode(&jwe.ciphertext, "ciphertext").unwrap();
+        ciphertext[0] ^= 1;
+        jwe.ciphertext = general_purpose::URL_SAFE_NO_PAD.encode(ciphertext);
+
+        let result = jwe.decrypt(&bob_kid, &bob, None);
+        assert!(matches!(result, Err(Web5Error::Crypto(_))));
+    }
+
+    #[test]
+    fn test_removed_recipient() {
+        let (bob_kid, bob_public_jwk, bob) = key_pair("did:example:bob#key-1");
+        let (carol_kid, carol_public_jwk, _) = key_pair("did:example:carol#key-1");
+
+        let mut jwe = Jwe::encrypt(
+            b"hello world",
+            TYP,
+            &[
+                (bob_kid.clone(), bob_public_jwk),
+                (carol_kid, carol_public_jwk),
+            ],
+            None,
+        )
+        .unwrap();
+        jwe.recipients.pop();
+
+        let result = jwe.decrypt(&bob_kid, &bob, None);
+        assert!(matches!(result, Err(Web5Error::Crypto(_))));
+    }
+}
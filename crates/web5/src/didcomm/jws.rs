@@ -0,0 +1,81 @@
+use crate::{
+    crypto::dsa::{Signer, Verifier},
+    errors::{Result, Web5Error},
+    json::{FromJson, ToJson},
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+/// The protected header of a DIDComm signed message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct JwsHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    pub alg: String,
+    pub kid: String,
+}
+
+impl FromJson for JwsHeader {}
+impl ToJson for JwsHeader {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct JwsSignature {
+    pub protected: String,
+    pub signature: String,
+}
+
+/// A JWS in the general JSON serialization, as used for DIDComm signed messages.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Jws {
+    pub payload: String,
+    pub signatures: Vec<JwsSignature>,
+}
+
+impl FromJson for Jws {}
+impl ToJson for Jws {}
+
+impl Jws {
+    pub fn sign(payload: &[u8], header: &JwsHeader, signer: &dyn Signer) -> Result<Self> {
+        let protected = general_purpose::URL_SAFE_NO_PAD.encode(header.to_json_string()?);
+        let payload = general_purpose::URL_SAFE_NO_PAD.encode(payload);
+        let signature = signer.sign(format!("{}.{}", protected, payload).as_bytes())?;
+
+        Ok(Self {
+            payload,
+            signatures: vec![JwsSignature {
+                protected,
+                signature: general_purpose::URL_SAFE_NO_PAD.encode(signature),
+            }],
+        })
+    }
+
+    /// The protected header of the only signature. DIDComm signed messages carry a single
+    /// signature from the sender.
+    pub fn header(&self) -> Result<JwsHeader> {
+        let signature = self.signature()?;
+        JwsHeader::from_json_byte_array(
+            &general_purpose::URL_SAFE_NO_PAD.decode(&signature.protected)?,
+        )
+    }
+
+    pub fn payload(&self) -> Result<Vec<u8>> {
+        Ok(general_purpose::URL_SAFE_NO_PAD.decode(&self.payload)?)
+    }
+
+    pub fn verify(&self, verifier: &dyn Verifier) -> Result<()> {
+        let signature = self.signature()?;
+        verifier.verify(
+            format!("{}.{}", signature.protected, self.payload).as_bytes(),
+            &general_purpose::URL_SAFE_NO_PAD.decode(&signature.signature)?,
+        )
+    }
+
+    fn signature(&self) -> Result<&JwsSignature> {
+        match self.signatures.as_slice() {
+            [signature] => Ok(signature),
+            _ => Err(Web5Error::Parameter(
+                "signed message must have exactly one signature".to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    datetime::{deserialize_optional_unix_timestamp, serialize_optional_unix_timestamp},
+    errors::{Result, Web5Error},
+    json::{FromJson, JsonObject, JsonValue, ToJson},
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// The message type of a [routing protocol](https://identity.foundation/didcomm-messaging/spec/v2.1/#routing-protocol-20)
+/// forward message.
+pub const FORWARD_MESSAGE_TYPE: &str = "https://didcomm.org/routing/2.0/forward";
+
+/// A DIDComm v2 [plaintext message](https://identity.foundation/didcomm-messaging/spec/v2.1/#plaintext-message-structure).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Message {
+    /// A unique identifier for the message.
+    pub id: String,
+    /// The URI identifying the protocol and message type.
+    #[serde(rename = "type")]
+    pub r#type: String,
+    /// The sender's DID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// The recipients' DIDs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Vec<String>>,
+    /// The id of the thread the message belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thid: Option<String>,
+    /// The id of the thread's parent thread.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pthid: Option<String>,
+    #[serde(
+        serialize_with = "serialize_optional_unix_timestamp",
+        deserialize_with = "deserialize_optional_unix_timestamp",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub created_time: Option<SystemTime>,
+    #[serde(
+        serialize_with = "serialize_optional_unix_timestamp",
+        deserialize_with = "deserialize_optional_unix_timestamp",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub expires_time: Option<SystemTime>,
+    /// The protocol specific content of the message.
+    pub body: JsonObject,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<Attachment>>,
+}
+
+impl FromJson for Message {}
+impl ToJson for Message {}
+
+/// A DIDComm v2 [attachment](https://identity.foundation/didcomm-messaging/spec/v2.1/#attachments).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Attachment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    pub data: AttachmentData,
+}
+
+/// The content of an attachment, inlined as JSON or base64url.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct AttachmentData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base64: Option<String>,
+}
+
+impl Message {
+    /// Creates a message with a random id and the current time as its `created_time`.
+    ///
+    /// # Arguments
+    ///
+    /// * `r#type` - The URI identifying the protocol and message type.
+    /// * `body` - The protocol specific content of the message.
+    ///
+    /// # Returns
+    ///
+    /// * `Message` - The new message, without a sender or recipients.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut message = Message::new("https://didcomm.org/basicmessage/2.0/message", body);
+    /// message.from = Some(alice.did.uri.clone());
+    /// message.to = Some(vec![bob_did_uri]);
+    /// ```
+    pub fn new(r#type: &str, body: JsonObject) -> Self {
+        // created_time is serialized in whole seconds
+        let created_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| UNIX_EPOCH + Duration::from_secs(d.as_secs()))
+            .unwrap_or(UNIX_EPOCH);
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            r#type: r#type.to_string(),
+            from: None,
+            to: None,
+            thid: None,
+            pthid: None,
+            created_time: Some(created_time),
+            expires_time: None,
+            body,
+            attachments: None,
+        }
+    }
+}
+
+/// The content of a forward message, which asks a mediator to deliver a packed message to `next`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardMessage {
+    /// The DID or key id of the next hop.
+    pub next: String,
+    /// The packed message to deliver.
+    pub forwarded_message: String,
+}
+
+impl ForwardMessage {
+    /// Creates the plaintext forward message addressed to a mediator.
+    pub(crate) fn to_message(&self, mediator: &str) -> Result<Message> {
+        let mut body = JsonObject::new();
+        body.insert_value("next", JsonValue::String(self.next.clone()));
+
+        let mut message = Message::new(FORWARD_MESSAGE_TYPE, body);
+        message.to = Some(vec![mediator.to_string()]);
+        message.attachments = Some(vec![Attachment {
+            id: None,
+            media_type: None,
+            data: AttachmentData {
+                json: Some(serde_json::from_str(&self.forwarded_message)?),
+                base64: None,
+            },
+        }]);
+        Ok(message)
+    }
+
+    /// Extracts the content of a forward message, as received by a mediator.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The unpacked forward message.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ForwardMessage>` - The next hop and the packed message to deliver to it, or an
+    ///   error if the message isn't a well formed forward message.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (message, _) = Message::unpack(&packed_message, &mediator_did).await?;
+    /// let forward = ForwardMessage::from_message(&message)?;
+    /// deliver(&forward.next, &forward.forwarded_message);
+    /// ```
+    pub fn from_message(message: &Message) -> Result<Self> {
+        if message.r#type != FORWARD_MESSAGE_TYPE {
+            return Err(Web5Error::Parameter(format!(
+                "message type {} is not {}",
+                message.r#type, FORWARD_MESSAGE_TYPE
+            )));
+        }
+
+        let next = match message.body.get_value("next") {
+            Some(JsonValue::String(next)) => next.clone(),
+            _ => {
+                return Err(Web5Error::Parameter(
+                    "forward message body must have next".to_string(),
+                ))
+            }
+        };
+
+        let data = match message.attachments.as_deref() {
+            Some([attachment]) => &attachment.data,
+            _ => {
+                return Err(Web5Error::Parameter(
+                    "forward message must have exactly one attachment".to_string(),
+                ))
+            }
+        };
+        let forwarded_message = match (&data.json, &data.base64) {
+            (Some(json), _) => serde_json::to_string(json)?,
+            (None, Some(base64)) => {
+                String::from_utf8(general_purpose::URL_SAFE_NO_PAD.decode(base64)?)
+                    .map_err(|e| Web5Error::Encoding(e.to_string()))?
+            }
+            (None, None) => {
+                return Err(Web5Error::Parameter(
+                    "forward message attachment must have json or base64 data".to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            next,
+            forwarded_message,
+        })
+    }
+}
@@ -0,0 +1,11 @@
+mod jwe;
+mod jws;
+mod message;
+mod pack;
+
+pub use message::{Attachment, AttachmentData, ForwardMessage, Message, FORWARD_MESSAGE_TYPE};
+pub use pack::{
+    find_didcomm_services, DidCommService, PackEncryptedOptions, PackEncryptedResult,
+    UnpackMetadata, DIDCOMM_MESSAGING_SERVICE_TYPE, ENCRYPTED_MEDIA_TYPE, PLAINTEXT_MEDIA_TYPE,
+    SIGNED_MEDIA_TYPE,
+};
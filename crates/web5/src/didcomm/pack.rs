@@ -0,0 +1,1035 @@
+use super::{
+    jwe::{Jwe, JweSender, ECDH_1PU_A256KW},
+    jws::{Jws, JwsHeader},
+    message::{ForwardMessage, Message},
+};
+use crate::{
+    crypto::{
+        dsa::{verifier_for_public_jwk, Dsa},
+        jwk::Jwk,
+        x25519::KeyAgreement,
+    },
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        data_model::{
+            document::{Document, VerificationRelationship},
+            service::ServiceEndpointEntry,
+            verification_method::VerificationMethod,
+        },
+        did::Did,
+        resolution::resolution_result::ResolutionResult,
+    },
+    errors::{Result, Web5Error},
+    json::{FromJson, JsonValue, ToJson},
+};
+use std::{str::FromStr, sync::Arc};
+
+/// The media type of a plaintext message.
+pub const PLAINTEXT_MEDIA_TYPE: &str = "application/didcomm-plain+json";
+/// The media type of a signed message.
+pub const SIGNED_MEDIA_TYPE: &str = "application/didcomm-signed+json";
+/// The media type of an encrypted message.
+pub const ENCRYPTED_MEDIA_TYPE: &str = "application/didcomm-encrypted+json";
+/// The service type which advertises a DID's DIDComm v2 endpoint.
+pub const DIDCOMM_MESSAGING_SERVICE_TYPE: &str = "DIDCommMessaging";
+
+const DIDCOMM_V2_PROFILE: &str = "didcomm/v2";
+const X25519_CURVE: &str = "X25519";
+// anoncrypt, wrapping authcrypt, wrapping a signed message, wrapping the plaintext
+const MAX_ENVELOPE_DEPTH: usize = 4;
+
+/// Options for [`Message::pack_encrypted`].
+#[derive(Default)]
+pub struct PackEncryptedOptions {
+    /// Sign the message before encrypting it, for non-repudiation. Requires a sender.
+    pub sign: bool,
+    /// Wrap the message in forward messages for the mediators named by the recipient's
+    /// `DIDCommMessaging` service routing keys. Defaults to true.
+    pub forward: Option<bool>,
+}
+
+/// The outcome of [`Message::pack_encrypted`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackEncryptedResult {
+    /// The encrypted message, wrapped in forward messages if the recipient uses mediators.
+    pub packed_message: String,
+    /// The URI the packed message should be delivered to, from the recipient's
+    /// `DIDCommMessaging` service, if it has one.
+    pub service_endpoint: Option<String>,
+}
+
+/// What [`Message::unpack`] learned about how a message was protected.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UnpackMetadata {
+    /// The message was encrypted.
+    pub encrypted: bool,
+    /// The sender was authenticated, with authcrypt or a signature.
+    pub authenticated: bool,
+    /// The message was signed, so the sender can't repudiate it.
+    pub non_repudiation: bool,
+    /// The message was anoncrypted, so the sender is hidden from intermediaries.
+    pub anonymous_sender: bool,
+    /// The sender's key agreement key id, if the message was authcrypted.
+    pub encrypted_from_kid: Option<String>,
+    /// The key ids the message was encrypted to.
+    pub encrypted_to_kids: Vec<String>,
+    /// The sender's signing key id, if the message was signed.
+    pub sign_from: Option<String>,
+}
+
+/// A DIDComm v2 endpoint advertised by a `DIDCommMessaging` service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DidCommService {
+    pub id: String,
+    /// The URI messages are delivered to.
+    pub uri: String,
+    /// The media types and profiles the endpoint accepts.
+    pub accept: Vec<String>,
+    /// The key ids of the mediators messages must be forwarded through, outermost first.
+    pub routing_keys: Vec<String>,
+}
+
+impl Message {
+    /// Packs the message as a plaintext message, without any protection.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The plaintext message JSON.
+    pub fn pack_plaintext(&self) -> Result<String> {
+        let mut value = serde_json::to_value(self)?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert(
+                "typ".to_string(),
+                serde_json::Value::String(PLAINTEXT_MEDIA_TYPE.to_string()),
+            );
+        }
+        Ok(serde_json::to_string(&value)?)
+    }
+
+    /// Packs the message as a signed message, with a key from the sender's `authentication`
+    /// verification relationship.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The sender, who must be the message's `from`.
+    /// * `verification_method_id` - Optional. The authentication key to sign with, by default the
+    ///   first one whose private key is held by the key manager.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The signed message JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let signed_message = message.pack_signed(&alice, None)?;
+    /// ```
+    pub fn pack_signed(
+        &self,
+        bearer_did: &BearerDid,
+        verification_method_id: Option<String>,
+    ) -> Result<String> {
+        if self.from.as_deref() != Some(bearer_did.did.uri.as_str()) {
+            return Err(Web5Error::Parameter(format!(
+                "message from must be the signer {}",
+                bearer_did.did.uri
+            )));
+        }
+
+        let (verification_method, signer) = bearer_did.select_signer(&SignerOptions {
+            verification_method_id,
+            verification_relationship: Some(VerificationRelationship::Authentication),
+            algorithms: None,
+        })?;
+        let alg = match Dsa::from_str(&verification_method.public_key_jwk.crv)? {
+            Dsa::Ed25519 => "EdDSA",
+            Dsa::Secp256k1 => "ES256K",
+            Dsa::Secp256r1 => "ES256",
+        };
+
+        let jws = Jws::sign(
+            self.pack_plaintext()?.as_bytes(),
+            &JwsHeader {
+                typ: Some(SIGNED_MEDIA_TYPE.to_string()),
+                alg: alg.to_string(),
+                kid: verification_method.id,
+            },
+            signer.as_ref(),
+        )?;
+        jws.to_json_string()
+    }
+
+    /// Packs the message as an encrypted message for a recipient, resolving the recipient's DID
+    /// for its X25519 `keyAgreement` keys.
+    ///
+    /// Without a sender the message is anoncrypted (`ECDH-ES+A256KW`), and with one it is
+    /// authcrypted (`ECDH-1PU+A256KW`) using one of the sender's X25519 `keyAgreement` keys. The
+    /// result is then forwarded through the mediators listed by the recipient's `DIDCommMessaging`
+    /// service.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - The recipient's DID, or a DID URL to encrypt to only one of its keys. The
+    ///   message's `to` must include the recipient's DID.
+    /// * `from` - Optional. The sender, who must be the message's `from`.
+    /// * `options` - Whether to sign the message first, and whether to forward it.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PackEncryptedResult>` - The packed message and where to deliver it.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let result = message
+    ///     .pack_encrypted(&bob_did_uri, Some(&alice), PackEncryptedOptions::default())
+    ///     .await?;
+    /// deliver(&result.service_endpoint.unwrap(), &result.packed_message);
+    /// ```
+    pub async fn pack_encrypted(
+        &self,
+        to: &str,
+        from: Option<&BearerDid>,
+        options: PackEncryptedOptions,
+    ) -> Result<PackEncryptedResult> {
+        let to_did = Did::parse(to)?;
+        check_recipient(self, &to_did.uri)?;
+        if let Some(from) = from {
+            if self.from.as_deref() != Some(from.did.uri.as_str()) {
+                return Err(Web5Error::Parameter(format!(
+                    "message from must be the sender {}",
+                    from.did.uri
+                )));
+            }
+        }
+
+        let payload = match (options.sign, from) {
+            (true, Some(from)) => self.pack_signed(from, None)?,
+            (true, None) => {
+                return Err(Web5Error::Parameter(
+                    "a sender is required to sign the message".to_string(),
+                ))
+            }
+            (false, _) => self.pack_plaintext()?,
+        };
+
+        let document = resolve_document(&to_did.uri).await?;
+        let recipients = key_agreement_keys(&document, to)?;
+
+        let jwe = match from {
+            Some(from) => {
+                let (kid, key_agreement) = sender_key_agreement(from)?;
+                Jwe::encrypt(
+                    payload.as_bytes(),
+                    ENCRYPTED_MEDIA_TYPE,
+                    &recipients,
+                    Some(JweSender {
+                        kid,
+                        key_agreement: key_agreement.as_ref(),
+                    }),
+                )?
+            }
+            None => Jwe::encrypt(payload.as_bytes(), ENCRYPTED_MEDIA_TYPE, &recipients, None)?,
+        };
+        let mut packed_message = jwe.to_json_string()?;
+
+        let service = didcomm_services(&document).into_iter().next();
+        if options.forward.unwrap_or(true) {
+            if let Some(service) = &service {
+                packed_message =
+                    wrap_in_forward(packed_message, &to_did.uri, &service.routing_keys).await?;
+            }
+        }
+
+        Ok(PackEncryptedResult {
+            packed_message,
+            service_endpoint: service.map(|s| s.uri),
+        })
+    }
+
+    /// Unpacks a plaintext, signed, or encrypted message, verifying every signature and
+    /// decrypting every encryption layer.
+    ///
+    /// Senders' DIDs are resolved to verify signatures and authcrypt, and the sender must be the
+    /// message's `from`. An encrypted message must list the recipient in its `to`.
+    ///
+    /// # Arguments
+    ///
+    /// * `packed_message` - The packed message JSON.
+    /// * `recipient` - The recipient, whose `keyAgreement` keys decrypt the message.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Message, UnpackMetadata)>` - The plaintext message and how it was protected.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (message, metadata) = Message::unpack(&packed_message, &bob).await?;
+    /// assert!(metadata.authenticated);
+    /// ```
+    pub async fn unpack(
+        packed_message: &str,
+        recipient: &BearerDid,
+    ) -> Result<(Message, UnpackMetadata)> {
+        let mut metadata = UnpackMetadata::default();
+        let mut packed_message = packed_message.to_string();
+
+        for _ in 0..MAX_ENVELOPE_DEPTH {
+            let value: serde_json::Value = serde_json::from_str(&packed_message)?;
+            if value.get("ciphertext").is_some() {
+                if metadata.sign_from.is_some() {
+                    return Err(Web5Error::Parameter(
+                        "signed message must not contain an encrypted message".to_string(),
+                    ));
+                }
+                let jwe = Jwe::from_json_string(&packed_message)?;
+                packed_message = decrypt(&jwe, recipient, &mut metadata).await?;
+            } else if value.get("signatures").is_some() {
+                if metadata.sign_from.is_some() {
+                    return Err(Web5Error::Parameter(
+                        "signed message must not contain a signed message".to_string(),
+                    ));
+                }
+                let jws = Jws::from_json_string(&packed_message)?;
+                packed_message = verify(&jws, &mut metadata).await?;
+            } else {
+                let message = Message::from_json_string(&packed_message)?;
+                check_sender(&message, &metadata)?;
+                if metadata.encrypted {
+                    check_recipient(&message, &recipient.did.uri)?;
+                }
+                return Ok((message, metadata));
+            }
+        }
+
+        Err(Web5Error::Parameter(
+            "message has too many nested envelopes".to_string(),
+        ))
+    }
+}
+
+/// Resolves a DID and returns its DIDComm v2 endpoints, in the order the services are listed.
+///
+/// # Arguments
+///
+/// * `did_uri` - The DID to discover endpoints for.
+///
+/// # Returns
+///
+/// * `Result<Vec<DidCommService>>` - The endpoints, empty if the DID has no `DIDCommMessaging`
+///   service.
+///
+/// # Examples
+///
+/// ```ignore
+/// let services = find_didcomm_services("did:web:example.com").await?;
+/// ```
+pub async fn find_didcomm_services(did_uri: &str) -> Result<Vec<DidCommService>> {
+    let document = resolve_document(did_uri).await?;
+    Ok(didcomm_services(&document))
+}
+
+fn didcomm_services(document: &Document) -> Vec<DidCommService> {
+    let mut didcomm_services = vec![];
+    for service in document.service.as_deref().unwrap_or_default() {
+        if service.r#type != DIDCOMM_MESSAGING_SERVICE_TYPE {
+            continue;
+        }
+
+        for entry in service.service_endpoint.entries() {
+            let didcomm_service = match entry {
+                ServiceEndpointEntry::Uri(uri) => DidCommService {
+                    id: service.id.clone(),
+                    uri,
+                    accept: vec![],
+                    routing_keys: vec![],
+                },
+                ServiceEndpointEntry::Map(map) => {
+                    let Some(JsonValue::String(uri)) = map.get_value("uri") else {
+                        continue;
+                    };
+                    DidCommService {
+                        id: service.id.clone(),
+                        uri: uri.clone(),
+                        accept: string_array(map.get_value("accept")),
+                        routing_keys: string_array(map.get_value("routingKeys")),
+                    }
+                }
+            };
+
+            // an endpoint without accept is assumed to accept didcomm v2
+            if didcomm_service.accept.is_empty()
+                || didcomm_service
+                    .accept
+                    .iter()
+                    .any(|a| a == DIDCOMM_V2_PROFILE)
+            {
+                didcomm_services.push(didcomm_service);
+            }
+        }
+    }
+    didcomm_services
+}
+
+fn string_array(value: Option<&JsonValue>) -> Vec<String> {
+    match value {
+        Some(JsonValue::Array(values)) => values
+            .iter()
+            .filter_map(|v| match v {
+                JsonValue::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Wraps the packed message in a forward message for each routing key, innermost last.
+/// Checks that an encrypted message lists its recipient in its `to`, so that a message encrypted
+/// for one party can't be passed off as addressed to another.
+fn check_recipient(message: &Message, recipient: &str) -> Result<()> {
+    match &message.to {
+        Some(to) if to.iter().any(|uri| uri == recipient) => Ok(()),
+        _ => Err(Web5Error::Parameter(format!(
+            "message to does not include {}",
+            recipient
+        ))),
+    }
+}
+
+async fn wrap_in_forward(
+    packed_message: String,
+    to: &str,
+    routing_keys: &[String],
+) -> Result<String> {
+    let mut packed_message = packed_message;
+    let mut next = to.to_string();
+    for routing_key in routing_keys.iter().rev() {
+        let mediator = Did::parse(routing_key)?.uri;
+        let forward = ForwardMessage {
+            next,
+            forwarded_message: packed_message,
+        }
+        .to_message(&mediator)?;
+
+        let document = resolve_document(&mediator).await?;
+        let recipients = key_agreement_keys(&document, routing_key)?;
+        packed_message = Jwe::encrypt(
+            forward.pack_plaintext()?.as_bytes(),
+            ENCRYPTED_MEDIA_TYPE,
+            &recipients,
+            None,
+        )?
+        .to_json_string()?;
+        next = routing_key.clone();
+    }
+    Ok(packed_message)
+}
+
+async fn decrypt(
+    jwe: &Jwe,
+    recipient: &BearerDid,
+    metadata: &mut UnpackMetadata,
+) -> Result<String> {
+    let header = jwe.header()?;
+
+    let kid = jwe
+        .recipients
+        .iter()
+        .map(|r| &r.header.kid)
+        .find(|kid| recipient.get_key_agreement(kid).is_ok())
+        .ok_or_else(|| {
+            Web5Error::NotFound(format!(
+                "message is not encrypted to a key agreement key of {}",
+                recipient.did.uri
+            ))
+        })?;
+    let key_agreement = recipient.get_key_agreement(kid)?;
+
+    let sender_public_jwk = match (header.alg.as_str(), &header.skid) {
+        (ECDH_1PU_A256KW, Some(skid)) => {
+            let document = resolve_document(&Did::parse(skid)?.uri).await?;
+            let (_, public_jwk) = key_agreement_keys(&document, skid)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    Web5Error::NotFound(format!("{} is not a key agreement key", skid))
+                })?;
+            Some(public_jwk)
+        }
+        (ECDH_1PU_A256KW, None) => {
+            return Err(Web5Error::Parameter(
+                "authcrypt message must have skid".to_string(),
+            ))
+        }
+        _ => None,
+    };
+
+    let plaintext = jwe.decrypt(kid, key_agreement.as_ref(), sender_public_jwk.as_ref())?;
+
+    metadata.encrypted = true;
+    match header.skid {
+        Some(skid) if sender_public_jwk.is_some() => {
+            metadata.authenticated = true;
+            metadata.encrypted_from_kid = Some(skid);
+        }
+        _ => metadata.anonymous_sender = true,
+    }
+    metadata.encrypted_to_kids = jwe
+        .recipients
+        .iter()
+        .map(|r| r.header.kid.clone())
+        .collect();
+
+    String::from_utf8(plaintext).map_err(|e| Web5Error::Encoding(e.to_string()))
+}
+
+async fn verify(jws: &Jws, metadata: &mut UnpackMetadata) -> Result<String> {
+    let header = jws.header()?;
+    let did_uri = Did::parse(&header.kid)?.uri;
+    let document = resolve_document(&did_uri).await?;
+
+    let verification_method = document
+        .verification_relationship(VerificationRelationship::Authentication)
+        .iter()
+        .map(|reference| absolute_id(&document, reference))
+        .find(|id| id == &header.kid)
+        .and_then(|id| find_verification_method(&document, &id))
+        .ok_or_else(|| {
+            Web5Error::Crypto(format!(
+                "{} is not an authentication verification method",
+                header.kid
+            ))
+        })?;
+
+    let public_jwk = verification_method.public_key_jwk.clone();
+    let verifier = verifier_for_public_jwk(public_jwk)?;
+    jws.verify(verifier.as_ref())?;
+
+    metadata.authenticated = true;
+    metadata.non_repudiation = true;
+    metadata.sign_from = Some(header.kid);
+
+    String::from_utf8(jws.payload()?).map_err(|e| Web5Error::Encoding(e.to_string()))
+}
+
+fn check_sender(message: &Message, metadata: &UnpackMetadata) -> Result<()> {
+    for kid in [&metadata.encrypted_from_kid, &metadata.sign_from]
+        .into_iter()
+        .flatten()
+    {
+        let sender = Did::parse(kid)?.uri;
+        if message.from.as_deref() != Some(sender.as_str()) {
+            return Err(Web5Error::Parameter(format!(
+                "message from does not match the sender {}",
+                sender
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the X25519 `keyAgreement` keys of the document, or only the key `uri` names if it is a
+/// DID URL with a fragment.
+fn key_agreement_keys(document: &Document, uri: &str) -> Result<Vec<(String, Jwk)>> {
+    let fragment = Did::parse(uri)?.fragment;
+    let keys = document
+        .verification_relationship(VerificationRelationship::KeyAgreement)
+        .iter()
+        .map(|reference| absolute_id(document, reference))
+        .filter(|id| fragment.is_none() || id == uri)
+        .filter_map(|id| find_verification_method(document, &id))
+        .filter(|vm| vm.public_key_jwk.crv == X25519_CURVE)
+        .map(|vm| (vm.id.clone(), vm.public_key_jwk.clone()))
+        .collect::<Vec<_>>();
+
+    if keys.is_empty() {
+        return Err(Web5Error::NotFound(format!(
+            "no {} key agreement key found for {}",
+            X25519_CURVE, uri
+        )));
+    }
+    Ok(keys)
+}
+
+/// Selects the sender's first X25519 `keyAgreement` key whose private key it holds.
+fn sender_key_agreement(from: &BearerDid) -> Result<(String, Arc<dyn KeyAgreement>)> {
+    let keys = key_agreement_keys(&from.document, &from.did.uri)?;
+    keys.into_iter()
+        .find_map(|(kid, _)| {
+            from.get_key_agreement(&kid)
+                .ok()
+                .map(|key_agreement| (kid, key_agreement))
+        })
+        .ok_or_else(|| {
+            Web5Error::NotFound(format!("no key agreement key found for {}", from.did.uri))
+        })
+}
+
+fn find_verification_method<'a>(
+    document: &'a Document,
+    id: &str,
+) -> Option<&'a VerificationMethod> {
+    document
+        .verification_method
+        .iter()
+        .find(|vm| absolute_id(document, &vm.id) == id)
+}
+
+fn absolute_id(document: &Document, reference: &str) -> String {
+    match reference.starts_with('#') {
+        true => format!("{}{}", document.id, reference),
+        false => reference.to_string(),
+    }
+}
+
+async fn resolve_document(did_uri: &str) -> Result<Document> {
+    let resolution_result = ResolutionResult::resolve(did_uri).await;
+    if let Some(e) = resolution_result.resolution_metadata.error {
+        return Err(e.into());
+    }
+    resolution_result.document.ok_or_else(|| {
+        Web5Error::NotFound(format!("failed to resolve did document for {}", did_uri))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::{
+            key_managers::{in_memory_key_manager::InMemoryKeyManager, KeyManager},
+            x25519::X25519Generator,
+        },
+        dids::{
+            data_model::service::Service,
+            methods::{
+                did_jwk::DidJwk,
+                did_web::{DidWeb, DidWebCreateOptions},
+            },
+        },
+        json::JsonObject,
+    };
+    use base64::{engine::general_purpose, Engine as _};
+    use mockito::{Server, ServerGuard};
+    use std::collections::HashMap;
+
+    const BASIC_MESSAGE_TYPE: &str = "https://didcomm.org/basicmessage/2.0/message";
+
+    /// Creates a did:web, with an Ed25519 key for signing and an X25519 key for key agreement,
+    /// hosted by the mock server.
+    async fn party(mock_server: &mut ServerGuard, service: Option<Service>) -> BearerDid {
+        let key_manager = Arc::new(InMemoryKeyManager::new());
+        let public_jwk = key_manager
+            .import_private_jwk(X25519Generator::generate())
+            .unwrap();
+
+        let bearer_did = DidWeb::create(
+            &mock_server.url(),
+            Some(DidWebCreateOptions {
+                key_manager: Some(key_manager),
+                verification_method: Some(vec![VerificationMethod {
+                    id: "#key-1".to_string(),
+                    r#type: "JsonWebKey".to_string(),
+                    controller: String::new(),
+                    public_key_jwk: public_jwk,
+                }]),
+                verification_relationships: Some(HashMap::from([(
+                    "#key-1".to_string(),
+                    vec![VerificationRelationship::KeyAgreement],
+                )])),
+                service: service.map(|s| vec![s]),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        mock_server
+            .mock("GET", "/.well-known/did.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(bearer_did.document.to_json_string().unwrap())
+            .create_async()
+            .await;
+        bearer_did
+    }
+
+    fn didcomm_service(uri: &str, routing_keys: Vec<String>) -> Service {
+        let mut endpoint = JsonObject::new();
+        endpoint.insert_value("uri", JsonValue::String(uri.to_string()));
+        endpoint.insert_value(
+            "accept",
+            JsonValue::Array(vec![JsonValue::String(DIDCOMM_V2_PROFILE.to_string())]),
+        );
+        endpoint.insert_value(
+            "routingKeys",
+            JsonValue::Array(routing_keys.into_iter().map(JsonValue::String).collect()),
+        );
+        Service {
+            id: "#didcomm".to_string(),
+            r#type: DIDCOMM_MESSAGING_SERVICE_TYPE.to_string(),
+            service_endpoint: endpoint.into(),
+            additional_properties: None,
+        }
+    }
+
+    fn basic_message(from: &BearerDid, to: &BearerDid) -> Message {
+        let mut body = JsonObject::new();
+        body.insert_value("content", JsonValue::String("hello bob".to_string()));
+
+        let mut message = Message::new(BASIC_MESSAGE_TYPE, body);
+        message.from = Some(from.did.uri.clone());
+        message.to = Some(vec![to.did.uri.clone()]);
+        message
+    }
+
+    /// A mediator which holds the messages forwarded to each of the parties it serves.
+    struct Mediator {
+        bearer_did: BearerDid,
+        queues: HashMap<String, Vec<String>>,
+    }
+
+    impl Mediator {
+        async fn receive(&mut self, packed_message: &str) {
+            let (message, metadata) = Message::unpack(packed_message, &self.bearer_did)
+                .await
+                .unwrap();
+            assert!(metadata.anonymous_sender);
+
+            let forward = ForwardMessage::from_message(&message).unwrap();
+            self.queues
+                .entry(Did::parse(&forward.next).unwrap().uri)
+                .or_default()
+                .push(forward.forwarded_message);
+        }
+    }
+
+    mod plaintext {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_round_trip() {
+            let mut mock_server = Server::new_async().await;
+            let alice = party(&mut mock_server, None).await;
+            let message = basic_message(&alice, &alice);
+
+            let (unpacked, metadata) = Message::unpack(&message.pack_plaintext().unwrap(), &alice)
+                .await
+                .unwrap();
+            assert_eq!(unpacked, message);
+            assert_eq!(metadata, UnpackMetadata::default());
+        }
+    }
+
+    mod signed {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_round_trip() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+            let message = basic_message(&alice, &bob);
+
+            let packed_message = message.pack_signed(&alice, None).unwrap();
+            let (unpacked, metadata) = Message::unpack(&packed_message, &bob).await.unwrap();
+            assert_eq!(unpacked, message);
+            assert!(metadata.non_repudiation);
+            assert!(metadata.authenticated);
+            assert_eq!(metadata.sign_from, Some(format!("{}#key-0", alice.did.uri)));
+        }
+
+        #[tokio::test]
+        async fn test_signer_must_be_from() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+
+            let result = basic_message(&alice, &bob).pack_signed(&bob, None);
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[tokio::test]
+        async fn test_tampered_payload() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+
+            let packed_message = basic_message(&alice, &bob)
+                .pack_signed(&alice, None)
+                .unwrap();
+            let mut jws = Jws::from_json_string(&packed_message).unwrap();
+            let mut forged = basic_message(&alice, &bob);
+            forged.body = JsonObject::new();
+            jws.payload = general_purpose::URL_SAFE_NO_PAD.encode(forged.pack_plaintext().unwrap());
+
+            let result = Message::unpack(&jws.to_json_string().unwrap(), &bob).await;
+            assert!(matches!(result, Err(Web5Error::Crypto(_))));
+        }
+    }
+
+    mod encrypted {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_anoncrypt() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+            let message = basic_message(&alice, &bob);
+
+            let result = message
+                .pack_encrypted(&bob.did.uri, None, PackEncryptedOptions::default())
+                .await
+                .unwrap();
+            assert_eq!(result.service_endpoint, None);
+
+            let (unpacked, metadata) = Message::unpack(&result.packed_message, &bob).await.unwrap();
+            assert_eq!(unpacked, message);
+            assert!(metadata.encrypted);
+            assert!(metadata.anonymous_sender);
+            assert!(!metadata.authenticated);
+            assert_eq!(
+                metadata.encrypted_to_kids,
+                vec![format!("{}#key-1", bob.did.uri)]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_authcrypt() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+            let message = basic_message(&alice, &bob);
+
+            let result = message
+                .pack_encrypted(&bob.did.uri, Some(&alice), PackEncryptedOptions::default())
+                .await
+                .unwrap();
+
+            let (unpacked, metadata) = Message::unpack(&result.packed_message, &bob).await.unwrap();
+            assert_eq!(unpacked, message);
+            assert!(metadata.encrypted);
+            assert!(metadata.authenticated);
+            assert!(!metadata.anonymous_sender);
+            assert!(!metadata.non_repudiation);
+            assert_eq!(
+                metadata.encrypted_from_kid,
+                Some(format!("{}#key-1", alice.did.uri))
+            );
+        }
+
+        #[tokio::test]
+        async fn test_signed_and_authcrypted() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+            let message = basic_message(&alice, &bob);
+
+            let result = message
+                .pack_encrypted(
+                    &bob.did.uri,
+                    Some(&alice),
+                    PackEncryptedOptions {
+                        sign: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+
+            let (unpacked, metadata) = Message::unpack(&result.packed_message, &bob).await.unwrap();
+            assert_eq!(unpacked, message);
+            assert!(metadata.encrypted);
+            assert!(metadata.non_repudiation);
+            assert_eq!(metadata.sign_from, Some(format!("{}#key-0", alice.did.uri)));
+        }
+
+        #[tokio::test]
+        async fn test_other_recipient_cannot_decrypt() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let mut carol_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+            let carol = party(&mut carol_server, None).await;
+
+            let result = basic_message(&alice, &bob)
+                .pack_encrypted(&bob.did.uri, Some(&alice), PackEncryptedOptions::default())
+                .await
+                .unwrap();
+
+            let result = Message::unpack(&result.packed_message, &carol).await;
+            assert!(matches!(result, Err(Web5Error::NotFound(_))));
+        }
+
+        #[tokio::test]
+        async fn test_sender_must_be_from() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+
+            let result = basic_message(&alice, &bob)
+                .pack_encrypted(&bob.did.uri, Some(&bob), PackEncryptedOptions::default())
+                .await;
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[tokio::test]
+        async fn test_recipient_must_be_to() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(&mut bob_server, None).await;
+            let mut message = basic_message(&alice, &bob);
+            message.to = None;
+
+            let result = message
+                .pack_encrypted(&bob.did.uri, None, PackEncryptedOptions::default())
+                .await;
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+
+            let document = resolve_document(&bob.did.uri).await.unwrap();
+            let recipients = key_agreement_keys(&document, &bob.did.uri).unwrap();
+            let packed_message = Jwe::encrypt(
+                message.pack_plaintext().unwrap().as_bytes(),
+                ENCRYPTED_MEDIA_TYPE,
+                &recipients,
+                None,
+            )
+            .unwrap()
+            .to_json_string()
+            .unwrap();
+
+            let result = Message::unpack(&packed_message, &bob).await;
+            match result {
+                Err(Web5Error::Parameter(msg)) => {
+                    assert_eq!(msg, format!("message to does not include {}", bob.did.uri))
+                }
+                _ => panic!("expected parameter error but got {:?}", result),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_recipient_without_key_agreement() {
+            let bob = DidJwk::create(None).unwrap();
+            let mut message = Message::new(BASIC_MESSAGE_TYPE, JsonObject::new());
+            message.to = Some(vec![bob.did.uri.clone()]);
+
+            let result = message
+                .pack_encrypted(&bob.did.uri, None, PackEncryptedOptions::default())
+                .await;
+            assert!(matches!(result, Err(Web5Error::NotFound(_))));
+        }
+    }
+
+    mod routing {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_find_didcomm_services() {
+            let mut bob_server = Server::new_async().await;
+            let bob = party(
+                &mut bob_server,
+                Some(didcomm_service(
+                    "https://mediator.example.com",
+                    vec!["did:example:mediator#key-1".to_string()],
+                )),
+            )
+            .await;
+
+            let services = find_didcomm_services(&bob.did.uri).await.unwrap();
+            assert_eq!(
+                services,
+                vec![DidCommService {
+                    id: format!("{}#didcomm", bob.did.uri),
+                    uri: "https://mediator.example.com".to_string(),
+                    accept: vec![DIDCOMM_V2_PROFILE.to_string()],
+                    routing_keys: vec!["did:example:mediator#key-1".to_string()],
+                }]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_forward_through_mediator() {
+            let mut mediator_server = Server::new_async().await;
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+
+            let mediator_did = party(&mut mediator_server, None).await;
+            let mediator_kid = format!("{}#key-1", mediator_did.did.uri);
+            let mut mediator = Mediator {
+                bearer_did: mediator_did,
+                queues: HashMap::new(),
+            };
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(
+                &mut bob_server,
+                Some(didcomm_service(
+                    "https://mediator.example.com",
+                    vec![mediator_kid.clone()],
+                )),
+            )
+            .await;
+            let message = basic_message(&alice, &bob);
+
+            let result = message
+                .pack_encrypted(&bob.did.uri, Some(&alice), PackEncryptedOptions::default())
+                .await
+                .unwrap();
+            assert_eq!(
+                result.service_endpoint,
+                Some("https://mediator.example.com".to_string())
+            );
+
+            // bob can't read the forward message, only the mediator can
+            assert!(Message::unpack(&result.packed_message, &bob).await.is_err());
+            mediator.receive(&result.packed_message).await;
+
+            let delivered = mediator.queues.remove(&bob.did.uri).unwrap();
+            assert_eq!(delivered.len(), 1);
+            let (unpacked, metadata) = Message::unpack(&delivered[0], &bob).await.unwrap();
+            assert_eq!(unpacked, message);
+            assert!(metadata.authenticated);
+        }
+
+        #[tokio::test]
+        async fn test_forward_disabled() {
+            let mut alice_server = Server::new_async().await;
+            let mut bob_server = Server::new_async().await;
+            let alice = party(&mut alice_server, None).await;
+            let bob = party(
+                &mut bob_server,
+                Some(didcomm_service(
+                    "https://mediator.example.com",
+                    vec!["did:example:mediator#key-1".to_string()],
+                )),
+            )
+            .await;
+            let message = basic_message(&alice, &bob);
+
+            let result = message
+                .pack_encrypted(
+                    &bob.did.uri,
+                    None,
+                    PackEncryptedOptions {
+                        forward: Some(false),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+
+            let (unpacked, _) = Message::unpack(&result.packed_message, &bob).await.unwrap();
+            assert_eq!(unpacked, message);
+        }
+    }
+}
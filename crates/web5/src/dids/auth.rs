@@ -0,0 +1,597 @@
+use crate::{
+    credentials::VerificationError,
+    crypto::dsa::verifier_for_public_jwk,
+    datetime::{deserialize_rfc3339, serialize_rfc3339},
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        data_model::document::{FindVerificationMethodOptions, VerificationRelationship},
+        did::Did,
+        resolution::resolution_result::ResolutionResult,
+    },
+    errors::{Result, Web5Error},
+    jose::{Jwt, JwtClaims},
+    json::{FromJson, JsonValue, ToJson},
+};
+use base64::{engine::general_purpose, Engine as _};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+/// The name of the JWT claim which carries the challenge nonce.
+pub const NONCE_CLAIM: &str = "nonce";
+
+/// The number of outstanding challenges an [`AuthVerifier`] holds at most. Once reached, issuing
+/// a challenge forgets the one which expires soonest.
+pub const MAX_OUTSTANDING_CHALLENGES: usize = 10_000;
+
+const NONCE_LENGTH: usize = 32;
+
+/// A challenge issued by a verifier to a DID holder who wishes to authenticate.
+///
+/// The holder proves control of their DID by answering with [`AuthChallenge::respond`], a JWT
+/// signed by one of the DID's `authentication` verification methods.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuthChallenge {
+    /// A random, single-use value which binds the response to this challenge.
+    pub nonce: String,
+    /// The identifier of the verifier, which the response must be addressed to.
+    pub audience: String,
+    /// The time after which the challenge can no longer be answered.
+    #[serde(
+        rename = "expiresAt",
+        serialize_with = "serialize_rfc3339",
+        deserialize_with = "deserialize_rfc3339"
+    )]
+    pub expires_at: SystemTime,
+}
+
+impl FromJson for AuthChallenge {}
+impl ToJson for AuthChallenge {}
+
+impl AuthChallenge {
+    /// Creates a challenge with a random nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `audience` - The identifier of the verifier, e.g. its origin or DID.
+    /// * `ttl` - How long the holder has to answer the challenge.
+    ///
+    /// # Returns
+    ///
+    /// * `AuthChallenge` - The new challenge.
+    pub fn new(audience: &str, ttl: Duration) -> Self {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+
+        Self {
+            nonce: general_purpose::URL_SAFE_NO_PAD.encode(nonce),
+            audience: audience.to_string(),
+            expires_at: SystemTime::now() + ttl,
+        }
+    }
+
+    /// Returns true if the challenge can no longer be answered.
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() > self.expires_at
+    }
+
+    /// Answers the challenge as the holder of a DID.
+    ///
+    /// The response is a JWT issued by and about the DID, addressed to the challenge's audience,
+    /// carrying the challenge's nonce, and signed with a key from the DID's `authentication`
+    /// verification relationship.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID authenticating.
+    /// * `verification_method_id` - Optional. The authentication key to sign with, by default the
+    ///   first one whose private key is held by the key manager.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The response as a compact JWS.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let challenge = AuthChallenge::from_json_string(&challenge_json)?;
+    /// let response = challenge.respond(&bearer_did, None)?;
+    /// ```
+    pub fn respond(
+        &self,
+        bearer_did: &BearerDid,
+        verification_method_id: Option<String>,
+    ) -> Result<String> {
+        if self.is_expired() {
+            return Err(
+                VerificationError::Authentication("challenge has expired".to_string()).into(),
+            );
+        }
+
+        let did_uri = bearer_did.did.uri.clone();
+        let claims = JwtClaims {
+            aud: Some(vec![self.audience.clone()]),
+            iss: Some(did_uri.clone()),
+            sub: Some(did_uri),
+            iat: Some(SystemTime::now()),
+            exp: Some(self.expires_at),
+            additional_properties: Some(HashMap::from([(
+                NONCE_CLAIM.to_string(),
+                JsonValue::String(self.nonce.clone()),
+            )])),
+            ..Default::default()
+        };
+
+        let jwt = Jwt::from_claims_with_options(
+            &claims,
+            bearer_did,
+            &SignerOptions {
+                verification_method_id,
+                verification_relationship: Some(VerificationRelationship::Authentication),
+                algorithms: None,
+            },
+        )?;
+        Ok(jwt.compact_jws)
+    }
+}
+
+/// Verifies a response to a challenge, without replay protection.
+///
+/// The signing key's DID is resolved, and the response must be signed by a verification method
+/// listed under the DID's `authentication` relationship, be issued by that DID, be addressed to the
+/// challenge's audience, and carry the challenge's nonce before the challenge expires. Prefer
+/// [`AuthVerifier::verify`], which additionally ensures each challenge is only answered once.
+///
+/// # Arguments
+///
+/// * `response` - The compact JWS returned by [`AuthChallenge::respond`].
+/// * `challenge` - The challenge the response answers.
+///
+/// # Returns
+///
+/// * `Result<String>` - The authenticated DID URI.
+///
+/// # Examples
+///
+/// ```ignore
+/// let did_uri = verify_auth_response(&response, &challenge).await?;
+/// ```
+pub async fn verify_auth_response(response: &str, challenge: &AuthChallenge) -> Result<String> {
+    let jwt = Jwt::from_compact_jws(response, false).await?;
+
+    match jwt
+        .claims
+        .additional_properties
+        .as_ref()
+        .and_then(|p| p.get(NONCE_CLAIM))
+    {
+        Some(JsonValue::String(nonce)) if nonce == &challenge.nonce => {}
+        Some(_) => return Err(VerificationError::ClaimMismatch(NONCE_CLAIM.to_string()).into()),
+        None => return Err(VerificationError::MissingClaim(NONCE_CLAIM.to_string()).into()),
+    }
+
+    if challenge.is_expired() {
+        return Err(VerificationError::Authentication("challenge has expired".to_string()).into());
+    }
+    if let Some(exp) = jwt.claims.exp {
+        if SystemTime::now() > exp {
+            return Err(
+                VerificationError::Authentication("response has expired".to_string()).into(),
+            );
+        }
+    }
+
+    match &jwt.claims.aud {
+        Some(aud) if aud.contains(&challenge.audience) => {}
+        Some(_) => return Err(VerificationError::ClaimMismatch("aud".to_string()).into()),
+        None => return Err(VerificationError::MissingClaim("aud".to_string()).into()),
+    }
+
+    let did_uri = Did::parse(&jwt.kid)?.uri;
+    match &jwt.claims.iss {
+        Some(iss) if iss == &did_uri => {}
+        Some(_) => return Err(VerificationError::ClaimMismatch("iss".to_string()).into()),
+        None => return Err(VerificationError::MissingClaim("iss".to_string()).into()),
+    }
+
+    let resolution_result = ResolutionResult::resolve(&did_uri).await;
+    if let Some(e) = resolution_result.resolution_metadata.error {
+        return Err(e.into());
+    }
+    let document = resolution_result.document.ok_or_else(|| {
+        Web5Error::NotFound(format!("failed to resolve did document for {}", did_uri))
+    })?;
+
+    if !document.has_verification_relationship(VerificationRelationship::Authentication, &jwt.kid) {
+        return Err(VerificationError::Authentication(format!(
+            "{} is not an authentication verification method",
+            jwt.kid
+        ))
+        .into());
+    }
+
+    let public_jwk = document
+        .find_verification_method(FindVerificationMethodOptions {
+            verification_method_id: Some(jwt.kid.clone()),
+        })?
+        .public_key_jwk;
+    let verifier = verifier_for_public_jwk(public_jwk)?;
+    let message = format!("{}.{}", jwt.parts[0], jwt.parts[1]);
+    verifier.verify(message.as_bytes(), &jwt.signature)?;
+
+    Ok(did_uri)
+}
+
+/// Issues challenges and verifies the responses to them, allowing each challenge to be answered
+/// only once.
+///
+/// Outstanding challenges are held in memory, so a verifier should be shared by every request
+/// handler of a service.
+pub struct AuthVerifier {
+    audience: String,
+    ttl: Duration,
+    max_challenges: usize,
+    challenges: Mutex<HashMap<String, AuthChallenge>>,
+}
+
+impl AuthVerifier {
+    /// Creates a verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `audience` - The identifier of the verifier, e.g. its origin or DID.
+    /// * `ttl` - How long holders have to answer a challenge.
+    pub fn new(audience: &str, ttl: Duration) -> Self {
+        Self {
+            audience: audience.to_string(),
+            ttl,
+            max_challenges: MAX_OUTSTANDING_CHALLENGES,
+            challenges: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Issues a new challenge, and forgets any outstanding challenges which have expired. If
+    /// [`MAX_OUTSTANDING_CHALLENGES`] are still outstanding, the one which expires soonest is
+    /// forgotten too.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuthChallenge>` - The challenge to send to the holder.
+    pub fn create_challenge(&self) -> Result<AuthChallenge> {
+        let challenge = AuthChallenge::new(&self.audience, self.ttl);
+
+        let mut challenges = self.challenges.lock()?;
+        challenges.retain(|_, c| !c.is_expired());
+        while challenges.len() >= self.max_challenges {
+            let soonest = challenges
+                .values()
+                .min_by_key(|c| c.expires_at)
+                .map(|c| c.nonce.clone());
+            match soonest {
+                Some(nonce) => challenges.remove(&nonce),
+                None => break,
+            };
+        }
+        challenges.insert(challenge.nonce.clone(), challenge.clone());
+
+        Ok(challenge)
+    }
+
+    /// Verifies a response to one of this verifier's challenges, as per [`verify_auth_response`].
+    ///
+    /// The challenge is consumed once a response to it is verified, so a response can never be
+    /// replayed. A response which fails verification leaves the challenge outstanding, so that
+    /// it can't be used to burn another holder's challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The compact JWS returned by [`AuthChallenge::respond`].
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The authenticated DID URI.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let verifier = AuthVerifier::new("https://example.com", Duration::from_secs(300));
+    /// let challenge = verifier.create_challenge()?;
+    /// // ... send the challenge to the holder, and receive their response
+    /// let did_uri = verifier.verify(&response).await?;
+    /// ```
+    pub async fn verify(&self, response: &str) -> Result<String> {
+        let jwt = Jwt::from_compact_jws(response, false).await?;
+        let nonce = match jwt
+            .claims
+            .additional_properties
+            .as_ref()
+            .and_then(|p| p.get(NONCE_CLAIM))
+        {
+            Some(JsonValue::String(nonce)) => nonce.clone(),
+            _ => return Err(VerificationError::MissingClaim(NONCE_CLAIM.to_string()).into()),
+        };
+
+        let unknown_challenge = || {
+            VerificationError::Authentication(
+                "unknown challenge, it may have already been answered".to_string(),
+            )
+        };
+
+        let challenge = self
+            .challenges
+            .lock()?
+            .get(&nonce)
+            .cloned()
+            .ok_or_else(unknown_challenge)?;
+
+        let did_uri = verify_auth_response(response, &challenge).await?;
+
+        // only one of concurrent verifications of the same challenge may consume it
+        self.challenges
+            .lock()?
+            .remove(&nonce)
+            .ok_or_else(unknown_challenge)?;
+
+        Ok(did_uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::{
+            dsa::ed25519::Ed25519Generator,
+            key_managers::{in_memory_key_manager::InMemoryKeyManager, KeyManager},
+        },
+        dids::{
+            data_model::verification_method::VerificationMethod,
+            methods::{
+                did_jwk::DidJwk,
+                did_web::{DidWeb, DidWebCreateOptions},
+            },
+        },
+    };
+    use std::sync::Arc;
+
+    const AUDIENCE: &str = "https://example.com";
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let verifier = AuthVerifier::new(AUDIENCE, Duration::from_secs(60));
+
+        let challenge = verifier.create_challenge().unwrap();
+        let response = challenge.respond(&bearer_did, None).unwrap();
+
+        let did_uri = verifier.verify(&response).await.unwrap();
+        assert_eq!(did_uri, bearer_did.did.uri);
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let verifier = AuthVerifier::new(AUDIENCE, Duration::from_secs(60));
+
+        let challenge = verifier.create_challenge().unwrap();
+        let response = challenge.respond(&bearer_did, None).unwrap();
+        verifier.verify(&response).await.unwrap();
+
+        let result = verifier.verify(&response).await;
+        assert!(matches!(
+            result,
+            Err(Web5Error::CredentialError(
+                VerificationError::Authentication(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_response_keeps_challenge() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let verifier = AuthVerifier::new(AUDIENCE, Duration::from_secs(60));
+
+        let challenge = verifier.create_challenge().unwrap();
+        let response = challenge.respond(&bearer_did, None).unwrap();
+
+        let other_did = DidJwk::create(None).unwrap();
+        let forged = other_did_signature(&response, &other_did);
+        assert!(verifier.verify(&forged).await.is_err());
+
+        let did_uri = verifier.verify(&response).await.unwrap();
+        assert_eq!(did_uri, bearer_did.did.uri);
+    }
+
+    #[tokio::test]
+    async fn test_bounds_outstanding_challenges() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let verifier = AuthVerifier {
+            max_challenges: 2,
+            ..AuthVerifier::new(AUDIENCE, Duration::from_secs(60))
+        };
+
+        let first = verifier.create_challenge().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        let second = verifier.create_challenge().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        let third = verifier.create_challenge().unwrap();
+        assert_eq!(verifier.challenges.lock().unwrap().len(), 2);
+
+        let result = verifier
+            .verify(&first.respond(&bearer_did, None).unwrap())
+            .await;
+        assert!(matches!(
+            result,
+            Err(Web5Error::CredentialError(
+                VerificationError::Authentication(_)
+            ))
+        ));
+        for challenge in [second, third] {
+            let response = challenge.respond(&bearer_did, None).unwrap();
+            assert!(verifier.verify(&response).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wrong_audience() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let challenge = AuthChallenge::new("https://attacker.example", Duration::from_secs(60));
+        let response = challenge.respond(&bearer_did, None).unwrap();
+
+        let result = verify_auth_response(
+            &response,
+            &AuthChallenge {
+                audience: AUDIENCE.to_string(),
+                ..challenge
+            },
+        )
+        .await;
+        assert_eq!(
+            result,
+            Err(VerificationError::ClaimMismatch("aud".to_string()).into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_nonce() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let challenge = AuthChallenge::new(AUDIENCE, Duration::from_secs(60));
+        let response = challenge.respond(&bearer_did, None).unwrap();
+
+        let other_challenge = AuthChallenge::new(AUDIENCE, Duration::from_secs(60));
+        let result = verify_auth_response(&response, &other_challenge).await;
+        assert_eq!(
+            result,
+            Err(VerificationError::ClaimMismatch(NONCE_CLAIM.to_string()).into())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_challenge() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let challenge = AuthChallenge::new(AUDIENCE, Duration::from_secs(60));
+        let response = challenge.respond(&bearer_did, None).unwrap();
+
+        let expired = AuthChallenge {
+            expires_at: SystemTime::now() - Duration::from_secs(1),
+            ..challenge
+        };
+        let result = verify_auth_response(&response, &expired).await;
+        assert!(matches!(
+            result,
+            Err(Web5Error::CredentialError(
+                VerificationError::Authentication(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_signature() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let challenge = AuthChallenge::new(AUDIENCE, Duration::from_secs(60));
+        let response = challenge.respond(&bearer_did, None).unwrap();
+
+        let other_did = DidJwk::create(None).unwrap();
+        let forged = other_did_signature(&response, &other_did);
+        let result = verify_auth_response(&forged, &challenge).await;
+        assert!(matches!(result, Err(Web5Error::Crypto(_))));
+    }
+
+    #[tokio::test]
+    async fn test_not_authentication_method() {
+        let mut bearer_did = DidJwk::create(None).unwrap();
+        bearer_did.document.authentication = None;
+        let challenge = AuthChallenge::new(AUDIENCE, Duration::from_secs(60));
+
+        let result = challenge.respond(&bearer_did, None);
+        assert!(matches!(result, Err(Web5Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_assertion_method_key() {
+        let mut mock_server = mockito::Server::new_async().await;
+        let key_manager = Arc::new(InMemoryKeyManager::new());
+        let public_jwk = key_manager
+            .import_private_jwk(Ed25519Generator::generate())
+            .unwrap();
+        let bearer_did = DidWeb::create(
+            &mock_server.url(),
+            Some(DidWebCreateOptions {
+                key_manager: Some(key_manager),
+                verification_method: Some(vec![VerificationMethod {
+                    id: "#key-1".to_string(),
+                    r#type: "JsonWebKey".to_string(),
+                    controller: "".to_string(),
+                    public_key_jwk: public_jwk,
+                }]),
+                verification_relationships: Some(HashMap::from([(
+                    "#key-1".to_string(),
+                    vec![VerificationRelationship::AssertionMethod],
+                )])),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        mock_server
+            .mock("GET", "/.well-known/did.json")
+            .with_status(200)
+            .with_body(bearer_did.document.to_json_string().unwrap())
+            .create_async()
+            .await;
+
+        let verifier = AuthVerifier::new(AUDIENCE, Duration::from_secs(60));
+        let challenge = verifier.create_challenge().unwrap();
+        let did_uri = bearer_did.did.uri.clone();
+        let claims = JwtClaims {
+            aud: Some(vec![AUDIENCE.to_string()]),
+            iss: Some(did_uri.clone()),
+            sub: Some(did_uri.clone()),
+            iat: Some(SystemTime::now()),
+            exp: Some(challenge.expires_at),
+            additional_properties: Some(HashMap::from([(
+                NONCE_CLAIM.to_string(),
+                JsonValue::String(challenge.nonce.clone()),
+            )])),
+            ..Default::default()
+        };
+        let response = Jwt::from_claims_with_options(
+            &claims,
+            &bearer_did,
+            &SignerOptions {
+                verification_method_id: Some(format!("{}#key-1", did_uri)),
+                verification_relationship: Some(VerificationRelationship::AssertionMethod),
+                algorithms: None,
+            },
+        )
+        .unwrap()
+        .compact_jws;
+
+        let result = verifier.verify(&response).await;
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::Authentication(message))) => {
+                assert!(message.contains("is not an authentication verification method"))
+            }
+            _ => panic!("expected authentication error but got {:?}", result),
+        }
+    }
+
+    fn other_did_signature(response: &str, other_did: &BearerDid) -> String {
+        let parts = response.split('.').collect::<Vec<&str>>();
+        let signer = other_did
+            .get_signer(&format!("{}#0", other_did.did.uri))
+            .unwrap();
+        let signature = signer
+            .sign(format!("{}.{}", parts[0], parts[1]).as_bytes())
+            .unwrap();
+        format!(
+            "{}.{}.{}",
+            parts[0],
+            parts[1],
+            general_purpose::URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+}
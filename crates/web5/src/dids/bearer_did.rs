@@ -1,5 +1,8 @@
 use super::{
-    data_model::document::{Document, FindVerificationMethodOptions},
+    data_model::{
+        document::{Document, FindVerificationMethodOptions, VerificationRelationship},
+        verification_method::VerificationMethod,
+    },
     did::Did,
     portable_did::PortableDid,
 };
@@ -7,11 +10,26 @@ use crate::{
     crypto::{
         dsa::Signer,
         key_managers::{in_memory_key_manager::InMemoryKeyManager, KeyExporter, KeyManager},
+        x25519::KeyAgreement,
     },
     errors::{Result, Web5Error},
 };
 use std::sync::Arc;
 
+/// Options for selecting which verification method a [`BearerDid`] signs with.
+///
+/// With no options set, the first verification method whose private key is held by the key
+/// manager is selected.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignerOptions {
+    /// Sign with exactly this verification method.
+    pub verification_method_id: Option<String>,
+    /// Only consider verification methods listed under this verification relationship.
+    pub verification_relationship: Option<VerificationRelationship>,
+    /// Acceptable JWK `alg` values, most preferred first.
+    pub algorithms: Option<Vec<String>>,
+}
+
 /// Represents a Decentralized Identifier (DID) along with its DID document and key manager.
 ///
 /// This struct provides functions to interact with the DID, such as signing data and exporting
@@ -65,6 +83,31 @@ impl BearerDid {
         })
     }
 
+    /// Creates a `BearerDid` from a password-encrypted Portable DID, as produced by
+    /// [`BearerDid::to_encrypted_portable_did`].
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypted_portable_did` - The encrypted Portable DID as a compact JWE.
+    /// * `password` - The password it was encrypted with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - A `BearerDid` instance or an error if decryption or key import fails.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let bearer_did = BearerDid::from_encrypted_portable_did(&encrypted_portable_did, "password")?;
+    /// ```
+    pub fn from_encrypted_portable_did(
+        encrypted_portable_did: &str,
+        password: &str,
+    ) -> Result<Self> {
+        let portable_did = PortableDid::from_encrypted(encrypted_portable_did, password)?;
+        Self::from_portable_did(portable_did)
+    }
+
     /// Returns a signer for the specified verification method ID.
     ///
     /// This signer can be used to sign data using a key associated with the DID.
@@ -101,6 +144,160 @@ impl BearerDid {
         self.key_manager.get_signer(public_jwk)
     }
 
+    /// Returns a key agreement for the specified verification method ID.
+    ///
+    /// The key agreement can be used to derive shared secrets with other parties, e.g. to decrypt
+    /// messages encrypted to one of the DID's `keyAgreement` keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `verification_method_id` - The ID of the verification method to use.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Arc<dyn KeyAgreement>>` - A key agreement instance or an error if the verification
+    ///   method is invalid or its key manager doesn't support key agreement.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let key_agreement = bearer_did.get_key_agreement("did:example:123#key-1")?;
+    /// let shared_secret = key_agreement.agree(&their_public_jwk)?;
+    /// ```
+    pub fn get_key_agreement(&self, verification_method_id: &str) -> Result<Arc<dyn KeyAgreement>> {
+        if verification_method_id.is_empty() {
+            return Err(Web5Error::Parameter(
+                "verification_method_id cannot be empty".to_string(),
+            ));
+        }
+
+        let public_jwk = self
+            .document
+            .find_verification_method(FindVerificationMethodOptions {
+                verification_method_id: Some(verification_method_id.to_string()),
+            })?
+            .public_key_jwk;
+        self.key_manager.get_key_agreement(public_jwk)
+    }
+
+    /// Selects a verification method according to the given options and returns it along with
+    /// a signer for its key.
+    ///
+    /// An explicit `verification_method_id` must satisfy the relationship and algorithm
+    /// constraints. Otherwise the candidates are the methods under the requested relationship
+    /// (or all methods), ordered by algorithm preference, and the first one whose private key is
+    /// held by the key manager is selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The relationship, algorithm preference and/or key id to select by.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(VerificationMethod, Arc<dyn Signer>)>` - The selected verification method and
+    ///   its signer, or an error if no verification method satisfies the options.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (verification_method, signer) = bearer_did.select_signer(&SignerOptions {
+    ///     verification_relationship: Some(VerificationRelationship::Authentication),
+    ///     ..Default::default()
+    /// })?;
+    /// let signature = signer.sign(b"Hello, world!")?;
+    /// ```
+    pub fn select_signer(
+        &self,
+        options: &SignerOptions,
+    ) -> Result<(VerificationMethod, Arc<dyn Signer>)> {
+        let candidates: Vec<&VerificationMethod> = match options.verification_relationship {
+            Some(relationship) => self
+                .document
+                .verification_relationship(relationship)
+                .iter()
+                .filter_map(|reference| {
+                    let id = self.document.resolve_reference(reference);
+                    self.document
+                        .verification_method
+                        .iter()
+                        .find(|vm| vm.id == id)
+                })
+                .collect(),
+            None => self.document.verification_method.iter().collect(),
+        };
+
+        if let Some(verification_method_id) = &options.verification_method_id {
+            if verification_method_id.is_empty() {
+                return Err(Web5Error::Parameter(
+                    "verification_method_id cannot be empty".to_string(),
+                ));
+            }
+
+            let verification_method =
+                self.document
+                    .find_verification_method(FindVerificationMethodOptions {
+                        verification_method_id: Some(verification_method_id.clone()),
+                    })?;
+
+            if let Some(relationship) = options.verification_relationship {
+                if !candidates.iter().any(|vm| &vm.id == verification_method_id) {
+                    return Err(Web5Error::Parameter(format!(
+                        "verification_method_id {} is not {}",
+                        verification_method_id,
+                        relationship_description(relationship)
+                    )));
+                }
+            }
+
+            if let Some(algorithms) = &options.algorithms {
+                if !has_algorithm(&verification_method, algorithms) {
+                    return Err(Web5Error::Parameter(format!(
+                        "verification_method_id {} does not use one of the algorithms {}",
+                        verification_method_id,
+                        algorithms.join(", ")
+                    )));
+                }
+            }
+
+            let signer = self
+                .key_manager
+                .get_signer(verification_method.public_key_jwk.clone())?;
+            return Ok((verification_method, signer));
+        }
+
+        let ordered: Vec<&VerificationMethod> = match &options.algorithms {
+            Some(algorithms) => algorithms
+                .iter()
+                .flat_map(|alg| {
+                    candidates
+                        .iter()
+                        .filter(move |vm| vm.public_key_jwk.alg.as_deref() == Some(alg.as_str()))
+                        .copied()
+                })
+                .collect(),
+            None => candidates,
+        };
+
+        for verification_method in ordered {
+            if let Ok(signer) = self
+                .key_manager
+                .get_signer(verification_method.public_key_jwk.clone())
+            {
+                return Ok((verification_method.clone(), signer));
+            }
+        }
+
+        Err(Web5Error::NotFound(
+            match options.verification_relationship {
+                Some(relationship) => format!(
+                    "no signing key found for {}",
+                    relationship_description(relationship)
+                ),
+                None => "no signing key found".to_string(),
+            },
+        ))
+    }
+
     /// Exports the `BearerDid` into a `PortableDid`.
     ///
     /// This method serializes the DID, its document, and associated private keys for transport or storage.
@@ -130,6 +327,50 @@ impl BearerDid {
             private_jwks,
         })
     }
+
+    /// Exports the `BearerDid` into a password-encrypted Portable DID.
+    ///
+    /// The Portable DID is encrypted as a compact JWE using `PBES2-HS512+A256KW` key management
+    /// and `A256GCM` content encryption, so backups don't hold private keys in clear text.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_exporter` - An instance of `KeyExporter` used to export private keys.
+    /// * `password` - The password to encrypt with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The encrypted Portable DID or an error if key export or encryption fails.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let encrypted_portable_did = bearer_did.to_encrypted_portable_did(key_manager, "password")?;
+    /// ```
+    pub fn to_encrypted_portable_did(
+        &self,
+        key_exporter: Arc<dyn KeyExporter>,
+        password: &str,
+    ) -> Result<String> {
+        self.to_portable_did(key_exporter)?.encrypt(password)
+    }
+}
+
+fn has_algorithm(verification_method: &VerificationMethod, algorithms: &[String]) -> bool {
+    match &verification_method.public_key_jwk.alg {
+        Some(alg) => algorithms.contains(alg),
+        None => false,
+    }
+}
+
+fn relationship_description(relationship: VerificationRelationship) -> &'static str {
+    match relationship {
+        VerificationRelationship::Authentication => "an authentication",
+        VerificationRelationship::AssertionMethod => "an assertion_method",
+        VerificationRelationship::KeyAgreement => "a key_agreement",
+        VerificationRelationship::CapabilityInvocation => "a capability_invocation",
+        VerificationRelationship::CapabilityDelegation => "a capability_delegation",
+    }
 }
 
 #[cfg(test)]
@@ -198,6 +439,147 @@ mod tests {
         }
     }
 
+    mod select_signer {
+        use super::*;
+        use crate::{
+            crypto::{dsa::secp256k1::Secp256k1Generator, key_managers::KeyManager},
+            dids::methods::did_web::{DidWeb, DidWebCreateOptions},
+        };
+        use std::collections::HashMap;
+
+        fn bearer_did_with_secp256k1_authentication() -> BearerDid {
+            let key_manager = Arc::new(InMemoryKeyManager::new());
+            let did_uri = "did:web:example.com";
+            let public_jwk = key_manager
+                .import_private_jwk(Secp256k1Generator::generate())
+                .unwrap();
+            let verification_method = VerificationMethod {
+                id: format!("{}#auth", did_uri),
+                r#type: "JsonWebKey".to_string(),
+                controller: did_uri.to_string(),
+                public_key_jwk: public_jwk,
+            };
+
+            DidWeb::create(
+                "example.com",
+                Some(DidWebCreateOptions {
+                    key_manager: Some(key_manager),
+                    verification_method: Some(vec![verification_method.clone()]),
+                    verification_relationships: Some(HashMap::from([
+                        (
+                            format!("{}#key-0", did_uri),
+                            vec![VerificationRelationship::AssertionMethod],
+                        ),
+                        (
+                            verification_method.id,
+                            vec![VerificationRelationship::Authentication],
+                        ),
+                    ])),
+                    ..Default::default()
+                }),
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_defaults_to_first_verification_method() {
+            let bearer_did = DidJwk::create(None).unwrap();
+
+            let (verification_method, _) =
+                bearer_did.select_signer(&SignerOptions::default()).unwrap();
+            assert_eq!(
+                verification_method,
+                bearer_did.document.verification_method[0]
+            );
+        }
+
+        #[test]
+        fn test_selects_by_relationship() {
+            let bearer_did = bearer_did_with_secp256k1_authentication();
+
+            let (verification_method, signer) = bearer_did
+                .select_signer(&SignerOptions {
+                    verification_relationship: Some(VerificationRelationship::Authentication),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(verification_method.id, "did:web:example.com#auth");
+            assert!(signer.sign(b"hello").is_ok());
+        }
+
+        #[test]
+        fn test_selects_by_relative_reference() {
+            let mut bearer_did = DidJwk::create(None).unwrap();
+            bearer_did.document.authentication = Some(vec!["#0".to_string()]);
+
+            let (verification_method, _) = bearer_did
+                .select_signer(&SignerOptions {
+                    verification_relationship: Some(VerificationRelationship::Authentication),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                verification_method,
+                bearer_did.document.verification_method[0]
+            );
+        }
+
+        #[test]
+        fn test_selects_by_algorithm_preference() {
+            let bearer_did = bearer_did_with_secp256k1_authentication();
+
+            let (verification_method, _) = bearer_did
+                .select_signer(&SignerOptions {
+                    algorithms: Some(vec!["ES256K".to_string(), "Ed25519".to_string()]),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(verification_method.id, "did:web:example.com#auth");
+
+            let (verification_method, _) = bearer_did
+                .select_signer(&SignerOptions {
+                    algorithms: Some(vec!["Ed25519".to_string()]),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(verification_method.id, "did:web:example.com#key-0");
+        }
+
+        #[test]
+        fn test_verification_method_id_not_in_relationship() {
+            let bearer_did = bearer_did_with_secp256k1_authentication();
+
+            let result = bearer_did.select_signer(&SignerOptions {
+                verification_method_id: Some("did:web:example.com#auth".to_string()),
+                verification_relationship: Some(VerificationRelationship::AssertionMethod),
+                ..Default::default()
+            });
+            match result {
+                Err(Web5Error::Parameter(msg)) => assert_eq!(
+                    msg,
+                    "verification_method_id did:web:example.com#auth is not an assertion_method"
+                ),
+                _ => panic!("Expected Web5Error::Parameter"),
+            }
+        }
+
+        #[test]
+        fn test_no_matching_verification_method() {
+            let bearer_did = DidJwk::create(None).unwrap();
+
+            let result = bearer_did.select_signer(&SignerOptions {
+                verification_relationship: Some(VerificationRelationship::KeyAgreement),
+                ..Default::default()
+            });
+            match result {
+                Err(Web5Error::NotFound(msg)) => {
+                    assert_eq!(msg, "no signing key found for a key_agreement")
+                }
+                _ => panic!("Expected Web5Error::NotFound"),
+            }
+        }
+    }
+
     mod to_portable_did {
         use super::*;
 
@@ -216,4 +598,33 @@ mod tests {
             assert_eq!(bearer_did.did.uri, portable_did.did_uri);
         }
     }
+
+    mod from_encrypted_portable_did {
+        use super::*;
+        use crate::crypto::pbes2;
+
+        #[test]
+        fn test_round_trip() {
+            let key_manager = Arc::new(InMemoryKeyManager::new());
+            let bearer_did = DidJwk::create(Some(DidJwkCreateOptions {
+                key_manager: Some(key_manager.clone()),
+                ..Default::default()
+            }))
+            .unwrap();
+
+            let encrypted_portable_did = bearer_did
+                .to_portable_did(key_manager)
+                .unwrap()
+                .encrypt_with_iterations("password", pbes2::MIN_ITERATIONS)
+                .unwrap();
+            let imported =
+                BearerDid::from_encrypted_portable_did(&encrypted_portable_did, "password")
+                    .unwrap();
+
+            assert_eq!(imported.did.uri, bearer_did.did.uri);
+            assert!(imported
+                .get_signer(&bearer_did.document.verification_method[0].id)
+                .is_ok());
+        }
+    }
 }
@@ -0,0 +1,554 @@
+use crate::{
+    credentials::VerificationError,
+    crypto::dsa::verifier_for_public_jwk,
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        data_model::document::{Document, FindVerificationMethodOptions, VerificationRelationship},
+        did::Did,
+        resolution::resolution_result::ResolutionResult,
+    },
+    errors::{Result, Web5Error},
+    jcs,
+    jose::{Jwt, JwtClaims},
+    json::{FromJson, JsonValue},
+};
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+/// The name of the JWT claim which carries the updated document of a document update.
+pub const DOCUMENT_CLAIM: &str = "document";
+
+/// The name of the JWT claim which carries the digest of the document a document update replaces,
+/// so the update can't be replayed against any other version of the document.
+pub const PREVIOUS_DOCUMENT_DIGEST_CLAIM: &str = "previousDocumentDigest";
+
+/// How long after it's issued a document update is accepted.
+pub const MAX_DOCUMENT_UPDATE_AGE: Duration = Duration::from_secs(300);
+
+/// How far in the future a document update's `iat` may be, to allow for clock skew.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+/// The verification relationships under which a controller's verification methods may act on
+/// behalf of the DIDs it controls.
+pub const CONTROLLER_RELATIONSHIPS: [VerificationRelationship; 2] = [
+    VerificationRelationship::CapabilityInvocation,
+    VerificationRelationship::CapabilityDelegation,
+];
+
+/// Returns whether the options select a controller verification method, which may sign on
+/// behalf of a DID other than the signer's own.
+pub(crate) fn signs_as_controller(options: &SignerOptions) -> bool {
+    options
+        .verification_relationship
+        .is_some_and(|r| CONTROLLER_RELATIONSHIPS.contains(&r))
+}
+
+/// Checks that a verification method may sign on behalf of a DID.
+///
+/// A verification method of the DID itself is always accepted. A verification method of another
+/// DID is accepted if that DID is listed in the `controller` of the DID's document, and the
+/// verification method is one of the controller's `capabilityInvocation` or
+/// `capabilityDelegation` verification methods. The signature itself isn't verified.
+///
+/// # Arguments
+///
+/// * `did_uri` - The DID signed for, such as a credential's issuer.
+/// * `verification_method_id` - The id of the signing verification method, such as a JWS `kid`.
+///
+/// # Returns
+///
+/// * `Result<()>` - Ok if the verification method is authorized, or a
+///   `VerificationError::UnauthorizedSigner` if it isn't.
+///
+/// # Examples
+///
+/// ```ignore
+/// let jwt = Jwt::from_compact_jws(&vc_jwt, true).await?;
+/// verify_controller_authorization(&jwt.claims.iss.unwrap(), &jwt.kid).await?;
+/// ```
+pub async fn verify_controller_authorization(
+    did_uri: &str,
+    verification_method_id: &str,
+) -> Result<()> {
+    let did = Did::parse(did_uri)?;
+    let signer_did = Did::parse(verification_method_id)?;
+    if signer_did.uri == did.uri {
+        return Ok(());
+    }
+
+    let document = resolve_document(&did.uri).await?;
+    authorize_controller(&document, &signer_did.uri, verification_method_id).await?;
+
+    Ok(())
+}
+
+/// Signs an updated DID document, as the DID itself or as one of its controllers.
+///
+/// The update is a JWT whose `sub` is the updated DID, whose `iss` is the signer, whose
+/// `document` claim is the updated document, and whose `previousDocumentDigest` claim is the
+/// [`document_digest`] of the current document. It's signed by a `capabilityInvocation`
+/// verification method of the signer.
+///
+/// # Arguments
+///
+/// * `current` - The DID's current document, which the update replaces.
+/// * `document` - The updated DID document.
+/// * `signer` - The DID itself, or a controller listed in its current document.
+///
+/// # Returns
+///
+/// * `Result<String>` - The compact JWS of the update.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut document = controlled_did.document.clone();
+/// document.service = Some(vec![service]);
+/// let update = sign_document_update(&controlled_did.document, &document, &controller_did)?;
+/// let controlled_did = DidWeb::apply_update(controlled_did, &update).await?;
+/// ```
+pub fn sign_document_update(
+    current: &Document,
+    document: &Document,
+    signer: &BearerDid,
+) -> Result<String> {
+    let claims = JwtClaims {
+        iss: Some(signer.did.uri.clone()),
+        sub: Some(document.id.clone()),
+        iat: Some(SystemTime::now()),
+        additional_properties: Some(HashMap::from([
+            (
+                DOCUMENT_CLAIM.to_string(),
+                serde_json::from_value::<JsonValue>(serde_json::to_value(document)?)?,
+            ),
+            (
+                PREVIOUS_DOCUMENT_DIGEST_CLAIM.to_string(),
+                JsonValue::String(document_digest(current)?),
+            ),
+        ])),
+        ..Default::default()
+    };
+
+    let jwt = Jwt::from_claims_with_options(
+        &claims,
+        signer,
+        &SignerOptions {
+            verification_relationship: Some(VerificationRelationship::CapabilityInvocation),
+            ..Default::default()
+        },
+    )?;
+    Ok(jwt.compact_jws)
+}
+
+/// Verifies an update made with [`sign_document_update`] against the DID's current document.
+///
+/// The update must be signed by one of the current document's `capabilityInvocation` or
+/// `capabilityDelegation` verification methods, or by such a verification method of a DID listed
+/// in the current document's `controller`. Controllers are resolved. Its `previousDocumentDigest`
+/// must be the digest of the current document, so an update applies only to the version it was
+/// signed against, and its `iat` must be at most [`MAX_DOCUMENT_UPDATE_AGE`] old.
+///
+/// # Arguments
+///
+/// * `update` - The compact JWS of the update.
+/// * `current` - The DID's current document.
+///
+/// # Returns
+///
+/// * `Result<Document>` - The updated document, or an error if the update isn't authorized.
+///
+/// # Examples
+///
+/// ```ignore
+/// let document = verify_document_update(&update, &bearer_did.document).await?;
+/// ```
+pub async fn verify_document_update(update: &str, current: &Document) -> Result<Document> {
+    let jwt = Jwt::from_compact_jws(update, false).await?;
+
+    let document = match jwt
+        .claims
+        .additional_properties
+        .as_ref()
+        .and_then(|p| p.get(DOCUMENT_CLAIM))
+    {
+        Some(document) => Document::from_json_string(&serde_json::to_string(document)?)?,
+        None => return Err(VerificationError::MissingClaim(DOCUMENT_CLAIM.to_string()).into()),
+    };
+    if document.id != current.id {
+        return Err(VerificationError::ClaimMismatch(DOCUMENT_CLAIM.to_string()).into());
+    }
+    match &jwt.claims.sub {
+        Some(sub) if sub == &current.id => {}
+        Some(_) => return Err(VerificationError::ClaimMismatch("sub".to_string()).into()),
+        None => return Err(VerificationError::MissingClaim("sub".to_string()).into()),
+    }
+    match jwt
+        .claims
+        .additional_properties
+        .as_ref()
+        .and_then(|p| p.get(PREVIOUS_DOCUMENT_DIGEST_CLAIM))
+    {
+        Some(JsonValue::String(digest)) if digest == &document_digest(current)? => {}
+        Some(_) => {
+            return Err(VerificationError::ClaimMismatch(
+                PREVIOUS_DOCUMENT_DIGEST_CLAIM.to_string(),
+            )
+            .into())
+        }
+        None => {
+            return Err(
+                VerificationError::MissingClaim(PREVIOUS_DOCUMENT_DIGEST_CLAIM.to_string()).into(),
+            )
+        }
+    }
+    let now = SystemTime::now();
+    match jwt.claims.iat {
+        Some(iat) if iat <= now + MAX_CLOCK_SKEW && iat + MAX_DOCUMENT_UPDATE_AGE >= now => {}
+        Some(_) => return Err(VerificationError::ClaimMismatch("iat".to_string()).into()),
+        None => return Err(VerificationError::MissingClaim("iat".to_string()).into()),
+    }
+
+    let signer_did_uri = Did::parse(&jwt.kid)?.uri;
+    match &jwt.claims.iss {
+        Some(iss) if iss == &signer_did_uri => {}
+        Some(_) => return Err(VerificationError::ClaimMismatch("iss".to_string()).into()),
+        None => return Err(VerificationError::MissingClaim("iss".to_string()).into()),
+    }
+
+    let signer_document = if signer_did_uri == current.id {
+        if !is_controller_method(current, &jwt.kid) {
+            return Err(VerificationError::UnauthorizedSigner(format!(
+                "{} is not a capability invocation or delegation verification method",
+                jwt.kid
+            ))
+            .into());
+        }
+        current.clone()
+    } else {
+        authorize_controller(current, &signer_did_uri, &jwt.kid).await?
+    };
+
+    let public_jwk = signer_document
+        .find_verification_method(FindVerificationMethodOptions {
+            verification_method_id: Some(jwt.kid.clone()),
+        })?
+        .public_key_jwk;
+    let verifier = verifier_for_public_jwk(public_jwk)?;
+    let message = format!("{}.{}", jwt.parts[0], jwt.parts[1]);
+    verifier.verify(message.as_bytes(), &jwt.signature)?;
+
+    Ok(document)
+}
+
+/// Returns the digest document updates are bound to: the base64url encoded SHA-256 digest of the
+/// document's JCS canonical JSON.
+///
+/// # Arguments
+///
+/// * `document` - The DID document.
+///
+/// # Returns
+///
+/// * `Result<String>` - The digest of the document.
+pub fn document_digest(document: &Document) -> Result<String> {
+    let canonical = jcs::canonicalize(document)?;
+    Ok(general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes())))
+}
+
+fn is_controller_method(document: &Document, verification_method_id: &str) -> bool {
+    CONTROLLER_RELATIONSHIPS
+        .iter()
+        .any(|r| document.has_verification_relationship(*r, verification_method_id))
+}
+
+pub(crate) async fn resolve_document(did_uri: &str) -> Result<Document> {
+    let resolution_result = ResolutionResult::resolve(did_uri).await;
+    if let Some(e) = resolution_result.resolution_metadata.error {
+        return Err(e.into());
+    }
+    resolution_result.document.ok_or_else(|| {
+        Web5Error::NotFound(format!("failed to resolve did document for {}", did_uri))
+    })
+}
+
+/// Checks that `controller_did_uri` controls `document`, and that the verification method is one
+/// of its controller methods, returning the controller's resolved document.
+async fn authorize_controller(
+    document: &Document,
+    controller_did_uri: &str,
+    verification_method_id: &str,
+) -> Result<Document> {
+    let controllers = document.controller.as_deref().unwrap_or_default();
+    if !controllers.iter().any(|c| c == controller_did_uri) {
+        return Err(VerificationError::UnauthorizedSigner(format!(
+            "{} is not a controller of {}",
+            controller_did_uri, document.id
+        ))
+        .into());
+    }
+
+    let controller_document = resolve_document(controller_did_uri).await?;
+    if !is_controller_method(&controller_document, verification_method_id) {
+        return Err(VerificationError::UnauthorizedSigner(format!(
+            "{} is not a capability invocation or delegation verification method",
+            verification_method_id
+        ))
+        .into());
+    }
+
+    Ok(controller_document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::methods::{
+        did_jwk::DidJwk,
+        did_web::{DidWeb, DidWebCreateOptions},
+    };
+    use crate::json::ToJson;
+    use mockito::{Server, ServerGuard};
+
+    async fn host_did_web(server: &mut ServerGuard, controller: Option<&BearerDid>) -> BearerDid {
+        let bearer_did = DidWeb::create(
+            &server.url(),
+            Some(DidWebCreateOptions {
+                controller: controller.map(|c| vec![c.did.uri.clone()]),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        server
+            .mock("GET", "/.well-known/did.json")
+            .with_status(200)
+            .with_body(bearer_did.document.to_json_string().unwrap())
+            .create_async()
+            .await;
+        bearer_did
+    }
+
+    mod verify_controller_authorization {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_accepts_own_verification_method() {
+            let bearer_did = DidJwk::create(None).unwrap();
+            let kid = &bearer_did.document.verification_method[0].id;
+
+            verify_controller_authorization(&bearer_did.did.uri, kid)
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_accepts_controller_verification_method() {
+            let controller = DidJwk::create(None).unwrap();
+            let mut server = Server::new_async().await;
+            let controlled = host_did_web(&mut server, Some(&controller)).await;
+
+            let kid = &controller.document.verification_method[0].id;
+            verify_controller_authorization(&controlled.did.uri, kid)
+                .await
+                .unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_rejects_non_controller() {
+            let stranger = DidJwk::create(None).unwrap();
+            let mut server = Server::new_async().await;
+            let controlled = host_did_web(&mut server, None).await;
+
+            let kid = &stranger.document.verification_method[0].id;
+            let result = verify_controller_authorization(&controlled.did.uri, kid).await;
+            match result {
+                Err(Web5Error::CredentialError(VerificationError::UnauthorizedSigner(msg))) => {
+                    assert!(msg.contains("is not a controller"))
+                }
+                _ => panic!("expected an unauthorized signer error, got {:?}", result),
+            }
+        }
+    }
+
+    mod verify_document_update {
+        use super::*;
+        use crate::dids::data_model::service::Service;
+
+        fn updated(document: &Document) -> Document {
+            let mut document = document.clone();
+            document.service = Some(vec![Service {
+                id: format!("{}#dwn", document.id),
+                r#type: "DecentralizedWebNode".to_string(),
+                service_endpoint: "https://dwn.example.com".into(),
+                additional_properties: None,
+            }]);
+            document
+        }
+
+        #[tokio::test]
+        async fn test_accepts_self_signed_update() {
+            let mut server = Server::new_async().await;
+            let bearer_did = host_did_web(&mut server, None).await;
+
+            let document = updated(&bearer_did.document);
+            let update =
+                sign_document_update(&bearer_did.document, &document, &bearer_did).unwrap();
+
+            let verified = verify_document_update(&update, &bearer_did.document)
+                .await
+                .unwrap();
+            assert_eq!(verified, document);
+        }
+
+        #[tokio::test]
+        async fn test_accepts_controller_signed_update() {
+            let controller = DidJwk::create(None).unwrap();
+            let mut server = Server::new_async().await;
+            let controlled = host_did_web(&mut server, Some(&controller)).await;
+
+            let document = updated(&controlled.document);
+            let update =
+                sign_document_update(&controlled.document, &document, &controller).unwrap();
+
+            let verified = verify_document_update(&update, &controlled.document)
+                .await
+                .unwrap();
+            assert_eq!(verified, document);
+        }
+
+        #[tokio::test]
+        async fn test_rejects_non_controller_update() {
+            let stranger = DidJwk::create(None).unwrap();
+            let mut server = Server::new_async().await;
+            let controlled = host_did_web(&mut server, None).await;
+
+            let update = sign_document_update(
+                &controlled.document,
+                &updated(&controlled.document),
+                &stranger,
+            )
+            .unwrap();
+
+            let result = verify_document_update(&update, &controlled.document).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    VerificationError::UnauthorizedSigner(_)
+                ))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_rejects_update_of_another_did() {
+            let mut server = Server::new_async().await;
+            let bearer_did = host_did_web(&mut server, None).await;
+            let other = DidJwk::create(None).unwrap();
+
+            let update =
+                sign_document_update(&bearer_did.document, &other.document, &other).unwrap();
+
+            let result = verify_document_update(&update, &bearer_did.document).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    VerificationError::ClaimMismatch(_)
+                ))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_rejects_replayed_update() {
+            let mut server = Server::new_async().await;
+            let bearer_did = host_did_web(&mut server, None).await;
+
+            let document = updated(&bearer_did.document);
+            let update =
+                sign_document_update(&bearer_did.document, &document, &bearer_did).unwrap();
+            let current = verify_document_update(&update, &bearer_did.document)
+                .await
+                .unwrap();
+
+            // the update no longer applies once the document has changed
+            let result = verify_document_update(&update, &current).await;
+            match result {
+                Err(Web5Error::CredentialError(VerificationError::ClaimMismatch(claim))) => {
+                    assert_eq!(claim, PREVIOUS_DOCUMENT_DIGEST_CLAIM)
+                }
+                _ => panic!("expected a claim mismatch, got {:?}", result),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_rejects_stale_update() {
+            let mut server = Server::new_async().await;
+            let bearer_did = host_did_web(&mut server, None).await;
+
+            let claims = JwtClaims {
+                iss: Some(bearer_did.did.uri.clone()),
+                sub: Some(bearer_did.did.uri.clone()),
+                iat: Some(SystemTime::now() - MAX_DOCUMENT_UPDATE_AGE - Duration::from_secs(1)),
+                additional_properties: Some(HashMap::from([
+                    (
+                        DOCUMENT_CLAIM.to_string(),
+                        serde_json::from_value::<JsonValue>(
+                            serde_json::to_value(updated(&bearer_did.document)).unwrap(),
+                        )
+                        .unwrap(),
+                    ),
+                    (
+                        PREVIOUS_DOCUMENT_DIGEST_CLAIM.to_string(),
+                        JsonValue::String(document_digest(&bearer_did.document).unwrap()),
+                    ),
+                ])),
+                ..Default::default()
+            };
+            let update = Jwt::from_claims_with_options(
+                &claims,
+                &bearer_did,
+                &SignerOptions {
+                    verification_relationship: Some(VerificationRelationship::CapabilityInvocation),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .compact_jws;
+
+            let result = verify_document_update(&update, &bearer_did.document).await;
+            match result {
+                Err(Web5Error::CredentialError(VerificationError::ClaimMismatch(claim))) => {
+                    assert_eq!(claim, "iat")
+                }
+                _ => panic!("expected a claim mismatch, got {:?}", result),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_rejects_tampered_update() {
+            let mut server = Server::new_async().await;
+            let bearer_did = host_did_web(&mut server, None).await;
+
+            let update = sign_document_update(
+                &bearer_did.document,
+                &updated(&bearer_did.document),
+                &bearer_did,
+            )
+            .unwrap();
+            let parts = update.split('.').collect::<Vec<_>>();
+            let other =
+                sign_document_update(&bearer_did.document, &bearer_did.document, &bearer_did)
+                    .unwrap();
+            let tampered = format!(
+                "{}.{}.{}",
+                parts[0],
+                parts[1],
+                other.split('.').nth(2).unwrap()
+            );
+
+            let result = verify_document_update(&tampered, &bearer_did.document).await;
+            assert!(matches!(result, Err(Web5Error::Crypto(_))));
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use super::{service::Service, verification_method::VerificationMethod};
+use super::{
+    document_diff::{diff_documents, DocumentDiff},
+    service::Service,
+    validation::{validate_document, DocumentViolation},
+    verification_method::VerificationMethod,
+};
 use crate::{
     errors::{Result, Web5Error},
     json::{FromJson, ToJson},
@@ -66,11 +71,137 @@ pub struct Document {
 impl FromJson for Document {}
 impl ToJson for Document {}
 
+/// The verification relationships defined by the [W3C DID Core specification](https://www.w3.org/TR/did-core/#verification-relationships),
+/// which express the purposes a verification method may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationRelationship {
+    Authentication,
+    AssertionMethod,
+    KeyAgreement,
+    CapabilityInvocation,
+    CapabilityDelegation,
+}
+
+impl VerificationRelationship {
+    /// All verification relationships, in the order they appear in a DID document.
+    pub const ALL: [VerificationRelationship; 5] = [
+        VerificationRelationship::Authentication,
+        VerificationRelationship::AssertionMethod,
+        VerificationRelationship::KeyAgreement,
+        VerificationRelationship::CapabilityInvocation,
+        VerificationRelationship::CapabilityDelegation,
+    ];
+
+    /// The name of the relationship's property in a DID document, e.g. `assertionMethod`.
+    pub fn property_name(&self) -> &'static str {
+        match self {
+            VerificationRelationship::Authentication => "authentication",
+            VerificationRelationship::AssertionMethod => "assertionMethod",
+            VerificationRelationship::KeyAgreement => "keyAgreement",
+            VerificationRelationship::CapabilityInvocation => "capabilityInvocation",
+            VerificationRelationship::CapabilityDelegation => "capabilityDelegation",
+        }
+    }
+}
+
 pub(crate) struct FindVerificationMethodOptions {
     pub verification_method_id: Option<String>,
 }
 
 impl Document {
+    /// Checks the DID document for conformance with the [W3C DID Core specification](https://www.w3.org/TR/did-core/).
+    ///
+    /// Every violation found is reported, including duplicate verification method ids,
+    /// verification relationships which reference nonexistent verification methods, ids whose DID
+    /// doesn't match the document `id`, and invalid service endpoints. Relative DID URLs (e.g.
+    /// `#key-0`) are resolved against the document `id` rather than reported.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<DocumentViolation>` - The violations found, empty if the document is conformant.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// for violation in document.validate() {
+    ///     println!("{:?} at {}: {}", violation.code, violation.path, violation.message);
+    /// }
+    /// ```
+    pub fn validate(&self) -> Vec<DocumentViolation> {
+        validate_document(self)
+    }
+
+    /// Computes the changes from this DID document to another version of it: verification methods,
+    /// verification relationships, services, controllers and alternative identifiers added,
+    /// removed or changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The newer version of the DID document.
+    ///
+    /// # Returns
+    ///
+    /// * `DocumentDiff` - The changes, empty if the documents are equivalent.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let diff = previous_document.diff(&document);
+    /// if diff.has_key_changes() {
+    ///     println!("keys changed: {:?}", diff.verification_methods);
+    /// }
+    /// ```
+    pub fn diff(&self, other: &Document) -> DocumentDiff {
+        diff_documents(self, other)
+    }
+
+    /// Returns the verification method ids listed under the given verification relationship.
+    pub fn verification_relationship(&self, relationship: VerificationRelationship) -> &[String] {
+        let references = match relationship {
+            VerificationRelationship::Authentication => &self.authentication,
+            VerificationRelationship::AssertionMethod => &self.assertion_method,
+            VerificationRelationship::KeyAgreement => &self.key_agreement,
+            VerificationRelationship::CapabilityInvocation => &self.capability_invocation,
+            VerificationRelationship::CapabilityDelegation => &self.capability_delegation,
+        };
+        references.as_deref().unwrap_or_default()
+    }
+
+    /// Returns whether a verification method is listed under the given verification relationship,
+    /// by its absolute id or by a reference relative to the document `id`.
+    pub fn has_verification_relationship(
+        &self,
+        relationship: VerificationRelationship,
+        verification_method_id: &str,
+    ) -> bool {
+        self.verification_relationship(relationship)
+            .iter()
+            .any(|reference| self.resolve_reference(reference) == verification_method_id)
+    }
+
+    /// Resolves a verification relationship reference relative to the document `id` (e.g. `#0`)
+    /// to an absolute DID URL. Absolute references are returned as-is.
+    pub(crate) fn resolve_reference(&self, reference: &str) -> String {
+        match reference.starts_with('#') {
+            true => format!("{}{}", self.id, reference),
+            false => reference.to_string(),
+        }
+    }
+
+    pub(crate) fn verification_relationship_mut(
+        &mut self,
+        relationship: VerificationRelationship,
+    ) -> &mut Option<Vec<String>> {
+        match relationship {
+            VerificationRelationship::Authentication => &mut self.authentication,
+            VerificationRelationship::AssertionMethod => &mut self.assertion_method,
+            VerificationRelationship::KeyAgreement => &mut self.key_agreement,
+            VerificationRelationship::CapabilityInvocation => &mut self.capability_invocation,
+            VerificationRelationship::CapabilityDelegation => &mut self.capability_delegation,
+        }
+    }
+
     /// Finds a verification method in the DID document by its ID.
     ///
     /// This method searches the `verification_method` field for a method matching the given ID.
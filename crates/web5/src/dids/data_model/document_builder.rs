@@ -0,0 +1,561 @@
+use super::{
+    document::{Document, VerificationRelationship},
+    service::Service,
+    verification_method::VerificationMethod,
+};
+use crate::{
+    dids::did::Did,
+    errors::{Result, Web5Error},
+};
+use std::collections::HashMap;
+
+/// The verification relationships the key generated by a DID method's `create` is registered
+/// under, unless overridden.
+pub(crate) const DEFAULT_RELATIONSHIPS: [VerificationRelationship; 4] = [
+    VerificationRelationship::Authentication,
+    VerificationRelationship::AssertionMethod,
+    VerificationRelationship::CapabilityInvocation,
+    VerificationRelationship::CapabilityDelegation,
+];
+
+/// Builds and mutates a DID document while keeping its verification methods, verification
+/// relationships and services consistent with one another.
+///
+/// Ids and references may be given either as absolute DID URLs (`did:example:123#key-1`) or relative
+/// to the document (`#key-1`), and are normalized to absolute DID URLs.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut builder = DocumentBuilder::new("did:example:123");
+/// builder
+///     .add_verification_method(
+///         verification_method,
+///         &[VerificationRelationship::Authentication, VerificationRelationship::AssertionMethod],
+///     )?
+///     .add_service(service)?;
+/// let document = builder.build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DocumentBuilder {
+    document: Document,
+}
+
+impl DocumentBuilder {
+    /// Creates a builder for an empty DID document with the given DID as its `id`.
+    pub fn new(id: &str) -> Self {
+        Self {
+            document: Document {
+                id: id.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Creates a builder from an existing DID document, normalizing its relative ids and references
+    /// to absolute DID URLs.
+    pub fn from_document(document: Document) -> Self {
+        let mut document = document;
+        let did = document.id.clone();
+
+        for vm in document.verification_method.iter_mut() {
+            vm.id = normalize_id(&did, &vm.id);
+            if vm.controller.is_empty() {
+                vm.controller = did.clone();
+            }
+        }
+        for relationship in VerificationRelationship::ALL {
+            if let Some(references) = document.verification_relationship_mut(relationship) {
+                references
+                    .iter_mut()
+                    .for_each(|reference| *reference = normalize_id(&did, reference));
+            }
+        }
+        for service in document.service.iter_mut().flatten() {
+            service.id = normalize_id(&did, &service.id);
+        }
+
+        Self { document }
+    }
+
+    /// Sets the `@context` of the DID document.
+    pub fn set_context(&mut self, context: Vec<String>) -> &mut Self {
+        self.document.context = Some(context);
+        self
+    }
+
+    /// Sets the controllers of the DID document, replacing any existing controllers.
+    pub fn set_controller(&mut self, controller: Vec<String>) -> &mut Self {
+        self.document.controller = match controller.is_empty() {
+            true => None,
+            false => Some(controller),
+        };
+        self
+    }
+
+    /// Sets the alternative identifiers of the DID subject, replacing any existing ones.
+    pub fn set_also_known_as(&mut self, also_known_as: Vec<String>) -> &mut Self {
+        self.document.also_known_as = match also_known_as.is_empty() {
+            true => None,
+            false => Some(also_known_as),
+        };
+        self
+    }
+
+    /// Adds a verification method and registers it under each of the given verification
+    /// relationships. A relative id is made absolute, and an empty controller defaults to the
+    /// document's DID.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Web5Error::Parameter` if a verification method with the same id already exists.
+    pub fn add_verification_method(
+        &mut self,
+        verification_method: VerificationMethod,
+        relationships: &[VerificationRelationship],
+    ) -> Result<&mut Self> {
+        let mut verification_method = verification_method;
+        verification_method.id = normalize_id(&self.document.id, &verification_method.id);
+        if verification_method.controller.is_empty() {
+            verification_method.controller = self.document.id.clone();
+        }
+
+        if self.has_verification_method(&verification_method.id) {
+            return Err(Web5Error::Parameter(format!(
+                "verification method {} already exists",
+                verification_method.id
+            )));
+        }
+
+        let id = verification_method.id.clone();
+        self.document.verification_method.push(verification_method);
+        for relationship in relationships {
+            self.add_verification_relationship(&id, *relationship)?;
+        }
+
+        Ok(self)
+    }
+
+    /// Removes a verification method along with every verification relationship referencing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Web5Error::NotFound` if no verification method has the given id.
+    pub fn remove_verification_method(&mut self, id: &str) -> Result<&mut Self> {
+        let id = normalize_id(&self.document.id, id);
+        if !self.has_verification_method(&id) {
+            return Err(Web5Error::NotFound(format!(
+                "verification method {} not found",
+                id
+            )));
+        }
+
+        self.document.verification_method.retain(|vm| vm.id != id);
+        for relationship in VerificationRelationship::ALL {
+            remove_reference(
+                self.document.verification_relationship_mut(relationship),
+                &id,
+            );
+        }
+
+        Ok(self)
+    }
+
+    /// Registers a verification method under a verification relationship. The reference may be to
+    /// a verification method in another DID document, such as one of a controller's keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Web5Error::NotFound` if the reference is to this DID document but no verification
+    /// method has the given id.
+    pub fn add_verification_relationship(
+        &mut self,
+        id: &str,
+        relationship: VerificationRelationship,
+    ) -> Result<&mut Self> {
+        let id = normalize_id(&self.document.id, id);
+
+        let is_local = Did::parse(&id)
+            .map(|did| did.uri == self.document.id)
+            .unwrap_or(true);
+        if is_local && !self.has_verification_method(&id) {
+            return Err(Web5Error::NotFound(format!(
+                "verification method {} not found",
+                id
+            )));
+        }
+
+        let references = self
+            .document
+            .verification_relationship_mut(relationship)
+            .get_or_insert_with(Vec::new);
+        if !references.contains(&id) {
+            references.push(id);
+        }
+
+        Ok(self)
+    }
+
+    /// Removes a verification method from a verification relationship, leaving the verification
+    /// method itself in place.
+    pub fn remove_verification_relationship(
+        &mut self,
+        id: &str,
+        relationship: VerificationRelationship,
+    ) -> &mut Self {
+        let id = normalize_id(&self.document.id, id);
+        remove_reference(
+            self.document.verification_relationship_mut(relationship),
+            &id,
+        );
+        self
+    }
+
+    /// Adds a service. A relative id is made absolute.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Web5Error::Parameter` if a service with the same id already exists.
+    pub fn add_service(&mut self, service: Service) -> Result<&mut Self> {
+        let mut service = service;
+        service.id = normalize_id(&self.document.id, &service.id);
+
+        let services = self.document.service.get_or_insert_with(Vec::new);
+        if services.iter().any(|s| s.id == service.id) {
+            return Err(Web5Error::Parameter(format!(
+                "service {} already exists",
+                service.id
+            )));
+        }
+        services.push(service);
+
+        Ok(self)
+    }
+
+    /// Removes a service.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Web5Error::NotFound` if no service has the given id.
+    pub fn remove_service(&mut self, id: &str) -> Result<&mut Self> {
+        let id = normalize_id(&self.document.id, id);
+
+        let services = self.document.service.get_or_insert_with(Vec::new);
+        let count = services.len();
+        services.retain(|s| s.id != id);
+        if services.len() == count {
+            return Err(Web5Error::NotFound(format!("service {} not found", id)));
+        }
+        if services.is_empty() {
+            self.document.service = None;
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the DID document built so far.
+    pub fn build(&self) -> Document {
+        self.document.clone()
+    }
+
+    fn has_verification_method(&self, id: &str) -> bool {
+        self.document
+            .verification_method
+            .iter()
+            .any(|vm| vm.id == id)
+    }
+}
+
+/// Makes an id relative to the DID document (`#key-1` or `key-1`) absolute. Absolute ids are
+/// returned unchanged.
+pub(crate) fn normalize_id(did: &str, id: &str) -> String {
+    if let Some(fragment) = id.strip_prefix('#') {
+        return format!("{}#{}", did, fragment);
+    }
+    if !id.is_empty() && !id.contains(':') {
+        return format!("{}#{}", did, id);
+    }
+    id.to_string()
+}
+
+/// Looks up the verification relationships assigned to a verification method, where the
+/// assignments may be keyed by either absolute or relative verification method ids.
+pub(crate) fn assigned_relationships(
+    assignments: Option<&HashMap<String, Vec<VerificationRelationship>>>,
+    did: &str,
+    id: &str,
+) -> Option<Vec<VerificationRelationship>> {
+    let id = normalize_id(did, id);
+    assignments?
+        .iter()
+        .find(|(key, _)| normalize_id(did, key) == id)
+        .map(|(_, relationships)| relationships.clone())
+}
+
+/// Checks that every verification relationship assignment is keyed by the id of one of the
+/// given verification methods, so that a mistyped id isn't silently ignored.
+///
+/// # Errors
+///
+/// Returns `Web5Error::Parameter` naming the first assignment, in sorted order, which matches no
+/// verification method.
+pub(crate) fn check_assignments(
+    assignments: Option<&HashMap<String, Vec<VerificationRelationship>>>,
+    did: &str,
+    ids: &[&str],
+) -> Result<()> {
+    let ids = ids
+        .iter()
+        .map(|id| normalize_id(did, id))
+        .collect::<Vec<_>>();
+    let mut keys = assignments
+        .into_iter()
+        .flat_map(|assignments| assignments.keys())
+        .collect::<Vec<_>>();
+    keys.sort();
+    for key in keys {
+        if !ids.contains(&normalize_id(did, key)) {
+            return Err(Web5Error::Parameter(format!(
+                "verification relationship assignment {} matches no verification method",
+                key
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn remove_reference(references: &mut Option<Vec<String>>, id: &str) {
+    if let Some(list) = references {
+        list.retain(|reference| reference != id);
+        if list.is_empty() {
+            *references = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::dsa::ed25519::Ed25519Generator;
+
+    const DID: &str = "did:example:123";
+
+    fn verification_method(id: &str) -> VerificationMethod {
+        let mut public_jwk = Ed25519Generator::generate();
+        public_jwk.d = None;
+        VerificationMethod {
+            id: id.to_string(),
+            r#type: "JsonWebKey".to_string(),
+            controller: "".to_string(),
+            public_key_jwk: public_jwk,
+        }
+    }
+
+    fn service(id: &str) -> Service {
+        Service {
+            id: id.to_string(),
+            r#type: "LinkedDomains".to_string(),
+            service_endpoint: "https://example.com".into(),
+            additional_properties: None,
+        }
+    }
+
+    mod add_verification_method {
+        use super::*;
+
+        #[test]
+        fn test_normalizes_ids_and_registers_relationships() {
+            let mut builder = DocumentBuilder::new(DID);
+            builder
+                .add_verification_method(
+                    verification_method("#key-0"),
+                    &[
+                        VerificationRelationship::Authentication,
+                        VerificationRelationship::KeyAgreement,
+                    ],
+                )
+                .unwrap();
+
+            let document = builder.build();
+            let id = format!("{}#key-0", DID);
+            assert_eq!(document.verification_method[0].id, id);
+            assert_eq!(document.verification_method[0].controller, DID);
+            assert_eq!(document.authentication, Some(vec![id.clone()]));
+            assert_eq!(document.key_agreement, Some(vec![id]));
+            assert_eq!(document.assertion_method, None);
+            assert_eq!(document.validate(), vec![]);
+        }
+
+        #[test]
+        fn test_duplicate_id() {
+            let mut builder = DocumentBuilder::new(DID);
+            builder
+                .add_verification_method(verification_method("key-0"), &[])
+                .unwrap();
+
+            let result = builder
+                .add_verification_method(verification_method(&format!("{}#key-0", DID)), &[]);
+            if let Err(Web5Error::Parameter(msg)) = result {
+                assert!(msg.contains("already exists"));
+            } else {
+                panic!("expected Web5Error::Parameter error");
+            }
+        }
+    }
+
+    mod remove_verification_method {
+        use super::*;
+
+        #[test]
+        fn test_removes_relationships() {
+            let mut builder = DocumentBuilder::new(DID);
+            builder
+                .add_verification_method(
+                    verification_method("#key-0"),
+                    &VerificationRelationship::ALL,
+                )
+                .unwrap()
+                .add_verification_method(
+                    verification_method("#key-1"),
+                    &[VerificationRelationship::Authentication],
+                )
+                .unwrap()
+                .remove_verification_method("#key-0")
+                .unwrap();
+
+            let document = builder.build();
+            assert_eq!(document.verification_method.len(), 1);
+            assert_eq!(
+                document.authentication,
+                Some(vec![format!("{}#key-1", DID)])
+            );
+            assert_eq!(document.assertion_method, None);
+            assert_eq!(document.capability_delegation, None);
+        }
+
+        #[test]
+        fn test_not_found() {
+            let result = DocumentBuilder::new(DID)
+                .remove_verification_method("#key-0")
+                .map(|_| ());
+            if let Err(Web5Error::NotFound(msg)) = result {
+                assert!(msg.contains("#key-0"));
+            } else {
+                panic!("expected Web5Error::NotFound error");
+            }
+        }
+    }
+
+    mod add_verification_relationship {
+        use super::*;
+
+        #[test]
+        fn test_dangling_reference() {
+            let result = DocumentBuilder::new(DID)
+                .add_verification_relationship("#key-0", VerificationRelationship::AssertionMethod)
+                .map(|_| ());
+            assert!(matches!(result, Err(Web5Error::NotFound(_))));
+        }
+
+        #[test]
+        fn test_external_reference() {
+            let mut builder = DocumentBuilder::new(DID);
+            builder
+                .add_verification_relationship(
+                    "did:example:controller#0",
+                    VerificationRelationship::CapabilityInvocation,
+                )
+                .unwrap();
+            assert_eq!(
+                builder.build().capability_invocation,
+                Some(vec!["did:example:controller#0".to_string()])
+            );
+        }
+
+        #[test]
+        fn test_no_duplicates() {
+            let mut builder = DocumentBuilder::new(DID);
+            builder
+                .add_verification_method(
+                    verification_method("#key-0"),
+                    &[VerificationRelationship::Authentication],
+                )
+                .unwrap()
+                .add_verification_relationship("#key-0", VerificationRelationship::Authentication)
+                .unwrap();
+            assert_eq!(builder.build().authentication.unwrap().len(), 1);
+        }
+    }
+
+    mod services {
+        use super::*;
+
+        #[test]
+        fn test_add_and_remove() {
+            let mut builder = DocumentBuilder::new(DID);
+            builder.add_service(service("#service-0")).unwrap();
+            assert_eq!(
+                builder.build().service.unwrap()[0].id,
+                format!("{}#service-0", DID)
+            );
+
+            assert!(matches!(
+                builder.add_service(service("service-0")).map(|_| ()),
+                Err(Web5Error::Parameter(_))
+            ));
+
+            builder.remove_service("#service-0").unwrap();
+            assert_eq!(builder.build().service, None);
+        }
+    }
+
+    mod from_document {
+        use super::*;
+
+        #[test]
+        fn test_normalizes_relative_ids() {
+            let document = Document {
+                id: DID.to_string(),
+                verification_method: vec![verification_method("#0")],
+                authentication: Some(vec!["#0".to_string()]),
+                service: Some(vec![service("#service-0")]),
+                ..Default::default()
+            };
+
+            let mut builder = DocumentBuilder::from_document(document);
+            builder.set_controller(vec!["did:example:controller".to_string()]);
+
+            let document = builder.build();
+            assert_eq!(document.validate(), vec![]);
+            assert_eq!(document.authentication, Some(vec![format!("{}#0", DID)]));
+            assert_eq!(
+                document.controller,
+                Some(vec!["did:example:controller".to_string()])
+            );
+        }
+    }
+
+    mod check_assignments {
+        use super::*;
+
+        #[test]
+        fn test_reports_first_unmatched_assignment_in_sorted_order() {
+            let mut assignments = HashMap::new();
+            for key in ["#5", "#3", "#0", "#4", "#1", "#2"] {
+                assignments.insert(
+                    key.to_string(),
+                    vec![VerificationRelationship::Authentication],
+                );
+            }
+
+            let result = check_assignments(Some(&assignments), DID, &["#0"]);
+            match result {
+                Err(Web5Error::Parameter(message)) => assert_eq!(
+                    message,
+                    "verification relationship assignment #1 matches no verification method"
+                ),
+                _ => panic!("expected a parameter error"),
+            }
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use super::{
+    document::{Document, VerificationRelationship},
+    document_builder::normalize_id,
+    service::Service,
+    verification_method::VerificationMethod,
+};
+use serde::{Deserialize, Serialize};
+
+/// The changes between two versions of a DID document, as returned by `Document::diff`.
+///
+/// Ids and references are compared as absolute DID URLs, so a relative `#key-1` and an absolute
+/// `did:example:123#key-1` are the same. The diff is deterministic: removed entries are listed in
+/// the order of the previous document, and added or changed entries in the order of the current
+/// one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentDiff {
+    /// Verification methods added, removed, or whose key, type or controller changed.
+    pub verification_methods: EntryDiff<VerificationMethod>,
+
+    /// The references added to or removed from each verification relationship, for only the
+    /// relationships which changed, in the order they appear in a DID document.
+    pub verification_relationships: Vec<RelationshipDiff>,
+
+    /// Services added, removed, or whose type, endpoint or properties changed.
+    pub services: EntryDiff<Service>,
+
+    /// Controllers added or removed.
+    pub controller: ValueDiff,
+
+    /// Alternative identifiers added or removed.
+    pub also_known_as: ValueDiff,
+}
+
+/// The entries, identified by id, added to, removed from, or changed in a list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryDiff<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+    pub changed: Vec<EntryChange<T>>,
+}
+
+/// An entry whose id is unchanged but whose content changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryChange<T> {
+    pub id: String,
+    pub before: T,
+    pub after: T,
+}
+
+/// The references added to and removed from a verification relationship.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationshipDiff {
+    pub relationship: VerificationRelationship,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The values added to and removed from a list of strings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValueDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl<T> Default for EntryDiff<T> {
+    fn default() -> Self {
+        Self {
+            added: vec![],
+            removed: vec![],
+            changed: vec![],
+        }
+    }
+}
+
+impl<T> EntryDiff<T> {
+    /// Returns true if no entry was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl ValueDiff {
+    /// Returns true if no value was added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl DocumentDiff {
+    /// Returns true if the documents are equivalent.
+    pub fn is_empty(&self) -> bool {
+        !self.has_key_changes()
+            && self.services.is_empty()
+            && self.controller.is_empty()
+            && self.also_known_as.is_empty()
+    }
+
+    /// Returns true if a verification method or verification relationship changed, which is what
+    /// security monitoring most often needs to alert on.
+    pub fn has_key_changes(&self) -> bool {
+        !self.verification_methods.is_empty() || !self.verification_relationships.is_empty()
+    }
+}
+
+/// Computes the changes from `before` to `after`.
+pub(crate) fn diff_documents(before: &Document, after: &Document) -> DocumentDiff {
+    let verification_methods = diff_entries(
+        &normalized_verification_methods(before),
+        &normalized_verification_methods(after),
+        |vm| vm.id.clone(),
+    );
+
+    let verification_relationships = VerificationRelationship::ALL
+        .into_iter()
+        .filter_map(|relationship| {
+            let diff = diff_values(
+                &normalized_references(before, relationship),
+                &normalized_references(after, relationship),
+            );
+            (!diff.is_empty()).then_some(RelationshipDiff {
+                relationship,
+                added: diff.added,
+                removed: diff.removed,
+            })
+        })
+        .collect();
+
+    let services = diff_entries(
+        &normalized_services(before),
+        &normalized_services(after),
+        |service| service.id.clone(),
+    );
+
+    DocumentDiff {
+        verification_methods,
+        verification_relationships,
+        services,
+        controller: diff_values(
+            before.controller.as_deref().unwrap_or_default(),
+            after.controller.as_deref().unwrap_or_default(),
+        ),
+        also_known_as: diff_values(
+            before.also_known_as.as_deref().unwrap_or_default(),
+            after.also_known_as.as_deref().unwrap_or_default(),
+        ),
+    }
+}
+
+fn normalized_verification_methods(document: &Document) -> Vec<VerificationMethod> {
+    document
+        .verification_method
+        .iter()
+        .map(|vm| {
+            let mut vm = vm.clone();
+            vm.id = normalize_id(&document.id, &vm.id);
+            if vm.controller.is_empty() {
+                vm.controller = document.id.clone();
+            }
+            vm
+        })
+        .collect()
+}
+
+fn normalized_references(
+    document: &Document,
+    relationship: VerificationRelationship,
+) -> Vec<String> {
+    document
+        .verification_relationship(relationship)
+        .iter()
+        .map(|reference| normalize_id(&document.id, reference))
+        .collect()
+}
+
+fn normalized_services(document: &Document) -> Vec<Service> {
+    document
+        .service
+        .iter()
+        .flatten()
+        .map(|service| {
+            let mut service = service.clone();
+            service.id = normalize_id(&document.id, &service.id);
+            service
+        })
+        .collect()
+}
+
+fn diff_entries<T: Clone + PartialEq>(
+    before: &[T],
+    after: &[T],
+    id: impl Fn(&T) -> String,
+) -> EntryDiff<T> {
+    let find = |entries: &[T], entry_id: &str| entries.iter().find(|e| id(e) == entry_id).cloned();
+
+    let mut diff = EntryDiff::default();
+    for entry in before {
+        if find(after, &id(entry)).is_none() {
+            diff.removed.push(entry.clone());
+        }
+    }
+    for entry in after {
+        match find(before, &id(entry)) {
+            None => diff.added.push(entry.clone()),
+            Some(previous) if previous != *entry => diff.changed.push(EntryChange {
+                id: id(entry),
+                before: previous,
+                after: entry.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff
+}
+
+fn diff_values(before: &[String], after: &[String]) -> ValueDiff {
+    ValueDiff {
+        added: after
+            .iter()
+            .filter(|value| !before.contains(value))
+            .cloned()
+            .collect(),
+        removed: before
+            .iter()
+            .filter(|value| !after.contains(value))
+            .cloned()
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{dsa::ed25519::Ed25519Generator, jwk::Jwk};
+
+    const DID: &str = "did:example:123";
+
+    fn public_jwk() -> Jwk {
+        let mut public_jwk = Ed25519Generator::generate();
+        public_jwk.d = None;
+        public_jwk
+    }
+
+    fn verification_method(fragment: &str) -> VerificationMethod {
+        VerificationMethod {
+            id: format!("{}#{}", DID, fragment),
+            r#type: "JsonWebKey".to_string(),
+            controller: DID.to_string(),
+            public_key_jwk: public_jwk(),
+        }
+    }
+
+    fn service(fragment: &str, endpoint: &str) -> Service {
+        Service {
+            id: format!("{}#{}", DID, fragment),
+            r#type: "LinkedDomains".to_string(),
+            service_endpoint: vec![endpoint.to_string()].into(),
+            additional_properties: None,
+        }
+    }
+
+    fn document() -> Document {
+        let key_0 = verification_method("key-0");
+        Document {
+            id: DID.to_string(),
+            verification_method: vec![key_0.clone()],
+            authentication: Some(vec![key_0.id.clone()]),
+            assertion_method: Some(vec![key_0.id]),
+            service: Some(vec![service("dwn", "https://dwn.example.com")]),
+            controller: Some(vec!["did:example:controller".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_equal_documents() {
+        let document = document();
+        let diff = document.diff(&document.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff, DocumentDiff::default());
+    }
+
+    #[test]
+    fn test_relative_ids_are_equivalent() {
+        let before = document();
+        let mut after = before.clone();
+        after.verification_method[0].id = "#key-0".to_string();
+        after.authentication = Some(vec!["#key-0".to_string()]);
+        after.service.as_mut().unwrap()[0].id = "#dwn".to_string();
+
+        assert!(before.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn test_key_added_and_removed() {
+        let before = document();
+        let mut after = before.clone();
+        let key_1 = verification_method("key-1");
+        after.verification_method = vec![key_1.clone()];
+        after.authentication = Some(vec![key_1.id.clone()]);
+        after.assertion_method = Some(vec![key_1.id.clone()]);
+        after.key_agreement = Some(vec![key_1.id.clone()]);
+
+        let diff = before.diff(&after);
+        assert!(diff.has_key_changes());
+        assert_eq!(diff.verification_methods.added, vec![key_1.clone()]);
+        assert_eq!(
+            diff.verification_methods.removed,
+            before.verification_method
+        );
+        assert_eq!(diff.verification_relationships.len(), 3);
+        assert_eq!(
+            diff.verification_relationships[0],
+            RelationshipDiff {
+                relationship: VerificationRelationship::Authentication,
+                added: vec![key_1.id.clone()],
+                removed: vec![format!("{}#key-0", DID)],
+            }
+        );
+        assert_eq!(
+            diff.verification_relationships[2],
+            RelationshipDiff {
+                relationship: VerificationRelationship::KeyAgreement,
+                added: vec![key_1.id],
+                removed: vec![],
+            }
+        );
+        assert!(diff.services.is_empty());
+    }
+
+    #[test]
+    fn test_key_rotated_in_place() {
+        let before = document();
+        let mut after = before.clone();
+        after.verification_method[0].public_key_jwk = public_jwk();
+
+        let diff = before.diff(&after);
+        assert!(diff.has_key_changes());
+        assert!(diff.verification_relationships.is_empty());
+        assert_eq!(
+            diff.verification_methods.changed,
+            vec![EntryChange {
+                id: format!("{}#key-0", DID),
+                before: before.verification_method[0].clone(),
+                after: after.verification_method[0].clone(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_service_controller_and_also_known_as_changes() {
+        let before = document();
+        let mut after = before.clone();
+        after.service = Some(vec![
+            service("dwn", "https://dwn.example.org"),
+            service("hub", "https://hub.example.com"),
+        ]);
+        after.controller = None;
+        after.also_known_as = Some(vec!["did:example:456".to_string()]);
+
+        let diff = before.diff(&after);
+        assert!(!diff.has_key_changes());
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.services.added,
+            vec![service("hub", "https://hub.example.com")]
+        );
+        assert_eq!(diff.services.changed.len(), 1);
+        assert_eq!(diff.services.changed[0].id, format!("{}#dwn", DID));
+        assert_eq!(
+            diff.controller.removed,
+            vec!["did:example:controller".to_string()]
+        );
+        assert_eq!(
+            diff.also_known_as.added,
+            vec!["did:example:456".to_string()]
+        );
+    }
+}
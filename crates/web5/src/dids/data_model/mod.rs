@@ -1,3 +1,6 @@
 pub mod document;
+pub mod document_builder;
+pub mod document_diff;
 pub mod service;
+pub mod validation;
 pub mod verification_method;
@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use crate::json::{FromJson, JsonObject, JsonValue, ToJson};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Service {
@@ -6,5 +7,167 @@ pub struct Service {
     #[serde(rename = "type")]
     pub r#type: String,
     #[serde(rename = "serviceEndpoint")]
-    pub service_endpoint: Vec<String>,
+    pub service_endpoint: ServiceEndpoint,
+    #[serde(
+        flatten,
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_additional_properties"
+    )]
+    pub additional_properties: Option<JsonObject>,
+}
+
+impl FromJson for Service {}
+impl ToJson for Service {}
+
+/// The value of a service's `serviceEndpoint` property, which per DID Core may be a URI
+/// string, a map, or an ordered set composed of URI strings and/or maps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ServiceEndpoint {
+    Uri(String),
+    Map(JsonObject),
+    Set(Vec<ServiceEndpointEntry>),
+}
+
+impl FromJson for ServiceEndpoint {}
+impl ToJson for ServiceEndpoint {}
+
+/// A single member of an ordered set `serviceEndpoint`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ServiceEndpointEntry {
+    Uri(String),
+    Map(JsonObject),
+}
+
+impl ServiceEndpoint {
+    /// Flattens the endpoint into its individual entries, in order.
+    pub fn entries(&self) -> Vec<ServiceEndpointEntry> {
+        match self {
+            ServiceEndpoint::Uri(uri) => vec![ServiceEndpointEntry::Uri(uri.clone())],
+            ServiceEndpoint::Map(map) => vec![ServiceEndpointEntry::Map(map.clone())],
+            ServiceEndpoint::Set(entries) => entries.clone(),
+        }
+    }
+
+    /// Collects the URIs referenced by the endpoint: every URI string entry, plus the
+    /// `uri` member of map entries (as used by DIDComm v2 services).
+    pub fn uris(&self) -> Vec<String> {
+        self.entries()
+            .into_iter()
+            .filter_map(|entry| match entry {
+                ServiceEndpointEntry::Uri(uri) => Some(uri),
+                ServiceEndpointEntry::Map(map) => match map.get_value("uri") {
+                    Some(JsonValue::String(uri)) => Some(uri.clone()),
+                    _ => None,
+                },
+            })
+            .collect()
+    }
+}
+
+impl From<String> for ServiceEndpoint {
+    fn from(value: String) -> Self {
+        ServiceEndpoint::Uri(value)
+    }
+}
+
+impl From<&str> for ServiceEndpoint {
+    fn from(value: &str) -> Self {
+        ServiceEndpoint::Uri(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for ServiceEndpoint {
+    fn from(value: Vec<String>) -> Self {
+        ServiceEndpoint::Set(value.into_iter().map(ServiceEndpointEntry::Uri).collect())
+    }
+}
+
+impl From<JsonObject> for ServiceEndpoint {
+    fn from(value: JsonObject) -> Self {
+        ServiceEndpoint::Map(value)
+    }
+}
+
+fn deserialize_additional_properties<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<JsonObject>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let object = JsonObject::deserialize(deserializer)?;
+    match object.properties.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(object)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod deserialize {
+        use super::*;
+
+        #[test]
+        fn test_string_endpoint() {
+            let service = Service::from_json_string(
+                r#"{"id":"did:web:example.com#linked","type":"LinkedDomains","serviceEndpoint":"https://example.com"}"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                service.service_endpoint,
+                ServiceEndpoint::Uri("https://example.com".to_string())
+            );
+            assert_eq!(service.additional_properties, None);
+        }
+
+        #[test]
+        fn test_map_endpoint() {
+            let service = Service::from_json_string(
+                r#"{"id":"did:web:example.com#didcomm","type":"DIDCommMessaging","serviceEndpoint":{"uri":"https://example.com/didcomm","accept":["didcomm/v2"],"routingKeys":[]}}"#,
+            )
+            .unwrap();
+
+            let ServiceEndpoint::Map(map) = &service.service_endpoint else {
+                panic!("expected map endpoint")
+            };
+            assert_eq!(
+                map.get_value("accept"),
+                Some(&JsonValue::Array(vec![JsonValue::String(
+                    "didcomm/v2".to_string()
+                )]))
+            );
+            assert_eq!(
+                service.service_endpoint.uris(),
+                vec!["https://example.com/didcomm".to_string()]
+            );
+        }
+
+        #[test]
+        fn test_set_endpoint_with_additional_properties() {
+            let service = Service::from_json_string(
+                r##"{"id":"did:web:example.com#dwn","type":"DecentralizedWebNode","serviceEndpoint":["https://dwn.example.com",{"nodes":["https://a.example.com"]}],"enc":"#enc","sig":["#sig"]}"##,
+            )
+            .unwrap();
+
+            assert_eq!(service.service_endpoint.entries().len(), 2);
+            assert_eq!(
+                service.service_endpoint.uris(),
+                vec!["https://dwn.example.com".to_string()]
+            );
+            let additional_properties = service.additional_properties.clone().unwrap();
+            assert_eq!(
+                additional_properties.get_value("enc"),
+                Some(&JsonValue::String("#enc".to_string()))
+            );
+
+            let round_tripped =
+                Service::from_json_string(&service.to_json_string().unwrap()).unwrap();
+            assert_eq!(service, round_tripped);
+        }
+    }
 }
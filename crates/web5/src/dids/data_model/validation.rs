@@ -0,0 +1,517 @@
+use super::{
+    document::{Document, VerificationRelationship},
+    service::{ServiceEndpoint, ServiceEndpointEntry},
+};
+use crate::dids::did::Did;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+use url::Url;
+
+/// The kind of DID Core conformance violation found in a DID document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ViolationCode {
+    /// A property which must be a DID (e.g. `id` or `controller`) is not a valid DID.
+    InvalidDid,
+    /// A property which must be a URI (e.g. an `alsoKnownAs` entry) is not a valid URI.
+    InvalidUri,
+    /// Two verification methods or two services share the same id.
+    DuplicateId,
+    /// A verification method or service id is a DID URL for a DID other than the document's `id`.
+    DidMismatch,
+    /// A verification method id is missing its fragment.
+    MissingFragment,
+    /// A verification relationship references a verification method of the document's DID which
+    /// doesn't exist.
+    DanglingReference,
+    /// A verification method is missing its `type`.
+    MissingType,
+    /// A verification method's `publicKeyJwk` contains private key material.
+    PrivateKeyMaterial,
+    /// A service is missing its `type`.
+    InvalidServiceType,
+    /// A service endpoint is empty or not a valid URI.
+    InvalidServiceEndpoint,
+}
+
+/// A single DID Core conformance violation found by [`Document::validate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentViolation {
+    /// The kind of violation.
+    pub code: ViolationCode,
+    /// The location of the offending value, e.g. `verificationMethod[1].id`.
+    pub path: String,
+    /// A human-readable description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for DocumentViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl DocumentViolation {
+    fn new(code: ViolationCode, path: String, message: String) -> Self {
+        Self {
+            code,
+            path,
+            message,
+        }
+    }
+}
+
+pub(crate) fn validate_document(document: &Document) -> Vec<DocumentViolation> {
+    let mut violations = vec![];
+
+    let document_did = match Did::parse(&document.id) {
+        Ok(did) if did.url == did.uri => Some(did.uri),
+        _ => {
+            violations.push(DocumentViolation::new(
+                ViolationCode::InvalidDid,
+                "id".to_string(),
+                format!("{} is not a valid DID", document.id),
+            ));
+            None
+        }
+    };
+
+    if let Some(controllers) = &document.controller {
+        for (i, controller) in controllers.iter().enumerate() {
+            if !matches!(Did::parse(controller), Ok(did) if did.url == did.uri) {
+                violations.push(DocumentViolation::new(
+                    ViolationCode::InvalidDid,
+                    format!("controller[{}]", i),
+                    format!("{} is not a valid DID", controller),
+                ));
+            }
+        }
+    }
+
+    if let Some(also_known_as) = &document.also_known_as {
+        for (i, uri) in also_known_as.iter().enumerate() {
+            if Url::parse(uri).is_err() {
+                violations.push(DocumentViolation::new(
+                    ViolationCode::InvalidUri,
+                    format!("alsoKnownAs[{}]", i),
+                    format!("{} is not a valid URI", uri),
+                ));
+            }
+        }
+    }
+
+    let mut verification_method_ids = HashSet::new();
+    for (i, vm) in document.verification_method.iter().enumerate() {
+        let path = format!("verificationMethod[{}]", i);
+
+        if let Some(violation) = check_own_did_url(
+            &vm.id,
+            document_did.as_deref(),
+            &format!("{}.id", path),
+            true,
+        ) {
+            violations.push(violation);
+        }
+
+        if !verification_method_ids.insert(absolute_id(&vm.id, &document.id)) {
+            violations.push(DocumentViolation::new(
+                ViolationCode::DuplicateId,
+                format!("{}.id", path),
+                format!("verification method id {} is not unique", vm.id),
+            ));
+        }
+
+        if vm.r#type.is_empty() {
+            violations.push(DocumentViolation::new(
+                ViolationCode::MissingType,
+                format!("{}.type", path),
+                "verification method type must not be empty".to_string(),
+            ));
+        }
+
+        if !matches!(Did::parse(&vm.controller), Ok(did) if did.url == did.uri) {
+            violations.push(DocumentViolation::new(
+                ViolationCode::InvalidDid,
+                format!("{}.controller", path),
+                format!("{} is not a valid DID", vm.controller),
+            ));
+        }
+
+        if !vm.public_key_jwk.is_public_key() {
+            violations.push(DocumentViolation::new(
+                ViolationCode::PrivateKeyMaterial,
+                format!("{}.publicKeyJwk", path),
+                "publicKeyJwk must not contain private key material".to_string(),
+            ));
+        }
+    }
+
+    for relationship in VerificationRelationship::ALL {
+        let references = document.verification_relationship(relationship);
+        for (i, reference) in references.iter().enumerate() {
+            let path = format!("{}[{}]", relationship.property_name(), i);
+            let did = match check_did_url(reference, document_did.as_deref(), &path, true) {
+                Ok(Some(did)) => did,
+                Ok(None) => continue,
+                Err(violation) => {
+                    violations.push(violation);
+                    continue;
+                }
+            };
+
+            // references to the methods of other DIDs can only be checked by resolving them
+            if Some(did.uri.as_str()) == document_did.as_deref()
+                && !verification_method_ids.contains(&absolute_id(reference, &document.id))
+            {
+                violations.push(DocumentViolation::new(
+                    ViolationCode::DanglingReference,
+                    path,
+                    format!("{} does not reference a verification method", reference),
+                ));
+            }
+        }
+    }
+
+    let mut service_ids = HashSet::new();
+    for (i, service) in document.service.iter().flatten().enumerate() {
+        let path = format!("service[{}]", i);
+
+        if service.id.starts_with("did:") || service.id.starts_with('#') {
+            if let Some(violation) = check_own_did_url(
+                &service.id,
+                document_did.as_deref(),
+                &format!("{}.id", path),
+                false,
+            ) {
+                violations.push(violation);
+            }
+        } else if Url::parse(&service.id).is_err() {
+            violations.push(DocumentViolation::new(
+                ViolationCode::InvalidUri,
+                format!("{}.id", path),
+                format!("{} is not a valid URI", service.id),
+            ));
+        }
+
+        if !service_ids.insert(absolute_id(&service.id, &document.id)) {
+            violations.push(DocumentViolation::new(
+                ViolationCode::DuplicateId,
+                format!("{}.id", path),
+                format!("service id {} is not unique", service.id),
+            ));
+        }
+
+        if service.r#type.is_empty() {
+            violations.push(DocumentViolation::new(
+                ViolationCode::InvalidServiceType,
+                format!("{}.type", path),
+                "service type must not be empty".to_string(),
+            ));
+        }
+
+        let endpoint_path = format!("{}.serviceEndpoint", path);
+        let entries = match &service.service_endpoint {
+            ServiceEndpoint::Uri(uri) => {
+                vec![(endpoint_path, ServiceEndpointEntry::Uri(uri.clone()))]
+            }
+            ServiceEndpoint::Map(map) => {
+                vec![(endpoint_path, ServiceEndpointEntry::Map(map.clone()))]
+            }
+            ServiceEndpoint::Set(entries) => {
+                if entries.is_empty() {
+                    violations.push(DocumentViolation::new(
+                        ViolationCode::InvalidServiceEndpoint,
+                        endpoint_path.clone(),
+                        "service endpoint must not be empty".to_string(),
+                    ));
+                }
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(j, entry)| (format!("{}[{}]", endpoint_path, j), entry.clone()))
+                    .collect()
+            }
+        };
+        for (entry_path, entry) in entries {
+            match entry {
+                ServiceEndpointEntry::Uri(uri) => {
+                    if Url::parse(&uri).is_err() {
+                        violations.push(DocumentViolation::new(
+                            ViolationCode::InvalidServiceEndpoint,
+                            entry_path,
+                            format!("{} is not a valid URI", uri),
+                        ));
+                    }
+                }
+                ServiceEndpointEntry::Map(map) => {
+                    if map.properties.is_empty() {
+                        violations.push(DocumentViolation::new(
+                            ViolationCode::InvalidServiceEndpoint,
+                            entry_path,
+                            "service endpoint map must not be empty".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+fn absolute_id(id: &str, document_id: &str) -> String {
+    match id.starts_with('#') {
+        true => format!("{}{}", document_id, id),
+        false => id.to_string(),
+    }
+}
+
+/// Checks that an id is a DID URL, resolving a relative DID URL (e.g. `#key-0`) against the
+/// document's DID. Returns `None` for a relative DID URL if the document's id isn't a valid DID.
+fn check_did_url(
+    id: &str,
+    document_did: Option<&str>,
+    path: &str,
+    require_fragment: bool,
+) -> Result<Option<Did>, DocumentViolation> {
+    let did_url = match (id.starts_with('#'), document_did) {
+        (true, Some(document_did)) => absolute_id(id, document_did),
+        (true, None) => return Ok(None),
+        (false, _) => id.to_string(),
+    };
+
+    let did = Did::parse(&did_url).map_err(|_| {
+        DocumentViolation::new(
+            ViolationCode::InvalidDid,
+            path.to_string(),
+            format!("{} is not a valid DID URL", id),
+        )
+    })?;
+
+    if require_fragment && did.fragment.as_deref().unwrap_or_default().is_empty() {
+        return Err(DocumentViolation::new(
+            ViolationCode::MissingFragment,
+            path.to_string(),
+            format!("{} is missing a fragment", id),
+        ));
+    }
+
+    Ok(Some(did))
+}
+
+/// Checks that the id of a verification method or service defined by the document is a DID URL
+/// for the document's DID.
+fn check_own_did_url(
+    id: &str,
+    document_did: Option<&str>,
+    path: &str,
+    require_fragment: bool,
+) -> Option<DocumentViolation> {
+    match (
+        check_did_url(id, document_did, path, require_fragment),
+        document_did,
+    ) {
+        (Err(violation), _) => Some(violation),
+        (Ok(Some(did)), Some(document_did)) if did.uri != document_did => {
+            Some(DocumentViolation::new(
+                ViolationCode::DidMismatch,
+                path.to_string(),
+                format!("{} does not belong to {}", id, document_did),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::dsa::ed25519::Ed25519Generator,
+        dids::data_model::{service::Service, verification_method::VerificationMethod},
+    };
+
+    const DID: &str = "did:example:123";
+
+    fn verification_method(id: &str) -> VerificationMethod {
+        let mut public_jwk = Ed25519Generator::generate();
+        public_jwk.d = None;
+        VerificationMethod {
+            id: id.to_string(),
+            r#type: "JsonWebKey".to_string(),
+            controller: DID.to_string(),
+            public_key_jwk: public_jwk,
+        }
+    }
+
+    fn document() -> Document {
+        let kid = format!("{}#0", DID);
+        Document {
+            id: DID.to_string(),
+            verification_method: vec![verification_method(&kid)],
+            authentication: Some(vec![kid.clone()]),
+            assertion_method: Some(vec![kid]),
+            service: Some(vec![Service {
+                id: format!("{}#service-0", DID),
+                r#type: "LinkedDomains".to_string(),
+                service_endpoint: "https://example.com".into(),
+                additional_properties: None,
+            }]),
+            ..Default::default()
+        }
+    }
+
+    fn codes(violations: &[DocumentViolation]) -> Vec<ViolationCode> {
+        violations.iter().map(|v| v.code).collect()
+    }
+
+    mod validate_document {
+        use super::*;
+
+        #[test]
+        fn test_conformant_document() {
+            assert_eq!(document().validate(), vec![]);
+        }
+
+        #[test]
+        fn test_invalid_id() {
+            let mut document = document();
+            document.id = "not-a-did".to_string();
+
+            let violations = document.validate();
+            assert_eq!(violations[0].code, ViolationCode::InvalidDid);
+            assert_eq!(violations[0].path, "id");
+        }
+
+        #[test]
+        fn test_duplicate_verification_method_id() {
+            let mut document = document();
+            document
+                .verification_method
+                .push(verification_method(&format!("{}#0", DID)));
+
+            let violations = document.validate();
+            assert_eq!(codes(&violations), vec![ViolationCode::DuplicateId]);
+            assert_eq!(violations[0].path, "verificationMethod[1].id");
+        }
+
+        #[test]
+        fn test_dangling_reference() {
+            let mut document = document();
+            document.key_agreement = Some(vec![format!("{}#missing", DID)]);
+
+            let violations = document.validate();
+            assert_eq!(codes(&violations), vec![ViolationCode::DanglingReference]);
+            assert_eq!(violations[0].path, "keyAgreement[0]");
+        }
+
+        #[test]
+        fn test_did_mismatch() {
+            let mut document = document();
+            document
+                .verification_method
+                .push(verification_method("did:example:456#0"));
+
+            let violations = document.validate();
+            assert_eq!(codes(&violations), vec![ViolationCode::DidMismatch]);
+            assert_eq!(violations[0].path, "verificationMethod[1].id");
+        }
+
+        #[test]
+        fn test_references_other_did() {
+            let mut document = document();
+            document.capability_invocation = Some(vec!["did:example:456#0".to_string()]);
+
+            assert_eq!(document.validate(), vec![]);
+        }
+
+        #[test]
+        fn test_relative_ids() {
+            let mut document = document();
+            document.verification_method[0].id = "#0".to_string();
+            document.authentication = Some(vec!["#0".to_string()]);
+            document.assertion_method = Some(vec![format!("{}#0", DID)]);
+            document.service.as_mut().unwrap()[0].id = "#service-0".to_string();
+
+            assert_eq!(document.validate(), vec![]);
+        }
+
+        #[test]
+        fn test_relative_dangling_reference() {
+            let mut document = document();
+            document.authentication = Some(vec!["#1".to_string()]);
+
+            let violations = document.validate();
+            assert_eq!(codes(&violations), vec![ViolationCode::DanglingReference]);
+            assert_eq!(violations[0].path, "authentication[0]");
+        }
+
+        #[test]
+        fn test_relative_duplicate_id() {
+            let mut document = document();
+            document.verification_method.push(verification_method("#0"));
+
+            let violations = document.validate();
+            assert_eq!(codes(&violations), vec![ViolationCode::DuplicateId]);
+        }
+
+        #[test]
+        fn test_missing_fragment() {
+            let mut document = document();
+            document.verification_method[0].id = DID.to_string();
+
+            let violations = document.validate();
+            assert!(codes(&violations).contains(&ViolationCode::MissingFragment));
+        }
+
+        #[test]
+        fn test_private_key_material() {
+            let mut document = document();
+            document.verification_method[0].public_key_jwk = Ed25519Generator::generate();
+
+            let violations = document.validate();
+            assert_eq!(codes(&violations), vec![ViolationCode::PrivateKeyMaterial]);
+        }
+
+        #[test]
+        fn test_invalid_service_endpoint() {
+            let mut document = document();
+            document.service = Some(vec![Service {
+                id: format!("{}#service-0", DID),
+                r#type: "".to_string(),
+                service_endpoint: vec!["not a uri".to_string()].into(),
+                additional_properties: None,
+            }]);
+
+            let violations = document.validate();
+            assert_eq!(
+                codes(&violations),
+                vec![
+                    ViolationCode::InvalidServiceType,
+                    ViolationCode::InvalidServiceEndpoint
+                ]
+            );
+            assert_eq!(violations[1].path, "service[0].serviceEndpoint[0]");
+        }
+
+        #[test]
+        fn test_reports_every_violation() {
+            let mut document = document();
+            document.controller = Some(vec!["nope".to_string()]);
+            document.also_known_as = Some(vec!["also nope".to_string()]);
+            document.key_agreement = Some(vec![format!("{}#missing", DID)]);
+            document.service.as_mut().unwrap()[0].service_endpoint = vec![].into();
+
+            let violations = document.validate();
+            assert_eq!(
+                codes(&violations),
+                vec![
+                    ViolationCode::InvalidDid,
+                    ViolationCode::InvalidUri,
+                    ViolationCode::DanglingReference,
+                    ViolationCode::InvalidServiceEndpoint,
+                ]
+            );
+        }
+    }
+}
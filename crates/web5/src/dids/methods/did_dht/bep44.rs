@@ -14,7 +14,7 @@ use chrono::Utc;
 /// Signature is 64 bytes and seq is 8 byets
 const MIN_MESSAGE_LEN: usize = 72;
 /// Maximum size of a bep44 v field
-const MAX_V_LEN: usize = 1000;
+pub(crate) const MAX_V_LEN: usize = 1000;
 /// Maximum size a bep44 encoded message
 const MAX_MESSAGE_LEN: usize = MAX_V_LEN + MIN_MESSAGE_LEN;
 
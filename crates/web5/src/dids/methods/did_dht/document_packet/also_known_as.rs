@@ -17,7 +17,7 @@ impl AlsoKnownAs {
 
     pub fn to_resource_record(
         also_known_as: &[String],
-    ) -> Result<ResourceRecord, DocumentPacketError> {
+    ) -> Result<ResourceRecord<'_>, DocumentPacketError> {
         let name = Name::new_unchecked("_aka._did.");
         let txt_record = TXT::new()
             .with_string(&also_known_as.join(","))?
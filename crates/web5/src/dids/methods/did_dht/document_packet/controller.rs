@@ -17,7 +17,7 @@ impl Controller {
 
     pub fn to_resource_record(
         controllers: &[String],
-    ) -> Result<ResourceRecord, DocumentPacketError> {
+    ) -> Result<ResourceRecord<'_>, DocumentPacketError> {
         let name = Name::new_unchecked("_cnt._did.");
         let txt_record = TXT::new().with_string(&controllers.join(","))?.into_owned();
 
@@ -1,3 +1,4 @@
+use super::{PacketRecordSize, PacketSizeEstimate};
 use crate::dids::data_model::document::Document;
 use crate::dids::data_model::{service::Service, verification_method::VerificationMethod};
 use crate::errors::Web5Error;
@@ -70,17 +71,65 @@ impl<'a> TryFrom<Packet<'a>> for Document {
 }
 
 impl Document {
-    pub fn to_packet(&self) -> Result<Packet, DocumentPacketError> {
+    pub fn to_packet(&self) -> Result<Packet<'_>, DocumentPacketError> {
+        let mut packet = Packet::new_reply(0);
+        packet.answers = self
+            .to_labeled_records()?
+            .into_iter()
+            .map(|(_, record)| record)
+            .collect();
+
+        Ok(packet)
+    }
+
+    /// Measures the compressed packet, and how many bytes each record adds to it. Because names
+    /// are compressed against those of the other records, a record's size is the difference
+    /// between the full packet and the packet without it.
+    pub(crate) fn packet_size(&self) -> Result<PacketSizeEstimate, DocumentPacketError> {
+        let records = self.to_labeled_records()?;
+        let build = |skip: Option<usize>| -> Result<usize, DocumentPacketError> {
+            let mut packet = Packet::new_reply(0);
+            packet.answers = records
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| Some(*idx) != skip)
+                .map(|(_, (_, record))| record.clone())
+                .collect();
+            Ok(packet.build_bytes_vec_compressed()?.len())
+        };
+
+        let size = build(None)?;
+        let mut record_sizes = records
+            .iter()
+            .enumerate()
+            .map(|(idx, (property, record))| {
+                Ok(PacketRecordSize {
+                    name: record.name.to_string(),
+                    property: property.clone(),
+                    size: size - build(Some(idx))?,
+                })
+            })
+            .collect::<Result<Vec<_>, DocumentPacketError>>()?;
+        record_sizes.sort_by_key(|record| std::cmp::Reverse(record.size));
+
+        Ok(PacketSizeEstimate {
+            size,
+            records: record_sizes,
+        })
+    }
+
+    /// The records of the document's packet, each labelled with the document property it encodes.
+    fn to_labeled_records(&self) -> Result<Vec<(String, ResourceRecord<'_>)>, DocumentPacketError> {
         // 0. Init root_record and empty answers array
         let did_uri = &self.id;
         let did_id = did_uri
             .split(':')
-            .last()
+            .next_back()
             .ok_or(DocumentPacketError::DocumentError(
                 "Malformed id".to_string(),
             ))?;
         let mut root_record = RootRecord::new(did_id);
-        let mut answers: Vec<ResourceRecord> = vec![];
+        let mut answers: Vec<(String, ResourceRecord)> = vec![];
 
         // 1. Add verification methods and verification relationships to root_record and answers
         let mut vm_id_to_idx: HashMap<String, u32> = HashMap::new();
@@ -101,7 +150,7 @@ impl Document {
                 let idx = 0;
 
                 let vm_record = vm.to_resource_record(did_uri, idx)?.to_owned();
-                answers.push(vm_record);
+                answers.push((vm.id.clone(), vm_record));
                 root_record.vm.push(idx);
                 vm_id_to_idx.insert(vm.id.clone(), idx);
             }
@@ -116,7 +165,7 @@ impl Document {
             }
 
             let vm_record = vm.to_resource_record(did_uri, idx)?.to_owned();
-            answers.push(vm_record);
+            answers.push((vm.id.clone(), vm_record));
             root_record.vm.push(idx);
             vm_id_to_idx.insert(vm.id.clone(), idx);
 
@@ -175,7 +224,7 @@ impl Document {
                 |(idx, src)| -> Result<(), DocumentPacketError> {
                     let idx = idx as u32;
                     let service_record = src.to_resource_record(idx)?;
-                    answers.push(service_record);
+                    answers.push((src.id.clone(), service_record));
                     root_record.svc.push(idx);
 
                     Ok(())
@@ -185,22 +234,30 @@ impl Document {
 
         // 4. Add controller to answers
         if let Some(controllers) = &self.controller {
-            answers.push(Controller::to_resource_record(controllers)?);
+            answers.push((
+                "controller".to_string(),
+                Controller::to_resource_record(controllers)?,
+            ));
         }
 
         // 5. Add alsoKnownAs to answers
         if let Some(also_known_as) = &self.also_known_as {
-            answers.push(AlsoKnownAs::to_resource_record(also_known_as)?);
+            answers.push((
+                "alsoKnownAs".to_string(),
+                AlsoKnownAs::to_resource_record(also_known_as)?,
+            ));
         }
 
-        // 6. Create Packet from root_record and answers
-        let mut packet = Packet::new_reply(0);
-        packet
-            .answers
-            .push(root_record.to_resource_record()?.into_owned());
-        packet.answers.append(&mut answers);
+        // 6. Prepend root_record to answers
+        answers.insert(
+            0,
+            (
+                did_uri.clone(),
+                root_record.to_resource_record()?.into_owned(),
+            ),
+        );
 
-        Ok(packet)
+        Ok(answers)
     }
 
     pub fn from_packet(packet: &Packet) -> Result<Document, DocumentPacketError> {
@@ -354,13 +411,14 @@ mod tests {
     fn test_to_and_from_packet_full_featured() {
         let did_uri = "did:dht:123";
 
-        let verification_method1 = generate_identity_key_vm(&did_uri);
+        let verification_method1 = generate_identity_key_vm(did_uri);
         let verification_method2 = generate_additional_vm(did_uri);
 
         let service = Service {
             id: "did:dht:123#foo".to_string(),
             r#type: "bar".to_string(),
-            service_endpoint: vec!["example.com".to_string()],
+            service_endpoint: vec!["example.com".to_string()].into(),
+            additional_properties: None,
         };
         let document = Document {
             id: did_uri.to_string(),
@@ -406,7 +464,7 @@ mod tests {
     fn test_to_and_from_packet() {
         let did_uri = "did:dht:123";
 
-        let verification_method = generate_identity_key_vm(&did_uri);
+        let verification_method = generate_identity_key_vm(did_uri);
         let document = Document {
             id: did_uri.to_string(),
             verification_method: vec![verification_method],
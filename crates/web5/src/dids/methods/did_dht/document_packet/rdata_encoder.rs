@@ -1,9 +1,25 @@
 use std::collections::HashMap;
 
-use simple_dns::{rdata::RData, ResourceRecord};
+use simple_dns::{
+    rdata::{RData, TXT},
+    CharacterString, ResourceRecord,
+};
 
 use super::DocumentPacketError;
 
+/// The maximum length in bytes of a single DNS character-string.
+const MAX_CHARACTER_STRING_LENGTH: usize = 255;
+
+/// Builds TXT record data from text, split into as many character-strings as its length requires.
+/// `text_from_record` joins them back together.
+pub fn txt_from_text(text: &str) -> Result<TXT<'static>, DocumentPacketError> {
+    let mut txt = TXT::new();
+    for chunk in text.as_bytes().chunks(MAX_CHARACTER_STRING_LENGTH) {
+        txt.add_char_string(CharacterString::new(chunk)?);
+    }
+    Ok(txt.into_owned())
+}
+
 pub fn text_from_record(record: &ResourceRecord) -> Result<String, DocumentPacketError> {
     let rdata_txt = match &record.rdata {
         RData::TXT(txt) => txt.clone(),
@@ -85,7 +85,7 @@ impl RootRecord {
         }
     }
 
-    pub fn to_resource_record(&self) -> Result<ResourceRecord, DocumentPacketError> {
+    pub fn to_resource_record(&self) -> Result<ResourceRecord<'_>, DocumentPacketError> {
         let fields = [
             ("vm", "k", &self.vm),
             ("asm", "k", &self.asm),
@@ -159,7 +159,7 @@ mod tests {
     #[test]
     fn to_and_from_resource_record_no_txt() {
         let did_id = "123";
-        let root_record = RootRecord::new(&did_id);
+        let root_record = RootRecord::new(did_id);
         let resource_record = root_record
             .to_resource_record()
             .expect("Expected to create resource record");
@@ -171,7 +171,7 @@ mod tests {
     #[test]
     fn to_and_from_resource_record_many_values() {
         let did_id = "123";
-        let mut root_record = RootRecord::new(&did_id);
+        let mut root_record = RootRecord::new(did_id);
         root_record.vm.push(0);
         root_record.agm.push(0);
         root_record.asm.push(1);
@@ -1,44 +1,161 @@
 use std::collections::HashMap;
 
-use simple_dns::{
-    rdata::{RData, TXT},
-    Name, ResourceRecord,
-};
+use base64::{engine::general_purpose, Engine as _};
+use simple_dns::{rdata::RData, Name, ResourceRecord};
 
 use url::Url;
 
-use crate::dids::data_model::service::Service;
+use crate::{
+    dids::data_model::service::{Service, ServiceEndpoint, ServiceEndpointEntry},
+    json::{JsonObject, JsonValue},
+};
 
 use super::{
-    rdata_encoder::{get_rdata_txt_value, record_rdata_to_hash_map},
+    rdata_encoder::{get_rdata_txt_value, record_rdata_to_hash_map, txt_from_text},
     DocumentPacketError, DEFAULT_TTL,
 };
 
 const NAME_PREFIX: &str = "_s";
 const NAME_SUFFIX: &str = "_did";
+const RESERVED_KEYS: [&str; 3] = ["id", "t", "se"];
+const RESERVED_CHARS: [char; 3] = [';', '=', ','];
 
 #[derive(Debug, PartialEq)]
 struct ServiceRdata {
     pub id: String,
-    pub se: Vec<String>,
+    pub se: ServiceEndpoint,
     pub t: String,
+    pub additional_properties: Option<JsonObject>,
 }
 
 impl TryFrom<HashMap<String, String>> for ServiceRdata {
     fn try_from(rdata_map: HashMap<String, String>) -> Result<Self, Self::Error> {
+        let mut additional_properties = JsonObject::new();
+        for (key, value) in &rdata_map {
+            if !RESERVED_KEYS.contains(&key.as_str()) {
+                additional_properties.insert_value(key, decode_value(value)?);
+            }
+        }
+
         Ok(ServiceRdata {
             id: get_rdata_txt_value(&rdata_map, "id")?,
-            se: get_rdata_txt_value(&rdata_map, "se")?
-                .split(',')
-                .map(|s| s.to_string())
-                .collect(),
+            se: decode_service_endpoint(&get_rdata_txt_value(&rdata_map, "se")?)?,
             t: get_rdata_txt_value(&rdata_map, "t")?,
+            additional_properties: match additional_properties.properties.is_empty() {
+                true => None,
+                false => Some(additional_properties),
+            },
         })
     }
 
     type Error = DocumentPacketError;
 }
 
+/// Values which can't be written in the comma-separated format of the did:dht spec (maps,
+/// non-string values, or strings containing one of `;`, `=` or `,`) are written as base64url
+/// encoded JSON behind this prefix. Strings which start with the prefix are encoded too, so a
+/// value is JSON if and only if it has the prefix.
+const JSON_VALUE_PREFIX: &str = "json:";
+
+fn encode_json<T: serde::Serialize>(value: &T) -> Result<String, DocumentPacketError> {
+    let json =
+        serde_json::to_vec(value).map_err(|e| DocumentPacketError::DocumentError(e.to_string()))?;
+    Ok(format!(
+        "{}{}",
+        JSON_VALUE_PREFIX,
+        general_purpose::URL_SAFE_NO_PAD.encode(json)
+    ))
+}
+
+fn decode_json<T: serde::de::DeserializeOwned>(
+    text: &str,
+) -> Result<Option<T>, DocumentPacketError> {
+    let encoded = match text.strip_prefix(JSON_VALUE_PREFIX) {
+        Some(encoded) => encoded,
+        None => return Ok(None),
+    };
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(encoded)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .map(Some)
+        .ok_or_else(|| DocumentPacketError::RDataError(format!("invalid JSON value {}", text)))
+}
+
+fn is_plain_text(text: &str) -> bool {
+    !text.is_empty() && !text.contains(RESERVED_CHARS) && !text.starts_with(JSON_VALUE_PREFIX)
+}
+
+/// Strings, and lists of two or more strings, use the comma-separated format of the spec.
+fn encode_value(value: &JsonValue) -> Result<String, DocumentPacketError> {
+    match value {
+        JsonValue::String(text) if is_plain_text(text) => Ok(text.clone()),
+        JsonValue::Array(values) if values.len() > 1 => {
+            let texts = values
+                .iter()
+                .map(|value| match value {
+                    JsonValue::String(text) if is_plain_text(text) => Some(text.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            match texts {
+                Some(texts) => Ok(texts.join(",")),
+                None => encode_json(value),
+            }
+        }
+        _ => encode_json(value),
+    }
+}
+
+fn decode_value(text: &str) -> Result<JsonValue, DocumentPacketError> {
+    if let Some(value) = decode_json(text)? {
+        return Ok(value);
+    }
+    if text.contains(',') {
+        return Ok(JsonValue::Array(
+            text.split(',')
+                .map(|s| JsonValue::String(s.to_string()))
+                .collect(),
+        ));
+    }
+    Ok(JsonValue::String(text.to_string()))
+}
+
+/// URIs, and sets of URIs, use the comma-separated `se` format of the spec, which always
+/// resolves as a set. Other endpoints are written as JSON.
+fn encode_service_endpoint(endpoint: &ServiceEndpoint) -> Result<String, DocumentPacketError> {
+    let uris = match endpoint {
+        ServiceEndpoint::Uri(uri) => Some(vec![uri.clone()]),
+        ServiceEndpoint::Map(_) => None,
+        ServiceEndpoint::Set(entries) => entries
+            .iter()
+            .map(|entry| match entry {
+                ServiceEndpointEntry::Uri(uri) => Some(uri.clone()),
+                ServiceEndpointEntry::Map(_) => None,
+            })
+            .collect(),
+    };
+
+    match uris {
+        Some(uris) if !uris.is_empty() && uris.iter().all(|uri| is_plain_text(uri)) => {
+            Ok(uris.join(","))
+        }
+        _ => encode_json(endpoint),
+    }
+}
+
+fn decode_service_endpoint(text: &str) -> Result<ServiceEndpoint, DocumentPacketError> {
+    if let Some(endpoint) = decode_json(text)? {
+        return Ok(endpoint);
+    }
+
+    Ok(text
+        .split(',')
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>()
+        .into())
+}
+
 impl Service {
     pub fn is_service_record_with_index(record: &ResourceRecord, idx: u32) -> bool {
         let labels = record.name.get_labels();
@@ -62,19 +179,33 @@ impl Service {
         format!("{}{}.{}", NAME_PREFIX, idx, NAME_SUFFIX)
     }
 
-    pub fn to_resource_record(&self, idx: u32) -> Result<ResourceRecord, DocumentPacketError> {
+    pub fn to_resource_record(&self, idx: u32) -> Result<ResourceRecord<'_>, DocumentPacketError> {
         let url = Url::parse(&self.id)
             .map_err(|_| DocumentPacketError::MissingFragment(self.id.clone()))?;
         let service_id_fragment = url
             .fragment()
             .ok_or(DocumentPacketError::MissingFragment(self.id.clone()))?;
 
-        let se = self.service_endpoint.join(",");
-
-        let parts = format!("id={};t={};se={}", service_id_fragment, self.r#type, se);
+        let se = encode_service_endpoint(&self.service_endpoint)?;
+
+        let mut parts = format!("id={};t={};se={}", service_id_fragment, self.r#type, se);
+        if let Some(additional_properties) = &self.additional_properties {
+            let mut keys = additional_properties.properties.keys().collect::<Vec<_>>();
+            keys.sort();
+            for key in keys {
+                if RESERVED_KEYS.contains(&key.as_str()) || key.contains(RESERVED_CHARS) {
+                    return Err(DocumentPacketError::DocumentError(format!(
+                        "service property name {} cannot be encoded",
+                        key
+                    )));
+                }
+                let value = encode_value(&additional_properties.properties[key])?;
+                parts.push_str(&format!(";{}={}", key, value));
+            }
+        }
         let name =
             Name::new_unchecked(&format!("{}{}.{}", NAME_PREFIX, idx, NAME_SUFFIX)).into_owned();
-        let txt_record = TXT::new().with_string(&parts)?.into_owned();
+        let txt_record = txt_from_text(&parts)?;
 
         Ok(ResourceRecord::new(
             name,
@@ -95,15 +226,16 @@ impl Service {
             id: format!("{}#{}", did_uri, service_rdata.id),
             r#type: service_rdata.t,
             service_endpoint: service_rdata.se,
+            additional_properties: service_rdata.additional_properties,
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use simple_dns::rdata::A;
+    use simple_dns::rdata::{A, TXT};
 
-    use super::*;
+    use super::{super::rdata_encoder::text_from_record, *};
 
     #[test]
     fn test_to_and_from_resource_record() {
@@ -115,7 +247,8 @@ mod tests {
         let service = Service {
             id: id.to_string(),
             r#type: r#type.to_string(),
-            service_endpoint: vec![service_endpoint.to_string()],
+            service_endpoint: service_endpoint.into(),
+            additional_properties: None,
         };
 
         let resource_record = service
@@ -125,7 +258,11 @@ mod tests {
         let service2 = Service::from_resource_record(did_uri, &resource_record)
             .expect("Failed to convert ResourceRecord to Service");
 
-        assert_eq!(service, service2);
+        // the se format of the spec always resolves as a set
+        assert_eq!(
+            service2.service_endpoint,
+            vec![service_endpoint.to_string()].into()
+        );
     }
 
     #[test]
@@ -138,7 +275,9 @@ mod tests {
         let service = Service {
             id: id.to_string(),
             r#type: r#type.to_string(),
-            service_endpoint: vec![service_endpoint.to_string(), service_endpoint.to_string()],
+            service_endpoint: vec![service_endpoint.to_string(), service_endpoint.to_string()]
+                .into(),
+            additional_properties: None,
         };
 
         let resource_record = service
@@ -151,6 +290,202 @@ mod tests {
         assert_eq!(service, service2);
     }
 
+    #[test]
+    fn test_to_and_from_resource_record_map_service_endpoint() {
+        let did_uri = "did:dht:123";
+
+        let mut endpoint = JsonObject::new();
+        endpoint.insert_value(
+            "uri",
+            JsonValue::String("https://foo.tbd.website".to_string()),
+        );
+        endpoint.insert_value(
+            "accept",
+            JsonValue::Array(vec![JsonValue::String("didcomm/v2".to_string())]),
+        );
+        endpoint.insert_value("routingKeys", JsonValue::Array(vec![]));
+
+        let mut additional_properties = JsonObject::new();
+        additional_properties.insert_value("enc", JsonValue::String("#enc".to_string()));
+        additional_properties.insert_value(
+            "sig",
+            JsonValue::Array(vec![JsonValue::String("#sig".to_string())]),
+        );
+        additional_properties.insert_value("note", JsonValue::String("a;b=c".to_string()));
+
+        let service = Service {
+            id: "did:dht:123#didcomm".to_string(),
+            r#type: "DIDCommMessaging".to_string(),
+            service_endpoint: ServiceEndpoint::Set(vec![
+                ServiceEndpointEntry::Map(endpoint),
+                ServiceEndpointEntry::Uri("https://bar.tbd.website".to_string()),
+            ]),
+            additional_properties: Some(additional_properties),
+        };
+
+        let resource_record = service
+            .to_resource_record(0)
+            .expect("Failed to convert Service to ResourceRecord");
+
+        let service2 = Service::from_resource_record(did_uri, &resource_record)
+            .expect("Failed to convert ResourceRecord to Service");
+
+        assert_eq!(service, service2);
+    }
+
+    #[test]
+    fn test_to_and_from_resource_record_long_service_endpoint() {
+        let did_uri = "did:dht:123";
+
+        let routing_keys = (0..4)
+            .map(|i| {
+                JsonValue::String(format!(
+                    "did:example:mediator{}#z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc",
+                    i
+                ))
+            })
+            .collect();
+        let mut endpoint = JsonObject::new();
+        endpoint.insert_value(
+            "uri",
+            JsonValue::String("https://mediator.example.com/didcomm".to_string()),
+        );
+        endpoint.insert_value(
+            "accept",
+            JsonValue::Array(vec![JsonValue::String("didcomm/v2".to_string())]),
+        );
+        endpoint.insert_value("routingKeys", JsonValue::Array(routing_keys));
+
+        let service = Service {
+            id: "did:dht:123#didcomm".to_string(),
+            r#type: "DIDCommMessaging".to_string(),
+            service_endpoint: ServiceEndpoint::Map(endpoint),
+            additional_properties: None,
+        };
+
+        let resource_record = service
+            .to_resource_record(0)
+            .expect("Failed to convert Service to ResourceRecord");
+        let text = text_from_record(&resource_record).unwrap();
+        assert!(text.len() > 255);
+
+        let service2 = Service::from_resource_record(did_uri, &resource_record)
+            .expect("Failed to convert ResourceRecord to Service");
+        assert_eq!(service, service2);
+    }
+
+    #[test]
+    fn test_to_and_from_resource_record_single_member_set() {
+        let did_uri = "did:dht:123";
+        let service = Service {
+            id: "did:dht:123#0".to_string(),
+            r#type: "some_type".to_string(),
+            service_endpoint: vec!["foo.tbd.website".to_string()].into(),
+            additional_properties: None,
+        };
+
+        let resource_record = service
+            .to_resource_record(0)
+            .expect("Failed to convert Service to ResourceRecord");
+        let text = text_from_record(&resource_record).unwrap();
+        assert_eq!(text, "id=0;t=some_type;se=foo.tbd.website");
+
+        let service2 = Service::from_resource_record(did_uri, &resource_record)
+            .expect("Failed to convert ResourceRecord to Service");
+        assert_eq!(service, service2);
+    }
+
+    #[test]
+    fn test_from_resource_record_comma_separated_properties() {
+        let name = Name::new_unchecked(&format!("_s{}._did", 0)).into_owned();
+        let txt = TXT::new()
+            .with_string("id=dwn;t=DecentralizedWebNode;se=https://dwn.tbd.website;enc=#enc;sig=#sig-0,#sig-1")
+            .unwrap();
+        let resource_record =
+            ResourceRecord::new(name, simple_dns::CLASS::IN, DEFAULT_TTL, RData::TXT(txt));
+
+        let service = Service::from_resource_record("did:dht:123", &resource_record).unwrap();
+        let additional_properties = service.additional_properties.unwrap();
+        assert_eq!(
+            additional_properties.get_value("enc"),
+            Some(&JsonValue::String("#enc".to_string()))
+        );
+        assert_eq!(
+            additional_properties.get_value("sig"),
+            Some(&JsonValue::Array(vec![
+                JsonValue::String("#sig-0".to_string()),
+                JsonValue::String("#sig-1".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_to_and_from_resource_record_ambiguous_values() {
+        let did_uri = "did:dht:123";
+        let mut additional_properties = JsonObject::new();
+        // base64url encoded JSON, which must not be mistaken for an encoded value
+        additional_properties.insert_value("plain", JsonValue::String("eyJhIjoxfQ".to_string()));
+        additional_properties.insert_value("prefixed", JsonValue::String("json:e30".to_string()));
+        additional_properties.insert_value(
+            "list",
+            JsonValue::Array(vec![
+                JsonValue::String("a".to_string()),
+                JsonValue::String("b".to_string()),
+            ]),
+        );
+        let service = Service {
+            id: "did:dht:123#0".to_string(),
+            r#type: "some_type".to_string(),
+            service_endpoint: vec!["json:e30".to_string()].into(),
+            additional_properties: Some(additional_properties),
+        };
+
+        let resource_record = service.to_resource_record(0).unwrap();
+        let text = text_from_record(&resource_record).unwrap();
+        assert!(text.contains(";list=a,b;"));
+        assert!(text.contains(";plain=eyJhIjoxfQ;"));
+
+        let service2 = Service::from_resource_record(did_uri, &resource_record).unwrap();
+        assert_eq!(service, service2);
+    }
+
+    #[test]
+    fn test_from_resource_record_invalid_json_value() {
+        let name = Name::new_unchecked(&format!("_s{}._did", 0)).into_owned();
+        let txt = TXT::new()
+            .with_string("id=0;t=some_type;se=json:not-json")
+            .unwrap();
+        let resource_record =
+            ResourceRecord::new(name, simple_dns::CLASS::IN, DEFAULT_TTL, RData::TXT(txt));
+
+        let error = Service::from_resource_record("did:dht:123", &resource_record)
+            .expect_err("Expected error because se is not valid JSON");
+        match error {
+            DocumentPacketError::RDataError(_) => {}
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn test_to_record_resource_reserved_property_name() {
+        let mut additional_properties = JsonObject::new();
+        additional_properties.insert_value("se", JsonValue::String("foo".to_string()));
+        let service = Service {
+            id: "did:dht:123#0".to_string(),
+            r#type: "some_type".to_string(),
+            service_endpoint: "foo.tbd.website".into(),
+            additional_properties: Some(additional_properties),
+        };
+
+        let error = service
+            .to_resource_record(0)
+            .expect_err("Expected error due to reserved property name");
+        match error {
+            DocumentPacketError::DocumentError(_) => {}
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn test_to_record_resource_service_id_multiple_sharps() {
         // The URL spec seems to say that multiple "#" is invalid,
@@ -163,7 +498,8 @@ mod tests {
         let service = Service {
             id: id.to_string(),
             r#type: r#type.to_string(),
-            service_endpoint: vec![service_endpoint.to_string()],
+            service_endpoint: vec![service_endpoint.to_string()].into(),
+            additional_properties: None,
         };
 
         let resource_record = service
@@ -182,7 +518,8 @@ mod tests {
         let service = Service {
             id: did_uri.to_string(),
             r#type: r#type.to_string(),
-            service_endpoint: vec![service_endpoint.to_string()],
+            service_endpoint: service_endpoint.into(),
+            additional_properties: None,
         };
 
         let resource_record = service
@@ -52,17 +52,17 @@ impl VerificationMethod {
             }
         };
 
-        return match labels.get(1) {
+        match labels.get(1) {
             None => false,
             Some(subdomain) => subdomain.to_string() == "_did",
-        };
+        }
     }
 
     pub fn to_resource_record(
         &self,
         did_uri: &str,
         idx: u32,
-    ) -> Result<ResourceRecord, DocumentPacketError> {
+    ) -> Result<ResourceRecord<'_>, DocumentPacketError> {
         let curve = match self.public_key_jwk.crv.as_str() {
             "Ed25519" => "Ed25519",
             "secp256k1" => "secp256k1",
@@ -211,7 +211,7 @@ mod tests {
         };
 
         let record = vm
-            .to_resource_record(&did_uri, 0)
+            .to_resource_record(did_uri, 0)
             .expect("Expected to convert verification method to DNS record");
         let reconstituted_vm = VerificationMethod::from_resource_record(did_uri, &record, false)
             .expect("Expected to convert DNS record back to verification method");
@@ -231,7 +231,7 @@ mod tests {
         };
 
         let record = vm
-            .to_resource_record(&did_uri, 0)
+            .to_resource_record(did_uri, 0)
             .expect("Expected to convert verification method to DNS record");
         let reconstituted_vm = VerificationMethod::from_resource_record(did_uri, &record, true)
             .expect("Expected to convert DNS record back to verification method");
@@ -255,7 +255,7 @@ mod tests {
         };
 
         let record = vm
-            .to_resource_record(&did_uri, 0)
+            .to_resource_record(did_uri, 0)
             .expect("Expected to convert verification method to DNS record");
         let reconstituted_vm = VerificationMethod::from_resource_record(did_uri, &record, false)
             .expect("Expected to convert DNS record back to verification method");
@@ -291,7 +291,7 @@ mod tests {
         };
 
         let record = vm
-            .to_resource_record(&did_uri, 0)
+            .to_resource_record(did_uri, 0)
             .expect("Expected to convert verification method to DNS record");
         let reconstituted_vm = VerificationMethod::from_resource_record(did_uri, &record, false)
             .expect("Expected to convert DNS record back to verification method");
@@ -314,13 +314,13 @@ mod tests {
             controller: did_uri.to_string(),
             public_key_jwk,
         };
-        vm.to_resource_record(&did_uri, 0)
+        vm.to_resource_record(did_uri, 0)
             .expect_err("Expected to fail because curve is not supported");
     }
 
     #[test]
     fn test_from_resource_record_unsupported_key_type() {
-        let parts = format!("t=19;k=foo;a=bar;c=baz"); // "t" is not a supported key type index
+        let parts = "t=19;k=foo;a=bar;c=baz".to_string(); // "t" is not a supported key type index
 
         let name = Name::new_unchecked("_k0._did");
         let txt_record = TXT::new().with_string(&parts).unwrap().into_owned();
@@ -354,7 +354,7 @@ mod tests {
         };
 
         let record = vm
-            .to_resource_record(&did_uri, 0)
+            .to_resource_record(did_uri, 0)
             .expect("Expected to convert verification method to DNS record");
         let reconstituted_vm = VerificationMethod::from_resource_record(did_uri, &record, false)
             .expect("Expected to convert DNS record back to verification method");
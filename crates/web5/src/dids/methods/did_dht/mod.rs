@@ -1,4 +1,6 @@
-use bep44::Bep44Message;
+use base64::{engine::general_purpose, Engine as _};
+use bep44::{Bep44Message, MAX_V_LEN};
+use sha2::{Digest, Sha256};
 use simple_dns::Packet;
 
 use crate::{
@@ -9,23 +11,40 @@ use crate::{
     },
     dids::{
         bearer_did::BearerDid,
+        controller::verify_document_update,
         data_model::{
-            document::Document, service::Service, verification_method::VerificationMethod,
+            document::{Document, VerificationRelationship},
+            document_builder::{
+                assigned_relationships, check_assignments, DocumentBuilder, DEFAULT_RELATIONSHIPS,
+            },
+            service::{Service, ServiceEndpoint, ServiceEndpointEntry},
+            verification_method::VerificationMethod,
         },
         did::Did,
+        methods::generate_key_agreement_key,
         resolution::{
-            resolution_metadata::ResolutionMetadataError, resolution_result::ResolutionResult,
+            resolution_metadata::{ResolutionMetadata, ResolutionMetadataError},
+            resolution_options::{get_default_resolution_options, ResolutionOptions},
+            resolution_result::ResolutionResult,
         },
     },
     errors::{Result, Web5Error},
+    json::{FromJson, JsonObject, JsonValue, ToJson},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::Arc,
 };
-use std::sync::Arc;
+use url::Url;
 
 mod bep44;
 mod document_packet;
 
 const JSON_WEB_KEY: &str = "JsonWebKey";
 const DEFAULT_RELAY: &str = "https://diddht.tbddev.org";
+/// The service property carrying the digest of a service offloaded to a linked resource.
+const LINKED_RESOURCE_DIGEST: &str = "linkedResourceDigest";
 
 fn create_identifier(identity_key_jwk: &Jwk) -> Result<String> {
     let pubkey_bytes = ed25519::public_jwk_extract_bytes(identity_key_jwk)?;
@@ -33,6 +52,134 @@ fn create_identifier(identity_key_jwk: &Jwk) -> Result<String> {
     Ok(format!("did:dht:{}", suffix))
 }
 
+/// Checks the DID method and decodes the identity key from the DID's id.
+fn identity_key(did: &Did) -> std::result::Result<Jwk, ResolutionMetadataError> {
+    if did.method != "dht" {
+        return Err(ResolutionMetadataError::MethodNotSupported);
+    }
+    let identity_key = zbase32::decode_full_bytes_str(&did.id)
+        .map_err(|_| ResolutionMetadataError::InvalidPublicKey)?;
+    ed25519::public_jwk_from_bytes(&identity_key)
+        .map_err(|_| ResolutionMetadataError::InvalidPublicKey)
+}
+
+fn linked_resource_digest(content: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(content))
+}
+
+fn is_linked_service(service: &Service) -> bool {
+    service
+        .additional_properties
+        .as_ref()
+        .and_then(|properties| properties.get_value(LINKED_RESOURCE_DIGEST))
+        .is_some()
+}
+
+/// Returns the url of the linked resource a stub points at, its only endpoint.
+fn linked_resource_url(service: &Service) -> Option<String> {
+    match service.service_endpoint.entries().as_slice() {
+        [ServiceEndpointEntry::Uri(url)] => Some(url.clone()),
+        _ => None,
+    }
+}
+
+/// Builds the stub replacing an offloaded service, and the linked resource it points at.
+fn link_service(service: &Service, base_url: &str) -> Result<(Service, LinkedResource)> {
+    let (_, fragment) = service.id.rsplit_once('#').ok_or_else(|| {
+        Web5Error::Parameter(format!("service id {} must have a fragment", service.id))
+    })?;
+    let url = format!("{}/{}.json", base_url.trim_end_matches('/'), fragment);
+    let content = service.to_json_string()?;
+
+    let mut additional_properties = JsonObject::new();
+    additional_properties.insert_value(
+        LINKED_RESOURCE_DIGEST,
+        JsonValue::String(linked_resource_digest(content.as_bytes())),
+    );
+    let stub = Service {
+        id: service.id.clone(),
+        r#type: service.r#type.clone(),
+        service_endpoint: vec![url.clone()].into(),
+        additional_properties: Some(additional_properties),
+    };
+
+    Ok((stub, LinkedResource { url, content }))
+}
+
+/// Restores services offloaded to linked resources, verifying each against its digest. The linked
+/// resources are taken from `linked_resources` by url if given, or else fetched over https if
+/// `fetch_linked_resources`. Services whose linked resource isn't fetched, or can't be, are left
+/// as stubs, and their urls returned alongside the document.
+async fn resolve_linked_services(
+    mut document: Document,
+    linked_resources: Option<&BTreeMap<String, String>>,
+    fetch_linked_resources: bool,
+) -> std::result::Result<(Document, Vec<String>), ResolutionMetadataError> {
+    let mut unresolved = vec![];
+    for service in document.service.iter_mut().flatten() {
+        let digest = match service
+            .additional_properties
+            .as_ref()
+            .and_then(|properties| properties.get_value(LINKED_RESOURCE_DIGEST))
+        {
+            Some(JsonValue::String(digest)) => digest.clone(),
+            Some(_) => return Err(ResolutionMetadataError::InvalidDidDocument),
+            None => continue,
+        };
+        let url =
+            linked_resource_url(service).ok_or(ResolutionMetadataError::InvalidDidDocument)?;
+
+        let fetched = match linked_resources {
+            Some(linked_resources) => Some(
+                linked_resources
+                    .get(&url)
+                    .map(|content| content.as_bytes().to_vec())
+                    .ok_or(ResolutionMetadataError::NotFound)?,
+            ),
+            None if fetch_linked_resources => fetch_linked_resource(&url).await.ok(),
+            None => None,
+        };
+        let content = match fetched {
+            Some(content) => content,
+            None => {
+                unresolved.push(url);
+                continue;
+            }
+        };
+        if linked_resource_digest(&content) != digest {
+            return Err(ResolutionMetadataError::InvalidDidDocument);
+        }
+
+        let linked_service = Service::from_json_byte_array(&content)
+            .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+        if linked_service.id != service.id || linked_service.r#type != service.r#type {
+            return Err(ResolutionMetadataError::InvalidDidDocument);
+        }
+        *service = linked_service;
+    }
+
+    Ok((document, unresolved))
+}
+
+/// Fetches a linked resource, which must be served over https.
+pub(crate) async fn fetch_linked_resource(url: &str) -> Result<Vec<u8>> {
+    if !Url::parse(url).is_ok_and(|url| url.scheme() == "https") {
+        return Err(Web5Error::Parameter(format!(
+            "linked resource {} is not an https url",
+            url
+        )));
+    }
+
+    let response = http_std::fetch(url, None).await?;
+    if response.status_code != 200 {
+        return Err(Web5Error::Network(format!(
+            "failed to fetch linked resource {}: {}",
+            url, response.status_code
+        )));
+    }
+    Ok(response.body)
+}
+
 /// Provides functionality for creating and resolving "did:dht" method Decentralized Identifiers (DIDs).
 ///
 /// A "did:dht" DID is derived from an identity key and is stored on a Distributed Hash Table (DHT).
@@ -54,7 +201,8 @@ pub struct DidDhtCreateOptions {
     /// The key manager used for key storage and management. If not provided, an in-memory key manager will be used.
     pub key_manager: Option<Arc<dyn KeyManager>>,
 
-    /// Optional services to add to the DID document.
+    /// Optional services to add to the DID document. A URI endpoint is added as a set of one
+    /// URI, as it resolves.
     pub service: Option<Vec<Service>>,
 
     /// Optional controllers for the DID document.
@@ -65,6 +213,91 @@ pub struct DidDhtCreateOptions {
 
     /// Optional additional verification methods for the DID document.
     pub verification_method: Option<Vec<VerificationMethod>>,
+
+    /// Optional verification relationship assignments, keyed by verification method id (absolute,
+    /// or relative such as `#key-1`). Additional verification methods without an assignment aren't
+    /// registered under any relationship, and an assignment for the identity key (`#0`) replaces
+    /// its default relationships. An assignment matching no verification method fails.
+    pub verification_relationships: Option<HashMap<String, Vec<VerificationRelationship>>>,
+
+    /// Generates an X25519 key, imported into the key manager, and registers it under
+    /// `keyAgreement`, identified by its JWK thumbprint, so that the DID can receive encrypted
+    /// messages. Defaults to false.
+    pub key_agreement: Option<bool>,
+}
+
+/// Options for publishing a "did:dht" DID document.
+#[derive(Default)]
+pub struct DidDhtPublishOptions {
+    /// The URL of the gateway to use for publishing. If not provided, the default gateway is used.
+    pub gateway_url: Option<String>,
+
+    /// The base URL under which services are hosted as linked resources, for documents too large
+    /// to publish, as `{base_url}/{service fragment}.json`. Since the URL counts towards the
+    /// packet size, it should be short, and specific to the DID. If not provided, services are
+    /// never offloaded.
+    pub linked_resource_base_url: Option<String>,
+}
+
+/// A service offloaded from a "did:dht" DID document, which must be hosted at `url` for the
+/// document to resolve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedResource {
+    /// The URL the resource must be served from.
+    pub url: String,
+    /// The JSON representation of the service.
+    pub content: String,
+}
+
+/// The number of bytes a DNS record adds to a "did:dht" DID document's packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketRecordSize {
+    /// The DNS name of the record, such as `_s0._did`.
+    pub name: String,
+    /// The id of the verification method or service the record encodes, `controller`,
+    /// `alsoKnownAs`, or the DID itself for the root record.
+    pub property: String,
+    /// The number of bytes the record adds to the compressed packet.
+    pub size: usize,
+}
+
+/// The encoded size of a "did:dht" DID document's DNS packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketSizeEstimate {
+    /// The size of the compressed packet, in bytes.
+    pub size: usize,
+    /// The size of each record, largest first.
+    pub records: Vec<PacketRecordSize>,
+}
+
+impl PacketSizeEstimate {
+    /// The maximum size of a packet which can be published to the DHT.
+    pub const MAX_SIZE: usize = MAX_V_LEN;
+
+    /// Returns whether the packet is too large to be published to the DHT.
+    pub fn exceeds_limit(&self) -> bool {
+        self.size > Self::MAX_SIZE
+    }
+}
+
+impl fmt::Display for PacketSizeEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "did:dht packet is {} bytes", self.size)?;
+        if self.exceeds_limit() {
+            write!(
+                f,
+                ", {} over the {} byte limit",
+                self.size - Self::MAX_SIZE,
+                Self::MAX_SIZE
+            )?;
+        }
+        let records = self
+            .records
+            .iter()
+            .map(|r| format!("{} ({}) {} bytes", r.name, r.property, r.size))
+            .collect::<Vec<_>>();
+        write!(f, "; records by size: {}", records.join(", "))
+    }
 }
 
 impl DidDht {
@@ -108,24 +341,52 @@ impl DidDht {
         };
 
         let did = Did::parse(&did_uri)?;
-        let document = Document {
-            id: did_uri.clone(),
-            service: options.service,
-            also_known_as: options.also_known_as,
-            controller: options.controller,
-            verification_method: {
-                let mut methods = vec![identity_key_verification_method.clone()];
-                if let Some(mut additional_methods) = options.verification_method {
-                    methods.append(&mut additional_methods);
-                }
-                methods
-            },
-            capability_delegation: Some(vec![identity_key_verification_method.id.clone()]),
-            capability_invocation: Some(vec![identity_key_verification_method.id.clone()]),
-            authentication: Some(vec![identity_key_verification_method.id.clone()]),
-            assertion_method: Some(vec![identity_key_verification_method.id.clone()]),
-            ..Default::default()
-        };
+        let additional_verification_methods = options.verification_method.unwrap_or_default();
+        let assignments = options.verification_relationships.as_ref();
+        check_assignments(
+            assignments,
+            &did_uri,
+            &std::iter::once(&identity_key_verification_method)
+                .chain(&additional_verification_methods)
+                .map(|vm| vm.id.as_str())
+                .collect::<Vec<_>>(),
+        )?;
+        let mut builder = DocumentBuilder::new(&did_uri);
+        builder.add_verification_method(
+            identity_key_verification_method.clone(),
+            &assigned_relationships(assignments, &did_uri, &identity_key_verification_method.id)
+                .unwrap_or_else(|| DEFAULT_RELATIONSHIPS.to_vec()),
+        )?;
+        for vm in additional_verification_methods {
+            let relationships =
+                assigned_relationships(assignments, &did_uri, &vm.id).unwrap_or_default();
+            builder.add_verification_method(vm, &relationships)?;
+        }
+        if options.key_agreement.unwrap_or(false) {
+            // keys other than the identity key are identified by their thumbprint once encoded
+            let public_jwk = generate_key_agreement_key(&key_manager)?;
+            let key_agreement_method = VerificationMethod {
+                id: format!("{}#{}", did_uri, public_jwk.compute_thumbprint()?),
+                r#type: JSON_WEB_KEY.to_string(),
+                controller: did_uri.clone(),
+                public_key_jwk: public_jwk,
+            };
+            builder.add_verification_method(
+                key_agreement_method,
+                &[VerificationRelationship::KeyAgreement],
+            )?;
+        }
+        for mut service in options.service.unwrap_or_default() {
+            // a URI endpoint is published in the comma-separated format, which resolves as a set
+            if let ServiceEndpoint::Uri(uri) = service.service_endpoint {
+                service.service_endpoint = vec![uri].into();
+            }
+            builder.add_service(service)?;
+        }
+        builder
+            .set_controller(options.controller.unwrap_or_default())
+            .set_also_known_as(options.also_known_as.unwrap_or_default());
+        let document = builder.build();
 
         let bearer_did = BearerDid {
             did,
@@ -161,12 +422,72 @@ impl DidDht {
     /// DidDht::publish(bearer_did, None)?;
     /// ```
     pub async fn publish(bearer_did: BearerDid, gateway_url: Option<String>) -> Result<()> {
-        let packet = bearer_did.document.to_packet().map_err(|e| {
+        DidDht::publish_with_options(
+            bearer_did,
+            DidDhtPublishOptions {
+                gateway_url,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Publishes a "did:dht" DID document to the DHT, offloading services to linked resources if
+    /// the document is otherwise too large to publish.
+    ///
+    /// Services are offloaded largest first, until the packet fits. Each offloaded service is
+    /// published as a stub whose `serviceEndpoint` is the linked resource's URL and whose
+    /// `linkedResourceDigest` is the SHA-256 digest of its content, which `DidDht::resolve`
+    /// verifies when restoring the service. Resolvers fetch linked resources over https only, so
+    /// `linked_resource_base_url` should be an https URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The `BearerDid` object representing the DID to be published.
+    /// * `options` - The gateway URL, and the base URL under which linked resources are hosted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<LinkedResource>>` - The linked resources the caller must host, or an error
+    ///   if the document can't be published.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let linked_resources = DidDht::publish_with_options(
+    ///     bearer_did,
+    ///     DidDhtPublishOptions {
+    ///         linked_resource_base_url: Some("https://example.com/dids".to_string()),
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .await?;
+    /// for resource in linked_resources {
+    ///     host(&resource.url, &resource.content);
+    /// }
+    /// ```
+    pub async fn publish_with_options(
+        bearer_did: BearerDid,
+        options: DidDhtPublishOptions,
+    ) -> Result<Vec<LinkedResource>> {
+        let (document, linked_resources) = match &options.linked_resource_base_url {
+            Some(base_url) => DidDht::offload_services(&bearer_did.document, base_url)?,
+            None => (bearer_did.document.clone(), vec![]),
+        };
+
+        let estimate = DidDht::estimate_packet_size(&document)?;
+        if estimate.exceeds_limit() {
+            return Err(Web5Error::Parameter(estimate.to_string()));
+        }
+
+        let packet = document.to_packet().map_err(|e| {
             Web5Error::Encoding(format!("failed to convert document to packet {}", e))
         })?;
 
         let packet_bytes = packet
-            .build_bytes_vec()
+            .build_bytes_vec_compressed()
             .map_err(|_| Web5Error::Encoding("failed to serialize packet as bytes".to_string()))?;
 
         let public_jwk = bearer_did.document.verification_method[0]
@@ -184,7 +505,8 @@ impl DidDht {
 
         let url = format!(
             "{}/{}",
-            gateway_url
+            options
+                .gateway_url
                 .unwrap_or_else(|| DEFAULT_RELAY.to_string())
                 .trim_end_matches('/'),
             bearer_did.did.id.trim_start_matches('/')
@@ -206,6 +528,7 @@ impl DidDht {
                     .collect(),
                 ),
                 body: Some(body),
+                ..Default::default()
             }),
         )
         .await?;
@@ -215,13 +538,150 @@ impl DidDht {
             ));
         }
 
-        Ok(())
+        Ok(linked_resources)
+    }
+
+    /// Applies a signed update to a "did:dht" DID document, and publishes the updated document as
+    /// `DidDht::publish_with_options` does.
+    ///
+    /// The update must be signed by the DID itself, or by one of the controllers listed in its
+    /// current document, as verified by `verify_document_update`. The packet is still signed by
+    /// the identity key, so it must be held by the DID's key manager, and the update can't change
+    /// the identity key.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID to update, holding its current document.
+    /// * `update` - The compact JWS made with `sign_document_update`.
+    /// * `options` - The gateway URL, and the base URL under which linked resources are hosted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(BearerDid, Vec<LinkedResource>)>` - The DID with its updated document and the
+    ///   linked resources the caller must host, or an error if the update isn't authorized or
+    ///   can't be published.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let update = sign_document_update(&bearer_did.document, &document, &controller_did)?;
+    /// let (bearer_did, linked_resources) =
+    ///     DidDht::apply_update(bearer_did, &update, DidDhtPublishOptions::default()).await?;
+    /// ```
+    pub async fn apply_update(
+        bearer_did: BearerDid,
+        update: &str,
+        options: DidDhtPublishOptions,
+    ) -> Result<(BearerDid, Vec<LinkedResource>)> {
+        let document = verify_document_update(update, &bearer_did.document).await?;
+
+        let identity_key_id = format!("{}#0", bearer_did.did.uri);
+        let identity_key = |document: &Document| {
+            document
+                .verification_method
+                .iter()
+                .find(|vm| vm.id == identity_key_id)
+                .map(|vm| vm.public_key_jwk.clone())
+        };
+        if identity_key(&document) != identity_key(&bearer_did.document) {
+            return Err(Web5Error::Parameter(
+                "an update cannot change the identity key".to_string(),
+            ));
+        }
+
+        let bearer_did = BearerDid {
+            document,
+            ..bearer_did
+        };
+        let linked_resources = DidDht::publish_with_options(bearer_did.clone(), options).await?;
+
+        Ok((bearer_did, linked_resources))
+    }
+
+    /// Estimates the size of the DNS packet a "did:dht" DID document is published as.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The DID document to measure.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<PacketSizeEstimate>` - The size of the compressed packet and of each of its
+    ///   records, or an error if the document can't be encoded as a packet.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let estimate = DidDht::estimate_packet_size(&bearer_did.document)?;
+    /// if estimate.exceeds_limit() {
+    ///     println!("{}", estimate);
+    /// }
+    /// ```
+    pub fn estimate_packet_size(document: &Document) -> Result<PacketSizeEstimate> {
+        document
+            .packet_size()
+            .map_err(|e| Web5Error::Encoding(format!("failed to convert document to packet {}", e)))
+    }
+
+    /// Replaces the largest services of a "did:dht" DID document with stubs pointing at linked
+    /// resources, until its packet fits within the size limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The DID document to shrink.
+    /// * `base_url` - The base URL under which the linked resources are hosted, specific to the DID.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Document, Vec<LinkedResource>)>` - The document to publish, and the linked
+    ///   resources to host. The document may still exceed the limit if offloading every service
+    ///   isn't enough.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (document, linked_resources) =
+    ///     DidDht::offload_services(&bearer_did.document, "https://example.com/dids")?;
+    /// ```
+    pub fn offload_services(
+        document: &Document,
+        base_url: &str,
+    ) -> Result<(Document, Vec<LinkedResource>)> {
+        let mut document = document.clone();
+        let mut linked_resources = vec![];
+
+        loop {
+            let estimate = DidDht::estimate_packet_size(&document)?;
+            if !estimate.exceeds_limit() {
+                break;
+            }
+
+            let services = document.service.as_deref().unwrap_or_default();
+            let largest = estimate.records.iter().find_map(|record| {
+                services.iter().position(|service| {
+                    service.id == record.property && !is_linked_service(service)
+                })
+            });
+            let Some(idx) = largest else {
+                break;
+            };
+
+            let (stub, linked_resource) = link_service(&services[idx], base_url)?;
+            if let Some(services) = document.service.as_mut() {
+                services[idx] = stub;
+            }
+            linked_resources.push(linked_resource);
+        }
+
+        Ok((document, linked_resources))
     }
 
     /// Resolves a "did:dht" DID into a `ResolutionResult`.
     ///
     /// This method retrieves the DID document associated with the "did:dht" DID from the DHT.
     /// It sends a GET request to the DHT gateway, verifies the document, and constructs the result.
+    /// Services offloaded to linked resources are fetched only if enabled in the options
+    /// configured via `resolution_options::set_default_resolution_options`.
     ///
     /// # Arguments
     ///
@@ -243,53 +703,100 @@ impl DidDht {
     ///
     /// Returns a `ResolutionMetadataError` if the DID cannot be resolved or verified.
     pub async fn resolve(uri: &str, gateway_url: Option<String>) -> ResolutionResult {
-        let result: std::result::Result<ResolutionResult, ResolutionMetadataError> = async {
-            // check did method and decode id
-            let did = Did::parse(uri).map_err(|_| ResolutionMetadataError::InvalidDid)?;
-            if did.method != "dht" {
-                return Ok(ResolutionResult::from(
-                    ResolutionMetadataError::MethodNotSupported,
-                ));
-            }
-            let identity_key = zbase32::decode_full_bytes_str(&did.id)
-                .map_err(|_| ResolutionMetadataError::InvalidPublicKey)?;
-            let identity_key = ed25519::public_jwk_from_bytes(&identity_key)
-                .map_err(|_| ResolutionMetadataError::InvalidPublicKey)?;
-
-            // construct http endpoint from gateway url and last part of did_uri
-            let url = format!(
-                "{}/{}",
-                gateway_url
-                    .unwrap_or_else(|| DEFAULT_RELAY.to_string())
-                    .trim_end_matches('/'),
-                did.id.trim_start_matches('/')
-            );
+        Self::resolve_with_options(uri, gateway_url, &get_default_resolution_options()).await
+    }
+
+    /// Resolves a "did:dht" DID with the given options, rather than those configured via
+    /// `resolution_options::set_default_resolution_options`.
+    ///
+    /// Services offloaded to linked resources are restored only if `fetch_linked_resources` is
+    /// enabled, and only from https urls. Otherwise, or if a linked resource can't be fetched, the
+    /// document is returned with the service's stub, and the linked resource's url is listed in
+    /// the resolution metadata's `unresolved_linked_resources`.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The DID URI to resolve.
+    /// * `gateway_url` - The URL of the gateway to use for resolution. If not provided, the default gateway is used.
+    /// * `options` - The options to resolve with.
+    ///
+    /// # Returns
+    ///
+    /// * `ResolutionResult` - The result of the resolution, containing the DID document and related metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let options = ResolutionOptions {
+    ///     fetch_linked_resources: true,
+    ///     ..Default::default()
+    /// };
+    /// let result = DidDht::resolve_with_options("did:dht:example", None, &options).await;
+    /// ```
+    pub async fn resolve_with_options(
+        uri: &str,
+        gateway_url: Option<String>,
+        options: &ResolutionOptions,
+    ) -> ResolutionResult {
+        let signed_packet = match Self::fetch_signed_packet(uri, gateway_url).await {
+            Ok(signed_packet) => signed_packet,
+            Err(e) => return ResolutionResult::from(e),
+        };
 
-            let response = http_std::fetch(&url, None)
-                .await // todo here
-                .map_err(|_| ResolutionMetadataError::InternalError)?;
+        Self::resolve_signed_packet(uri, &signed_packet, None, options.fetch_linked_resources).await
+    }
 
-            if response.status_code == 404 {
-                return Err(ResolutionMetadataError::NotFound);
-            } else if response.status_code != 200 {
-                return Err(ResolutionMetadataError::InternalError);
-            }
+    /// Fetches the BEP44 signed packet of a "did:dht" DID from the gateway, without verifying it.
+    pub(crate) async fn fetch_signed_packet(
+        uri: &str,
+        gateway_url: Option<String>,
+    ) -> std::result::Result<Vec<u8>, ResolutionMetadataError> {
+        let did = Did::parse(uri).map_err(|_| ResolutionMetadataError::InvalidDid)?;
+        identity_key(&did)?;
 
-            // bep44 decode and verify response body bytes
-            let bep44_message = Bep44Message::decode(&response.body)
-                .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
-            bep44_message
-                .verify(&Ed25519Verifier::new(identity_key))
-                .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+        // construct http endpoint from gateway url and last part of did_uri
+        let url = format!(
+            "{}/{}",
+            gateway_url
+                .unwrap_or_else(|| DEFAULT_RELAY.to_string())
+                .trim_end_matches('/'),
+            did.id.trim_start_matches('/')
+        );
 
-            // convert bep44 decoded value from DNS packet to did doc
-            let packet = Packet::parse(&bep44_message.v)
-                .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
-            let document: Document = packet
-                .try_into()
-                .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+        let response = http_std::fetch(&url, None)
+            .await
+            .map_err(|_| ResolutionMetadataError::InternalError)?;
+
+        if response.status_code == 404 {
+            return Err(ResolutionMetadataError::NotFound);
+        } else if response.status_code != 200 {
+            return Err(ResolutionMetadataError::InternalError);
+        }
+
+        Ok(response.body)
+    }
+
+    /// Resolves a "did:dht" DID from its BEP44 signed packet, which is verified against the
+    /// identity key the DID is derived from, so may come from any source.
+    ///
+    /// Services offloaded to linked resources are restored from `linked_resources`, keyed by url,
+    /// if given, or else fetched if `fetch_linked_resources`.
+    pub(crate) async fn resolve_signed_packet(
+        uri: &str,
+        signed_packet: &[u8],
+        linked_resources: Option<&BTreeMap<String, String>>,
+        fetch_linked_resources: bool,
+    ) -> ResolutionResult {
+        let result: std::result::Result<ResolutionResult, ResolutionMetadataError> = async {
+            let document = Self::decode_signed_packet(uri, signed_packet)?;
+            let (document, unresolved) =
+                resolve_linked_services(document, linked_resources, fetch_linked_resources).await?;
 
             Ok(ResolutionResult {
+                resolution_metadata: ResolutionMetadata {
+                    unresolved_linked_resources: Some(unresolved).filter(|urls| !urls.is_empty()),
+                    ..Default::default()
+                },
                 document: Some(document),
                 ..Default::default()
             })
@@ -297,16 +804,152 @@ impl DidDht {
         .await;
 
         match result {
-            Ok(resolution_result) => resolution_result,
+            Ok(resolution_result) => resolution_result.validated(),
             Err(e) => ResolutionResult::from(e),
         }
     }
+
+    /// Returns the urls of the linked resources the document's services are offloaded to.
+    pub(crate) fn linked_resource_urls(document: &Document) -> Vec<String> {
+        document
+            .service
+            .iter()
+            .flatten()
+            .filter(|service| is_linked_service(service))
+            .filter_map(linked_resource_url)
+            .collect()
+    }
+
+    /// Verifies a BEP44 signed packet and decodes its document, leaving linked services unresolved.
+    pub(crate) fn decode_signed_packet(
+        uri: &str,
+        signed_packet: &[u8],
+    ) -> std::result::Result<Document, ResolutionMetadataError> {
+        let did = Did::parse(uri).map_err(|_| ResolutionMetadataError::InvalidDid)?;
+        let identity_key = identity_key(&did)?;
+
+        // bep44 decode and verify response body bytes
+        let bep44_message = Bep44Message::decode(signed_packet)
+            .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+        bep44_message
+            .verify(&Ed25519Verifier::new(identity_key))
+            .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+
+        // convert bep44 decoded value from DNS packet to did doc
+        let packet = Packet::parse(&bep44_message.v)
+            .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+        let document: Document = packet
+            .try_into()
+            .map_err(|_| ResolutionMetadataError::InvalidDidDocument)?;
+        if document.id != did.uri {
+            return Err(ResolutionMetadataError::InvalidDidDocument);
+        }
+
+        Ok(document)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn large_service(did_uri: &str, fragment: &str) -> Service {
+        Service {
+            id: format!("{}#{}", did_uri, fragment),
+            r#type: "DecentralizedWebNode".to_string(),
+            service_endpoint: vec![format!("https://example.com/{}", "a".repeat(200))].into(),
+            additional_properties: None,
+        }
+    }
+
+    async fn create_with_services(fragments: &[&str]) -> BearerDid {
+        let mut bearer_did = DidDht::create(Some(DidDhtCreateOptions {
+            publish: Some(false),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+        bearer_did.document.service = Some(
+            fragments
+                .iter()
+                .map(|fragment| large_service(&bearer_did.did.uri, fragment))
+                .collect(),
+        );
+        bearer_did
+    }
+
+    mod estimate_packet_size {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_matches_compressed_packet() {
+            let bearer_did = create_with_services(&["dwn"]).await;
+            let packet = bearer_did.document.to_packet().unwrap();
+            let compressed = packet.build_bytes_vec_compressed().unwrap();
+
+            let estimate = DidDht::estimate_packet_size(&bearer_did.document).unwrap();
+            assert_eq!(estimate.size, compressed.len());
+            assert!(estimate.size < packet.build_bytes_vec().unwrap().len());
+            assert!(!estimate.exceeds_limit());
+
+            // compressed packets decode to the same document
+            let decoded = Document::from_packet(&Packet::parse(&compressed).unwrap()).unwrap();
+            assert_eq!(decoded, bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_reports_records_largest_first() {
+            let bearer_did = create_with_services(&["dwn", "dwn2", "dwn3", "dwn4", "dwn5"]).await;
+
+            let estimate = DidDht::estimate_packet_size(&bearer_did.document).unwrap();
+            assert!(estimate.exceeds_limit());
+            assert_eq!(estimate.records.len(), 7);
+            assert!(estimate.records[0].name.starts_with("_s"));
+            assert!(estimate.records[0]
+                .property
+                .starts_with(&bearer_did.did.uri));
+            assert!(estimate.records[0].size > 200);
+            assert!(estimate
+                .records
+                .windows(2)
+                .all(|pair| pair[0].size >= pair[1].size));
+        }
+    }
+
+    mod offload_services {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_offloads_until_packet_fits() {
+            let bearer_did = create_with_services(&["dwn", "dwn2", "dwn3", "dwn4"]).await;
+
+            let (document, linked_resources) =
+                DidDht::offload_services(&bearer_did.document, "https://example.com/dids/")
+                    .unwrap();
+            assert!(!DidDht::estimate_packet_size(&document)
+                .unwrap()
+                .exceeds_limit());
+            assert!(!linked_resources.is_empty() && linked_resources.len() < 4);
+
+            let services = document.service.unwrap();
+            let linked = services.iter().filter(|s| is_linked_service(s)).count();
+            assert_eq!(linked, linked_resources.len());
+            assert!(linked_resources[0]
+                .url
+                .starts_with("https://example.com/dids/"));
+        }
+
+        #[tokio::test]
+        async fn test_leaves_small_document_unchanged() {
+            let bearer_did = create_with_services(&["dwn"]).await;
+
+            let (document, linked_resources) =
+                DidDht::offload_services(&bearer_did.document, "https://example.com").unwrap();
+            assert_eq!(document, bearer_did.document);
+            assert!(linked_resources.is_empty());
+        }
+    }
+
     mod create {
         use super::*;
 
@@ -385,7 +1028,8 @@ mod tests {
             let service = Service {
                 id: "did:web:example.com#service-0".to_string(),
                 r#type: "SomeService".to_string(),
-                service_endpoint: vec!["https://example.com/service".to_string()],
+                service_endpoint: vec!["https://example.com/service".to_string()].into(),
+                additional_properties: None,
             };
 
             let result = DidDht::create(Some(DidDhtCreateOptions {
@@ -434,11 +1078,123 @@ mod tests {
             let did_web = result.unwrap();
             assert_eq!(did_web.document.controller.unwrap(), controllers);
         }
+
+        #[tokio::test]
+        async fn test_should_assign_verification_relationships() {
+            let mut public_jwk = Ed25519Generator::generate();
+            public_jwk.d = None;
+            let additional_verification_method = VerificationMethod {
+                id: "#key-1".to_string(),
+                r#type: "JsonWebKey".to_string(),
+                controller: "".to_string(),
+                public_key_jwk: public_jwk,
+            };
+
+            let bearer_did = DidDht::create(Some(DidDhtCreateOptions {
+                publish: Some(false),
+                verification_method: Some(vec![additional_verification_method]),
+                verification_relationships: Some(HashMap::from([(
+                    "#key-1".to_string(),
+                    vec![
+                        VerificationRelationship::Authentication,
+                        VerificationRelationship::AssertionMethod,
+                    ],
+                )])),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+            let document = &bearer_did.document;
+            let identity_key_id = format!("{}#0", bearer_did.did.uri);
+            let key_id = format!("{}#key-1", bearer_did.did.uri);
+            assert_eq!(
+                document.authentication,
+                Some(vec![identity_key_id.clone(), key_id.clone()])
+            );
+            assert_eq!(document.capability_invocation, Some(vec![identity_key_id]));
+            assert_eq!(document.validate(), vec![]);
+
+            // the relationships survive the did:dht packet encoding
+            let packet = document.to_packet().unwrap();
+            let decoded = Document::from_packet(&packet).unwrap();
+            assert_eq!(decoded.assertion_method.unwrap().len(), 2);
+            assert_eq!(decoded.capability_invocation.unwrap().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_unmatched_assignment() {
+            let result = DidDht::create(Some(DidDhtCreateOptions {
+                publish: Some(false),
+                verification_relationships: Some(HashMap::from([(
+                    "#key-1".to_string(),
+                    vec![VerificationRelationship::Authentication],
+                )])),
+                ..Default::default()
+            }))
+            .await;
+
+            match result {
+                Err(Web5Error::Parameter(message)) => assert!(message.contains("#key-1")),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_can_generate_key_agreement_key() {
+            let key_manager = Arc::new(InMemoryKeyManager::new());
+            let bearer_did = DidDht::create(Some(DidDhtCreateOptions {
+                publish: Some(false),
+                key_manager: Some(key_manager.clone()),
+                key_agreement: Some(true),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+            let document = &bearer_did.document;
+            let public_jwk = document.verification_method[1].public_key_jwk.clone();
+            let key_id = format!(
+                "{}#{}",
+                bearer_did.did.uri,
+                public_jwk.compute_thumbprint().unwrap()
+            );
+            assert_eq!(document.key_agreement, Some(vec![key_id.clone()]));
+            assert_eq!(public_jwk.crv, "X25519");
+            assert!(key_manager.get_key_agreement(public_jwk).is_ok());
+            assert!(bearer_did.get_key_agreement(&key_id).is_ok());
+
+            // the X25519 key survives the did:dht packet encoding
+            let packet = document.to_packet().unwrap();
+            let decoded = Document::from_packet(&packet).unwrap();
+            assert_eq!(&decoded, document);
+        }
     }
 
     mod publish {
         use super::*;
 
+        #[tokio::test]
+        async fn test_oversized_document_names_records() {
+            let mut mock_server = mockito::Server::new_async().await;
+            let mock = mock_server
+                .mock("PUT", mockito::Matcher::Any)
+                .expect(0)
+                .create();
+
+            let bearer_did = create_with_services(&["dwn", "dwn2", "dwn3", "dwn4", "dwn5"]).await;
+            let result = DidDht::publish(bearer_did.clone(), Some(mock_server.url())).await;
+
+            match result {
+                Err(Web5Error::Parameter(message)) => {
+                    assert!(message.contains("over the 1000 byte limit"));
+                    assert!(message.contains(&format!("_s0._did ({}#dwn)", bearer_did.did.uri)));
+                }
+                _ => panic!("expected a parameter error, got {:?}", result),
+            }
+            mock.assert();
+        }
+
         #[tokio::test]
         async fn test_can_specify_gateway_url() {
             let mut mock_server = mockito::Server::new_async().await;
@@ -501,6 +1257,74 @@ mod tests {
         }
     }
 
+    mod apply_update {
+        use super::*;
+        use crate::dids::{controller::sign_document_update, methods::did_jwk::DidJwk};
+
+        #[tokio::test]
+        async fn test_publishes_controller_signed_update() {
+            let mut mock_server = mockito::Server::new_async().await;
+            let mock = mock_server
+                .mock("PUT", mockito::Matcher::Any)
+                .expect(1)
+                .with_status(200)
+                .create();
+
+            let controller = DidJwk::create(None).unwrap();
+            let bearer_did = DidDht::create(Some(DidDhtCreateOptions {
+                publish: Some(false),
+                controller: Some(vec![controller.did.uri.clone()]),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+            let mut document = bearer_did.document.clone();
+            document.also_known_as = Some(vec!["did:example:123".to_string()]);
+            let update =
+                sign_document_update(&bearer_did.document, &document, &controller).unwrap();
+
+            let (updated, linked_resources) = DidDht::apply_update(
+                bearer_did,
+                &update,
+                DidDhtPublishOptions {
+                    gateway_url: Some(mock_server.url()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            assert_eq!(updated.document, document);
+            assert!(linked_resources.is_empty());
+            mock.assert();
+        }
+
+        #[tokio::test]
+        async fn test_rejects_identity_key_change() {
+            let bearer_did = DidDht::create(Some(DidDhtCreateOptions {
+                publish: Some(false),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+            let mut document = bearer_did.document.clone();
+            document.verification_method[0].public_key_jwk =
+                ed25519::to_public_jwk(&Ed25519Generator::generate());
+            let update =
+                sign_document_update(&bearer_did.document, &document, &bearer_did).unwrap();
+
+            let result =
+                DidDht::apply_update(bearer_did, &update, DidDhtPublishOptions::default()).await;
+            match result {
+                Err(Web5Error::Parameter(msg)) => {
+                    assert_eq!(msg, "an update cannot change the identity key")
+                }
+                _ => panic!("expected a parameter error"),
+            }
+        }
+    }
+
     mod resolve {
         use super::*;
         use std::sync::Mutex;
@@ -609,14 +1433,14 @@ mod tests {
 
             let bearer_did = create_result.unwrap();
 
-            let stored_body = published_body.lock().unwrap();
+            let stored_body = published_body.lock().unwrap().clone();
 
             let mock_resolve = mock_server
                 .mock("GET", format!("/{}", bearer_did.did.id).as_str())
                 .expect(1)
                 .with_status(200)
                 .with_header("content-type", "application/octet-stream")
-                .with_body(stored_body.clone()) // Use the captured body as the response
+                .with_body(stored_body) // Use the captured body as the response
                 .create();
 
             let resolution_result =
@@ -630,5 +1454,193 @@ mod tests {
             mock_publish.assert();
             mock_resolve.assert();
         }
+
+        async fn publish_with_linked_resources(
+            mock_server: &mut mockito::ServerGuard,
+            linked_resource_base_url: &str,
+        ) -> (BearerDid, Vec<LinkedResource>) {
+            let published_body = Arc::new(Mutex::new(Vec::new()));
+            let published_body_clone = Arc::clone(&published_body);
+            let mock_publish = mock_server
+                .mock("PUT", mockito::Matcher::Any)
+                .expect(1)
+                .with_status(200)
+                .with_body_from_request(move |request| {
+                    *published_body_clone.lock().unwrap() = request.body().unwrap().to_vec();
+                    vec![]
+                })
+                .create();
+
+            let bearer_did = create_with_services(&["dwn", "dwn2", "dwn3", "dwn4", "dwn5"]).await;
+            let linked_resources = DidDht::publish_with_options(
+                bearer_did.clone(),
+                DidDhtPublishOptions {
+                    gateway_url: Some(mock_server.url()),
+                    linked_resource_base_url: Some(linked_resource_base_url.to_string()),
+                },
+            )
+            .await
+            .unwrap();
+            assert!(!linked_resources.is_empty());
+            mock_publish.assert();
+
+            mock_server
+                .mock("GET", format!("/{}", bearer_did.did.id).as_str())
+                .with_status(200)
+                .with_body(published_body.lock().unwrap().clone())
+                .create();
+            (bearer_did, linked_resources)
+        }
+
+        fn assert_unresolved(
+            resolution_result: ResolutionResult,
+            linked_resources: &[LinkedResource],
+        ) {
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            let mut unresolved = resolution_result
+                .resolution_metadata
+                .unresolved_linked_resources
+                .unwrap();
+            unresolved.sort();
+            let mut urls = linked_resources
+                .iter()
+                .map(|resource| resource.url.clone())
+                .collect::<Vec<_>>();
+            urls.sort();
+            assert_eq!(unresolved, urls);
+
+            // the services are left as stubs
+            let document = resolution_result.document.unwrap();
+            assert_eq!(DidDht::linked_resource_urls(&document).len(), urls.len());
+        }
+
+        #[tokio::test]
+        async fn test_resolves_linked_services() {
+            let mut mock_server = mockito::Server::new_async().await;
+            let (bearer_did, linked_resources) =
+                publish_with_linked_resources(&mut mock_server, "https://example.com/linked").await;
+
+            let signed_packet =
+                DidDht::fetch_signed_packet(&bearer_did.did.uri, Some(mock_server.url()))
+                    .await
+                    .unwrap();
+            let contents = linked_resources
+                .iter()
+                .map(|resource| (resource.url.clone(), resource.content.clone()))
+                .collect::<BTreeMap<_, _>>();
+            let resolution_result = DidDht::resolve_signed_packet(
+                &bearer_did.did.uri,
+                &signed_packet,
+                Some(&contents),
+                false,
+            )
+            .await;
+
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            assert_eq!(
+                resolution_result
+                    .resolution_metadata
+                    .unresolved_linked_resources,
+                None
+            );
+            assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_does_not_fetch_linked_resources_by_default() {
+            let mut mock_server = mockito::Server::new_async().await;
+            let base_url = format!("{}/linked", mock_server.url());
+            let (bearer_did, linked_resources) =
+                publish_with_linked_resources(&mut mock_server, &base_url).await;
+            let mock_linked = mock_server
+                .mock("GET", mockito::Matcher::Regex("^/linked/".to_string()))
+                .expect(0)
+                .create();
+
+            let resolution_result = DidDht::resolve_with_options(
+                &bearer_did.did.uri,
+                Some(mock_server.url()),
+                &ResolutionOptions::default(),
+            )
+            .await;
+
+            assert_unresolved(resolution_result, &linked_resources);
+            mock_linked.assert();
+        }
+
+        #[tokio::test]
+        async fn test_fetches_linked_resources_over_https_only() {
+            let mut mock_server = mockito::Server::new_async().await;
+            let base_url = format!("{}/linked", mock_server.url());
+            let (bearer_did, linked_resources) =
+                publish_with_linked_resources(&mut mock_server, &base_url).await;
+            let mock_linked = mock_server
+                .mock("GET", mockito::Matcher::Regex("^/linked/".to_string()))
+                .expect(0)
+                .create();
+
+            let resolution_result = DidDht::resolve_with_options(
+                &bearer_did.did.uri,
+                Some(mock_server.url()),
+                &ResolutionOptions {
+                    fetch_linked_resources: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            assert_unresolved(resolution_result, &linked_resources);
+            mock_linked.assert();
+        }
+
+        #[tokio::test]
+        async fn test_returns_document_if_linked_resource_fetch_fails() {
+            let mut mock_server = mockito::Server::new_async().await;
+            // nothing listens on port 1, so fetching fails
+            let (bearer_did, linked_resources) =
+                publish_with_linked_resources(&mut mock_server, "https://127.0.0.1:1/linked").await;
+
+            let resolution_result = DidDht::resolve_with_options(
+                &bearer_did.did.uri,
+                Some(mock_server.url()),
+                &ResolutionOptions {
+                    fetch_linked_resources: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            assert_unresolved(resolution_result, &linked_resources);
+        }
+
+        #[tokio::test]
+        async fn test_rejects_tampered_linked_service() {
+            let mut mock_server = mockito::Server::new_async().await;
+            let (bearer_did, linked_resources) =
+                publish_with_linked_resources(&mut mock_server, "https://example.com/linked").await;
+
+            let signed_packet =
+                DidDht::fetch_signed_packet(&bearer_did.did.uri, Some(mock_server.url()))
+                    .await
+                    .unwrap();
+            let mut tampered = large_service(&bearer_did.did.uri, "dwn");
+            tampered.service_endpoint = ServiceEndpoint::Uri("https://attacker.com".to_string());
+            let contents = linked_resources
+                .iter()
+                .map(|resource| (resource.url.clone(), tampered.to_json_string().unwrap()))
+                .collect::<BTreeMap<_, _>>();
+            let resolution_result = DidDht::resolve_signed_packet(
+                &bearer_did.did.uri,
+                &signed_packet,
+                Some(&contents),
+                false,
+            )
+            .await;
+
+            assert_eq!(
+                resolution_result.resolution_metadata.error,
+                Some(ResolutionMetadataError::InvalidDidDocument)
+            );
+        }
     }
 }
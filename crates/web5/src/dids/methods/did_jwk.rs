@@ -1,8 +1,12 @@
 use crate::{
     crypto::{
-        dsa::{ed25519::Ed25519Generator, secp256k1::Secp256k1Generator, Dsa},
+        dsa::{
+            ed25519::Ed25519Generator, secp256k1::Secp256k1Generator,
+            secp256r1::Secp256r1Generator, Dsa,
+        },
         jwk::Jwk,
         key_managers::{in_memory_key_manager::InMemoryKeyManager, KeyManager},
+        x25519::X25519Generator,
     },
     dids::{
         bearer_did::BearerDid,
@@ -12,15 +16,21 @@ use crate::{
             resolution_metadata::ResolutionMetadataError, resolution_result::ResolutionResult,
         },
     },
-    errors::Result,
+    errors::{Result, Web5Error},
 };
 use base64::{engine::general_purpose, Engine as _};
 use std::sync::Arc;
 
+const X25519_CURVE: &str = "X25519";
+
 #[derive(Default)]
 pub struct DidJwkCreateOptions {
     pub key_manager: Option<Arc<dyn KeyManager>>,
     pub dsa: Option<Dsa>,
+    /// Generates an X25519 key in place of a signing key. Since a "did:jwk" DID holds a single
+    /// key, its key is then registered only under `keyAgreement`, and `dsa` must not be set.
+    /// Defaults to false.
+    pub key_agreement: Option<bool>,
 }
 
 pub struct DidJwk;
@@ -61,9 +71,19 @@ impl DidJwk {
             .key_manager
             .unwrap_or_else(|| Arc::new(InMemoryKeyManager::new()));
 
-        let private_jwk = match options.dsa.unwrap_or(Dsa::Ed25519) {
-            Dsa::Ed25519 => Ed25519Generator::generate(),
-            Dsa::Secp256k1 => Secp256k1Generator::generate(),
+        let key_agreement = options.key_agreement.unwrap_or(false);
+        let private_jwk = match (key_agreement, options.dsa) {
+            (true, Some(_)) => {
+                return Err(Web5Error::Parameter(
+                    "dsa cannot be set for a key agreement did:jwk".to_string(),
+                ))
+            }
+            (true, None) => X25519Generator::generate(),
+            (false, dsa) => match dsa.unwrap_or(Dsa::Ed25519) {
+                Dsa::Ed25519 => Ed25519Generator::generate(),
+                Dsa::Secp256k1 => Secp256k1Generator::generate(),
+                Dsa::Secp256r1 => Secp256r1Generator::generate(),
+            },
         };
         let mut public_jwk = key_manager.import_private_jwk(private_jwk)?;
         public_jwk.d = None;
@@ -75,23 +95,7 @@ impl DidJwk {
 
         let did = Did::parse(&did_uri)?;
 
-        let verification_method_id = format!("{}#0", did_uri);
-
-        let document = Document {
-            context: Some(vec!["https://www.w3.org/ns/did/v1".to_string()]),
-            id: did_uri.clone(),
-            verification_method: vec![VerificationMethod {
-                id: verification_method_id.clone(),
-                r#type: "JsonWebKey".to_string(),
-                controller: did_uri.clone(),
-                public_key_jwk: public_jwk.clone(),
-            }],
-            authentication: Some(vec![verification_method_id.clone()]),
-            assertion_method: Some(vec![verification_method_id.clone()]),
-            capability_invocation: Some(vec![verification_method_id.clone()]),
-            capability_delegation: Some(vec![verification_method_id.clone()]),
-            ..Default::default()
-        };
+        let document = document(&did_uri, public_jwk);
 
         Ok(BearerDid {
             did,
@@ -140,32 +144,45 @@ impl DidJwk {
             Err(_) => return ResolutionResult::from(ResolutionMetadataError::InvalidDid),
         };
 
-        let kid = format!("{}#0", did.uri);
-        let document = Document {
-            context: Some(vec!["https://www.w3.org/ns/did/v1".to_string()]),
-            id: did.uri.clone(),
-            verification_method: vec![VerificationMethod {
-                id: kid.clone(),
-                r#type: "JsonWebKey".to_string(),
-                controller: did.uri.clone(),
-                public_key_jwk: public_jwk,
-            }],
-            assertion_method: Some(vec![kid.clone()]),
-            authentication: Some(vec![kid.clone()]),
-            capability_invocation: Some(vec![kid.clone()]),
-            capability_delegation: Some(vec![kid.clone()]),
-
-            // TODO: https://github.com/TBD54566975/web5-rs/issues/257 - If the JWK contains a `use` property with the value "sig" then the `keyAgreement` property
-            // is not included in the DID Document. If the `use` value is "enc" then only the `keyAgreement`
-            // property is included in the DID Document.
-            // key_agreement: if public_jwk.use_.as_deref() != Some("sig") { Some(vec![kid.clone()]) } else { None },
-            ..Default::default()
-        };
+        let document = document(&did.uri, public_jwk);
 
         ResolutionResult {
             document: Some(document),
             ..Default::default()
         }
+        .validated()
+    }
+}
+
+/// Builds the document of a "did:jwk" DID. An X25519 key can only be used for key agreement, so
+/// it's registered under `keyAgreement` alone, while any other key is registered under all other
+/// verification relationships.
+fn document(did_uri: &str, public_jwk: Jwk) -> Document {
+    let kid = format!("{}#0", did_uri);
+    let key_agreement = public_jwk.crv == X25519_CURVE;
+
+    // TODO: https://github.com/TBD54566975/web5-rs/issues/257 - If the JWK contains a `use` property with the value "sig" then the `keyAgreement` property
+    // is not included in the DID Document. If the `use` value is "enc" then only the `keyAgreement`
+    // property is included in the DID Document.
+    let relationship = |included: bool| match included {
+        true => Some(vec![kid.clone()]),
+        false => None,
+    };
+    Document {
+        context: Some(vec!["https://www.w3.org/ns/did/v1".to_string()]),
+        id: did_uri.to_string(),
+        verification_method: vec![VerificationMethod {
+            id: kid.clone(),
+            r#type: "JsonWebKey".to_string(),
+            controller: did_uri.to_string(),
+            public_key_jwk: public_jwk,
+        }],
+        assertion_method: relationship(!key_agreement),
+        authentication: relationship(!key_agreement),
+        key_agreement: relationship(key_agreement),
+        capability_invocation: relationship(!key_agreement),
+        capability_delegation: relationship(!key_agreement),
+        ..Default::default()
     }
 }
 
@@ -225,6 +242,36 @@ mod tests {
             assert_eq!(public_jwk.kty, "OKP".to_string());
             assert_eq!(public_jwk.crv, "Ed25519".to_string());
         }
+
+        #[test]
+        fn test_can_generate_key_agreement_key() {
+            let key_manager = Arc::new(InMemoryKeyManager::new());
+            let bearer_did = DidJwk::create(Some(DidJwkCreateOptions {
+                key_manager: Some(key_manager.clone()),
+                key_agreement: Some(true),
+                ..Default::default()
+            }))
+            .unwrap();
+
+            let document = &bearer_did.document;
+            let key_id = format!("{}#0", bearer_did.did.uri);
+            assert_eq!(document.key_agreement, Some(vec![key_id]));
+            assert_eq!(document.authentication, None);
+            assert_eq!(document.assertion_method, None);
+            let public_jwk = document.verification_method[0].public_key_jwk.clone();
+            assert_eq!(public_jwk.crv, "X25519");
+            assert!(key_manager.get_key_agreement(public_jwk).is_ok());
+        }
+
+        #[test]
+        fn test_key_agreement_excludes_dsa() {
+            let result = DidJwk::create(Some(DidJwkCreateOptions {
+                dsa: Some(Dsa::Ed25519),
+                key_agreement: Some(true),
+                ..Default::default()
+            }));
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
     }
 
     mod resolve {
@@ -248,5 +295,18 @@ mod tests {
             let resolution_result = DidJwk::resolve(&bearer_did.did.uri);
             assert_eq!(resolution_result.document, Some(bearer_did.document));
         }
+
+        #[test]
+        fn test_create_then_resolve_key_agreement() {
+            let bearer_did = DidJwk::create(Some(DidJwkCreateOptions {
+                key_agreement: Some(true),
+                ..Default::default()
+            }))
+            .unwrap();
+
+            let resolution_result = DidJwk::resolve(&bearer_did.did.uri);
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            assert_eq!(resolution_result.document, Some(bearer_did.document));
+        }
     }
 }
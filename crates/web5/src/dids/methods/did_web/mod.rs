@@ -1,18 +1,30 @@
 mod resolver;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     crypto::{
-        dsa::{ed25519::Ed25519Generator, secp256k1::Secp256k1Generator, Dsa},
+        dsa::{
+            ed25519::Ed25519Generator, secp256k1::Secp256k1Generator,
+            secp256r1::Secp256r1Generator, Dsa,
+        },
         key_managers::{in_memory_key_manager::InMemoryKeyManager, KeyManager},
     },
     dids::{
         bearer_did::BearerDid,
+        controller::verify_document_update,
         data_model::{
-            document::Document, service::Service, verification_method::VerificationMethod,
+            document::VerificationRelationship,
+            document_builder::{
+                assigned_relationships, check_assignments, DocumentBuilder, DEFAULT_RELATIONSHIPS,
+            },
+            service::Service,
+            verification_method::VerificationMethod,
         },
         did::Did,
+        methods::generate_key_agreement_key,
         resolution::{
             resolution_metadata::ResolutionMetadataError, resolution_result::ResolutionResult,
         },
@@ -20,6 +32,8 @@ use crate::{
     errors::{Result, Web5Error},
 };
 use resolver::Resolver;
+#[cfg(not(target_arch = "wasm32"))]
+pub use server::DidWebServer;
 use url::Url;
 
 /// Provides functionality for creating and resolving "did:web" method Decentralized Identifiers (DIDs).
@@ -51,6 +65,17 @@ pub struct DidWebCreateOptions {
 
     /// Optional additional verification methods for the DID document.
     pub verification_method: Option<Vec<VerificationMethod>>,
+
+    /// Optional verification relationship assignments, keyed by verification method id (absolute,
+    /// or relative such as `#key-1`). Additional verification methods without an assignment aren't
+    /// registered under any relationship, and an assignment for the generated key (`#key-0`)
+    /// replaces its default relationships. An assignment matching no verification method fails.
+    pub verification_relationships: Option<HashMap<String, Vec<VerificationRelationship>>>,
+
+    /// Generates an X25519 key, imported into the key manager, and registers it under
+    /// `keyAgreement` as `#key-agreement`, so that the DID can receive encrypted messages.
+    /// Defaults to false.
+    pub key_agreement: Option<bool>,
 }
 
 impl DidWeb {
@@ -85,6 +110,7 @@ impl DidWeb {
         let private_jwk = match options.dsa.unwrap_or(Dsa::Ed25519) {
             Dsa::Ed25519 => Ed25519Generator::generate(),
             Dsa::Secp256k1 => Secp256k1Generator::generate(),
+            Dsa::Secp256r1 => Secp256r1Generator::generate(),
         };
         let mut public_jwk = key_manager.import_private_jwk(private_jwk)?;
         public_jwk.d = None;
@@ -143,25 +169,48 @@ impl DidWeb {
             public_key_jwk: public_jwk,
         };
 
-        let document = Document {
-            id: did_uri.clone(),
-            context: Some(vec!["https://www.w3.org/ns/did/v1".to_string()]),
-            verification_method: {
-                let mut methods = vec![verification_method.clone()];
-                if let Some(mut additional_methods) = options.verification_method {
-                    methods.append(&mut additional_methods);
-                }
-                methods
-            },
-            service: options.service,
-            also_known_as: options.also_known_as,
-            controller: options.controller,
-            capability_delegation: Some(vec![verification_method.id.clone()]),
-            capability_invocation: Some(vec![verification_method.id.clone()]),
-            authentication: Some(vec![verification_method.id.clone()]),
-            assertion_method: Some(vec![verification_method.id.clone()]),
-            ..Default::default()
-        };
+        let additional_verification_methods = options.verification_method.unwrap_or_default();
+        let assignments = options.verification_relationships.as_ref();
+        check_assignments(
+            assignments,
+            &did_uri,
+            &std::iter::once(&verification_method)
+                .chain(&additional_verification_methods)
+                .map(|vm| vm.id.as_str())
+                .collect::<Vec<_>>(),
+        )?;
+        let mut builder = DocumentBuilder::new(&did_uri);
+        builder
+            .set_context(vec!["https://www.w3.org/ns/did/v1".to_string()])
+            .add_verification_method(
+                verification_method.clone(),
+                &assigned_relationships(assignments, &did_uri, &verification_method.id)
+                    .unwrap_or_else(|| DEFAULT_RELATIONSHIPS.to_vec()),
+            )?;
+        for vm in additional_verification_methods {
+            let relationships =
+                assigned_relationships(assignments, &did_uri, &vm.id).unwrap_or_default();
+            builder.add_verification_method(vm, &relationships)?;
+        }
+        if options.key_agreement.unwrap_or(false) {
+            let key_agreement_method = VerificationMethod {
+                id: format!("{}#key-agreement", did_uri),
+                r#type: "JsonWebKey".to_string(),
+                controller: did_uri.clone(),
+                public_key_jwk: generate_key_agreement_key(&key_manager)?,
+            };
+            builder.add_verification_method(
+                key_agreement_method,
+                &[VerificationRelationship::KeyAgreement],
+            )?;
+        }
+        for service in options.service.unwrap_or_default() {
+            builder.add_service(service)?;
+        }
+        builder
+            .set_controller(options.controller.unwrap_or_default())
+            .set_also_known_as(options.also_known_as.unwrap_or_default());
+        let document = builder.build();
 
         Ok(BearerDid {
             did: Did::parse(&did_uri)?,
@@ -170,6 +219,70 @@ impl DidWeb {
         })
     }
 
+    /// Returns the path at which the DID document of a "did:web" DID must be hosted.
+    ///
+    /// A DID with only a domain is hosted at `/.well-known/did.json`, while a DID with a path is
+    /// hosted at that path followed by `/did.json`, e.g. `did:web:example.com:users:alice` is
+    /// hosted at `/users/alice/did.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `did_uri` - The "did:web" DID URI.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The hosting path, or an error if the DID isn't a "did:web" DID.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let path = DidWeb::hosting_path("did:web:example.com:users:alice")?;
+    /// assert_eq!(path, "/users/alice/did.json");
+    /// ```
+    pub fn hosting_path(did_uri: &str) -> Result<String> {
+        let did = Did::parse(did_uri)?;
+        if did.method != "web" {
+            return Err(Web5Error::Parameter(format!(
+                "{} is not a did:web DID",
+                did_uri
+            )));
+        }
+
+        Ok(resolver::hosting_path(&did))
+    }
+
+    /// Applies a signed update to a "did:web" DID document.
+    ///
+    /// The update must be signed by the DID itself, or by one of the controllers listed in its
+    /// current document, as verified by `verify_document_update`. Since "did:web" documents are
+    /// hosted by the domain, the updated document must then be served in place of the current one.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID to update, holding its current document.
+    /// * `update` - The compact JWS made with `sign_document_update`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<BearerDid>` - The DID with its updated document, or an error if the update isn't
+    ///   authorized.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let update = sign_document_update(&bearer_did.document, &document, &controller_did)?;
+    /// let bearer_did = DidWeb::apply_update(bearer_did, &update).await?;
+    /// host(&bearer_did.document.to_json_string()?);
+    /// ```
+    pub async fn apply_update(bearer_did: BearerDid, update: &str) -> Result<BearerDid> {
+        let document = verify_document_update(update, &bearer_did.document).await?;
+
+        Ok(BearerDid {
+            document,
+            ..bearer_did
+        })
+    }
+
     /// Resolves a "did:web" DID into a `ResolutionResult`.
     ///
     /// This method resolves a DID URI by fetching the DID document from the `.well-known` directory of
@@ -205,7 +318,7 @@ impl DidWeb {
         };
 
         match resolution_result {
-            Ok(resolution_result) => resolution_result,
+            Ok(resolution_result) => resolution_result.validated(),
             Err(e) => ResolutionResult::from(e),
         }
     }
@@ -396,7 +509,8 @@ mod tests {
             let service = Service {
                 id: "did:web:example.com#service-0".to_string(),
                 r#type: "SomeService".to_string(),
-                service_endpoint: vec!["https://example.com/service".to_string()],
+                service_endpoint: "https://example.com/service".into(),
+                additional_properties: None,
             };
 
             let result = DidWeb::create(
@@ -448,6 +562,133 @@ mod tests {
             let did_web = result.unwrap();
             assert_eq!(did_web.document.controller.unwrap(), controllers);
         }
+
+        #[test]
+        fn test_should_assign_verification_relationships() {
+            let mut public_jwk = Ed25519Generator::generate();
+            public_jwk.d = None;
+            let additional_verification_method = VerificationMethod {
+                id: "#key-1".to_string(),
+                r#type: "JsonWebKey".to_string(),
+                controller: "".to_string(),
+                public_key_jwk: public_jwk,
+            };
+
+            let did_web = DidWeb::create(
+                "https://example.com",
+                Some(DidWebCreateOptions {
+                    verification_method: Some(vec![additional_verification_method]),
+                    verification_relationships: Some(HashMap::from([
+                        (
+                            "#key-0".to_string(),
+                            vec![VerificationRelationship::AssertionMethod],
+                        ),
+                        (
+                            "#key-1".to_string(),
+                            vec![VerificationRelationship::Authentication],
+                        ),
+                    ])),
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+
+            let document = did_web.document;
+            assert_eq!(
+                document.verification_method[1].id,
+                "did:web:example.com#key-1"
+            );
+            assert_eq!(
+                document.verification_method[1].controller,
+                "did:web:example.com"
+            );
+            assert_eq!(
+                document.assertion_method,
+                Some(vec!["did:web:example.com#key-0".to_string()])
+            );
+            assert_eq!(
+                document.authentication,
+                Some(vec!["did:web:example.com#key-1".to_string()])
+            );
+            assert_eq!(document.capability_invocation, None);
+            assert_eq!(document.validate(), vec![]);
+        }
+
+        #[test]
+        fn test_unmatched_assignment() {
+            let result = DidWeb::create(
+                "https://example.com",
+                Some(DidWebCreateOptions {
+                    verification_relationships: Some(HashMap::from([(
+                        "#key-1".to_string(),
+                        vec![VerificationRelationship::Authentication],
+                    )])),
+                    ..Default::default()
+                }),
+            );
+
+            match result {
+                Err(Web5Error::Parameter(message)) => assert!(message.contains("#key-1")),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_can_generate_key_agreement_key() {
+            let key_manager = Arc::new(InMemoryKeyManager::new());
+            let bearer_did = DidWeb::create(
+                "localhost",
+                Some(DidWebCreateOptions {
+                    key_manager: Some(key_manager.clone()),
+                    key_agreement: Some(true),
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+
+            let document = &bearer_did.document;
+            let key_id = "did:web:localhost#key-agreement".to_string();
+            assert_eq!(document.key_agreement, Some(vec![key_id.clone()]));
+            assert_eq!(
+                document.authentication,
+                Some(vec!["did:web:localhost#key-0".to_string()])
+            );
+            let public_jwk = document.verification_method[1].public_key_jwk.clone();
+            assert_eq!(public_jwk.crv, "X25519");
+            assert!(key_manager.get_key_agreement(public_jwk).is_ok());
+            assert!(bearer_did.get_key_agreement(&key_id).is_ok());
+            assert_eq!(document.validate(), vec![]);
+        }
+    }
+
+    mod hosting_path {
+        use super::*;
+
+        #[test]
+        fn test_domain() {
+            assert_eq!(
+                DidWeb::hosting_path("did:web:example.com").unwrap(),
+                "/.well-known/did.json"
+            );
+            assert_eq!(
+                DidWeb::hosting_path("did:web:localhost%3A8080").unwrap(),
+                "/.well-known/did.json"
+            );
+        }
+
+        #[test]
+        fn test_path() {
+            assert_eq!(
+                DidWeb::hosting_path("did:web:example.com:users:alice").unwrap(),
+                "/users/alice/did.json"
+            );
+        }
+
+        #[test]
+        fn test_other_method() {
+            let result = DidWeb::hosting_path("did:jwk:eyJrdHkiOiJPS1AifQ");
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
     }
 
     mod resolve {
@@ -486,5 +727,84 @@ mod tests {
             let resolved_document = resolution_result.document.unwrap();
             assert_eq!(resolved_document, bearer_did.document);
         }
+
+        #[tokio::test]
+        async fn test_resolves_relative_ids() {
+            let mut mock_server = Server::new_async().await;
+            let url = mock_server.url();
+
+            let bearer_did = DidWeb::create(&url, None).unwrap();
+            let mut document = bearer_did.document.clone();
+            document.verification_method[0].id = "#key-0".to_string();
+            document.authentication = Some(vec!["#key-0".to_string()]);
+            document.assertion_method = Some(vec!["#key-0".to_string()]);
+            document.capability_invocation = Some(vec!["did:example:456#0".to_string()]);
+
+            let _ = mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(serde_json::to_string(&document).unwrap())
+                .create();
+
+            let resolution_result = DidWeb::resolve(&bearer_did.did.uri).await;
+
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            assert_eq!(resolution_result.document, Some(document));
+        }
+
+        #[tokio::test]
+        async fn test_relative_dangling_reference() {
+            let mut mock_server = Server::new_async().await;
+            let url = mock_server.url();
+
+            let bearer_did = DidWeb::create(&url, None).unwrap();
+            let mut document = bearer_did.document.clone();
+            document.authentication = Some(vec!["#missing".to_string()]);
+
+            let _ = mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(serde_json::to_string(&document).unwrap())
+                .create();
+
+            let resolution_result = DidWeb::resolve(&bearer_did.did.uri).await;
+
+            assert_eq!(
+                resolution_result.resolution_metadata.error,
+                Some(ResolutionMetadataError::InvalidDidDocument)
+            );
+        }
+
+        #[tokio::test]
+        async fn test_invalid_did_document() {
+            let mut mock_server = Server::new_async().await;
+            let url = mock_server.url();
+
+            let bearer_did = DidWeb::create(&url, None).unwrap();
+            let mut document = bearer_did.document.clone();
+            document.authentication = Some(vec![format!("{}#missing", bearer_did.did.uri)]);
+
+            let _ = mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(serde_json::to_string(&document).unwrap())
+                .create();
+
+            let resolution_result = DidWeb::resolve(&bearer_did.did.uri).await;
+
+            assert_eq!(
+                resolution_result.resolution_metadata.error,
+                Some(ResolutionMetadataError::InvalidDidDocument)
+            );
+            assert!(resolution_result
+                .resolution_metadata
+                .error_message
+                .unwrap()
+                .contains("authentication[0]"));
+            assert!(resolution_result.document.is_none());
+        }
     }
 }
@@ -16,20 +16,30 @@ pub struct Resolver {
     http_url: String,
 }
 
+/// The path of the DID document on the DID's host: `/.well-known/did.json` for a bare domain,
+/// or the DID's colon-delimited path followed by `/did.json`.
+pub(crate) fn hosting_path(did: &Did) -> String {
+    // note: delimited is : generally, but ; is allowed by the spec. The did-web spec (§3.2) says
+    // ; should be avoided because of it's potential use for matrix URIs.
+    match did.id.split_once(':') {
+        Some((_, path)) => format!(
+            "/{}/did.json",
+            path.split(':').collect::<Vec<&str>>().join("/")
+        ),
+        None => "/.well-known/did.json".to_string(),
+    }
+}
+
 impl Resolver {
     pub fn new(did: Did) -> Result<Self, ResolutionMetadataError> {
-        // note: delimited is : generally, but ; is allowed by the spec. The did-web spec (§3.2) says
-        // ; should be avoided because of it's potential use for matrix URIs.
-        let http_url = match did.id.split_once(':') {
-            Some((domain, path)) => format!(
-                "{}/{}",
-                domain.replace(PORT_SEP, ":"),
-                path.split(':').collect::<Vec<&str>>().join("/"),
-            ),
-            None => format!("{}/{}", did.id.replace(PORT_SEP, ":"), ".well-known",),
-        };
+        let domain = did
+            .id
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .replace(PORT_SEP, ":");
 
-        let url = Url::parse(&format!("http://{}", http_url))
+        let url = Url::parse(&format!("http://{}", domain))
             .map_err(|_| ResolutionMetadataError::InvalidDid)?;
         let protocol =
             match url.host_str() == Some("localhost") || url.host_str() == Some("127.0.0.1") {
@@ -38,7 +48,7 @@ impl Resolver {
             };
 
         Ok(Self {
-            http_url: format!("{}://{}/did.json", protocol, http_url),
+            http_url: format!("{}://{}{}", protocol, domain, hosting_path(&did)),
         })
     }
 
@@ -0,0 +1,534 @@
+use super::DidWeb;
+use crate::{
+    credentials::VerificationError,
+    dids::{
+        controller::{verify_document_update, PREVIOUS_DOCUMENT_DIGEST_CLAIM},
+        data_model::document::Document,
+        resolution::universal_resolver::DID_JSON_MEDIA_TYPE,
+    },
+    errors::{Result, Web5Error},
+    jose::Jwt,
+};
+use async_trait::async_trait;
+use http_std::{Handler, Method, Request, Response, Server};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// A lightweight HTTP server hosting one or more "did:web" DID documents.
+///
+/// Each document is served at its `DidWeb::hosting_path`, as `application/did+json`, in answer to
+/// `GET`. A `PUT` to the same path with an update made with `sign_document_update` replaces the
+/// document, if the update is signed by the DID or one of its controllers and is bound to the
+/// hosted document. Only conformant documents are hosted.
+///
+/// Requests are matched by path alone, so documents of different domains may be hosted together
+/// as long as their paths differ.
+#[derive(Default)]
+pub struct DidWebServer {
+    documents: RwLock<HashMap<String, Document>>,
+}
+
+impl DidWebServer {
+    /// Creates a server hosting no documents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hosts a DID document, replacing the document of the same DID if it's already hosted.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The "did:web" DID document to host.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An error if the document isn't of a "did:web" DID, isn't conformant, or if
+    ///   the document of another DID is hosted at the same path.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let server = Arc::new(DidWebServer::new());
+    /// server.host(bearer_did.document.clone())?;
+    /// server.serve("127.0.0.1:8080").await?;
+    /// ```
+    pub fn host(&self, document: Document) -> Result<()> {
+        let path = DidWeb::hosting_path(&document.id)?;
+        check_conformance(&document)?;
+
+        let mut documents = self.documents.write()?;
+        if let Some(hosted) = documents.get(&path) {
+            if hosted.id != document.id {
+                return Err(Web5Error::Parameter(format!(
+                    "{} is already hosting {}",
+                    path, hosted.id
+                )));
+            }
+        }
+        documents.insert(path, document);
+
+        Ok(())
+    }
+
+    /// Stops hosting the document of a DID.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Document>>` - The document which was hosted, if any.
+    pub fn remove(&self, did_uri: &str) -> Result<Option<Document>> {
+        let path = DidWeb::hosting_path(did_uri)?;
+        let mut documents = self.documents.write()?;
+        match documents.get(&path) {
+            Some(hosted) if hosted.id == did_uri => Ok(documents.remove(&path)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the hosted document of a DID, if any.
+    pub fn document(&self, did_uri: &str) -> Result<Option<Document>> {
+        let path = DidWeb::hosting_path(did_uri)?;
+        let documents = self.documents.read()?;
+        Ok(documents.get(&path).filter(|d| d.id == did_uri).cloned())
+    }
+
+    /// Verifies a signed update against the hosted document of its DID, and hosts the updated
+    /// document in its place. If the hosted document changes while the update is verified, the
+    /// update no longer applies and is rejected, as if it had been signed against a stale
+    /// document.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The compact JWS made with `sign_document_update`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Document>` - The updated document, or an error if the DID isn't hosted, the
+    ///   update isn't authorized or isn't bound to the hosted document, or the updated document
+    ///   isn't conformant.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let update = sign_document_update(&current, &document, &controller_did)?;
+    /// server.apply_update(&update).await?;
+    /// ```
+    pub async fn apply_update(&self, update: &str) -> Result<Document> {
+        let jwt = Jwt::from_compact_jws(update, false).await?;
+        let did_uri = jwt
+            .claims
+            .sub
+            .ok_or_else(|| VerificationError::MissingClaim("sub".to_string()))?;
+
+        let current = self
+            .document(&did_uri)?
+            .ok_or_else(|| Web5Error::NotFound(format!("{} is not hosted", did_uri)))?;
+        let document = verify_document_update(update, &current).await?;
+        check_conformance(&document)?;
+
+        // compare and swap, since the hosted document may have been updated meanwhile
+        let path = DidWeb::hosting_path(&did_uri)?;
+        let mut documents = self.documents.write()?;
+        if documents.get(&path) != Some(&current) {
+            return Err(VerificationError::ClaimMismatch(
+                PREVIOUS_DOCUMENT_DIGEST_CLAIM.to_string(),
+            )
+            .into());
+        }
+        documents.insert(path, document.clone());
+
+        Ok(document)
+    }
+
+    /// Binds to the given address and serves the hosted documents until an error occurs.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The socket address to listen on, e.g. `0.0.0.0:8080`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let server = Arc::new(DidWebServer::new());
+    /// server.host(bearer_did.document.clone())?;
+    /// tokio::spawn(server.clone().serve("127.0.0.1:8080"));
+    /// ```
+    pub async fn serve(self: Arc<Self>, addr: &str) -> Result<()> {
+        let server = Server::bind(addr).await?;
+        server.serve(self).await?;
+        Ok(())
+    }
+
+    async fn update(&self, path: &str, body: &[u8]) -> Response {
+        let update = match std::str::from_utf8(body) {
+            Ok(update) => update.trim(),
+            Err(_) => return text_response(400, "update must be a compact JWS"),
+        };
+
+        // the update must be for the document hosted at the path it's sent to
+        let hosted = match self.documents.read() {
+            Ok(documents) => documents.get(path).map(|d| d.id.clone()),
+            Err(_) => return text_response(500, "internal error"),
+        };
+        let subject = match Jwt::from_compact_jws(update, false).await {
+            Ok(jwt) => jwt.claims.sub,
+            Err(e) => return text_response(400, &e.to_string()),
+        };
+        if hosted.is_none() {
+            return text_response(404, "not found");
+        }
+        if subject != hosted {
+            return text_response(400, "update is not for the document at this path");
+        }
+
+        match self.apply_update(update).await {
+            Ok(document) => document_response(&document),
+            Err(Web5Error::CredentialError(VerificationError::ClaimMismatch(claim)))
+                if claim == PREVIOUS_DOCUMENT_DIGEST_CLAIM =>
+            {
+                text_response(409, "update is not for the hosted version of the document")
+            }
+            Err(e @ Web5Error::CredentialError(VerificationError::UnauthorizedSigner(_)))
+            | Err(e @ Web5Error::Crypto(_)) => text_response(403, &e.to_string()),
+            Err(e @ Web5Error::NotFound(_)) => text_response(404, &e.to_string()),
+            Err(e) => text_response(400, &e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for DidWebServer {
+    async fn handle(&self, request: Request) -> Response {
+        match request.method {
+            Method::Get => {
+                let document = match self.documents.read() {
+                    Ok(documents) => documents.get(&request.path).cloned(),
+                    Err(_) => return text_response(500, "internal error"),
+                };
+                match document {
+                    Some(document) => document_response(&document),
+                    None => text_response(404, "not found"),
+                }
+            }
+            Method::Put => self.update(&request.path, &request.body).await,
+            _ => text_response(405, "method not allowed"),
+        }
+    }
+}
+
+fn check_conformance(document: &Document) -> Result<()> {
+    let violations = document.validate();
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    Err(Web5Error::Parameter(format!(
+        "{} is not a conformant DID document: {}",
+        document.id,
+        violations
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join("; ")
+    )))
+}
+
+fn document_response(document: &Document) -> Response {
+    match serde_json::to_vec(document) {
+        Ok(body) => {
+            let mut headers = HashMap::new();
+            headers.insert("Content-Type".to_string(), DID_JSON_MEDIA_TYPE.to_string());
+            // did:web documents are fetched by browser based resolvers too
+            headers.insert("Access-Control-Allow-Origin".to_string(), "*".to_string());
+            Response {
+                status_code: 200,
+                headers,
+                body,
+            }
+        }
+        Err(_) => text_response(500, "failed to serialize document"),
+    }
+}
+
+fn text_response(status_code: u16, body: &str) -> Response {
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "text/plain".to_string());
+    Response {
+        status_code,
+        headers,
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::{
+        bearer_did::BearerDid,
+        controller::sign_document_update,
+        methods::{did_jwk::DidJwk, did_web::DidWebCreateOptions},
+        resolution::resolution_metadata::ResolutionMetadataError,
+    };
+    use http_std::FetchOptions;
+
+    async fn put(url: &str, update: String) -> http_std::Response {
+        http_std::fetch(
+            &format!("{}/.well-known/did.json", url),
+            Some(FetchOptions {
+                method: Some(Method::Put),
+                body: Some(update.into_bytes()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Starts a server on an ephemeral port, returning it and its `http://` url.
+    async fn start() -> (Arc<DidWebServer>, String) {
+        let server = Server::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        let did_web_server = Arc::new(DidWebServer::new());
+        tokio::spawn(server.serve(did_web_server.clone()));
+        (did_web_server, url)
+    }
+
+    fn create(domain: &str, controller: Option<&BearerDid>) -> BearerDid {
+        DidWeb::create(
+            domain,
+            Some(DidWebCreateOptions {
+                controller: controller.map(|c| vec![c.did.uri.clone()]),
+                ..Default::default()
+            }),
+        )
+        .unwrap()
+    }
+
+    mod serve {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_resolves_hosted_documents() {
+            let (server, url) = start().await;
+            let bearer_did = create(&url, None);
+            let path_did = create(&format!("{}/users/alice", url), None);
+            server.host(bearer_did.document.clone()).unwrap();
+            server.host(path_did.document.clone()).unwrap();
+
+            let resolution_result = DidWeb::resolve(&bearer_did.did.uri).await;
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
+
+            let resolution_result = DidWeb::resolve(&path_did.did.uri).await;
+            assert_eq!(resolution_result.document.unwrap(), path_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_serves_did_json_media_type() {
+            let (server, url) = start().await;
+            let bearer_did = create(&url, None);
+            server.host(bearer_did.document.clone()).unwrap();
+
+            let response = http_std::fetch(&format!("{}/.well-known/did.json", url), None)
+                .await
+                .unwrap();
+            assert_eq!(response.status_code, 200);
+            let content_type = response
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+                .map(|(_, v)| v.clone());
+            assert_eq!(content_type, Some(DID_JSON_MEDIA_TYPE.to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_not_found() {
+            let (_server, url) = start().await;
+            let bearer_did = create(&url, None);
+
+            let resolution_result = DidWeb::resolve(&bearer_did.did.uri).await;
+            assert_eq!(
+                resolution_result.resolution_metadata.error,
+                Some(ResolutionMetadataError::NotFound)
+            );
+        }
+
+        #[tokio::test]
+        async fn test_put_applies_controller_signed_update() {
+            let (server, url) = start().await;
+            let controller = DidJwk::create(None).unwrap();
+            let bearer_did = create(&url, Some(&controller));
+            server.host(bearer_did.document.clone()).unwrap();
+
+            let mut document = bearer_did.document.clone();
+            document.also_known_as = Some(vec!["did:example:123".to_string()]);
+            let update =
+                sign_document_update(&bearer_did.document, &document, &controller).unwrap();
+            let response = http_std::fetch(
+                &format!("{}/.well-known/did.json", url),
+                Some(FetchOptions {
+                    method: Some(Method::Put),
+                    body: Some(update.into_bytes()),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status_code, 200);
+
+            let resolution_result = DidWeb::resolve(&bearer_did.did.uri).await;
+            assert_eq!(resolution_result.document.unwrap(), document);
+        }
+
+        #[tokio::test]
+        async fn test_put_rejects_unauthorized_update() {
+            let (server, url) = start().await;
+            let stranger = DidJwk::create(None).unwrap();
+            let bearer_did = create(&url, None);
+            server.host(bearer_did.document.clone()).unwrap();
+
+            let mut document = bearer_did.document.clone();
+            document.also_known_as = Some(vec!["did:example:123".to_string()]);
+            let update = sign_document_update(&bearer_did.document, &document, &stranger).unwrap();
+            let response = http_std::fetch(
+                &format!("{}/.well-known/did.json", url),
+                Some(FetchOptions {
+                    method: Some(Method::Put),
+                    body: Some(update.into_bytes()),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(response.status_code, 403);
+            assert_eq!(
+                server.document(&bearer_did.did.uri).unwrap().unwrap(),
+                bearer_did.document
+            );
+        }
+    }
+
+    mod apply_update {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_put_rejects_replayed_update() {
+            let (server, url) = start().await;
+            let bearer_did = create(&url, None);
+            server.host(bearer_did.document.clone()).unwrap();
+
+            let mut document = bearer_did.document.clone();
+            document.also_known_as = Some(vec!["did:example:123".to_string()]);
+            let update =
+                sign_document_update(&bearer_did.document, &document, &bearer_did).unwrap();
+            assert_eq!(put(&url, update.clone()).await.status_code, 200);
+
+            let mut other = document.clone();
+            other.also_known_as = Some(vec!["did:example:456".to_string()]);
+            let other_update = sign_document_update(&document, &other, &bearer_did).unwrap();
+            assert_eq!(put(&url, other_update).await.status_code, 200);
+
+            assert_eq!(put(&url, update).await.status_code, 409);
+            assert_eq!(
+                server.document(&bearer_did.did.uri).unwrap().unwrap(),
+                other
+            );
+        }
+
+        #[tokio::test]
+        async fn test_rejects_concurrent_updates_of_same_version() {
+            let (server, url) = start().await;
+            // resolving the controller suspends each update between reading and hosting
+            let controller = create(&format!("{}/controller", url), None);
+            let bearer_did = create(&url, Some(&controller));
+            server.host(controller.document.clone()).unwrap();
+            server.host(bearer_did.document.clone()).unwrap();
+
+            let updates = ["did:example:123", "did:example:456"].map(|also_known_as| {
+                let mut document = bearer_did.document.clone();
+                document.also_known_as = Some(vec![also_known_as.to_string()]);
+                sign_document_update(&bearer_did.document, &document, &controller).unwrap()
+            });
+            let results = tokio::join!(
+                server.apply_update(&updates[0]),
+                server.apply_update(&updates[1])
+            );
+
+            match results {
+                (Ok(_), Err(e)) | (Err(e), Ok(_)) => assert!(matches!(
+                    e,
+                    Web5Error::CredentialError(VerificationError::ClaimMismatch(_))
+                )),
+                _ => panic!("expected exactly one update to apply, got {:?}", results),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_put_rejects_nonconformant_document() {
+            let (server, url) = start().await;
+            let bearer_did = create(&url, None);
+            server.host(bearer_did.document.clone()).unwrap();
+
+            let mut document = bearer_did.document.clone();
+            document.assertion_method = Some(vec![format!("{}#missing", bearer_did.did.uri)]);
+            let update =
+                sign_document_update(&bearer_did.document, &document, &bearer_did).unwrap();
+
+            let response = put(&url, update).await;
+            assert_eq!(response.status_code, 400);
+            assert!(String::from_utf8(response.body)
+                .unwrap()
+                .contains("is not a conformant DID document"));
+            assert_eq!(
+                server.document(&bearer_did.did.uri).unwrap().unwrap(),
+                bearer_did.document
+            );
+        }
+    }
+
+    mod host {
+        use super::*;
+
+        #[test]
+        fn test_rejects_nonconformant_document() {
+            let server = DidWebServer::new();
+            let mut document = create("https://example.com", None).document;
+            document.verification_method.clear();
+
+            let result = server.host(document);
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[test]
+        fn test_rejects_other_did_at_same_path() {
+            let server = DidWebServer::new();
+            server
+                .host(create("https://example.com", None).document)
+                .unwrap();
+
+            let result = server.host(create("https://example.org", None).document);
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[test]
+        fn test_rejects_other_did_methods() {
+            let server = DidWebServer::new();
+            let result = server.host(DidJwk::create(None).unwrap().document);
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[test]
+        fn test_remove() {
+            let server = DidWebServer::new();
+            let bearer_did = create("https://example.com", None);
+            server.host(bearer_did.document.clone()).unwrap();
+
+            assert_eq!(
+                server.remove(&bearer_did.did.uri).unwrap(),
+                Some(bearer_did.document.clone())
+            );
+            assert_eq!(server.document(&bearer_did.did.uri).unwrap(), None);
+        }
+    }
+}
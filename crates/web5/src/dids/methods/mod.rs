@@ -2,3 +2,17 @@ pub mod did_dht;
 pub mod did_web;
 
 pub mod did_jwk;
+
+use crate::{
+    crypto::{jwk::Jwk, key_managers::KeyManager, x25519::X25519Generator},
+    errors::Result,
+};
+use std::sync::Arc;
+
+/// Generates an X25519 key for key agreement, imports it into the key manager, and returns its
+/// public JWK.
+pub(crate) fn generate_key_agreement_key(key_manager: &Arc<dyn KeyManager>) -> Result<Jwk> {
+    let mut public_jwk = key_manager.import_private_jwk(X25519Generator::generate())?;
+    public_jwk.d = None;
+    Ok(public_jwk)
+}
@@ -2,6 +2,8 @@ pub mod data_model;
 pub mod methods;
 pub mod resolution;
 
+pub mod auth;
 pub mod bearer_did;
+pub mod controller;
 pub mod did;
 pub mod portable_did;
@@ -1,6 +1,7 @@
 use super::data_model::document::Document;
 use crate::{
-    crypto::jwk::Jwk,
+    crypto::{jwk::Jwk, pbes2},
+    errors::Result,
     json::{FromJson, ToJson},
 };
 use serde::{Deserialize, Serialize};
@@ -24,3 +25,101 @@ pub struct PortableDid {
 
 impl FromJson for PortableDid {}
 impl ToJson for PortableDid {}
+
+impl PortableDid {
+    /// Encrypts the Portable DID under a password, so that it can be backed up without exposing
+    /// its private keys.
+    ///
+    /// The result is a compact JWE using `PBES2-HS512+A256KW` key management and `A256GCM`
+    /// content encryption, whose plaintext is the Portable DID JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to encrypt with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The encrypted Portable DID as a compact JWE.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let encrypted_portable_did = portable_did.encrypt("correct horse battery staple")?;
+    /// let portable_did = PortableDid::from_encrypted(&encrypted_portable_did, "correct horse battery staple")?;
+    /// ```
+    pub fn encrypt(&self, password: &str) -> Result<String> {
+        self.encrypt_with_iterations(password, pbes2::PBES2_ITERATIONS)
+    }
+
+    pub(crate) fn encrypt_with_iterations(
+        &self,
+        password: &str,
+        iterations: u32,
+    ) -> Result<String> {
+        pbes2::encrypt(
+            self.to_json_string()?.as_bytes(),
+            password,
+            Some("application/json"),
+            iterations,
+        )
+    }
+
+    /// Decrypts a Portable DID produced by [`PortableDid::encrypt`].
+    ///
+    /// # Arguments
+    ///
+    /// * `encrypted_portable_did` - The encrypted Portable DID as a compact JWE.
+    /// * `password` - The password it was encrypted with.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - The decrypted Portable DID, or a `Web5Error::Crypto` if the password is wrong.
+    pub fn from_encrypted(encrypted_portable_did: &str, password: &str) -> Result<Self> {
+        let plaintext = pbes2::decrypt(encrypted_portable_did, password)?;
+        Self::from_json_byte_array(&plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        crypto::key_managers::{in_memory_key_manager::InMemoryKeyManager, KeyExporter},
+        dids::methods::did_jwk::{DidJwk, DidJwkCreateOptions},
+        errors::Web5Error,
+    };
+    use std::sync::Arc;
+
+    mod encrypt {
+        use super::*;
+
+        #[test]
+        fn test_round_trip() {
+            let key_manager = Arc::new(InMemoryKeyManager::new());
+            let bearer_did = DidJwk::create(Some(DidJwkCreateOptions {
+                key_manager: Some(key_manager.clone()),
+                ..Default::default()
+            }))
+            .unwrap();
+            let portable_did = PortableDid {
+                did_uri: bearer_did.did.uri.clone(),
+                document: bearer_did.document.clone(),
+                private_jwks: key_manager.export_private_jwks().unwrap(),
+            };
+
+            let encrypted_portable_did = portable_did
+                .encrypt_with_iterations("password", pbes2::MIN_ITERATIONS)
+                .unwrap();
+            assert!(!encrypted_portable_did.contains(&bearer_did.did.uri));
+
+            let decrypted =
+                PortableDid::from_encrypted(&encrypted_portable_did, "password").unwrap();
+            assert_eq!(decrypted.did_uri, portable_did.did_uri);
+            assert_eq!(decrypted.document, portable_did.document);
+            assert_eq!(decrypted.private_jwks, portable_did.private_jwks);
+
+            let result = PortableDid::from_encrypted(&encrypted_portable_did, "not the password");
+            assert!(matches!(result, Err(Web5Error::Crypto(_))));
+        }
+    }
+}
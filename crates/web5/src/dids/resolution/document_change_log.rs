@@ -0,0 +1,169 @@
+use crate::{
+    dids::data_model::{document::Document, document_diff::DocumentDiff},
+    errors::Result,
+};
+use std::{collections::HashMap, sync::RwLock, time::SystemTime};
+
+/// A change detected between two resolutions of the same DID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentChange {
+    /// The DID whose document changed.
+    pub did: String,
+
+    /// The changes from the previously resolved document to the newly resolved one.
+    pub diff: DocumentDiff,
+
+    /// When the change was detected.
+    pub detected_at: SystemTime,
+}
+
+/// Records the last resolved document of each DID, and the changes between consecutive
+/// resolutions, so that security monitoring can alert on, for example, key changes.
+///
+/// Once set as the `document_change_log` of the `ResolutionOptions` DIDs are resolved with,
+/// `ResolutionResult::resolve_with_options` records every successfully resolved document.
+///
+/// # Examples
+///
+/// ```ignore
+/// let change_log = Arc::new(DocumentChangeLog::new());
+/// let options = ResolutionOptions {
+///     document_change_log: Some(change_log.clone()),
+///     ..Default::default()
+/// };
+///
+/// ResolutionResult::resolve_with_options(did_uri, &options).await;
+/// // ...
+/// ResolutionResult::resolve_with_options(did_uri, &options).await;
+/// for change in change_log.changes(did_uri)? {
+///     if change.diff.has_key_changes() {
+///         alert(&change);
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct DocumentChangeLog {
+    documents: RwLock<HashMap<String, Document>>,
+    changes: RwLock<Vec<DocumentChange>>,
+}
+
+impl DocumentChangeLog {
+    /// Creates an empty change log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a resolved document against the previously recorded version of its DID.
+    ///
+    /// # Arguments
+    ///
+    /// * `document` - The newly resolved DID document.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<DocumentChange>>` - The change recorded, or `None` if the DID wasn't
+    ///   recorded before or its document is unchanged.
+    pub fn record(&self, document: &Document) -> Result<Option<DocumentChange>> {
+        let previous = self
+            .documents
+            .write()?
+            .insert(document.id.clone(), document.clone());
+
+        let diff = match previous {
+            Some(previous) => previous.diff(document),
+            None => return Ok(None),
+        };
+        if diff.is_empty() {
+            return Ok(None);
+        }
+
+        let change = DocumentChange {
+            did: document.id.clone(),
+            diff,
+            detected_at: SystemTime::now(),
+        };
+        self.changes.write()?.push(change.clone());
+
+        Ok(Some(change))
+    }
+
+    /// Returns the last recorded document of a DID, if any.
+    pub fn document(&self, did_uri: &str) -> Result<Option<Document>> {
+        Ok(self.documents.read()?.get(did_uri).cloned())
+    }
+
+    /// Returns the changes recorded for a DID, oldest first.
+    pub fn changes(&self, did_uri: &str) -> Result<Vec<DocumentChange>> {
+        Ok(self
+            .changes
+            .read()?
+            .iter()
+            .filter(|change| change.did == did_uri)
+            .cloned()
+            .collect())
+    }
+
+    /// Returns and clears the changes recorded for all DIDs, oldest first. The last recorded
+    /// documents are kept, so later changes are still detected.
+    pub fn drain_changes(&self) -> Result<Vec<DocumentChange>> {
+        Ok(std::mem::take(&mut *self.changes.write()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::methods::did_jwk::DidJwk;
+
+    mod record {
+        use super::*;
+
+        #[test]
+        fn test_first_resolution_is_not_a_change() {
+            let change_log = DocumentChangeLog::new();
+            let document = DidJwk::create(None).unwrap().document;
+
+            assert_eq!(change_log.record(&document).unwrap(), None);
+            assert_eq!(change_log.document(&document.id).unwrap(), Some(document));
+        }
+
+        #[test]
+        fn test_unchanged_document() {
+            let change_log = DocumentChangeLog::new();
+            let document = DidJwk::create(None).unwrap().document;
+            change_log.record(&document).unwrap();
+
+            assert_eq!(change_log.record(&document).unwrap(), None);
+            assert!(change_log.changes(&document.id).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_records_changes_against_previous_version() {
+            let change_log = DocumentChangeLog::new();
+            let document = DidJwk::create(None).unwrap().document;
+            change_log.record(&document).unwrap();
+
+            let mut rotated = document.clone();
+            rotated.verification_method[0].public_key_jwk =
+                DidJwk::create(None).unwrap().document.verification_method[0]
+                    .public_key_jwk
+                    .clone();
+            let change = change_log.record(&rotated).unwrap().unwrap();
+            assert!(change.diff.has_key_changes());
+            assert_eq!(change.diff, document.diff(&rotated));
+
+            let mut aliased = rotated.clone();
+            aliased.also_known_as = Some(vec!["did:example:123".to_string()]);
+            change_log.record(&aliased).unwrap();
+
+            let changes = change_log.changes(&document.id).unwrap();
+            assert_eq!(changes.len(), 2);
+            assert_eq!(changes[0], change);
+            assert!(!changes[1].diff.has_key_changes());
+
+            assert_eq!(change_log.drain_changes().unwrap().len(), 2);
+            assert!(change_log.changes(&document.id).unwrap().is_empty());
+            assert_eq!(change_log.document(&document.id).unwrap(), Some(aliased));
+        }
+    }
+}
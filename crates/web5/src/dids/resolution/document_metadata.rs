@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 /// This struct provides additional information about a resolved DID document, including timestamps,
 /// versioning, and deactivation status. It helps track changes and updates to the DID document
 /// over time.
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct DocumentMetadata {
     /// The timestamp when the DID document was created (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
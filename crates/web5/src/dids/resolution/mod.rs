@@ -1,3 +1,9 @@
+pub mod document_change_log;
 pub mod document_metadata;
+pub mod resolution_bundle;
 pub mod resolution_metadata;
+pub mod resolution_options;
 pub mod resolution_result;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod resolver_service;
+pub mod universal_resolver;
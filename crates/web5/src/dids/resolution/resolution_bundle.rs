@@ -0,0 +1,479 @@
+use super::{resolution_metadata::ResolutionMetadataError, resolution_result::ResolutionResult};
+use crate::{
+    credentials::VerificationError,
+    dids::{
+        bearer_did::BearerDid,
+        did::Did,
+        methods::{
+            did_dht::{fetch_linked_resource, DidDht},
+            did_jwk::DidJwk,
+        },
+    },
+    errors::{Result, Web5Error},
+    jose::Jws,
+    json::{FromJson, ToJson},
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A set of DID resolutions exported from an online machine, to resolve DIDs on a machine without
+/// network access, e.g. to verify credentials on a kiosk.
+///
+/// "did:dht" DIDs are bundled as their BEP44 signed packets, which are verified against the
+/// identity key each DID is derived from when resolved, so can't be tampered with. Their services
+/// offloaded to linked resources are bundled too, and verified against their digests. Other DIDs
+/// are bundled as their resolution results, which are only as trustworthy as the bundle itself, so
+/// bundles should be signed with `sign` and loaded with `from_signed`.
+///
+/// Once set as the `resolution_bundle` of the default `ResolutionOptions`,
+/// `ResolutionResult::resolve`, and so the verification of signed credentials and presentations,
+/// resolves bundled DIDs from the bundle.
+///
+/// # Examples
+///
+/// ```ignore
+/// // online
+/// let bundle = ResolutionBundle::export(&[issuer_did_uri], None).await?;
+/// let signed_bundle = bundle.sign(&bundle_signer)?;
+///
+/// // offline
+/// let bundle = ResolutionBundle::from_signed(&signed_bundle, &[bundle_signer_did_uri]).await?;
+/// set_default_resolution_options(ResolutionOptions {
+///     resolution_bundle: Some(Arc::new(bundle)),
+///     ..Default::default()
+/// });
+/// let vc = VerifiableCredential::from_vc_jwt(vc_jwt, true).await?;
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolutionBundle {
+    /// Resolution results, keyed by DID URI.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resolution_results: BTreeMap<String, ResolutionResult>,
+
+    /// BEP44 signed "did:dht" packets, base64url encoded, keyed by DID URI.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dht_packets: BTreeMap<String, String>,
+
+    /// The content of linked resources which "did:dht" services are offloaded to, keyed by url.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub linked_resources: BTreeMap<String, String>,
+}
+
+impl FromJson for ResolutionBundle {}
+impl ToJson for ResolutionBundle {}
+
+/// Options for exporting a resolution bundle.
+#[derive(Default)]
+pub struct ResolutionBundleExportOptions {
+    /// The URL of the gateway to fetch "did:dht" packets from. If not provided, the default
+    /// gateway is used.
+    pub gateway_url: Option<String>,
+}
+
+impl ResolutionBundle {
+    /// Resolves each DID, and bundles the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `did_uris` - The DIDs to bundle.
+    /// * `options` - Optional parameters such as the "did:dht" gateway.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ResolutionBundle>` - The bundle, or an error if any of the DIDs fails to resolve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let bundle = ResolutionBundle::export(&[issuer_did_uri], None).await?;
+    /// std::fs::write("bundle.json", bundle.to_json_string()?)?;
+    /// ```
+    pub async fn export(
+        did_uris: &[String],
+        options: Option<ResolutionBundleExportOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+
+        let mut bundle = Self::default();
+        for did_uri in did_uris {
+            let did = Did::parse(did_uri)?;
+            if did.method == "dht" {
+                bundle
+                    .add_dht_packet(did_uri, options.gateway_url.clone())
+                    .await?;
+                continue;
+            }
+
+            let resolution_result = ResolutionResult::resolve(did_uri).await;
+            if let Some(e) = resolution_result.resolution_metadata.error {
+                return Err(e.into());
+            }
+            bundle
+                .resolution_results
+                .insert(did_uri.clone(), resolution_result);
+        }
+
+        Ok(bundle)
+    }
+
+    async fn add_dht_packet(&mut self, did_uri: &str, gateway_url: Option<String>) -> Result<()> {
+        let signed_packet = DidDht::fetch_signed_packet(did_uri, gateway_url).await?;
+        let document = DidDht::decode_signed_packet(did_uri, &signed_packet)?;
+
+        for url in DidDht::linked_resource_urls(&document) {
+            let content = fetch_linked_resource(&url).await?;
+            let content = String::from_utf8(content).map_err(|_| {
+                Web5Error::Encoding(format!("linked resource {} is not utf-8", url))
+            })?;
+            self.linked_resources.insert(url, content);
+        }
+
+        self.dht_packets.insert(
+            did_uri.to_string(),
+            general_purpose::URL_SAFE_NO_PAD.encode(signed_packet),
+        );
+        Ok(())
+    }
+
+    /// Returns true if the DID is bundled.
+    pub fn contains(&self, did_uri: &str) -> bool {
+        self.resolution_results.contains_key(did_uri) || self.dht_packets.contains_key(did_uri)
+    }
+
+    /// Resolves a DID from the bundle, without network access.
+    ///
+    /// "did:dht" packets are verified with `Bep44Message::verify` against the DID's identity key,
+    /// and "did:jwk" DIDs, which are self-contained, resolve even when not bundled.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The DID URI to resolve.
+    ///
+    /// # Returns
+    ///
+    /// * `ResolutionResult` - The bundled resolution, or a `NotFound` error if the DID isn't bundled.
+    pub async fn resolve(&self, uri: &str) -> ResolutionResult {
+        if let Some(signed_packet) = self.dht_packets.get(uri) {
+            let signed_packet = match general_purpose::URL_SAFE_NO_PAD.decode(signed_packet) {
+                Ok(signed_packet) => signed_packet,
+                Err(_) => {
+                    return ResolutionResult::from(ResolutionMetadataError::InvalidDidDocument)
+                }
+            };
+            return DidDht::resolve_signed_packet(
+                uri,
+                &signed_packet,
+                Some(&self.linked_resources),
+                false,
+            )
+            .await;
+        }
+
+        if let Some(resolution_result) = self.resolution_results.get(uri) {
+            return resolution_result.clone().validated();
+        }
+
+        match Did::parse(uri) {
+            Ok(did) if did.method == "jwk" => DidJwk::resolve(uri),
+            Ok(_) => ResolutionResult::from(ResolutionMetadataError::NotFound),
+            Err(_) => ResolutionResult::from(ResolutionMetadataError::InvalidDid),
+        }
+    }
+
+    /// Signs the bundle as a compact JWS, so that it can be checked for tampering when loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `bearer_did` - The DID signing the bundle, with its assertion method key.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String>` - The compact JWS, whose payload is the bundle's JSON.
+    pub fn sign(&self, bearer_did: &BearerDid) -> Result<String> {
+        let payload = self.to_json_string()?;
+        Ok(Jws::from_payload(payload.as_bytes(), bearer_did, None)?.compact_jws)
+    }
+
+    /// Loads a bundle signed with `sign`, verifying its signature and that its signer is trusted.
+    ///
+    /// The signer's DID is resolved from the bundle itself when that can't be tampered with, i.e.
+    /// for "did:jwk" DIDs and bundled "did:dht" packets, so that the bundle can be loaded without
+    /// network access. Otherwise it is resolved with `ResolutionResult::resolve`.
+    ///
+    /// # Arguments
+    ///
+    /// * `compact_jws` - The signed bundle.
+    /// * `trusted_signers` - The DIDs trusted to sign bundles.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ResolutionBundle>` - The bundle, or an error if the signature is invalid or the
+    ///   signer isn't trusted.
+    pub async fn from_signed(compact_jws: &str, trusted_signers: &[String]) -> Result<Self> {
+        let jws = Jws::from_compact_jws(compact_jws, false).await?;
+        let bundle = Self::from_json_byte_array(&jws.payload)?;
+
+        let signer = Did::parse(&jws.kid)?;
+        if !trusted_signers.contains(&signer.uri) {
+            return Err(VerificationError::UnauthorizedSigner(signer.uri).into());
+        }
+
+        let resolution_result = match signer.method.as_str() {
+            "jwk" => DidJwk::resolve(&signer.uri),
+            "dht" if bundle.dht_packets.contains_key(&signer.uri) => {
+                bundle.resolve(&signer.uri).await
+            }
+            _ => ResolutionResult::resolve(&signer.uri).await,
+        };
+        if let Some(e) = resolution_result.resolution_metadata.error {
+            return Err(e.into());
+        }
+        let document = resolution_result.document.ok_or_else(|| {
+            Web5Error::Parameter(format!("failed to resolve did document for {}", jws.kid))
+        })?;
+        jws.verify_with_document(&document)?;
+
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::methods::{
+        did_dht::DidDhtCreateOptions,
+        did_web::{DidWeb, DidWebCreateOptions},
+    };
+    use crate::dids::resolution::resolution_options::ResolutionOptions;
+    use mockito::{Server, ServerGuard};
+    use std::sync::{Arc, Mutex};
+
+    /// Creates a did:dht published to the mock gateway, which then serves its signed packet.
+    async fn published_did_dht(mock_server: &mut ServerGuard) -> BearerDid {
+        let published_body = Arc::new(Mutex::new(Vec::new()));
+        let published_body_clone = Arc::clone(&published_body);
+        let _ = mock_server
+            .mock("PUT", mockito::Matcher::Any)
+            .with_status(200)
+            .with_body_from_request(move |request| {
+                *published_body_clone.lock().unwrap() = request.body().unwrap().to_vec();
+                vec![]
+            })
+            .create_async()
+            .await;
+
+        let bearer_did = DidDht::create(Some(DidDhtCreateOptions {
+            gateway_url: Some(mock_server.url()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+        let signed_packet = published_body.lock().unwrap().clone();
+        let _ = mock_server
+            .mock("GET", format!("/{}", bearer_did.did.id).as_str())
+            .with_status(200)
+            .with_body(signed_packet)
+            .create_async()
+            .await;
+        bearer_did
+    }
+
+    mod export {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_did_dht_resolves_offline() {
+            let mut mock_server = Server::new_async().await;
+            let bearer_did = published_did_dht(&mut mock_server).await;
+
+            let bundle = ResolutionBundle::export(
+                std::slice::from_ref(&bearer_did.did.uri),
+                Some(ResolutionBundleExportOptions {
+                    gateway_url: Some(mock_server.url()),
+                }),
+            )
+            .await
+            .unwrap();
+            assert!(bundle.dht_packets.contains_key(&bearer_did.did.uri));
+            assert!(bundle.resolution_results.is_empty());
+            drop(mock_server);
+
+            let json = bundle.to_json_string().unwrap();
+            let bundle = ResolutionBundle::from_json_string(&json).unwrap();
+            let resolution_result = bundle.resolve(&bearer_did.did.uri).await;
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_did_web_resolves_offline() {
+            let mut mock_server = Server::new_async().await;
+            let bearer_did = DidWeb::create(&mock_server.url(), None).unwrap();
+            let _ = mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_body(bearer_did.document.to_json_string().unwrap())
+                .create_async()
+                .await;
+
+            let bundle = ResolutionBundle::export(std::slice::from_ref(&bearer_did.did.uri), None)
+                .await
+                .unwrap();
+            drop(mock_server);
+
+            let resolution_result = bundle.resolve(&bearer_did.did.uri).await;
+            assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_fails_if_did_does_not_resolve() {
+            let result = ResolutionBundle::export(&["did:example:123".to_string()], None).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::Resolution(
+                    ResolutionMetadataError::MethodNotSupported
+                ))
+            ));
+        }
+    }
+
+    mod resolve {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_rejects_tampered_dht_packet() {
+            let mut mock_server = Server::new_async().await;
+            let bearer_did = published_did_dht(&mut mock_server).await;
+            let mut bundle = ResolutionBundle::export(
+                std::slice::from_ref(&bearer_did.did.uri),
+                Some(ResolutionBundleExportOptions {
+                    gateway_url: Some(mock_server.url()),
+                }),
+            )
+            .await
+            .unwrap();
+
+            let packet = bundle.dht_packets.get_mut(&bearer_did.did.uri).unwrap();
+            let mut signed_packet = general_purpose::URL_SAFE_NO_PAD.decode(&packet).unwrap();
+            let last = signed_packet.len() - 1;
+            signed_packet[last] ^= 1;
+            *packet = general_purpose::URL_SAFE_NO_PAD.encode(signed_packet);
+
+            let resolution_result = bundle.resolve(&bearer_did.did.uri).await;
+            assert_eq!(
+                resolution_result.resolution_metadata.error,
+                Some(ResolutionMetadataError::InvalidDidDocument)
+            );
+        }
+
+        #[tokio::test]
+        async fn test_not_bundled() {
+            let bundle = ResolutionBundle::default();
+            let resolution_result = bundle.resolve("did:web:example.com").await;
+            assert_eq!(
+                resolution_result.resolution_metadata.error,
+                Some(ResolutionMetadataError::NotFound)
+            );
+
+            let bearer_did = DidJwk::create(None).unwrap();
+            let resolution_result = bundle.resolve(&bearer_did.did.uri).await;
+            assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_resolution_result_resolve_uses_bundle() {
+            let bearer_did = DidWeb::create(
+                "https://offline.example.com/bundle-test",
+                Some(DidWebCreateOptions::default()),
+            )
+            .unwrap();
+            let mut bundle = ResolutionBundle::default();
+            bundle.resolution_results.insert(
+                bearer_did.did.uri.clone(),
+                ResolutionResult {
+                    document: Some(bearer_did.document.clone()),
+                    ..Default::default()
+                },
+            );
+
+            let options = ResolutionOptions {
+                resolution_bundle: Some(Arc::new(bundle)),
+                ..Default::default()
+            };
+            let resolution_result =
+                ResolutionResult::resolve_with_options(&bearer_did.did.uri, &options).await;
+
+            assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
+        }
+    }
+
+    mod from_signed {
+        use super::*;
+
+        fn bundle() -> ResolutionBundle {
+            let bearer_did = DidJwk::create(None).unwrap();
+            let mut bundle = ResolutionBundle::default();
+            bundle.resolution_results.insert(
+                bearer_did.did.uri.clone(),
+                ResolutionResult {
+                    document: Some(bearer_did.document),
+                    ..Default::default()
+                },
+            );
+            bundle
+        }
+
+        #[tokio::test]
+        async fn test_verifies_trusted_signer() {
+            let signer = DidJwk::create(None).unwrap();
+            let bundle = bundle();
+            let signed_bundle = bundle.sign(&signer).unwrap();
+
+            let loaded = ResolutionBundle::from_signed(
+                &signed_bundle,
+                std::slice::from_ref(&signer.did.uri),
+            )
+            .await
+            .unwrap();
+            assert_eq!(
+                loaded.to_json_string().unwrap(),
+                bundle.to_json_string().unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_rejects_untrusted_signer() {
+            let signer = DidJwk::create(None).unwrap();
+            let signed_bundle = bundle().sign(&signer).unwrap();
+
+            let result = ResolutionBundle::from_signed(&signed_bundle, &[]).await;
+            assert!(matches!(
+                result,
+                Err(Web5Error::CredentialError(
+                    VerificationError::UnauthorizedSigner(_)
+                ))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_rejects_tampered_bundle() {
+            let signer = DidJwk::create(None).unwrap();
+            let signed_bundle = bundle().sign(&signer).unwrap();
+            let parts = signed_bundle.split('.').collect::<Vec<_>>();
+            let tampered = format!(
+                "{}.{}.{}",
+                parts[0],
+                general_purpose::URL_SAFE_NO_PAD.encode(bundle().to_json_string().unwrap()),
+                parts[2]
+            );
+
+            let result =
+                ResolutionBundle::from_signed(&tampered, std::slice::from_ref(&signer.did.uri))
+                    .await;
+            assert!(matches!(result, Err(Web5Error::Crypto(_))));
+        }
+    }
+}
@@ -9,6 +9,19 @@ pub struct ResolutionMetadata {
     /// An optional error that provides details on any issues encountered during the DID resolution process.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ResolutionMetadataError>,
+
+    /// An optional human-readable description of the error, such as the DID Core conformance
+    /// violations of an `InvalidDidDocument`.
+    #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+
+    /// The urls of the linked resources which "did:dht" services are offloaded to but which
+    /// weren't fetched, or couldn't be, leaving the services' stubs in the document.
+    #[serde(
+        rename = "unresolvedLinkedResources",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unresolved_linked_resources: Option<Vec<String>>,
 }
 
 #[derive(thiserror::Error, Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -38,3 +51,68 @@ pub enum ResolutionMetadataError {
     #[serde(rename = "internalError")]
     InternalError,
 }
+
+impl ResolutionMetadataError {
+    /// Returns the HTTP status code a resolver serving DID resolution over HTTP(S) should answer
+    /// with, per the [DID Resolution HTTP(S) binding](https://w3c-ccg.github.io/did-resolution/#bindings-https).
+    pub fn http_status_code(&self) -> u16 {
+        match self {
+            ResolutionMetadataError::InvalidDid => 400,
+            ResolutionMetadataError::NotFound => 404,
+            ResolutionMetadataError::RepresentationNotSupported => 406,
+            ResolutionMetadataError::MethodNotSupported => 501,
+            ResolutionMetadataError::InvalidDidDocument
+            | ResolutionMetadataError::InvalidPublicKey
+            | ResolutionMetadataError::InvalidDidDocumentLength
+            | ResolutionMetadataError::InternalError => 500,
+        }
+    }
+
+    /// Maps an HTTP status code returned by a remote resolver back to a `ResolutionMetadataError`.
+    pub fn from_http_status_code(status_code: u16) -> Self {
+        match status_code {
+            400 => ResolutionMetadataError::InvalidDid,
+            404 | 410 => ResolutionMetadataError::NotFound,
+            406 => ResolutionMetadataError::RepresentationNotSupported,
+            501 => ResolutionMetadataError::MethodNotSupported,
+            _ => ResolutionMetadataError::InternalError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod http_status_code {
+        use super::*;
+
+        #[test]
+        fn test_round_trip() {
+            for error in [
+                ResolutionMetadataError::InvalidDid,
+                ResolutionMetadataError::NotFound,
+                ResolutionMetadataError::RepresentationNotSupported,
+                ResolutionMetadataError::MethodNotSupported,
+                ResolutionMetadataError::InternalError,
+            ] {
+                assert_eq!(
+                    ResolutionMetadataError::from_http_status_code(error.http_status_code()),
+                    error
+                );
+            }
+        }
+
+        #[test]
+        fn test_invalid_document_is_server_error() {
+            assert_eq!(
+                ResolutionMetadataError::InvalidDidDocument.http_status_code(),
+                500
+            );
+            assert_eq!(
+                ResolutionMetadataError::InvalidDidDocumentLength.http_status_code(),
+                500
+            );
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use super::{
+    document_change_log::DocumentChangeLog, resolution_bundle::ResolutionBundle,
+    universal_resolver::UniversalResolverClient,
+};
+use lazy_static::lazy_static;
+use std::sync::{Arc, RwLock};
+
+/// Options for resolving DIDs with `ResolutionResult::resolve_with_options`.
+///
+/// `ResolutionResult::resolve`, and so the verification of signed credentials and presentations,
+/// uses the options configured via [`set_default_resolution_options`].
+///
+/// # Examples
+///
+/// ```ignore
+/// let options = ResolutionOptions {
+///     universal_resolver: Some(Arc::new(UniversalResolverClient::new("https://dev.uniresolver.io"))),
+///     ..Default::default()
+/// };
+/// let result = ResolutionResult::resolve_with_options("did:key:z6Mk...", &options).await;
+/// ```
+#[derive(Clone, Default)]
+pub struct ResolutionOptions {
+    /// The Universal Resolver to delegate DID methods which are not implemented natively to. If
+    /// not provided, such DID methods fail with `MethodNotSupported`.
+    pub universal_resolver: Option<Arc<UniversalResolverClient>>,
+
+    /// The change log to record successfully resolved documents in. If not provided, documents
+    /// aren't recorded.
+    pub document_change_log: Option<Arc<DocumentChangeLog>>,
+
+    /// The bundle to resolve the DIDs it contains from, without network access. DIDs not in the
+    /// bundle are resolved as usual.
+    pub resolution_bundle: Option<Arc<ResolutionBundle>>,
+
+    /// Whether to fetch the linked resources "did:dht" services are offloaded to, over https
+    /// only. Defaults to false, leaving the services' stubs in the document.
+    pub fetch_linked_resources: bool,
+}
+
+lazy_static! {
+    static ref DEFAULT_RESOLUTION_OPTIONS: RwLock<ResolutionOptions> =
+        RwLock::new(ResolutionOptions::default());
+}
+
+/// Configures the options `ResolutionResult::resolve` resolves DIDs with. Passing
+/// `ResolutionOptions::default()` restores the defaults.
+pub fn set_default_resolution_options(options: ResolutionOptions) {
+    if let Ok(mut current) = DEFAULT_RESOLUTION_OPTIONS.write() {
+        *current = options;
+    }
+}
+
+/// Returns the options configured via [`set_default_resolution_options`].
+pub fn get_default_resolution_options() -> ResolutionOptions {
+    DEFAULT_RESOLUTION_OPTIONS
+        .read()
+        .map(|current| current.clone())
+        .unwrap_or_default()
+}
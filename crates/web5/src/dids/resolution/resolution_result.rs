@@ -1,4 +1,8 @@
-use super::{document_metadata::DocumentMetadata, resolution_metadata::ResolutionMetadata};
+use super::{
+    document_metadata::DocumentMetadata,
+    resolution_metadata::ResolutionMetadata,
+    resolution_options::{get_default_resolution_options, ResolutionOptions},
+};
 use crate::dids::{
     data_model::document::Document,
     did::Did,
@@ -29,6 +33,10 @@ impl ResolutionResult {
     ///
     /// This function attempts to resolve the DID URI by parsing the DID and invoking
     /// the appropriate resolution method based on the DID method (e.g., `jwk`, `dht`, `web`).
+    /// DIDs are resolved with the options configured via
+    /// `resolution_options::set_default_resolution_options`: DIDs in their bundle are resolved
+    /// from it, without network access; other DID methods are delegated to their Universal
+    /// Resolver; and resolved documents are recorded in their change log, if any.
     /// If the resolution is successful, the DID document and metadata are returned.
     /// Otherwise, an error is returned in the `resolution_metadata`.
     ///
@@ -51,16 +59,79 @@ impl ResolutionResult {
     /// }
     /// ```
     pub async fn resolve(uri: &str) -> Self {
+        Self::resolve_with_options(uri, &get_default_resolution_options()).await
+    }
+
+    /// Resolves a DID URI into a `ResolutionResult` with the given options, rather than those
+    /// configured via `resolution_options::set_default_resolution_options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The DID URI to resolve.
+    /// * `options` - The options to resolve with.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A `ResolutionResult` containing the resolved DID document or an error.
+    pub async fn resolve_with_options(uri: &str, options: &ResolutionOptions) -> Self {
         let did = match Did::parse(uri) {
             Ok(did) => did,
             Err(_) => return ResolutionResult::from(ResolutionMetadataError::InvalidDid),
         };
 
-        match did.method.as_str() {
-            "jwk" => DidJwk::resolve(uri),
-            "dht" => DidDht::resolve(uri, None).await,
-            "web" => DidWeb::resolve(uri).await,
-            _ => ResolutionResult::from(ResolutionMetadataError::MethodNotSupported),
+        let bundle = options
+            .resolution_bundle
+            .as_ref()
+            .filter(|bundle| bundle.contains(uri));
+        let resolution_result = match (bundle, did.method.as_str()) {
+            (Some(bundle), _) => bundle.resolve(uri).await,
+            (None, "jwk") => DidJwk::resolve(uri),
+            (None, "dht") => DidDht::resolve_with_options(uri, None, options).await,
+            (None, "web") => DidWeb::resolve(uri).await,
+            (None, _) => match &options.universal_resolver {
+                Some(universal_resolver) => universal_resolver.resolve(uri).await,
+                None => ResolutionResult::from(ResolutionMetadataError::MethodNotSupported),
+            },
+        };
+
+        if let (Some(change_log), None, Some(document)) = (
+            &options.document_change_log,
+            &resolution_result.resolution_metadata.error,
+            &resolution_result.document,
+        ) {
+            // recording is best effort, and must not fail the resolution
+            let _ = change_log.record(document);
+        }
+
+        resolution_result
+    }
+}
+
+impl ResolutionResult {
+    /// Validates the resolved DID document, replacing the result with an `InvalidDidDocument`
+    /// error describing each violation if the document isn't conformant.
+    pub(crate) fn validated(self) -> Self {
+        let violations = match &self.document {
+            Some(document) => document.validate(),
+            None => return self,
+        };
+        if violations.is_empty() {
+            return self;
+        }
+
+        Self {
+            resolution_metadata: ResolutionMetadata {
+                error: Some(ResolutionMetadataError::InvalidDidDocument),
+                error_message: Some(
+                    violations
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
         }
     }
 }
@@ -84,7 +155,10 @@ impl From<ResolutionMetadataError> for ResolutionResult {
     /// ```
     fn from(error: ResolutionMetadataError) -> Self {
         Self {
-            resolution_metadata: ResolutionMetadata { error: Some(error) },
+            resolution_metadata: ResolutionMetadata {
+                error: Some(error),
+                ..Default::default()
+            },
             ..Default::default()
         }
     }
@@ -93,7 +167,11 @@ impl From<ResolutionMetadataError> for ResolutionResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dids::resolution::{
+        document_change_log::DocumentChangeLog, universal_resolver::UniversalResolverClient,
+    };
     use mockito::Server;
+    use std::sync::Arc;
 
     mod resolve {
         use super::*;
@@ -137,6 +215,67 @@ mod tests {
             assert_eq!(resolution_result.document.unwrap(), bearer_did.document);
         }
 
+        #[tokio::test]
+        async fn test_records_changes_in_change_log() {
+            let mut mock_server = Server::new_async().await;
+            let bearer_did = DidWeb::create(&mock_server.url(), None).unwrap();
+            let mut rotated = bearer_did.document.clone();
+            rotated.verification_method[0].public_key_jwk =
+                DidJwk::create(None).unwrap().document.verification_method[0]
+                    .public_key_jwk
+                    .clone();
+
+            let change_log = Arc::new(DocumentChangeLog::new());
+            let options = ResolutionOptions {
+                document_change_log: Some(change_log.clone()),
+                ..Default::default()
+            };
+
+            let mock = mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_body(serde_json::to_string(&bearer_did.document).unwrap())
+                .create();
+            ResolutionResult::resolve_with_options(&bearer_did.did.uri, &options).await;
+            mock.remove();
+            let _ = mock_server
+                .mock("GET", "/.well-known/did.json")
+                .with_status(200)
+                .with_body(serde_json::to_string(&rotated).unwrap())
+                .create();
+            ResolutionResult::resolve_with_options(&bearer_did.did.uri, &options).await;
+
+            let changes = change_log.changes(&bearer_did.did.uri).unwrap();
+            assert_eq!(changes.len(), 1);
+            assert!(changes[0].diff.has_key_changes());
+            assert_eq!(
+                change_log.document(&bearer_did.did.uri).unwrap(),
+                Some(rotated)
+            );
+        }
+
+        #[tokio::test]
+        async fn test_delegates_to_universal_resolver() {
+            let mut mock_server = Server::new_async().await;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123")
+                .with_status(200)
+                .with_body(r#"{"didDocument":{"id":"did:example:123","verificationMethod":[]}}"#)
+                .create();
+            let options = ResolutionOptions {
+                universal_resolver: Some(Arc::new(UniversalResolverClient::new(
+                    &mock_server.url(),
+                ))),
+                ..Default::default()
+            };
+
+            let resolution_result =
+                ResolutionResult::resolve_with_options("did:example:123", &options).await;
+
+            assert_eq!(resolution_result.resolution_metadata.error, None);
+            assert_eq!(resolution_result.document.unwrap().id, "did:example:123");
+        }
+
         #[tokio::test]
         async fn test_method_not_supported() {
             let resolution_result = ResolutionResult::resolve("did:example:123").await;
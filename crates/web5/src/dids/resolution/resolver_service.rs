@@ -0,0 +1,514 @@
+use super::{
+    resolution_metadata::ResolutionMetadataError,
+    resolution_options::ResolutionOptions,
+    resolution_result::ResolutionResult,
+    universal_resolver::{
+        UniversalResolutionResult, DID_JSON_MEDIA_TYPE, DID_LD_JSON_MEDIA_TYPE,
+        DID_RESOLUTION_MEDIA_TYPE,
+    },
+};
+use crate::errors::Result;
+use async_trait::async_trait;
+use http_std::{Handler, Method, Request, Response, Server};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc};
+
+const IDENTIFIERS_PATH: &str = "/1.0/identifiers/";
+const DID_CORE_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+
+/// The representation of a resolution answer selected via the `Accept` header.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Representation {
+    /// The full DID Resolution Result, including resolution and document metadata.
+    ResolutionResult,
+    /// The DID document as `application/did+json`.
+    DidJson,
+    /// The DID document as `application/did+ld+json`, with an `@context`.
+    DidLdJson,
+}
+
+impl Representation {
+    /// Selects the representation to respond with from an HTTP `Accept` header value, honouring
+    /// `q` weights. A missing header selects the full DID Resolution Result.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Self>` - The selected representation, or `None` if no acceptable representation
+    ///   is supported.
+    pub fn negotiate(accept: Option<&str>) -> Option<Self> {
+        let accept = match accept {
+            None => return Some(Representation::ResolutionResult),
+            Some(accept) if accept.trim().is_empty() => {
+                return Some(Representation::ResolutionResult)
+            }
+            Some(accept) => accept,
+        };
+
+        let mut candidates = accept
+            .split(',')
+            .filter_map(|media_range| {
+                let mut parts = media_range.split(';').map(|part| part.trim());
+                let media_type = parts.next()?.to_ascii_lowercase();
+
+                let mut quality = 1.0_f32;
+                let mut profile = None;
+                for param in parts {
+                    if let Some((key, value)) = param.split_once('=') {
+                        let value = value.trim().trim_matches('"');
+                        match key.trim() {
+                            "q" => quality = value.parse().unwrap_or(0.0),
+                            "profile" => profile = Some(value.to_string()),
+                            _ => {}
+                        }
+                    }
+                }
+
+                let representation = match (media_type.as_str(), profile.as_deref()) {
+                    ("application/did+ld+json", _) => Representation::DidLdJson,
+                    ("application/did+json", _) => Representation::DidJson,
+                    ("application/ld+json", Some(profile))
+                        if profile.contains("https://w3id.org/did-resolution") =>
+                    {
+                        Representation::ResolutionResult
+                    }
+                    ("application/ld+json", _) => Representation::DidLdJson,
+                    ("application/did-resolution", _)
+                    | ("application/json", _)
+                    | ("application/*", _)
+                    | ("*/*", _) => Representation::ResolutionResult,
+                    _ => return None,
+                };
+
+                if quality <= 0.0 {
+                    return None;
+                }
+                Some((representation, quality))
+            })
+            .collect::<Vec<_>>();
+
+        // stable sort, so that equally weighted media ranges keep the client's order
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        candidates.into_iter().next().map(|(r, _)| r)
+    }
+
+    fn media_type(&self) -> &'static str {
+        match self {
+            Representation::ResolutionResult => DID_RESOLUTION_MEDIA_TYPE,
+            Representation::DidJson => DID_JSON_MEDIA_TYPE,
+            Representation::DidLdJson => DID_LD_JSON_MEDIA_TYPE,
+        }
+    }
+}
+
+/// An HTTP service compatible with the [Universal Resolver](https://github.com/decentralized-identity/universal-resolver)
+/// API, answering `GET /1.0/identifiers/{did}` with the result of `ResolutionResult::resolve`.
+///
+/// DIDs are resolved with the given resolution options, or with those configured via
+/// `resolution_options::set_default_resolution_options` if not provided.
+#[derive(Default)]
+pub struct ResolverService {
+    /// The options to resolve DIDs with.
+    pub resolution_options: Option<ResolutionOptions>,
+}
+
+impl ResolverService {
+    /// Binds to the given address and serves DID resolution requests until an error occurs.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The socket address to listen on, e.g. `0.0.0.0:8080`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// ResolverService::default().serve("0.0.0.0:8080").await?;
+    /// ```
+    pub async fn serve(self, addr: &str) -> Result<()> {
+        let server = Server::bind(addr).await?;
+        server.serve(Arc::new(self)).await?;
+        Ok(())
+    }
+
+    async fn resolve(&self, identifier: &str, accept: Option<&str>) -> Response {
+        let representation = match Representation::negotiate(accept) {
+            Some(representation) => representation,
+            None => {
+                return error_response(ResolutionMetadataError::RepresentationNotSupported);
+            }
+        };
+
+        let resolution_result = match &self.resolution_options {
+            Some(options) => ResolutionResult::resolve_with_options(identifier, options).await,
+            None => ResolutionResult::resolve(identifier).await,
+        };
+        if let Some(error) = &resolution_result.resolution_metadata.error {
+            return error_response(error.clone());
+        }
+
+        let deactivated = resolution_result
+            .document_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.deactivated)
+            .unwrap_or(false);
+
+        let mut document = match &resolution_result.document {
+            Some(document) => document.clone(),
+            None => return error_response(ResolutionMetadataError::InternalError),
+        };
+
+        let status_code = if deactivated { 410 } else { 200 };
+        match representation {
+            Representation::ResolutionResult => json_response(
+                status_code,
+                DID_RESOLUTION_MEDIA_TYPE,
+                &UniversalResolutionResult::new(
+                    resolution_result,
+                    Some(DID_LD_JSON_MEDIA_TYPE.to_string()),
+                ),
+            ),
+            Representation::DidJson => {
+                json_response(status_code, representation.media_type(), &document)
+            }
+            Representation::DidLdJson => {
+                let context = document.context.get_or_insert_with(Vec::new);
+                if !context.iter().any(|c| c == DID_CORE_CONTEXT) {
+                    context.insert(0, DID_CORE_CONTEXT.to_string());
+                }
+                json_response(status_code, representation.media_type(), &document)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for ResolverService {
+    async fn handle(&self, request: Request) -> Response {
+        let identifier = match request.path.strip_prefix(IDENTIFIERS_PATH) {
+            Some(identifier) if !identifier.is_empty() => identifier,
+            _ => return text_response(404, "not found"),
+        };
+
+        if !matches!(request.method, Method::Get) {
+            return text_response(405, "method not allowed");
+        }
+
+        // the DID may be sent percent-encoded in its entirety, in which case it won't start with
+        // a literal `did:` and must be decoded; otherwise, it's used as-is so that percent-encoded
+        // characters within the DID (e.g. the port of a did:web) are preserved
+        let identifier = if identifier.starts_with("did:") {
+            identifier.to_string()
+        } else {
+            percent_decode_str(identifier)
+                .decode_utf8_lossy()
+                .to_string()
+        };
+
+        self.resolve(&identifier, request.header("Accept")).await
+    }
+}
+
+fn error_response(error: ResolutionMetadataError) -> Response {
+    json_response(
+        error.http_status_code(),
+        DID_RESOLUTION_MEDIA_TYPE,
+        &UniversalResolutionResult::new(ResolutionResult::from(error), None),
+    )
+}
+
+fn json_response<T: Serialize>(status_code: u16, content_type: &str, body: &T) -> Response {
+    match serde_json::to_vec(body) {
+        Ok(body) => {
+            let mut headers = HashMap::new();
+            headers.insert("Content-Type".to_string(), content_type.to_string());
+            Response {
+                status_code,
+                headers,
+                body,
+            }
+        }
+        Err(_) => text_response(500, "failed to serialize response"),
+    }
+}
+
+fn text_response(status_code: u16, body: &str) -> Response {
+    let mut headers = HashMap::new();
+    headers.insert("Content-Type".to_string(), "text/plain".to_string());
+    Response {
+        status_code,
+        headers,
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::{
+        data_model::document::Document,
+        methods::did_jwk::DidJwk,
+        resolution::{
+            document_metadata::DocumentMetadata, resolution_bundle::ResolutionBundle,
+            universal_resolver::DID_RESOLUTION_CONTEXT,
+        },
+    };
+    use serde_json::Value;
+
+    fn get(path: &str, accept: Option<&str>) -> Request {
+        let mut headers = HashMap::new();
+        if let Some(accept) = accept {
+            headers.insert("accept".to_string(), accept.to_string());
+        }
+        Request {
+            method: Method::Get,
+            path: path.to_string(),
+            query: None,
+            headers,
+            body: vec![],
+        }
+    }
+
+    fn content_type(response: &Response) -> &str {
+        response.headers.get("Content-Type").unwrap()
+    }
+
+    mod negotiate {
+        use super::*;
+
+        #[test]
+        fn test_defaults_to_resolution_result() {
+            assert_eq!(
+                Representation::negotiate(None),
+                Some(Representation::ResolutionResult)
+            );
+            assert_eq!(
+                Representation::negotiate(Some("*/*")),
+                Some(Representation::ResolutionResult)
+            );
+        }
+
+        #[test]
+        fn test_media_types() {
+            assert_eq!(
+                Representation::negotiate(Some("application/did+json")),
+                Some(Representation::DidJson)
+            );
+            assert_eq!(
+                Representation::negotiate(Some("application/did+ld+json")),
+                Some(Representation::DidLdJson)
+            );
+            assert_eq!(
+                Representation::negotiate(Some(
+                    "application/ld+json;profile=\"https://w3id.org/did-resolution\""
+                )),
+                Some(Representation::ResolutionResult)
+            );
+        }
+
+        #[test]
+        fn test_quality_weights() {
+            assert_eq!(
+                Representation::negotiate(Some(
+                    "application/did+ld+json;q=0.5, application/did+json"
+                )),
+                Some(Representation::DidJson)
+            );
+            assert_eq!(
+                Representation::negotiate(Some("application/did+json;q=0, text/html")),
+                None
+            );
+        }
+
+        #[test]
+        fn test_unsupported() {
+            assert_eq!(Representation::negotiate(Some("text/html")), None);
+        }
+    }
+
+    mod handle {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_resolution_result() {
+            let bearer_did = DidJwk::create(None).unwrap();
+
+            let response = ResolverService::default()
+                .handle(get(
+                    &format!("/1.0/identifiers/{}", bearer_did.did.uri),
+                    None,
+                ))
+                .await;
+
+            assert_eq!(response.status_code, 200);
+            assert_eq!(content_type(&response), DID_RESOLUTION_MEDIA_TYPE);
+
+            let body: Value = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(body["@context"], DID_RESOLUTION_CONTEXT);
+            assert_eq!(
+                body["didResolutionMetadata"]["contentType"],
+                DID_LD_JSON_MEDIA_TYPE
+            );
+            assert!(body.get("didDocumentMetadata").is_some());
+
+            let document: Document = serde_json::from_value(body["didDocument"].clone()).unwrap();
+            assert_eq!(document, bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_did_ld_json() {
+            let bearer_did = DidJwk::create(None).unwrap();
+
+            let response = ResolverService::default()
+                .handle(get(
+                    &format!("/1.0/identifiers/{}", bearer_did.did.uri),
+                    Some(DID_LD_JSON_MEDIA_TYPE),
+                ))
+                .await;
+
+            assert_eq!(response.status_code, 200);
+            assert_eq!(content_type(&response), DID_LD_JSON_MEDIA_TYPE);
+
+            let document: Document = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(document.id, bearer_did.did.uri);
+            assert_eq!(document.context.unwrap()[0], DID_CORE_CONTEXT);
+        }
+
+        #[tokio::test]
+        async fn test_percent_encoded_did() {
+            let bearer_did = DidJwk::create(None).unwrap();
+            let encoded = bearer_did.did.uri.replace(':', "%3A");
+
+            let response = ResolverService::default()
+                .handle(get(
+                    &format!("/1.0/identifiers/{}", encoded),
+                    Some(DID_JSON_MEDIA_TYPE),
+                ))
+                .await;
+
+            assert_eq!(response.status_code, 200);
+            let document: Document = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(document, bearer_did.document);
+        }
+
+        #[tokio::test]
+        async fn test_malformed_percent_encoding() {
+            let response = ResolverService::default()
+                .handle(get("/1.0/identifiers/%é9", None))
+                .await;
+
+            assert_eq!(response.status_code, 400);
+            let body: Value = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(body["didResolutionMetadata"]["error"], "invalidDid");
+        }
+
+        #[tokio::test]
+        async fn test_deactivated_did() {
+            let bearer_did = DidJwk::create(None).unwrap();
+            let mut bundle = ResolutionBundle::default();
+            bundle.resolution_results.insert(
+                bearer_did.did.uri.clone(),
+                ResolutionResult {
+                    document: Some(bearer_did.document.clone()),
+                    document_metadata: Some(DocumentMetadata {
+                        deactivated: Some(true),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            );
+            let service = ResolverService {
+                resolution_options: Some(ResolutionOptions {
+                    resolution_bundle: Some(Arc::new(bundle)),
+                    ..Default::default()
+                }),
+            };
+
+            for accept in [
+                None,
+                Some(DID_JSON_MEDIA_TYPE),
+                Some(DID_LD_JSON_MEDIA_TYPE),
+            ] {
+                let response = service
+                    .handle(get(
+                        &format!("/1.0/identifiers/{}", bearer_did.did.uri),
+                        accept,
+                    ))
+                    .await;
+                assert_eq!(response.status_code, 410);
+            }
+        }
+
+        #[tokio::test]
+        async fn test_invalid_did() {
+            let response = ResolverService::default()
+                .handle(get("/1.0/identifiers/did:invalid", None))
+                .await;
+
+            assert_eq!(response.status_code, 400);
+            let body: Value = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(body["didResolutionMetadata"]["error"], "invalidDid");
+            assert_eq!(body["didDocument"], Value::Null);
+        }
+
+        #[tokio::test]
+        async fn test_method_not_supported() {
+            let response = ResolverService::default()
+                .handle(get("/1.0/identifiers/did:example:123", None))
+                .await;
+
+            assert_eq!(response.status_code, 501);
+            let body: Value = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(body["didResolutionMetadata"]["error"], "methodNotSupported");
+        }
+
+        #[tokio::test]
+        async fn test_representation_not_supported() {
+            let bearer_did = DidJwk::create(None).unwrap();
+
+            let response = ResolverService::default()
+                .handle(get(
+                    &format!("/1.0/identifiers/{}", bearer_did.did.uri),
+                    Some("text/html"),
+                ))
+                .await;
+
+            assert_eq!(response.status_code, 406);
+            let body: Value = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(
+                body["didResolutionMetadata"]["error"],
+                "representationNotSupported"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_unknown_path() {
+            let response = ResolverService::default()
+                .handle(get("/1.0/other", None))
+                .await;
+            assert_eq!(response.status_code, 404);
+        }
+    }
+
+    mod serve {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_over_http() {
+            let server = Server::bind("127.0.0.1:0").await.unwrap();
+            let addr = server.local_addr().unwrap();
+            tokio::spawn(server.serve(Arc::new(ResolverService::default())));
+
+            let bearer_did = DidJwk::create(None).unwrap();
+            let response = http_std::fetch(
+                &format!("http://{}/1.0/identifiers/{}", addr, bearer_did.did.uri),
+                None,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(response.status_code, 200);
+            let body: UniversalResolutionResult = serde_json::from_slice(&response.body).unwrap();
+            assert_eq!(body.did_document, Some(bearer_did.document));
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use super::{
+    document_metadata::DocumentMetadata,
+    resolution_metadata::{ResolutionMetadata, ResolutionMetadataError},
+    resolution_result::ResolutionResult,
+};
+use crate::dids::data_model::document::Document;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The JSON-LD context of a DID Resolution Result.
+pub const DID_RESOLUTION_CONTEXT: &str = "https://w3id.org/did-resolution/v1";
+
+// the DID is sent percent-encoded in its entirety, leaving only unreserved characters
+const DID_PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The media type of a full DID Resolution Result.
+pub const DID_RESOLUTION_MEDIA_TYPE: &str =
+    "application/ld+json;profile=\"https://w3id.org/did-resolution\"";
+
+/// The media type of a plain JSON DID document representation.
+pub const DID_JSON_MEDIA_TYPE: &str = "application/did+json";
+
+/// The media type of a JSON-LD DID document representation.
+pub const DID_LD_JSON_MEDIA_TYPE: &str = "application/did+ld+json";
+
+/// DID resolution metadata in the shape returned by the Universal Resolver.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UniversalResolutionMetadata {
+    /// The media type of the returned representation.
+    #[serde(rename = "contentType", skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+
+    /// The error code, if resolution failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// A human-readable description of the error, if resolution failed.
+    #[serde(rename = "errorMessage", skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+
+    /// The urls of the linked resources which "did:dht" services are offloaded to but which
+    /// weren't fetched.
+    #[serde(
+        rename = "unresolvedLinkedResources",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unresolved_linked_resources: Option<Vec<String>>,
+}
+
+/// A DID Resolution Result as served by the Universal Resolver at `GET /1.0/identifiers/{did}`.
+///
+/// This is the wire representation of a [`ResolutionResult`], using the property names from the
+/// [DID Resolution specification](https://w3c-ccg.github.io/did-resolution/#did-resolution-result).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UniversalResolutionResult {
+    /// The JSON-LD context of the resolution result.
+    #[serde(rename = "@context", skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+
+    /// The resolved DID document, if the resolution was successful.
+    #[serde(rename = "didDocument")]
+    pub did_document: Option<Document>,
+
+    /// Metadata about the DID resolution process.
+    #[serde(rename = "didResolutionMetadata", default)]
+    pub did_resolution_metadata: UniversalResolutionMetadata,
+
+    /// Metadata about the resolved DID document.
+    #[serde(rename = "didDocumentMetadata", default)]
+    pub did_document_metadata: Option<DocumentMetadata>,
+}
+
+impl UniversalResolutionResult {
+    /// Builds the wire representation of a `ResolutionResult` for the given content type.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution_result` - The result of resolving a DID.
+    /// * `content_type` - The media type of the DID document representation.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The resolution result with Universal Resolver property names.
+    pub fn new(resolution_result: ResolutionResult, content_type: Option<String>) -> Self {
+        let error = resolution_result.resolution_metadata.error;
+        Self {
+            context: Some(DID_RESOLUTION_CONTEXT.to_string()),
+            did_document: resolution_result.document,
+            did_resolution_metadata: UniversalResolutionMetadata {
+                content_type: match error {
+                    None => content_type,
+                    Some(_) => None,
+                },
+                error: error.as_ref().map(error_code),
+                error_message: resolution_result
+                    .resolution_metadata
+                    .error_message
+                    .or_else(|| error.as_ref().map(|e| e.to_string())),
+                unresolved_linked_resources: resolution_result
+                    .resolution_metadata
+                    .unresolved_linked_resources,
+            },
+            did_document_metadata: Some(resolution_result.document_metadata.unwrap_or_default()),
+        }
+    }
+}
+
+impl From<UniversalResolutionResult> for ResolutionResult {
+    fn from(value: UniversalResolutionResult) -> Self {
+        let error = value
+            .did_resolution_metadata
+            .error
+            .map(|code| parse_error_code(&code));
+        let error_message = value
+            .did_resolution_metadata
+            .error_message
+            .filter(|_| error.is_some());
+        let document_metadata = value
+            .did_document_metadata
+            .filter(|metadata| metadata != &DocumentMetadata::default());
+
+        ResolutionResult {
+            resolution_metadata: ResolutionMetadata {
+                error,
+                error_message,
+                unresolved_linked_resources: value
+                    .did_resolution_metadata
+                    .unresolved_linked_resources,
+            },
+            document: value.did_document,
+            document_metadata,
+        }
+    }
+}
+
+fn error_code(error: &ResolutionMetadataError) -> String {
+    match serde_json::to_value(error) {
+        Ok(Value::String(code)) => code,
+        _ => "internalError".to_string(),
+    }
+}
+
+fn parse_error_code(code: &str) -> ResolutionMetadataError {
+    serde_json::from_value(Value::String(code.to_string())).unwrap_or(match code {
+        // error codes used by Universal Resolver drivers which predate DID Core
+        "unsupportedDidMethod" => ResolutionMetadataError::MethodNotSupported,
+        _ => ResolutionMetadataError::InternalError,
+    })
+}
+
+/// A client for a [Universal Resolver](https://github.com/decentralized-identity/universal-resolver)
+/// instance, used to resolve DID methods which are not implemented natively.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniversalResolverClient {
+    base_url: String,
+}
+
+impl UniversalResolverClient {
+    /// Creates a client for the Universal Resolver instance at the given base URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the resolver, e.g. `https://dev.uniresolver.io`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let client = UniversalResolverClient::new("https://dev.uniresolver.io");
+    /// let result = client.resolve("did:key:z6Mk...").await;
+    /// ```
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Resolves a DID URI by calling `GET {base_url}/1.0/identifiers/{did}`, with the DID
+    /// percent-encoded. The resolved document is validated as documents resolved natively are.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The DID URI to resolve.
+    ///
+    /// # Returns
+    ///
+    /// * `ResolutionResult` - The resolved DID document or an error in the resolution metadata.
+    pub async fn resolve(&self, uri: &str) -> ResolutionResult {
+        self.fetch(uri).await.validated()
+    }
+
+    async fn fetch(&self, uri: &str) -> ResolutionResult {
+        let url = format!(
+            "{}/1.0/identifiers/{}",
+            self.base_url,
+            utf8_percent_encode(uri, DID_PATH_SEGMENT)
+        );
+
+        let mut headers = HashMap::new();
+        headers.insert("Accept".to_string(), DID_RESOLUTION_MEDIA_TYPE.to_string());
+
+        let response = match http_std::fetch(
+            &url,
+            Some(http_std::FetchOptions {
+                headers: Some(headers),
+                ..Default::default()
+            }),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(_) => return ResolutionResult::from(ResolutionMetadataError::InternalError),
+        };
+
+        let body = match serde_json::from_slice::<Value>(&response.body) {
+            Ok(body) => body,
+            Err(_) => {
+                return ResolutionResult::from(ResolutionMetadataError::from_http_status_code(
+                    response.status_code,
+                ))
+            }
+        };
+
+        if body.get("didDocument").is_some() || body.get("didResolutionMetadata").is_some() {
+            return match serde_json::from_value::<UniversalResolutionResult>(body) {
+                Ok(result) => {
+                    let mut result = ResolutionResult::from(result);
+                    if result.resolution_metadata.error.is_none() && result.document.is_none() {
+                        result.resolution_metadata.error = Some(
+                            ResolutionMetadataError::from_http_status_code(response.status_code),
+                        );
+                    }
+                    result
+                }
+                Err(_) => ResolutionResult::from(ResolutionMetadataError::InvalidDidDocument),
+            };
+        }
+
+        if !(200..300).contains(&response.status_code) {
+            return ResolutionResult::from(ResolutionMetadataError::from_http_status_code(
+                response.status_code,
+            ));
+        }
+
+        // some drivers answer with a bare DID document
+        match serde_json::from_value::<Document>(body) {
+            Ok(document) => ResolutionResult {
+                document: Some(document),
+                ..Default::default()
+            },
+            Err(_) => ResolutionResult::from(ResolutionMetadataError::InvalidDidDocument),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    mod resolve {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_resolution_result() {
+            let mut mock_server = Server::new_async().await;
+            let body = r#"{
+                "@context": "https://w3id.org/did-resolution/v1",
+                "didDocument": {
+                    "id": "did:example:123",
+                    "verificationMethod": []
+                },
+                "didResolutionMetadata": { "contentType": "application/did+ld+json" },
+                "didDocumentMetadata": { "versionId": "1" }
+            }"#;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123")
+                .match_header("accept", DID_RESOLUTION_MEDIA_TYPE)
+                .with_status(200)
+                .with_body(body)
+                .create();
+
+            let client = UniversalResolverClient::new(&mock_server.url());
+            let result = client.resolve("did:example:123").await;
+
+            assert_eq!(result.resolution_metadata.error, None);
+            assert_eq!(result.document.unwrap().id, "did:example:123");
+            assert_eq!(
+                result.document_metadata.unwrap().version_id,
+                Some("1".to_string())
+            );
+        }
+
+        #[tokio::test]
+        async fn test_bare_document() {
+            let mut mock_server = Server::new_async().await;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123")
+                .with_status(200)
+                .with_body(r#"{"id":"did:example:123","verificationMethod":[]}"#)
+                .create();
+
+            let client = UniversalResolverClient::new(&mock_server.url());
+            let result = client.resolve("did:example:123").await;
+
+            assert_eq!(result.resolution_metadata.error, None);
+            assert_eq!(result.document.unwrap().id, "did:example:123");
+            assert_eq!(result.document_metadata, None);
+        }
+
+        #[tokio::test]
+        async fn test_percent_encodes_did() {
+            let mut mock_server = Server::new_async().await;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123%3Fversion%2F1")
+                .with_status(200)
+                .with_body(r#"{"id":"did:example:123","verificationMethod":[]}"#)
+                .create();
+
+            let client = UniversalResolverClient::new(&mock_server.url());
+            let result = client.resolve("did:example:123?version/1").await;
+
+            assert_eq!(result.resolution_metadata.error, None);
+        }
+
+        #[tokio::test]
+        async fn test_invalid_document() {
+            let mut mock_server = Server::new_async().await;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123")
+                .with_status(200)
+                .with_body(r#"{"id":"not-a-did","verificationMethod":[]}"#)
+                .create();
+
+            let client = UniversalResolverClient::new(&mock_server.url());
+            let result = client.resolve("did:example:123").await;
+
+            assert_eq!(
+                result.resolution_metadata.error,
+                Some(ResolutionMetadataError::InvalidDidDocument)
+            );
+            assert_eq!(result.document, None);
+        }
+
+        #[tokio::test]
+        async fn test_error_in_metadata() {
+            let mut mock_server = Server::new_async().await;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123")
+                .with_status(404)
+                .with_body(r#"{"didDocument":null,"didResolutionMetadata":{"error":"notFound"}}"#)
+                .create();
+
+            let client = UniversalResolverClient::new(&mock_server.url());
+            let result = client.resolve("did:example:123").await;
+
+            assert_eq!(
+                result.resolution_metadata.error,
+                Some(ResolutionMetadataError::NotFound)
+            );
+            assert_eq!(result.document, None);
+        }
+
+        #[tokio::test]
+        async fn test_error_status_without_body() {
+            let mut mock_server = Server::new_async().await;
+            let _ = mock_server
+                .mock("GET", "/1.0/identifiers/did%3Aexample%3A123")
+                .with_status(501)
+                .create();
+
+            let client = UniversalResolverClient::new(&mock_server.url());
+            let result = client.resolve("did:example:123").await;
+
+            assert_eq!(
+                result.resolution_metadata.error,
+                Some(ResolutionMetadataError::MethodNotSupported)
+            );
+        }
+    }
+
+    mod universal_resolution_result {
+        use super::*;
+
+        #[test]
+        fn test_error_serialization() {
+            let result = UniversalResolutionResult::new(
+                ResolutionResult::from(ResolutionMetadataError::InvalidDid),
+                Some(DID_LD_JSON_MEDIA_TYPE.to_string()),
+            );
+            let json = serde_json::to_value(&result).unwrap();
+
+            assert_eq!(json["@context"], DID_RESOLUTION_CONTEXT);
+            assert_eq!(json["didDocument"], Value::Null);
+            assert_eq!(json["didResolutionMetadata"]["error"], "invalidDid");
+            assert!(json["didResolutionMetadata"].get("contentType").is_none());
+            assert_eq!(json["didDocumentMetadata"], serde_json::json!({}));
+        }
+
+        #[test]
+        fn test_unknown_error_code() {
+            let result = ResolutionResult::from(UniversalResolutionResult {
+                did_resolution_metadata: UniversalResolutionMetadata {
+                    error: Some("somethingElse".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            assert_eq!(
+                result.resolution_metadata.error,
+                Some(ResolutionMetadataError::InternalError)
+            );
+        }
+    }
+}
@@ -30,6 +30,10 @@ pub enum Web5Error {
     Network(String),
     #[error("datetime error {0}")]
     DateTime(String),
+    #[error("json-ld error {0}")]
+    JsonLd(String),
+    #[error("rdf error {0}")]
+    Rdf(String),
 
     #[error(transparent)]
     Http(#[from] HttpError),
@@ -0,0 +1,176 @@
+use crate::errors::{Result, Web5Error};
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// Serializes a value per the [JSON Canonicalization Scheme (RFC 8785)](https://www.rfc-editor.org/rfc/rfc8785).
+///
+/// Object members are sorted by the UTF-16 code units of their names, strings are escaped
+/// minimally, numbers are formatted as ECMAScript would, and no whitespace is emitted.
+///
+/// # Arguments
+///
+/// * `value` - Any value which serializes to JSON.
+///
+/// # Returns
+///
+/// * `Result<String>` - The canonical JSON text.
+///
+/// # Examples
+///
+/// ```ignore
+/// let canonical = canonicalize(&serde_json::json!({ "b": 1.50, "a": [true, null] }))?;
+/// assert_eq!(canonical, r#"{"a":[true,null],"b":1.5}"#);
+/// ```
+pub fn canonicalize<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    let value = serde_json::to_value(value)?;
+    let mut canonical = String::new();
+    write_value(&value, &mut canonical)?;
+    Ok(canonical)
+}
+
+fn write_value(value: &Value, out: &mut String) -> Result<()> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&format_number(n)?),
+        Value::String(s) => out.push_str(&serde_json::to_string(s)?),
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(value, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<(&String, &Value)> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(name)?);
+                out.push(':');
+                write_value(value, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+/// Formats a number as ECMAScript's `Number.prototype.toString` would, which is how RFC 8785
+/// defines the canonical form of a number.
+fn format_number(number: &Number) -> Result<String> {
+    let value = number
+        .as_f64()
+        .ok_or_else(|| Web5Error::Json(format!("{} is not an IEEE 754 number", number)))?;
+    if !value.is_finite() {
+        return Err(Web5Error::Json(format!("{} is not a finite number", value)));
+    }
+    if value == 0.0 {
+        return Ok("0".to_string());
+    }
+
+    // Rust's exponential formatting yields the shortest digits which round trip, which are the
+    // digits ECMAScript uses; only their placement differs.
+    let exponential = format!("{:e}", value.abs());
+    let (mantissa, exponent) = exponential
+        .split_once('e')
+        .ok_or_else(|| Web5Error::Json(format!("unable to format {}", value)))?;
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent
+        .parse()
+        .map_err(|_| Web5Error::Json(format!("unable to format {}", value)))?;
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let formatted = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        match k {
+            1 => format!("{}e{}{}", digits, sign, (n - 1).abs()),
+            _ => format!(
+                "{}.{}e{}{}",
+                &digits[..1],
+                &digits[1..],
+                sign,
+                (n - 1).abs()
+            ),
+        }
+    };
+
+    Ok(match value.is_sign_negative() {
+        true => format!("-{}", formatted),
+        false => formatted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    mod canonicalize {
+        use super::*;
+
+        #[test]
+        fn test_rfc_8785_example() {
+            let value: Value = serde_json::from_str(
+                r#"{
+                    "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                    "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+                    "literals": [null, true, false]
+                }"#,
+            )
+            .unwrap();
+
+            assert_eq!(
+                canonicalize(&value).unwrap(),
+                r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+            );
+        }
+
+        #[test]
+        fn test_sorts_by_utf16_code_units() {
+            // U+1F600 is encoded as a surrogate pair, which sorts before U+FB33 in UTF-16 but
+            // after it in UTF-8.
+            let value = json!({ "\u{fb33}": 1, "\u{1f600}": 2, "a": 3, "\r": 4 });
+            assert_eq!(
+                canonicalize(&value).unwrap(),
+                "{\"\\r\":4,\"a\":3,\"\u{1f600}\":2,\"\u{fb33}\":1}"
+            );
+        }
+
+        #[test]
+        fn test_numbers() {
+            let cases = [
+                (json!(0), "0"),
+                (json!(-0.0), "0"),
+                (json!(1), "1"),
+                (json!(-1.5), "-1.5"),
+                (json!(1e21), "1e+21"),
+                (json!(1e20), "100000000000000000000"),
+                (json!(1.5e-7), "1.5e-7"),
+                (json!(0.000001), "0.000001"),
+                (json!(123456789.5), "123456789.5"),
+                (json!(9007199254740992_u64), "9007199254740992"),
+                (json!(295147905179352825856.0), "295147905179352830000"),
+                (json!(5e-324), "5e-324"),
+                (json!(-1.7976931348623157e308), "-1.7976931348623157e+308"),
+            ];
+            for (value, expected) in cases {
+                assert_eq!(canonicalize(&value).unwrap(), expected);
+            }
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
     credentials::VerificationError,
-    crypto::dsa::{ed25519::Ed25519Verifier, secp256k1::Secp256k1Verifier, Dsa, Verifier},
+    crypto::{dsa::verifier_for_public_jwk, jwk::Jwk},
     datetime::{deserialize_optional_unix_timestamp, serialize_optional_unix_timestamp},
     dids::{
-        bearer_did::BearerDid, data_model::document::FindVerificationMethodOptions,
+        bearer_did::{BearerDid, SignerOptions},
+        data_model::document::{Document, FindVerificationMethodOptions, VerificationRelationship},
         resolution::resolution_result::ResolutionResult,
     },
     errors::{Result, Web5Error},
@@ -11,7 +12,7 @@ use crate::{
 };
 use base64::Engine;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, str::FromStr, sync::Arc, time::SystemTime};
+use std::{collections::HashMap, time::SystemTime};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct JoseHeader {
@@ -21,11 +22,19 @@ pub struct JoseHeader {
     pub alg: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cty: Option<String>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<HashMap<String, JsonValue>>,
 }
 
 impl FromJson for JoseHeader {}
 impl ToJson for JoseHeader {}
 
+/// Header parameters with a dedicated [`JoseHeader`] field, which must not be repeated in
+/// `additional_properties`.
+const RESERVED_HEADER_PARAMETERS: [&str; 4] = ["typ", "alg", "kid", "cty"];
+
 pub struct Jws {
     pub kid: String,
     pub parts: Vec<String>,
@@ -42,29 +51,64 @@ impl Jws {
         bearer_did: &BearerDid,
         verification_method_id: Option<String>,
     ) -> Result<Self> {
-        let verification_method_id = verification_method_id
-            .unwrap_or_else(|| bearer_did.document.verification_method[0].id.clone());
-
-        let is_assertion_method =
-            if let Some(assertion_methods) = &bearer_did.document.assertion_method {
-                assertion_methods.contains(&verification_method_id)
-            } else {
-                false
-            };
+        Self::from_payload_with_options(
+            payload,
+            bearer_did,
+            &SignerOptions {
+                verification_method_id,
+                verification_relationship: Some(VerificationRelationship::AssertionMethod),
+                algorithms: None,
+            },
+        )
+    }
 
-        if !is_assertion_method {
+    pub fn from_payload_with_options(
+        payload: &[u8],
+        bearer_did: &BearerDid,
+        options: &SignerOptions,
+    ) -> Result<Self> {
+        Self::from_payload_with_header(
+            payload,
+            JoseHeader {
+                typ: Some("JWT".to_string()),
+                alg: None,
+                kid: None,
+                cty: None,
+                additional_properties: None,
+            },
+            bearer_did,
+            options,
+        )
+    }
+
+    /// Signs the payload with the given protected header, whose `alg` and `kid` are set from the
+    /// verification method selected by `options`.
+    ///
+    /// Fails if `additional_properties` carries one of the registered parameters `typ`, `alg`,
+    /// `kid` or `cty`, which would otherwise be serialized twice.
+    pub fn from_payload_with_header(
+        payload: &[u8],
+        header: JoseHeader,
+        bearer_did: &BearerDid,
+        options: &SignerOptions,
+    ) -> Result<Self> {
+        if let Some(name) = header
+            .additional_properties
+            .as_ref()
+            .and_then(|properties| {
+                RESERVED_HEADER_PARAMETERS
+                    .into_iter()
+                    .find(|name| properties.contains_key(*name))
+            })
+        {
             return Err(Web5Error::Parameter(format!(
-                "verification_method_id {} is not an assertion_method",
-                verification_method_id
+                "header parameter {} cannot be set as an additional property",
+                name
             )));
         }
 
-        let verification_method =
-            bearer_did
-                .document
-                .find_verification_method(FindVerificationMethodOptions {
-                    verification_method_id: Some(verification_method_id.clone()),
-                })?;
+        let (verification_method, signer) = bearer_did.select_signer(options)?;
+        let verification_method_id = verification_method.id;
         let alg = verification_method
             .public_key_jwk
             .alg
@@ -73,9 +117,9 @@ impl Jws {
             ))?;
 
         let header = JoseHeader {
-            typ: Some("JWT".to_string()),
             alg: Some(alg),
             kid: Some(verification_method_id.clone()),
+            ..header
         };
 
         let header_part =
@@ -85,7 +129,6 @@ impl Jws {
 
         let message = format!("{}.{}", header_part, claims_part);
 
-        let signer = bearer_did.get_signer(&verification_method_id)?;
         let signature = signer.sign(message.as_bytes())?;
 
         let signature_part =
@@ -106,6 +149,46 @@ impl Jws {
     }
 
     pub async fn from_compact_jws(compact_jws: &str, verify: bool) -> Result<Self> {
+        let jws = Self::parse(compact_jws)?;
+        if jws.kid.is_empty() {
+            return Err(VerificationError::MissingKid.into());
+        }
+
+        if verify {
+            let document = jws.resolve_kid().await?;
+            jws.verify_with_document(&document)?;
+        }
+
+        Ok(jws)
+    }
+
+    /// Resolves the DID document of the verification method the `kid` header identifies.
+    pub(crate) async fn resolve_kid(&self) -> Result<Document> {
+        if self.kid.is_empty() {
+            return Err(VerificationError::MissingKid.into());
+        }
+
+        let resolution_result = ResolutionResult::resolve(&self.kid).await;
+        match resolution_result.resolution_metadata.error {
+            Some(e) => Err(e.into()),
+            None => resolution_result
+                .document
+                .ok_or(Web5Error::Parameter(format!(
+                    "failed to resolve did document for {}",
+                    self.kid
+                ))),
+        }
+    }
+
+    /// Constructs a JWS from its compact serialization, verifying the signature against the given
+    /// public key rather than one identified by the `kid` header, which may be absent.
+    pub(crate) fn from_compact_jws_with_jwk(compact_jws: &str, public_jwk: &Jwk) -> Result<Self> {
+        let jws = Self::parse(compact_jws)?;
+        verify_with_jwk(&jws.parts, &jws.signature, public_jwk)?;
+        Ok(jws)
+    }
+
+    pub(crate) fn parse(compact_jws: &str) -> Result<Self> {
         let parts = compact_jws
             .split('.')
             .map(String::from)
@@ -135,40 +218,8 @@ impl Jws {
                 Web5Error::Parameter(format!("failed to base64 decode signature part {}", e))
             })?;
 
-        let kid = header.kid.clone().ok_or(VerificationError::MissingKid)?;
-        if kid.is_empty() {
-            return Err(VerificationError::MissingKid.into());
-        }
-
-        if verify {
-            let resolution_result = ResolutionResult::resolve(&kid).await;
-            let document = match resolution_result.resolution_metadata.error {
-                Some(e) => return Err(e.into()),
-                None => match resolution_result.document {
-                    Some(d) => d,
-                    None => {
-                        return Err(Web5Error::Parameter(format!(
-                            "failed to resolve did document for {}",
-                            kid
-                        )))
-                    }
-                },
-            };
-
-            let public_jwk = document
-                .find_verification_method(FindVerificationMethodOptions {
-                    verification_method_id: Some(kid.clone()),
-                })?
-                .public_key_jwk;
-            let verifier: Arc<dyn Verifier> = match Dsa::from_str(&public_jwk.crv)? {
-                Dsa::Ed25519 => Arc::new(Ed25519Verifier::new(public_jwk)),
-                Dsa::Secp256k1 => Arc::new(Secp256k1Verifier::new(public_jwk)),
-            };
-
-            let payload = format!("{}.{}", parts[0], parts[1]);
-
-            verifier.verify(payload.as_bytes(), &signature)?;
-        }
+        // a key binding JWT, verified against the holder's key, need not identify it
+        let kid = header.kid.clone().unwrap_or_default();
 
         Ok(Self {
             kid,
@@ -180,6 +231,36 @@ impl Jws {
             detached_compact_jws: format!("{}..{}", parts[0], parts[2]),
         })
     }
+
+    /// Verifies the signature against the key its `kid` identifies in the given DID document,
+    /// rather than in the document resolved for it.
+    pub(crate) fn verify_with_document(&self, document: &Document) -> Result<()> {
+        verify_signature(&self.parts, &self.kid, &self.signature, document)
+    }
+}
+
+fn verify_signature(
+    parts: &[String],
+    kid: &str,
+    signature: &[u8],
+    document: &Document,
+) -> Result<()> {
+    let public_jwk = document
+        .find_verification_method(FindVerificationMethodOptions {
+            verification_method_id: Some(kid.to_string()),
+        })?
+        .public_key_jwk;
+
+    verify_with_jwk(parts, signature, &public_jwk)
+}
+
+fn verify_with_jwk(parts: &[String], signature: &[u8], public_jwk: &Jwk) -> Result<()> {
+    let public_jwk = public_jwk.clone();
+    let verifier = verifier_for_public_jwk(public_jwk)?;
+
+    let payload = format!("{}.{}", parts[0], parts[1]);
+
+    verifier.verify(payload.as_bytes(), signature)
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
@@ -220,6 +301,7 @@ pub struct JwtClaims {
 impl FromJson for JwtClaims {}
 impl ToJson for JwtClaims {}
 
+#[derive(Clone)]
 pub struct Jwt {
     pub kid: String,
     pub parts: Vec<String>,
@@ -238,8 +320,21 @@ impl Jwt {
     ) -> Result<Self> {
         let payload = claims.to_json_string()?;
         let jws = Jws::from_payload(payload.as_bytes(), bearer_did, verification_method_id)?;
+        Ok(Self::from_jws(claims, jws))
+    }
 
-        Ok(Self {
+    pub fn from_claims_with_options(
+        claims: &JwtClaims,
+        bearer_did: &BearerDid,
+        options: &SignerOptions,
+    ) -> Result<Self> {
+        let payload = claims.to_json_string()?;
+        let jws = Jws::from_payload_with_options(payload.as_bytes(), bearer_did, options)?;
+        Ok(Self::from_jws(claims, jws))
+    }
+
+    fn from_jws(claims: &JwtClaims, jws: Jws) -> Self {
+        Self {
             kid: jws.kid,
             parts: jws.parts,
             header: jws.header,
@@ -247,21 +342,86 @@ impl Jwt {
             signature: jws.signature,
             compact_jws: jws.compact_jws,
             detached_compact_jws: jws.detached_compact_jws,
-        })
+        }
     }
 
     pub async fn from_compact_jws(compact_jws: &str, verify: bool) -> Result<Self> {
         let jws = Jws::from_compact_jws(compact_jws, verify).await?;
+        Self::try_from(jws)
+    }
+
+    /// Constructs a JWT from its compact serialization, verifying the signature against the given
+    /// public key rather than one identified by the `kid` header, which may be absent.
+    pub(crate) fn from_compact_jws_with_jwk(compact_jws: &str, public_jwk: &Jwk) -> Result<Self> {
+        let jws = Jws::from_compact_jws_with_jwk(compact_jws, public_jwk)?;
+        Self::try_from(jws)
+    }
+}
+
+impl TryFrom<Jws> for Jwt {
+    type Error = Web5Error;
+
+    fn try_from(jws: Jws) -> Result<Self> {
         let claims = JwtClaims::from_json_byte_array(&jws.payload)?;
+        Ok(Self::from_jws(&claims, jws))
+    }
+}
 
-        Ok(Self {
-            kid: jws.kid,
-            parts: jws.parts,
-            header: jws.header,
-            claims: claims.clone(),
-            signature: jws.signature,
-            compact_jws: jws.compact_jws,
-            detached_compact_jws: jws.detached_compact_jws,
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dids::methods::did_jwk::DidJwk;
+
+    #[test]
+    fn test_rejects_reserved_additional_properties() {
+        let bearer_did = DidJwk::create(None).unwrap();
+
+        for name in RESERVED_HEADER_PARAMETERS {
+            let header = JoseHeader {
+                typ: Some("JWT".to_string()),
+                alg: None,
+                kid: None,
+                cty: None,
+                additional_properties: Some(HashMap::from([(
+                    name.to_string(),
+                    JsonValue::String("none".to_string()),
+                )])),
+            };
+
+            let result = Jws::from_payload_with_header(
+                b"{}",
+                header,
+                &bearer_did,
+                &SignerOptions::default(),
+            );
+            assert!(matches!(result, Err(Web5Error::Parameter(_))), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_keeps_unreserved_additional_properties() {
+        let bearer_did = DidJwk::create(None).unwrap();
+        let header = JoseHeader {
+            typ: Some("JWT".to_string()),
+            alg: None,
+            kid: None,
+            cty: None,
+            additional_properties: Some(HashMap::from([(
+                "nonce".to_string(),
+                JsonValue::String("abc".to_string()),
+            )])),
+        };
+
+        let jws =
+            Jws::from_payload_with_header(b"{}", header, &bearer_did, &SignerOptions::default())
+                .unwrap();
+        let header = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(&jws.parts[0])
+            .unwrap();
+        let header = JoseHeader::from_json_string(std::str::from_utf8(&header).unwrap()).unwrap();
+        assert_eq!(
+            header.additional_properties.unwrap()["nonce"],
+            JsonValue::String("abc".to_string())
+        );
     }
 }
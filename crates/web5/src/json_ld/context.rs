@@ -0,0 +1,728 @@
+use super::{document_loader::load_context, json_ld_error};
+use crate::errors::Result;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Limits how deeply contexts may reference other contexts, which also stops reference cycles.
+const MAX_REMOTE_CONTEXTS: usize = 32;
+
+const KEYWORDS: [&str; 26] = [
+    "@base",
+    "@container",
+    "@context",
+    "@default",
+    "@direction",
+    "@embed",
+    "@explicit",
+    "@graph",
+    "@id",
+    "@import",
+    "@included",
+    "@index",
+    "@json",
+    "@language",
+    "@list",
+    "@nest",
+    "@none",
+    "@omitDefault",
+    "@prefix",
+    "@preserve",
+    "@propagate",
+    "@protected",
+    "@requireAll",
+    "@reverse",
+    "@set",
+    "@type",
+];
+
+const VALUE_KEYWORDS: [&str; 3] = ["@value", "@version", "@vocab"];
+
+pub(crate) fn is_keyword(value: &str) -> bool {
+    KEYWORDS.contains(&value) || VALUE_KEYWORDS.contains(&value)
+}
+
+/// Whether the value has the form of a keyword, `@` followed by letters, which is reserved for
+/// future keywords and must be ignored.
+fn has_keyword_form(value: &str) -> bool {
+    value.len() > 1 && value.starts_with('@') && value[1..].chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Whether the value is an absolute IRI, i.e. begins with a scheme.
+pub(crate) fn is_absolute_iri(value: &str) -> bool {
+    match value.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+pub(crate) fn is_blank_node_identifier(value: &str) -> bool {
+    value.starts_with("_:")
+}
+
+/// Returns the index of the first colon after the first character, which separates the prefix
+/// of a compact IRI from its suffix.
+fn prefix_colon(value: &str) -> Option<usize> {
+    value
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == ':')
+        .map(|(i, _)| i)
+}
+
+/// The definition of a term, which maps it to an IRI along with how its values are expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TermDefinition {
+    /// The IRI or keyword the term expands to, or `None` if the term is explicitly unmapped.
+    pub iri: Option<String>,
+    pub prefix: bool,
+    pub protected: bool,
+    pub type_mapping: Option<String>,
+    pub container: Vec<String>,
+    /// The language of the term's string values, where `Some(None)` explicitly removes the
+    /// default language.
+    pub language: Option<Option<String>>,
+    /// The term's scoped context, applied to its values, or to nodes of that type for a class.
+    pub context: Option<Value>,
+}
+
+impl TermDefinition {
+    pub fn has_container(&self, container: &str) -> bool {
+        self.container.iter().any(|c| c == container)
+    }
+
+    /// Whether two definitions are the same, which allows a protected term to be redefined.
+    fn same_as(&self, other: &TermDefinition) -> bool {
+        TermDefinition {
+            protected: other.protected,
+            ..self.clone()
+        } == *other
+    }
+}
+
+/// The context under which a JSON-LD document is being expanded.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ActiveContext {
+    pub vocab: Option<String>,
+    pub default_language: Option<String>,
+    pub terms: HashMap<String, TermDefinition>,
+    /// The context to revert to when entering a new node object, set by type-scoped contexts,
+    /// which don't propagate.
+    pub previous: Option<Box<ActiveContext>>,
+}
+
+impl ActiveContext {
+    pub fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.terms.get(term)
+    }
+
+    /// Processes a local context on top of this one per the JSON-LD 1.1 "Context Processing"
+    /// algorithm.
+    pub fn process(
+        &self,
+        local_context: &Value,
+        override_protected: bool,
+        propagate: bool,
+    ) -> Result<ActiveContext> {
+        self.process_with_remote(local_context, &mut vec![], override_protected, propagate)
+    }
+
+    fn process_with_remote(
+        &self,
+        local_context: &Value,
+        remote_contexts: &mut Vec<String>,
+        override_protected: bool,
+        mut propagate: bool,
+    ) -> Result<ActiveContext> {
+        let mut result = self.clone();
+
+        if let Some(value) = local_context.get("@propagate") {
+            propagate = value
+                .as_bool()
+                .ok_or_else(|| json_ld_error("invalid @propagate value"))?;
+        }
+        if !propagate && result.previous.is_none() {
+            result.previous = Some(Box::new(self.clone()));
+        }
+
+        let contexts = match local_context {
+            Value::Array(contexts) => contexts.clone(),
+            context => vec![context.clone()],
+        };
+        for context in contexts {
+            match context {
+                Value::Null => {
+                    if !override_protected && result.terms.values().any(|t| t.protected) {
+                        return Err(json_ld_error(
+                            "invalid context nullification of protected terms",
+                        ));
+                    }
+                    result = ActiveContext {
+                        previous: match propagate {
+                            true => None,
+                            false => Some(Box::new(result)),
+                        },
+                        ..Default::default()
+                    };
+                }
+                Value::String(url) => {
+                    if remote_contexts.len() >= MAX_REMOTE_CONTEXTS {
+                        return Err(json_ld_error("context overflow"));
+                    }
+                    let document = load_context(&url)?;
+                    let context = document
+                        .get("@context")
+                        .ok_or_else(|| json_ld_error(&format!("invalid remote context {}", url)))?;
+                    remote_contexts.push(url);
+                    result = result.process_with_remote(context, remote_contexts, false, true)?;
+                    remote_contexts.pop();
+                }
+                Value::Object(context) => {
+                    result.process_definitions(&context, remote_contexts, override_protected)?
+                }
+                _ => return Err(json_ld_error("invalid local context")),
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn process_definitions(
+        &mut self,
+        context: &Map<String, Value>,
+        remote_contexts: &mut Vec<String>,
+        override_protected: bool,
+    ) -> Result<()> {
+        if let Some(version) = context.get("@version") {
+            if version.as_f64() != Some(1.1) {
+                return Err(json_ld_error("invalid @version value"));
+            }
+        }
+        for unsupported in ["@import", "@direction"] {
+            if context.get(unsupported).is_some_and(|v| !v.is_null()) {
+                return Err(json_ld_error(&format!("{} is not supported", unsupported)));
+            }
+        }
+        if let Some(base) = context.get("@base") {
+            if !base.is_null() && !base.as_str().is_some_and(is_absolute_iri) {
+                return Err(json_ld_error("invalid base IRI"));
+            }
+        }
+        if let Some(vocab) = context.get("@vocab") {
+            self.vocab = match vocab {
+                Value::Null => None,
+                Value::String(vocab) => {
+                    let expanded = self.expand_iri(vocab, true)?;
+                    match expanded {
+                        Some(iri) if is_absolute_iri(&iri) || is_blank_node_identifier(&iri) => {
+                            Some(iri)
+                        }
+                        _ => return Err(json_ld_error("invalid vocab mapping")),
+                    }
+                }
+                _ => return Err(json_ld_error("invalid vocab mapping")),
+            };
+        }
+        if let Some(language) = context.get("@language") {
+            self.default_language = match language {
+                Value::Null => None,
+                Value::String(language) => Some(language.to_lowercase()),
+                _ => return Err(json_ld_error("invalid default language")),
+            };
+        }
+        let protected = match context.get("@protected") {
+            None => false,
+            Some(protected) => protected
+                .as_bool()
+                .ok_or_else(|| json_ld_error("invalid @protected value"))?,
+        };
+
+        let mut defined = HashMap::new();
+        for term in context.keys() {
+            if matches!(
+                term.as_str(),
+                "@base"
+                    | "@direction"
+                    | "@import"
+                    | "@language"
+                    | "@propagate"
+                    | "@protected"
+                    | "@version"
+                    | "@vocab"
+            ) {
+                continue;
+            }
+            self.create_term_definition(
+                context,
+                term,
+                &mut defined,
+                protected,
+                override_protected,
+                remote_contexts,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Defines a term per the JSON-LD 1.1 "Create Term Definition" algorithm, first defining any
+    /// terms of the same local context its IRI depends on.
+    fn create_term_definition(
+        &mut self,
+        local_context: &Map<String, Value>,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+        default_protected: bool,
+        override_protected: bool,
+        remote_contexts: &mut Vec<String>,
+    ) -> Result<()> {
+        match defined.get(term) {
+            Some(true) => return Ok(()),
+            Some(false) => {
+                return Err(json_ld_error(&format!("cyclic IRI mapping for {}", term)));
+            }
+            None => {}
+        }
+        if term.is_empty() {
+            return Err(json_ld_error("invalid term definition for an empty term"));
+        }
+        defined.insert(term.to_string(), false);
+
+        let value = local_context.get(term).cloned().unwrap_or(Value::Null);
+        if term == "@type" {
+            // only @type's container may be declared, as a set, which doesn't change expansion
+            let valid = value.as_object().is_some_and(|definition| {
+                definition.get("@container") == Some(&Value::String("@set".to_string()))
+                    && definition
+                        .keys()
+                        .all(|key| key == "@container" || key == "@protected")
+            });
+            if !valid {
+                return Err(json_ld_error("keyword redefinition of @type"));
+            }
+            defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+        if is_keyword(term) {
+            return Err(json_ld_error(&format!("keyword redefinition of {}", term)));
+        }
+        if has_keyword_form(term) {
+            defined.insert(term.to_string(), true);
+            return Ok(());
+        }
+
+        let previous_definition = self.terms.remove(term);
+
+        let (value, simple_term) = match value {
+            Value::Null => (Map::from_iter([("@id".to_string(), Value::Null)]), false),
+            Value::String(id) => (
+                Map::from_iter([("@id".to_string(), Value::String(id))]),
+                true,
+            ),
+            Value::Object(value) => (value, false),
+            _ => {
+                return Err(json_ld_error(&format!(
+                    "invalid term definition for {}",
+                    term
+                )))
+            }
+        };
+
+        let mut definition = TermDefinition {
+            iri: None,
+            prefix: false,
+            protected: match value.get("@protected") {
+                None => default_protected,
+                Some(protected) => protected
+                    .as_bool()
+                    .ok_or_else(|| json_ld_error("invalid @protected value"))?,
+            },
+            type_mapping: None,
+            container: vec![],
+            language: None,
+            context: None,
+        };
+
+        if let Some(type_mapping) = value.get("@type") {
+            let type_mapping = type_mapping
+                .as_str()
+                .ok_or_else(|| json_ld_error(&format!("invalid type mapping for {}", term)))?;
+            let type_mapping = self
+                .expand_iri_defining(
+                    type_mapping,
+                    local_context,
+                    defined,
+                    default_protected,
+                    override_protected,
+                    remote_contexts,
+                )?
+                .filter(|t| {
+                    matches!(t.as_str(), "@id" | "@json" | "@none" | "@vocab") || is_absolute_iri(t)
+                })
+                .ok_or_else(|| json_ld_error(&format!("invalid type mapping for {}", term)))?;
+            definition.type_mapping = Some(type_mapping);
+        }
+
+        for unsupported in ["@reverse", "@nest", "@index"] {
+            if value.contains_key(unsupported) {
+                return Err(json_ld_error(&format!(
+                    "{} in the definition of {} is not supported",
+                    unsupported, term
+                )));
+            }
+        }
+
+        let has_term_form = prefix_colon(term).is_some() || term.contains('/');
+        match value.get("@id") {
+            Some(id) if id.as_str() != Some(term) => match id {
+                Value::Null => {}
+                Value::String(id) => {
+                    if !is_keyword(id) && has_keyword_form(id) {
+                        defined.insert(term.to_string(), true);
+                        return Ok(());
+                    }
+                    let iri = self
+                        .expand_iri_defining(
+                            id,
+                            local_context,
+                            defined,
+                            default_protected,
+                            override_protected,
+                            remote_contexts,
+                        )?
+                        .filter(|iri| {
+                            is_keyword(iri) || is_absolute_iri(iri) || is_blank_node_identifier(iri)
+                        })
+                        .ok_or_else(|| {
+                            json_ld_error(&format!("invalid IRI mapping for {}", term))
+                        })?;
+                    if iri == "@context" {
+                        return Err(json_ld_error("invalid keyword alias of @context"));
+                    }
+                    if has_term_form {
+                        // a term which looks like an IRI must expand to that IRI
+                        defined.insert(term.to_string(), true);
+                        if self.expand_iri(term, true)?.as_deref() != Some(iri.as_str()) {
+                            return Err(json_ld_error(&format!(
+                                "invalid IRI mapping for {}",
+                                term
+                            )));
+                        }
+                    }
+                    definition.prefix = !has_term_form
+                        && simple_term
+                        && (iri.ends_with([':', '/', '?', '#', '[', ']', '@'])
+                            || is_blank_node_identifier(&iri));
+                    definition.iri = Some(iri);
+                }
+                _ => {
+                    return Err(json_ld_error(&format!("invalid IRI mapping for {}", term)));
+                }
+            },
+            _ => {
+                definition.iri = Some(match prefix_colon(term) {
+                    Some(colon) => {
+                        let (prefix, suffix) = (&term[..colon], &term[colon + 1..]);
+                        if local_context.contains_key(prefix) {
+                            self.create_term_definition(
+                                local_context,
+                                prefix,
+                                defined,
+                                default_protected,
+                                override_protected,
+                                remote_contexts,
+                            )?;
+                        }
+                        match self.terms.get(prefix).and_then(|t| t.iri.as_ref()) {
+                            Some(prefix_iri) => format!("{}{}", prefix_iri, suffix),
+                            None => term.to_string(),
+                        }
+                    }
+                    None if term.contains('/') => self
+                        .expand_iri(term, true)?
+                        .filter(|iri| is_absolute_iri(iri))
+                        .ok_or_else(|| {
+                            json_ld_error(&format!("invalid IRI mapping for {}", term))
+                        })?,
+                    None => match &self.vocab {
+                        Some(vocab) => format!("{}{}", vocab, term),
+                        None => {
+                            return Err(json_ld_error(&format!("invalid IRI mapping for {}", term)))
+                        }
+                    },
+                });
+            }
+        }
+
+        if let Some(container) = value.get("@container") {
+            let container = match container {
+                Value::String(container) => vec![container.clone()],
+                container => {
+                    serde_json::from_value::<Vec<String>>(container.clone()).map_err(|_| {
+                        json_ld_error(&format!("invalid container mapping for {}", term))
+                    })?
+                }
+            };
+            let valid = match container.as_slice() {
+                [single] => matches!(
+                    single.as_str(),
+                    "@graph" | "@index" | "@language" | "@list" | "@set"
+                ),
+                [..] => container
+                    .iter()
+                    .all(|c| matches!(c.as_str(), "@graph" | "@index" | "@set")),
+            };
+            if !valid {
+                return Err(json_ld_error(&format!(
+                    "invalid container mapping for {}",
+                    term
+                )));
+            }
+            definition.container = container;
+        }
+
+        if let Some(context) = value.get("@context") {
+            definition.context = Some(context.clone());
+        }
+
+        if let Some(language) = value.get("@language") {
+            if definition.type_mapping.is_none() {
+                definition.language = Some(match language {
+                    Value::Null => None,
+                    Value::String(language) => Some(language.to_lowercase()),
+                    _ => {
+                        return Err(json_ld_error(&format!(
+                            "invalid language mapping for {}",
+                            term
+                        )))
+                    }
+                });
+            }
+        }
+
+        if let Some(prefix) = value.get("@prefix") {
+            if has_term_form {
+                return Err(json_ld_error(&format!(
+                    "invalid term definition for {}",
+                    term
+                )));
+            }
+            definition.prefix = prefix
+                .as_bool()
+                .ok_or_else(|| json_ld_error("invalid @prefix value"))?;
+        }
+
+        if let Some(key) = value.keys().find(|key| {
+            !matches!(
+                key.as_str(),
+                "@id"
+                    | "@type"
+                    | "@container"
+                    | "@context"
+                    | "@language"
+                    | "@prefix"
+                    | "@protected"
+            )
+        }) {
+            return Err(json_ld_error(&format!(
+                "invalid term definition for {}: unexpected {}",
+                term, key
+            )));
+        }
+
+        if !override_protected {
+            if let Some(previous_definition) = previous_definition {
+                if previous_definition.protected {
+                    if !definition.same_as(&previous_definition) {
+                        return Err(json_ld_error(&format!(
+                            "protected term redefinition of {}",
+                            term
+                        )));
+                    }
+                    definition = previous_definition;
+                }
+            }
+        }
+
+        self.terms.insert(term.to_string(), definition);
+        defined.insert(term.to_string(), true);
+        Ok(())
+    }
+
+    /// Expands a term, compact IRI, or IRI per the JSON-LD 1.1 "IRI Expansion" algorithm.
+    /// Returns `None` for terms which are explicitly unmapped or have the form of a keyword.
+    pub fn expand_iri(&self, value: &str, vocab: bool) -> Result<Option<String>> {
+        if is_keyword(value) {
+            return Ok(Some(value.to_string()));
+        }
+        if has_keyword_form(value) {
+            return Ok(None);
+        }
+        if vocab {
+            if let Some(definition) = self.terms.get(value) {
+                return Ok(definition.iri.clone());
+            }
+        }
+        if let Some(colon) = prefix_colon(value) {
+            let (prefix, suffix) = (&value[..colon], &value[colon + 1..]);
+            if prefix == "_" || suffix.starts_with("//") {
+                return Ok(Some(value.to_string()));
+            }
+            if let Some(definition) = self.terms.get(prefix) {
+                if let (Some(iri), true) = (&definition.iri, definition.prefix) {
+                    return Ok(Some(format!("{}{}", iri, suffix)));
+                }
+            }
+            if is_absolute_iri(value) {
+                return Ok(Some(value.to_string()));
+            }
+        }
+        if vocab {
+            if let Some(vocab) = &self.vocab {
+                return Ok(Some(format!("{}{}", vocab, value)));
+            }
+        }
+        Ok(Some(value.to_string()))
+    }
+
+    /// Expands a value used within a local context, first defining the terms of that context it
+    /// depends on.
+    fn expand_iri_defining(
+        &mut self,
+        value: &str,
+        local_context: &Map<String, Value>,
+        defined: &mut HashMap<String, bool>,
+        default_protected: bool,
+        override_protected: bool,
+        remote_contexts: &mut Vec<String>,
+    ) -> Result<Option<String>> {
+        let mut dependencies = vec![value];
+        if let Some(colon) = prefix_colon(value) {
+            dependencies.push(&value[..colon]);
+        }
+        for dependency in dependencies {
+            if local_context.contains_key(dependency) && defined.get(dependency) != Some(&true) {
+                self.create_term_definition(
+                    local_context,
+                    dependency,
+                    defined,
+                    default_protected,
+                    override_protected,
+                    remote_contexts,
+                )?;
+            }
+        }
+        self.expand_iri(value, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    mod process {
+        use super::*;
+
+        #[test]
+        fn test_defines_terms() {
+            let context = ActiveContext::default()
+                .process(
+                    &json!({
+                        "ex": "https://example.com/",
+                        "name": "ex:name",
+                        "knows": { "@id": "ex:knows", "@type": "@id", "@container": "@set" },
+                        "ex:age": { "@type": "http://www.w3.org/2001/XMLSchema#integer" }
+                    }),
+                    false,
+                    true,
+                )
+                .unwrap();
+
+            assert_eq!(
+                context.expand_iri("name", true).unwrap().unwrap(),
+                "https://example.com/name"
+            );
+            let knows = context.term("knows").unwrap();
+            assert_eq!(knows.iri.as_deref(), Some("https://example.com/knows"));
+            assert_eq!(knows.type_mapping.as_deref(), Some("@id"));
+            assert!(knows.has_container("@set"));
+            assert_eq!(
+                context.term("ex:age").unwrap().iri.as_deref(),
+                Some("https://example.com/age")
+            );
+            assert_eq!(
+                context.expand_iri("ex:other", true).unwrap().unwrap(),
+                "https://example.com/other"
+            );
+        }
+
+        #[test]
+        fn test_protected_terms() {
+            let context = ActiveContext::default()
+                .process(
+                    &json!({ "@protected": true, "name": "https://schema.org/name" }),
+                    false,
+                    true,
+                )
+                .unwrap();
+
+            // an identical definition is allowed
+            assert!(context
+                .process(&json!({ "name": "https://schema.org/name" }), false, true)
+                .is_ok());
+            assert_eq!(
+                context
+                    .process(&json!({ "name": "https://example.com/name" }), false, true)
+                    .unwrap_err(),
+                json_ld_error("protected term redefinition of name")
+            );
+            assert_eq!(
+                context.process(&Value::Null, false, true).unwrap_err(),
+                json_ld_error("invalid context nullification of protected terms")
+            );
+            // property-scoped contexts may override protected terms
+            assert!(context
+                .process(&json!({ "name": "https://example.com/name" }), true, true)
+                .is_ok());
+        }
+
+        #[test]
+        fn test_loads_bundled_contexts() {
+            let context = ActiveContext::default()
+                .process(&json!("https://www.w3.org/ns/credentials/v2"), false, true)
+                .unwrap();
+            assert_eq!(
+                context
+                    .expand_iri("VerifiableCredential", true)
+                    .unwrap()
+                    .unwrap(),
+                "https://www.w3.org/2018/credentials#VerifiableCredential"
+            );
+            assert_eq!(
+                context
+                    .expand_iri("AlumniCredential", true)
+                    .unwrap()
+                    .unwrap(),
+                "https://www.w3.org/ns/credentials/issuer-dependent#AlumniCredential"
+            );
+        }
+
+        #[test]
+        fn test_unknown_remote_context() {
+            let result = ActiveContext::default().process(
+                &json!("https://example.com/unknown"),
+                false,
+                true,
+            );
+            assert_eq!(
+                result.unwrap_err(),
+                json_ld_error("unable to load context https://example.com/unknown")
+            );
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use super::json_ld_error;
+use crate::errors::{Result, Web5Error};
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::{collections::HashMap, sync::RwLock};
+
+/// The contexts which JSON-LD processing loads without network access.
+const BUNDLED_CONTEXTS: [(&str, &str); 5] = [
+    (
+        "https://www.w3.org/2018/credentials/v1",
+        include_str!("contexts/credentials_v1.jsonld"),
+    ),
+    (
+        "https://www.w3.org/ns/credentials/v2",
+        include_str!("contexts/credentials_v2.jsonld"),
+    ),
+    (
+        "https://w3id.org/security/data-integrity/v2",
+        include_str!("contexts/data_integrity_v2.jsonld"),
+    ),
+    (
+        "https://www.w3.org/ns/did/v1",
+        include_str!("contexts/did_v1.jsonld"),
+    ),
+    (
+        "https://w3id.org/vc/status-list/2021/v1",
+        include_str!("contexts/status_list_2021.jsonld"),
+    ),
+];
+
+lazy_static! {
+    static ref REGISTERED_CONTEXTS: RwLock<HashMap<String, Value>> = RwLock::new(HashMap::new());
+}
+
+/// Registers a context document for JSON-LD processing to load by URL, in addition to the bundled
+/// Verifiable Credentials 1.1 and 2.0, Data Integrity, DID, and Status List 2021 contexts.
+///
+/// Contexts are never fetched over the network, because a context served by a third party could
+/// change the meaning of a signed document; issuers' contexts must be registered up front.
+///
+/// # Arguments
+///
+/// * `url` - The URL by which documents reference the context.
+/// * `document` - The context document, a JSON object with an `@context` property.
+///
+/// # Example
+///
+/// ```ignore
+/// register_context(
+///     "https://www.w3.org/ns/credentials/examples/v2",
+///     r#"{ "@context": { "@vocab": "https://www.w3.org/ns/credentials/examples#" } }"#,
+/// )?;
+/// ```
+pub fn register_context(url: &str, document: &str) -> Result<()> {
+    let document: Value = serde_json::from_str(document)?;
+    if !document
+        .get("@context")
+        .is_some_and(|c| c.is_object() || c.is_array())
+    {
+        return Err(json_ld_error(&format!(
+            "context document for {} must contain an @context",
+            url
+        )));
+    }
+
+    let mut registered = REGISTERED_CONTEXTS
+        .write()
+        .map_err(|e| Web5Error::Mutex(e.to_string()))?;
+    registered.insert(url.to_string(), document);
+    Ok(())
+}
+
+/// Loads a context document, preferring bundled contexts over registered ones so that the
+/// standard contexts can't be replaced.
+pub(crate) fn load_context(url: &str) -> Result<Value> {
+    if let Some((_, document)) = BUNDLED_CONTEXTS.iter().find(|(u, _)| *u == url) {
+        return Ok(serde_json::from_str(document)?);
+    }
+
+    REGISTERED_CONTEXTS
+        .read()
+        .map_err(|e| Web5Error::Mutex(e.to_string()))?
+        .get(url)
+        .cloned()
+        .ok_or_else(|| json_ld_error(&format!("unable to load context {}", url)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod load_context {
+        use super::*;
+
+        #[test]
+        fn test_bundled_contexts() {
+            for (url, _) in BUNDLED_CONTEXTS {
+                assert!(load_context(url).unwrap()["@context"].is_object());
+            }
+        }
+
+        #[test]
+        fn test_registered_context() {
+            let url = "https://example.com/contexts/registered";
+            assert!(load_context(url).is_err());
+
+            register_context(
+                url,
+                r#"{ "@context": { "name": "https://schema.org/name" } }"#,
+            )
+            .unwrap();
+            assert_eq!(
+                load_context(url).unwrap()["@context"]["name"],
+                "https://schema.org/name"
+            );
+        }
+
+        #[test]
+        fn test_cannot_replace_bundled_context() {
+            let url = "https://www.w3.org/ns/credentials/v2";
+            register_context(
+                url,
+                r#"{ "@context": { "name": "https://example.com/name" } }"#,
+            )
+            .unwrap();
+            assert_eq!(
+                load_context(url).unwrap()["@context"]["name"],
+                "https://schema.org/name"
+            );
+        }
+    }
+}
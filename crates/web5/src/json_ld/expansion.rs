@@ -0,0 +1,622 @@
+use super::{
+    context::{is_absolute_iri, is_keyword, ActiveContext},
+    json_ld_error,
+};
+use crate::errors::Result;
+use serde_json::{Map, Value};
+
+/// Expands a JSON-LD document per the JSON-LD 1.1 "Expansion" algorithm, removing its contexts
+/// so that every property and type is an absolute IRI.
+///
+/// Expansion runs in "safe mode": rather than silently dropping properties and types which the
+/// document's contexts don't define, it fails, as a signature over a dataset which omits them
+/// wouldn't protect them.
+///
+/// # Arguments
+///
+/// * `document` - The JSON-LD document, whose remote contexts must be known to the document loader.
+///
+/// # Returns
+///
+/// * `Result<Value>` - The expanded document, always an array of node objects.
+///
+/// # Examples
+///
+/// ```ignore
+/// let expanded = expand(&json!({
+///     "@context": { "name": "https://schema.org/name" },
+///     "name": "Alice"
+/// }))?;
+/// assert_eq!(expanded, json!([{ "https://schema.org/name": [{ "@value": "Alice" }] }]));
+/// ```
+pub fn expand(document: &Value) -> Result<Value> {
+    let expanded = expand_element(&ActiveContext::default(), None, document, false)?;
+    let expanded = match expanded {
+        Value::Object(mut object) if object.len() == 1 && object.contains_key("@graph") => {
+            object.remove("@graph").unwrap_or(Value::Null)
+        }
+        expanded => expanded,
+    };
+    Ok(match expanded {
+        Value::Null => Value::Array(vec![]),
+        Value::Array(expanded) => Value::Array(expanded),
+        expanded => Value::Array(vec![expanded]),
+    })
+}
+
+fn expand_element(
+    active_context: &ActiveContext,
+    active_property: Option<&str>,
+    element: &Value,
+    from_map: bool,
+) -> Result<Value> {
+    let property_definition = active_property.and_then(|p| active_context.term(p));
+    let property_scoped_context = property_definition.and_then(|d| d.context.clone());
+
+    match element {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let is_list = property_definition.is_some_and(|d| d.has_container("@list"));
+            let mut result = vec![];
+            for item in items {
+                let mut expanded = expand_element(active_context, active_property, item, from_map)?;
+                if is_list && expanded.is_array() {
+                    expanded = list_object(expanded);
+                }
+                match expanded {
+                    Value::Array(expanded) => result.extend(expanded),
+                    Value::Null => {}
+                    expanded => result.push(expanded),
+                }
+            }
+            Ok(Value::Array(result))
+        }
+        Value::Object(element) => expand_object(
+            active_context,
+            active_property,
+            element,
+            property_scoped_context,
+            from_map,
+        ),
+        scalar => {
+            if active_property.is_none() || active_property == Some("@graph") {
+                return Err(json_ld_error(&format!(
+                    "free-floating scalar {} is not allowed",
+                    scalar
+                )));
+            }
+            let active_context = match &property_scoped_context {
+                Some(context) => active_context.process(context, true, true)?,
+                None => active_context.clone(),
+            };
+            expand_value(&active_context, active_property.unwrap_or_default(), scalar)
+        }
+    }
+}
+
+fn expand_object(
+    active_context: &ActiveContext,
+    active_property: Option<&str>,
+    element: &Map<String, Value>,
+    property_scoped_context: Option<Value>,
+    from_map: bool,
+) -> Result<Value> {
+    let mut active_context = active_context.clone();
+
+    // contexts scoped to a type don't apply to nested nodes
+    if let Some(previous) = &active_context.previous {
+        if !from_map {
+            let mut expanded_keys = vec![];
+            for key in element.keys() {
+                expanded_keys.push(active_context.expand_iri(key, true)?);
+            }
+            let is_value = expanded_keys.iter().any(|k| k.as_deref() == Some("@value"));
+            let is_reference =
+                expanded_keys.len() == 1 && expanded_keys[0].as_deref() == Some("@id");
+            if !is_value && !is_reference {
+                active_context = *previous.clone();
+            }
+        }
+    }
+
+    if let Some(context) = property_scoped_context {
+        active_context = active_context.process(&context, true, true)?;
+    }
+    if let Some(context) = element.get("@context") {
+        active_context = active_context.process(context, false, true)?;
+    }
+
+    let type_scoped_context = active_context.clone();
+    let mut keys: Vec<&String> = element.keys().collect();
+    keys.sort();
+    for key in &keys {
+        if type_scoped_context.expand_iri(key, true)?.as_deref() != Some("@type") {
+            continue;
+        }
+        let mut types: Vec<&str> = match &element[key.as_str()] {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        types.sort();
+        for t in types {
+            if let Some(context) = type_scoped_context.term(t).and_then(|d| d.context.as_ref()) {
+                active_context = active_context.process(context, false, false)?;
+            }
+        }
+    }
+
+    let mut result = Map::new();
+    for key in keys {
+        let value = &element[key.as_str()];
+        if key == "@context" {
+            continue;
+        }
+        let expanded_property = match active_context.expand_iri(key, true)? {
+            Some(p) if p.contains(':') || is_keyword(&p) => p,
+            _ => {
+                return Err(json_ld_error(&format!(
+                    "property {} is not defined by the context",
+                    key
+                )))
+            }
+        };
+
+        if is_keyword(&expanded_property) {
+            if result.contains_key(&expanded_property) {
+                return Err(json_ld_error(&format!(
+                    "colliding keywords {}",
+                    expanded_property
+                )));
+            }
+            let expanded_value = match expanded_property.as_str() {
+                "@id" => {
+                    let id = value
+                        .as_str()
+                        .ok_or_else(|| json_ld_error("invalid @id value"))?;
+                    match active_context.expand_iri(id, false)? {
+                        Some(id) => Value::String(id),
+                        None => Value::Null,
+                    }
+                }
+                "@type" => {
+                    let expand_type = |t: &Value| -> Result<Value> {
+                        let t = t
+                            .as_str()
+                            .ok_or_else(|| json_ld_error("invalid type value"))?;
+                        Ok(match type_scoped_context.expand_iri(t, true)? {
+                            Some(t) => Value::String(t),
+                            None => Value::Null,
+                        })
+                    };
+                    match value {
+                        Value::Array(types) => Value::Array(
+                            types
+                                .iter()
+                                .map(expand_type)
+                                .collect::<Result<Vec<Value>>>()?,
+                        ),
+                        t => expand_type(t)?,
+                    }
+                }
+                "@graph" => Value::Array(as_array(expand_element(
+                    &active_context,
+                    Some("@graph"),
+                    value,
+                    false,
+                )?)),
+                "@value" => {
+                    if value.is_array() || value.is_object() {
+                        let is_json = element.iter().any(|(k, v)| {
+                            active_context.expand_iri(k, true).ok().flatten().as_deref()
+                                == Some("@type")
+                                && v.as_str() == Some("@json")
+                        });
+                        if !is_json {
+                            return Err(json_ld_error("invalid value object value"));
+                        }
+                    }
+                    result.insert("@value".to_string(), value.clone());
+                    continue;
+                }
+                "@language" => Value::String(
+                    value
+                        .as_str()
+                        .ok_or_else(|| json_ld_error("invalid language-tagged string"))?
+                        .to_lowercase(),
+                ),
+                "@index" => match value {
+                    Value::String(_) => value.clone(),
+                    _ => return Err(json_ld_error("invalid @index value")),
+                },
+                "@list" => {
+                    if active_property.is_none() || active_property == Some("@graph") {
+                        continue;
+                    }
+                    Value::Array(as_array(expand_element(
+                        &active_context,
+                        active_property,
+                        value,
+                        false,
+                    )?))
+                }
+                "@set" => expand_element(&active_context, active_property, value, false)?,
+                keyword => {
+                    return Err(json_ld_error(&format!("{} is not supported", keyword)));
+                }
+            };
+            if !expanded_value.is_null() {
+                result.insert(expanded_property, expanded_value);
+            }
+            continue;
+        }
+
+        let definition = active_context.term(key);
+        let has_container =
+            |container: &str| definition.is_some_and(|d| d.has_container(container));
+        let mut expanded_value = if definition
+            .and_then(|d| d.type_mapping.as_deref())
+            .is_some_and(|t| t == "@json")
+        {
+            Value::Object(Map::from_iter([
+                ("@value".to_string(), value.clone()),
+                ("@type".to_string(), Value::String("@json".to_string())),
+            ]))
+        } else if has_container("@language") && value.is_object() {
+            expand_language_map(&active_context, key, value)?
+        } else if has_container("@index") && value.is_object() {
+            expand_index_map(&active_context, key, value, has_container("@graph"))?
+        } else {
+            expand_element(&active_context, Some(key), value, false)?
+        };
+
+        if expanded_value.is_null() {
+            continue;
+        }
+        if has_container("@list") && !is_list_object(&expanded_value) {
+            expanded_value = list_object(expanded_value);
+        }
+        if has_container("@graph") && !has_container("@index") {
+            expanded_value = Value::Array(
+                as_array(expanded_value)
+                    .into_iter()
+                    .map(|v| {
+                        Value::Object(Map::from_iter([(
+                            "@graph".to_string(),
+                            Value::Array(as_array(v)),
+                        )]))
+                    })
+                    .collect(),
+            );
+        }
+
+        add_values(&mut result, &expanded_property, expanded_value);
+    }
+
+    post_process(result, active_property)
+}
+
+/// Validates the expanded object, and simplifies or drops it where the algorithm requires.
+fn post_process(mut result: Map<String, Value>, active_property: Option<&str>) -> Result<Value> {
+    if let Some(value) = result.get("@value") {
+        if let Some(key) = result
+            .keys()
+            .find(|k| !matches!(k.as_str(), "@value" | "@type" | "@language" | "@index"))
+        {
+            return Err(json_ld_error(&format!("invalid value object with {}", key)));
+        }
+        if result.contains_key("@type") && result.contains_key("@language") {
+            return Err(json_ld_error(
+                "invalid value object with both @type and @language",
+            ));
+        }
+        let value_type = result.get("@type").and_then(Value::as_str);
+        if value_type == Some("@json") {
+            return Ok(Value::Object(result));
+        }
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        if !value.is_string() && result.contains_key("@language") {
+            return Err(json_ld_error("invalid language-tagged value"));
+        }
+        if let Some(value_type) = result.get("@type") {
+            if !value_type.as_str().is_some_and(is_absolute_iri) {
+                return Err(json_ld_error(&format!(
+                    "invalid typed value {}",
+                    value_type
+                )));
+            }
+        }
+    } else if let Some(types) = result.get_mut("@type") {
+        if !types.is_array() {
+            *types = Value::Array(vec![types.take()]);
+        }
+    } else if result.contains_key("@set") || result.contains_key("@list") {
+        if result
+            .keys()
+            .any(|k| !matches!(k.as_str(), "@set" | "@list" | "@index"))
+        {
+            return Err(json_ld_error("invalid set or list object"));
+        }
+        if let Some(set) = result.remove("@set") {
+            return Ok(set);
+        }
+    }
+
+    if result.len() == 1 && result.contains_key("@language") {
+        return Ok(Value::Null);
+    }
+
+    if active_property.is_none() || active_property == Some("@graph") {
+        let free_floating = result.is_empty()
+            || result.contains_key("@value")
+            || result.contains_key("@list")
+            || (result.len() == 1 && result.contains_key("@id"));
+        if free_floating {
+            return Ok(Value::Null);
+        }
+    }
+
+    Ok(Value::Object(result))
+}
+
+/// Expands a scalar per the JSON-LD 1.1 "Value Expansion" algorithm.
+fn expand_value(
+    active_context: &ActiveContext,
+    active_property: &str,
+    value: &Value,
+) -> Result<Value> {
+    let definition = active_context.term(active_property);
+    let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+
+    if let Value::String(s) = value {
+        match type_mapping {
+            Some("@id") => {
+                if let Some(id) = active_context.expand_iri(s, false)? {
+                    return Ok(Value::Object(Map::from_iter([(
+                        "@id".to_string(),
+                        Value::String(id),
+                    )])));
+                }
+            }
+            Some("@vocab") => {
+                if let Some(id) = active_context.expand_iri(s, true)? {
+                    return Ok(Value::Object(Map::from_iter([(
+                        "@id".to_string(),
+                        Value::String(id),
+                    )])));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut result = Map::from_iter([("@value".to_string(), value.clone())]);
+    match type_mapping {
+        Some(t) if !matches!(t, "@id" | "@vocab" | "@none") => {
+            result.insert("@type".to_string(), Value::String(t.to_string()));
+        }
+        _ if value.is_string() => {
+            let language = match definition.and_then(|d| d.language.clone()) {
+                Some(language) => language,
+                None => active_context.default_language.clone(),
+            };
+            if let Some(language) = language {
+                result.insert("@language".to_string(), Value::String(language));
+            }
+        }
+        _ => {}
+    }
+    Ok(Value::Object(result))
+}
+
+fn expand_language_map(active_context: &ActiveContext, key: &str, value: &Value) -> Result<Value> {
+    let mut languages: Vec<(&String, &Value)> = value.as_object().into_iter().flatten().collect();
+    languages.sort_by_key(|(language, _)| *language);
+
+    let mut expanded = vec![];
+    for (language, values) in languages {
+        let is_none = active_context.expand_iri(language, true)?.as_deref() == Some("@none");
+        for item in as_array(values.clone()) {
+            match item {
+                Value::Null => {}
+                Value::String(_) => {
+                    let mut value = Map::from_iter([("@value".to_string(), item)]);
+                    if !is_none {
+                        value.insert(
+                            "@language".to_string(),
+                            Value::String(language.to_lowercase()),
+                        );
+                    }
+                    expanded.push(Value::Object(value));
+                }
+                _ => {
+                    return Err(json_ld_error(&format!(
+                        "invalid language map value for {}",
+                        key
+                    )))
+                }
+            }
+        }
+    }
+    Ok(Value::Array(expanded))
+}
+
+fn expand_index_map(
+    active_context: &ActiveContext,
+    key: &str,
+    value: &Value,
+    is_graph: bool,
+) -> Result<Value> {
+    let mut indexes: Vec<(&String, &Value)> = value.as_object().into_iter().flatten().collect();
+    indexes.sort_by_key(|(index, _)| *index);
+
+    let mut expanded = vec![];
+    for (index, values) in indexes {
+        let is_none = active_context.expand_iri(index, true)?.as_deref() == Some("@none");
+        for item in as_array(expand_element(active_context, Some(key), values, true)?) {
+            let mut item = match (is_graph, item) {
+                (true, Value::Object(item)) if !item.contains_key("@graph") => Map::from_iter([(
+                    "@graph".to_string(),
+                    Value::Array(vec![Value::Object(item)]),
+                )]),
+                (_, Value::Object(item)) => item,
+                _ => {
+                    return Err(json_ld_error(&format!(
+                        "invalid index map value for {}",
+                        key
+                    )))
+                }
+            };
+            if !is_none && !item.contains_key("@index") {
+                item.insert("@index".to_string(), Value::String(index.clone()));
+            }
+            expanded.push(Value::Object(item));
+        }
+    }
+    Ok(Value::Array(expanded))
+}
+
+fn add_values(result: &mut Map<String, Value>, property: &str, value: Value) {
+    let values = result
+        .entry(property.to_string())
+        .or_insert_with(|| Value::Array(vec![]));
+    if let Value::Array(values) = values {
+        values.extend(as_array(value));
+    }
+}
+
+fn as_array(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(values) => values,
+        Value::Null => vec![],
+        value => vec![value],
+    }
+}
+
+fn is_list_object(value: &Value) -> bool {
+    value.get("@list").is_some()
+}
+
+fn list_object(value: Value) -> Value {
+    Value::Object(Map::from_iter([(
+        "@list".to_string(),
+        Value::Array(as_array(value)),
+    )]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    mod expand {
+        use super::*;
+
+        #[test]
+        fn test_expands_terms_and_values() {
+            let expanded = expand(&json!({
+                "@context": {
+                    "@vocab": "https://example.com/vocab#",
+                    "schema": "https://schema.org/",
+                    "name": "schema:name",
+                    "knows": { "@id": "schema:knows", "@type": "@id" },
+                    "tags": { "@id": "schema:keywords", "@container": "@list" },
+                    "label": { "@id": "schema:label", "@container": "@language" }
+                },
+                "@id": "did:example:alice",
+                "@type": "Person",
+                "name": "Alice",
+                "knows": "did:example:bob",
+                "tags": ["a", "b"],
+                // language maps are expanded in key order, where "FR" sorts before "en"
+                "label": { "en": "Alice", "FR": ["Alice"] },
+                "age": 42
+            }))
+            .unwrap();
+
+            assert_eq!(
+                expanded,
+                json!([{
+                    "@id": "did:example:alice",
+                    "@type": ["https://example.com/vocab#Person"],
+                    "https://example.com/vocab#age": [{ "@value": 42 }],
+                    "https://schema.org/keywords": [{ "@list": [{ "@value": "a" }, { "@value": "b" }] }],
+                    "https://schema.org/knows": [{ "@id": "did:example:bob" }],
+                    "https://schema.org/label": [
+                        { "@value": "Alice", "@language": "fr" },
+                        { "@value": "Alice", "@language": "en" }
+                    ],
+                    "https://schema.org/name": [{ "@value": "Alice" }]
+                }])
+            );
+        }
+
+        #[test]
+        fn test_type_scoped_contexts_do_not_propagate() {
+            let expanded = expand(&json!({
+                "@context": {
+                    "Outer": {
+                        "@id": "https://example.com/Outer",
+                        "@context": { "name": "https://example.com/outer-name" }
+                    },
+                    "name": "https://example.com/name",
+                    "child": "https://example.com/child"
+                },
+                "@type": "Outer",
+                "name": "outer",
+                "child": { "name": "inner" }
+            }))
+            .unwrap();
+
+            assert_eq!(
+                expanded,
+                json!([{
+                    "@type": ["https://example.com/Outer"],
+                    "https://example.com/child": [{
+                        "https://example.com/name": [{ "@value": "inner" }]
+                    }],
+                    "https://example.com/outer-name": [{ "@value": "outer" }]
+                }])
+            );
+        }
+
+        #[test]
+        fn test_graph_containers_and_json_literals() {
+            let expanded = expand(&json!({
+                "@context": {
+                    "proof": { "@id": "https://example.com/proof", "@container": "@graph" },
+                    "value": "https://example.com/value",
+                    "data": { "@id": "https://example.com/data", "@type": "@json" }
+                },
+                "proof": { "value": "signature" },
+                "data": { "b": [1, 2], "a": null }
+            }))
+            .unwrap();
+
+            assert_eq!(
+                expanded,
+                json!([{
+                    "https://example.com/data": [{ "@value": { "b": [1, 2], "a": null }, "@type": "@json" }],
+                    "https://example.com/proof": [{
+                        "@graph": [{ "https://example.com/value": [{ "@value": "signature" }] }]
+                    }]
+                }])
+            );
+        }
+
+        #[test]
+        fn test_undefined_property() {
+            let result = expand(&json!({
+                "@context": { "name": "https://schema.org/name" },
+                "name": "Alice",
+                "nickname": "Al"
+            }));
+            assert_eq!(
+                result.unwrap_err(),
+                json_ld_error("property nickname is not defined by the context")
+            );
+        }
+    }
+}
@@ -0,0 +1,14 @@
+mod context;
+mod document_loader;
+mod expansion;
+mod to_rdf;
+
+use crate::errors::Web5Error;
+
+pub use document_loader::register_context;
+pub use expansion::expand;
+pub use to_rdf::to_rdf;
+
+fn json_ld_error(message: &str) -> Web5Error {
+    Web5Error::JsonLd(message.to_string())
+}
@@ -0,0 +1,454 @@
+use super::{
+    context::{is_absolute_iri, is_blank_node_identifier, is_keyword},
+    expansion::expand,
+    json_ld_error,
+};
+use crate::{
+    errors::Result,
+    jcs,
+    rdf::{
+        Quad, Term, RDF_FIRST, RDF_JSON, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN, XSD_DOUBLE,
+        XSD_INTEGER, XSD_STRING,
+    },
+};
+use serde_json::{Map, Number, Value};
+use std::collections::{BTreeMap, HashMap};
+
+const DEFAULT_GRAPH: &str = "@default";
+
+/// Converts a JSON-LD document to an RDF dataset per the JSON-LD 1.1 "Deserialize JSON-LD to
+/// RDF" algorithm, after expanding it.
+///
+/// Nodes without an `@id` become blank nodes labeled `b0`, `b1`, ..., so the quads are typically
+/// passed to [`crate::rdf::canonicalization::canonicalize`] before being compared or signed.
+///
+/// # Arguments
+///
+/// * `document` - The JSON-LD document, whose remote contexts must be known to the document loader.
+///
+/// # Returns
+///
+/// * `Result<Vec<Quad>>` - The quads of the dataset.
+///
+/// # Examples
+///
+/// ```ignore
+/// let quads = to_rdf(&serde_json::to_value(&verifiable_credential)?)?;
+/// let canonical = canonicalize(&quads)?;
+/// ```
+pub fn to_rdf(document: &Value) -> Result<Vec<Quad>> {
+    let expanded = expand(document)?;
+
+    let mut node_map = NodeMap::default();
+    node_map.add(&expanded, DEFAULT_GRAPH, None, None)?;
+
+    let mut quads = vec![];
+    let graphs = std::mem::take(&mut node_map.graphs);
+    for (graph_name, nodes) in &graphs {
+        let graph = match graph_name.as_str() {
+            DEFAULT_GRAPH => None,
+            name => Some(node_term(name)?),
+        };
+        for (subject, node) in nodes {
+            let subject = node_term(subject)?;
+            for (property, values) in node {
+                let values = values.as_array().map(Vec::as_slice).unwrap_or_default();
+                if property == "@type" {
+                    for value in values {
+                        let t = value
+                            .as_str()
+                            .ok_or_else(|| json_ld_error("invalid type value"))?;
+                        quads.push(Quad {
+                            subject: subject.clone(),
+                            predicate: Term::Iri(RDF_TYPE.to_string()),
+                            object: node_term(t)?,
+                            graph: graph.clone(),
+                        });
+                    }
+                    continue;
+                }
+                if is_keyword(property) || is_blank_node_identifier(property) {
+                    continue;
+                }
+                if !is_absolute_iri(property) {
+                    return Err(json_ld_error(&format!("invalid property {}", property)));
+                }
+                for value in values {
+                    let mut list_quads = vec![];
+                    let object = node_map.object_to_rdf(value, &graph, &mut list_quads)?;
+                    quads.push(Quad {
+                        subject: subject.clone(),
+                        predicate: Term::Iri(property.clone()),
+                        object,
+                        graph: graph.clone(),
+                    });
+                    quads.extend(list_quads);
+                }
+            }
+        }
+    }
+
+    Ok(quads)
+}
+
+/// The nodes of each graph, keyed by graph name and node identifier, with blank node
+/// identifiers relabeled so that nodes without an `@id` can be referenced.
+#[derive(Default)]
+struct NodeMap {
+    graphs: BTreeMap<String, BTreeMap<String, Map<String, Value>>>,
+    issued: HashMap<String, String>,
+    counter: usize,
+}
+
+impl NodeMap {
+    fn issue(&mut self, existing: Option<&str>) -> String {
+        if let Some(issued) = existing.and_then(|e| self.issued.get(e)) {
+            return issued.clone();
+        }
+        let issued = format!("_:b{}", self.counter);
+        self.counter += 1;
+        if let Some(existing) = existing {
+            self.issued.insert(existing.to_string(), issued.clone());
+        }
+        issued
+    }
+
+    fn node(&mut self, graph: &str, id: &str) -> &mut Map<String, Value> {
+        self.graphs
+            .entry(graph.to_string())
+            .or_default()
+            .entry(id.to_string())
+            .or_insert_with(|| Map::from_iter([("@id".to_string(), Value::String(id.to_string()))]))
+    }
+
+    /// Adds an expanded element to the node map per the JSON-LD 1.1 "Node Map Generation"
+    /// algorithm, as a value of `property` of the `subject` node when both are given.
+    fn add(
+        &mut self,
+        element: &Value,
+        graph: &str,
+        subject: Option<&str>,
+        property: Option<&str>,
+    ) -> Result<Option<Value>> {
+        match element {
+            Value::Array(elements) => {
+                for element in elements {
+                    if let (Some(value), Some(subject), Some(property)) = (
+                        self.add(element, graph, subject, property)?,
+                        subject,
+                        property,
+                    ) {
+                        self.add_property_value(graph, subject, property, value);
+                    }
+                }
+                Ok(None)
+            }
+            Value::Object(element) if element.contains_key("@value") => {
+                let mut value = element.clone();
+                if let Some(Value::String(t)) = value.get("@type") {
+                    if is_blank_node_identifier(t) {
+                        let t = self.issue(Some(t));
+                        value.insert("@type".to_string(), Value::String(t));
+                    }
+                }
+                Ok(Some(Value::Object(value)))
+            }
+            Value::Object(element) if element.contains_key("@list") => {
+                let mut items = vec![];
+                for item in element["@list"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                {
+                    match item {
+                        Value::Array(_) => {
+                            return Err(json_ld_error("nested lists are not supported"));
+                        }
+                        item => {
+                            if let Some(value) = self.add(item, graph, None, None)? {
+                                items.push(value);
+                            }
+                        }
+                    }
+                }
+                Ok(Some(Value::Object(Map::from_iter([(
+                    "@list".to_string(),
+                    Value::Array(items),
+                )]))))
+            }
+            Value::Object(element) => {
+                let id = match element.get("@id").and_then(Value::as_str) {
+                    Some(id) if !is_blank_node_identifier(id) => id.to_string(),
+                    id => self.issue(id),
+                };
+                self.node(graph, &id);
+
+                if let Some(types) = element.get("@type").and_then(Value::as_array) {
+                    for t in types {
+                        let t = match t.as_str() {
+                            Some(t) if is_blank_node_identifier(t) => self.issue(Some(t)),
+                            Some(t) => t.to_string(),
+                            None => return Err(json_ld_error("invalid type value")),
+                        };
+                        self.add_property_value(graph, &id, "@type", Value::String(t));
+                    }
+                }
+                if let Some(index) = element.get("@index") {
+                    self.node(graph, &id)
+                        .insert("@index".to_string(), index.clone());
+                }
+                if let Some(graph_value) = element.get("@graph") {
+                    self.graphs.entry(id.clone()).or_default();
+                    self.add(graph_value, &id, None, None)?;
+                }
+
+                let mut properties: Vec<(&String, &Value)> =
+                    element.iter().filter(|(key, _)| !is_keyword(key)).collect();
+                properties.sort_by_key(|(property, _)| *property);
+                for (property, values) in properties {
+                    let property = match is_blank_node_identifier(property) {
+                        true => self.issue(Some(property)),
+                        false => property.clone(),
+                    };
+                    self.node(graph, &id)
+                        .entry(property.clone())
+                        .or_insert_with(|| Value::Array(vec![]));
+                    self.add(values, graph, Some(&id), Some(&property))?;
+                }
+
+                Ok(Some(Value::Object(Map::from_iter([(
+                    "@id".to_string(),
+                    Value::String(id),
+                )]))))
+            }
+            _ => Ok(Some(element.clone())),
+        }
+    }
+
+    /// Appends a value to a node's property, ignoring duplicates other than lists.
+    fn add_property_value(&mut self, graph: &str, subject: &str, property: &str, value: Value) {
+        let values = self
+            .node(graph, subject)
+            .entry(property.to_string())
+            .or_insert_with(|| Value::Array(vec![]));
+        if let Value::Array(values) = values {
+            if value.get("@list").is_none() && values.contains(&value) {
+                return;
+            }
+            values.push(value);
+        }
+    }
+
+    /// Converts a node reference, value object, or list object to an RDF term, adding the quads
+    /// which describe a list to `list_quads`.
+    fn object_to_rdf(
+        &mut self,
+        item: &Value,
+        graph: &Option<Term>,
+        list_quads: &mut Vec<Quad>,
+    ) -> Result<Term> {
+        if let Some(list) = item.get("@list") {
+            let items = list.as_array().map(Vec::as_slice).unwrap_or_default();
+            return self.list_to_rdf(items, graph, list_quads);
+        }
+        if let Some(value) = item.get("@value") {
+            return value_to_rdf(item, value);
+        }
+        match item.get("@id").and_then(Value::as_str) {
+            Some(id) => node_term(id),
+            None => Err(json_ld_error(&format!("invalid node reference {}", item))),
+        }
+    }
+
+    fn list_to_rdf(
+        &mut self,
+        items: &[Value],
+        graph: &Option<Term>,
+        list_quads: &mut Vec<Quad>,
+    ) -> Result<Term> {
+        if items.is_empty() {
+            return Ok(Term::Iri(RDF_NIL.to_string()));
+        }
+
+        let nodes: Vec<Term> = items
+            .iter()
+            .map(|_| Term::BlankNode(self.issue(None)[2..].to_string()))
+            .collect();
+        for (i, item) in items.iter().enumerate() {
+            let mut nested_quads = vec![];
+            let first = self.object_to_rdf(item, graph, &mut nested_quads)?;
+            list_quads.push(Quad {
+                subject: nodes[i].clone(),
+                predicate: Term::Iri(RDF_FIRST.to_string()),
+                object: first,
+                graph: graph.clone(),
+            });
+            list_quads.extend(nested_quads);
+            list_quads.push(Quad {
+                subject: nodes[i].clone(),
+                predicate: Term::Iri(RDF_REST.to_string()),
+                object: nodes
+                    .get(i + 1)
+                    .cloned()
+                    .unwrap_or_else(|| Term::Iri(RDF_NIL.to_string())),
+                graph: graph.clone(),
+            });
+        }
+        Ok(nodes[0].clone())
+    }
+}
+
+/// Converts a node identifier to an IRI or blank node. Relative IRIs are rejected rather than
+/// dropped, for the same reason expansion rejects undefined terms.
+fn node_term(id: &str) -> Result<Term> {
+    if let Some(label) = id.strip_prefix("_:") {
+        return Ok(Term::BlankNode(label.to_string()));
+    }
+    if is_absolute_iri(id) {
+        return Ok(Term::Iri(id.to_string()));
+    }
+    Err(json_ld_error(&format!(
+        "relative IRI {} is not allowed",
+        id
+    )))
+}
+
+fn value_to_rdf(item: &Value, value: &Value) -> Result<Term> {
+    let datatype = item.get("@type").and_then(Value::as_str);
+    if let Some(language) = item.get("@language").and_then(Value::as_str) {
+        let value = value
+            .as_str()
+            .ok_or_else(|| json_ld_error("invalid language-tagged value"))?;
+        return Ok(Term::language_literal(value, language));
+    }
+
+    if datatype == Some("@json") {
+        return Ok(Term::literal(&jcs::canonicalize(value)?, RDF_JSON));
+    }
+    if let Some(datatype) = datatype {
+        if !is_absolute_iri(datatype) {
+            return Err(json_ld_error(&format!("invalid typed value {}", datatype)));
+        }
+    }
+
+    Ok(match value {
+        Value::Bool(b) => Term::literal(&b.to_string(), datatype.unwrap_or(XSD_BOOLEAN)),
+        Value::Number(n) => number_to_rdf(n, datatype)?,
+        Value::String(s) => Term::literal(s, datatype.unwrap_or(XSD_STRING)),
+        _ => return Err(json_ld_error(&format!("invalid value {}", value))),
+    })
+}
+
+/// Converts a number to an `xsd:integer` literal, or to a canonical `xsd:double` literal such as
+/// `5.3E0` if it has a fractional part, is too large to be an integer, or is typed as a double.
+fn number_to_rdf(number: &Number, datatype: Option<&str>) -> Result<Term> {
+    let value = number
+        .as_f64()
+        .ok_or_else(|| json_ld_error(&format!("invalid number {}", number)))?;
+    let is_double = datatype == Some(XSD_DOUBLE)
+        || (number.is_f64() && (value.fract() != 0.0 || value.abs() >= 1e21));
+
+    if !is_double {
+        let integer = match number.is_f64() {
+            true => format!("{:.0}", value),
+            false => number.to_string(),
+        };
+        return Ok(Term::literal(&integer, datatype.unwrap_or(XSD_INTEGER)));
+    }
+
+    let exponential = format!("{:.15e}", value);
+    let (mantissa, exponent) = exponential
+        .split_once('e')
+        .ok_or_else(|| json_ld_error(&format!("invalid number {}", number)))?;
+    let mut mantissa = mantissa.trim_end_matches('0').to_string();
+    if mantissa.ends_with('.') {
+        mantissa.push('0');
+    }
+    Ok(Term::literal(
+        &format!("{}E{}", mantissa, exponent),
+        datatype.unwrap_or(XSD_DOUBLE),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rdf::canonicalization::canonicalize;
+    use serde_json::json;
+
+    mod to_rdf {
+        use super::*;
+
+        #[test]
+        fn test_literals() {
+            let quads = to_rdf(&json!({
+                "@context": {
+                    "@vocab": "https://example.com/#",
+                    "greeting": { "@id": "https://example.com/#greeting", "@language": "EN" },
+                    "data": { "@id": "https://example.com/#data", "@type": "@json" }
+                },
+                "@id": "https://example.com/subject",
+                "boolean": true,
+                "integer": 42,
+                "integral": 5.0,
+                "double": 5.3,
+                "large": 1e21,
+                "greeting": "hello",
+                "data": { "b": 1, "a": [true] }
+            }))
+            .unwrap();
+
+            let canonical = canonicalize(&quads).unwrap();
+            assert_eq!(
+                canonical,
+                "<https://example.com/subject> <https://example.com/#boolean> \"true\"^^<http://www.w3.org/2001/XMLSchema#boolean> .\n\
+                 <https://example.com/subject> <https://example.com/#data> \"{\\\"a\\\":[true],\\\"b\\\":1}\"^^<http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON> .\n\
+                 <https://example.com/subject> <https://example.com/#double> \"5.3E0\"^^<http://www.w3.org/2001/XMLSchema#double> .\n\
+                 <https://example.com/subject> <https://example.com/#greeting> \"hello\"@en .\n\
+                 <https://example.com/subject> <https://example.com/#integer> \"42\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+                 <https://example.com/subject> <https://example.com/#integral> \"5\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n\
+                 <https://example.com/subject> <https://example.com/#large> \"1.0E21\"^^<http://www.w3.org/2001/XMLSchema#double> .\n"
+            );
+        }
+
+        #[test]
+        fn test_blank_nodes_lists_and_graphs() {
+            let quads = to_rdf(&json!({
+                "@context": {
+                    "@vocab": "https://example.com/#",
+                    "items": { "@id": "https://example.com/#items", "@container": "@list" },
+                    "proof": { "@id": "https://example.com/#proof", "@type": "@id", "@container": "@graph" }
+                },
+                "@id": "https://example.com/subject",
+                "items": ["a", "b"],
+                "proof": { "value": "signature" }
+            }))
+            .unwrap();
+
+            let canonical = canonicalize(&quads).unwrap();
+            assert_eq!(
+                canonical,
+                "<https://example.com/subject> <https://example.com/#items> _:c14n3 .\n\
+                 <https://example.com/subject> <https://example.com/#proof> _:c14n2 .\n\
+                 _:c14n0 <https://example.com/#value> \"signature\" _:c14n2 .\n\
+                 _:c14n1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"b\" .\n\
+                 _:c14n1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> <http://www.w3.org/1999/02/22-rdf-syntax-ns#nil> .\n\
+                 _:c14n3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#first> \"a\" .\n\
+                 _:c14n3 <http://www.w3.org/1999/02/22-rdf-syntax-ns#rest> _:c14n1 .\n"
+            );
+        }
+
+        #[test]
+        fn test_relative_iri() {
+            let result = to_rdf(&json!({
+                "@context": { "name": "https://schema.org/name" },
+                "@id": "alice",
+                "name": "Alice"
+            }));
+            assert_eq!(
+                result.unwrap_err(),
+                json_ld_error("relative IRI alice is not allowed")
+            );
+        }
+    }
+}
@@ -1,11 +1,16 @@
 pub mod credentials;
 pub mod crypto;
+pub mod didcomm;
 pub mod dids;
 
 mod datetime;
 pub mod errors;
+pub mod jcs;
 pub mod jose;
 pub mod json;
+pub mod json_ld;
+pub mod rdf;
+pub mod sd_jwt;
 
 #[cfg(test)]
 mod test_vectors;
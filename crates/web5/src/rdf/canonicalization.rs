@@ -0,0 +1,476 @@
+use super::{Quad, Term};
+use crate::errors::{Result, Web5Error};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Bounds the number of N-degree hashing steps, so that maliciously crafted datasets with many
+/// indistinguishable blank nodes ("poison graphs") fail fast instead of running for hours.
+const MAX_WORK: usize = 100_000;
+
+/// Canonicalizes an RDF dataset per [RDF Dataset Canonicalization (RDFC-1.0)](https://www.w3.org/TR/rdf-canon/),
+/// formerly known as URDNA2015.
+///
+/// Blank nodes are deterministically relabeled `c14n0`, `c14n1`, ... so that isomorphic datasets
+/// produce the same output regardless of their original blank node labels or quad order.
+///
+/// # Arguments
+///
+/// * `dataset` - The quads of the dataset to canonicalize.
+///
+/// # Returns
+///
+/// * `Result<String>` - The canonical N-Quads document, one sorted quad per line.
+///
+/// # Examples
+///
+/// ```ignore
+/// let quads = json_ld::to_rdf(&credential_json)?;
+/// let canonical = canonicalize(&quads)?;
+/// ```
+pub fn canonicalize(dataset: &[Quad]) -> Result<String> {
+    let mut canonicalizer = Canonicalizer::new(dataset);
+    let canonical_issuer = canonicalizer.issue_canonical_identifiers()?;
+
+    let mut lines: Vec<String> = dataset
+        .iter()
+        .map(|quad| relabel(quad, &|label| canonical_issuer.issued(label)).to_nquads())
+        .collect();
+    lines.sort();
+    lines.dedup();
+
+    Ok(lines.concat())
+}
+
+/// Issues identifiers with a prefix and an incrementing counter, remembering the order in which
+/// existing identifiers were first seen.
+#[derive(Clone)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            issued: HashMap::new(),
+            order: vec![],
+        }
+    }
+
+    fn issue(&mut self, existing: &str) -> String {
+        if let Some(issued) = self.issued.get(existing) {
+            return issued.clone();
+        }
+        let issued = format!("{}{}", self.prefix, self.order.len());
+        self.issued.insert(existing.to_string(), issued.clone());
+        self.order.push(existing.to_string());
+        issued
+    }
+
+    fn issued(&self, existing: &str) -> Option<String> {
+        self.issued.get(existing).cloned()
+    }
+}
+
+struct Canonicalizer<'a> {
+    dataset: &'a [Quad],
+    blank_node_quads: BTreeMap<String, Vec<usize>>,
+    first_degree_hashes: HashMap<String, String>,
+    canonical_issuer: IdentifierIssuer,
+    work: usize,
+}
+
+impl<'a> Canonicalizer<'a> {
+    fn new(dataset: &'a [Quad]) -> Self {
+        let mut blank_node_quads: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (index, quad) in dataset.iter().enumerate() {
+            for label in blank_node_labels(quad) {
+                let quads = blank_node_quads.entry(label.to_string()).or_default();
+                if quads.last() != Some(&index) {
+                    quads.push(index);
+                }
+            }
+        }
+
+        Self {
+            dataset,
+            blank_node_quads,
+            first_degree_hashes: HashMap::new(),
+            canonical_issuer: IdentifierIssuer::new("c14n"),
+            work: 0,
+        }
+    }
+
+    fn issue_canonical_identifiers(&mut self) -> Result<IdentifierIssuer> {
+        let mut hash_to_blank_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let labels: Vec<String> = self.blank_node_quads.keys().cloned().collect();
+        for label in labels {
+            let hash = self.hash_first_degree_quads(&label);
+            hash_to_blank_nodes.entry(hash).or_default().push(label);
+        }
+
+        // blank nodes with a unique first degree hash are labeled in hash order
+        let mut shared = vec![];
+        for (_, labels) in hash_to_blank_nodes {
+            match labels.len() {
+                1 => {
+                    self.canonical_issuer.issue(&labels[0]);
+                }
+                _ => shared.push(labels),
+            }
+        }
+
+        // the rest are distinguished by the blank nodes they are connected to
+        for labels in shared {
+            let mut hash_path_list = vec![];
+            for label in labels {
+                if self.canonical_issuer.issued(&label).is_some() {
+                    continue;
+                }
+                let mut temporary_issuer = IdentifierIssuer::new("b");
+                temporary_issuer.issue(&label);
+                hash_path_list.push(self.hash_n_degree_quads(&label, temporary_issuer)?);
+            }
+
+            hash_path_list.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (_, issuer) in hash_path_list {
+                for existing in issuer.order {
+                    self.canonical_issuer.issue(&existing);
+                }
+            }
+        }
+
+        Ok(self.canonical_issuer.clone())
+    }
+
+    fn hash_first_degree_quads(&mut self, label: &str) -> String {
+        if let Some(hash) = self.first_degree_hashes.get(label) {
+            return hash.clone();
+        }
+
+        let mut nquads: Vec<String> = self.blank_node_quads[label]
+            .iter()
+            .map(|&index| {
+                relabel(&self.dataset[index], &|other| match other == label {
+                    true => Some("a".to_string()),
+                    false => Some("z".to_string()),
+                })
+                .to_nquads()
+            })
+            .collect();
+        nquads.sort();
+
+        let hash = sha256_hex(&nquads.concat());
+        self.first_degree_hashes
+            .insert(label.to_string(), hash.clone());
+        hash
+    }
+
+    fn hash_related_blank_node(
+        &mut self,
+        related: &str,
+        quad: &Quad,
+        issuer: &IdentifierIssuer,
+        position: &str,
+    ) -> String {
+        let identifier = match self
+            .canonical_issuer
+            .issued(related)
+            .or_else(|| issuer.issued(related))
+        {
+            Some(issued) => format!("_:{}", issued),
+            None => self.hash_first_degree_quads(related),
+        };
+
+        let mut input = position.to_string();
+        if position != "g" {
+            input.push_str(&quad.predicate.to_nquads());
+        }
+        input.push_str(&identifier);
+        sha256_hex(&input)
+    }
+
+    fn hash_n_degree_quads(
+        &mut self,
+        label: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> Result<(String, IdentifierIssuer)> {
+        self.spend_work()?;
+
+        let mut hash_to_related: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let dataset = self.dataset;
+        for &index in &self.blank_node_quads[label].clone() {
+            let quad = &dataset[index];
+            let components = [
+                (Some(&quad.subject), "s"),
+                (Some(&quad.object), "o"),
+                (quad.graph.as_ref(), "g"),
+            ];
+            for (component, position) in components {
+                if let Some(related) = component.and_then(Term::blank_node_label) {
+                    if related != label {
+                        let hash = self.hash_related_blank_node(related, quad, &issuer, position);
+                        hash_to_related
+                            .entry(hash)
+                            .or_default()
+                            .push(related.to_string());
+                    }
+                }
+            }
+        }
+
+        let mut data_to_hash = String::new();
+        for (related_hash, mut related) in hash_to_related {
+            data_to_hash.push_str(&related_hash);
+            let mut chosen: Option<(String, IdentifierIssuer)> = None;
+
+            related.sort();
+            loop {
+                self.spend_work()?;
+                if let Some(candidate) = self.hash_permutation(&related, &issuer, &chosen)? {
+                    chosen = Some(candidate);
+                }
+                if !next_permutation(&mut related) {
+                    break;
+                }
+            }
+
+            if let Some((chosen_path, chosen_issuer)) = chosen {
+                data_to_hash.push_str(&chosen_path);
+                issuer = chosen_issuer;
+            }
+        }
+
+        Ok((sha256_hex(&data_to_hash), issuer))
+    }
+
+    /// Computes the path for one ordering of related blank nodes, returning `None` as soon as
+    /// it can no longer be smaller than the chosen path.
+    fn hash_permutation(
+        &mut self,
+        permutation: &[String],
+        issuer: &IdentifierIssuer,
+        chosen: &Option<(String, IdentifierIssuer)>,
+    ) -> Result<Option<(String, IdentifierIssuer)>> {
+        let exceeds_chosen = |path: &str| match chosen {
+            Some((chosen_path, _)) => {
+                path.len() >= chosen_path.len() && path > chosen_path.as_str()
+            }
+            None => false,
+        };
+
+        let mut issuer_copy = issuer.clone();
+        let mut path = String::new();
+        let mut recursion_list = vec![];
+
+        for related in permutation {
+            match self.canonical_issuer.issued(related) {
+                Some(canonical) => path.push_str(&format!("_:{}", canonical)),
+                None => {
+                    if issuer_copy.issued(related).is_none() {
+                        recursion_list.push(related.clone());
+                    }
+                    path.push_str(&format!("_:{}", issuer_copy.issue(related)));
+                }
+            }
+            if exceeds_chosen(&path) {
+                return Ok(None);
+            }
+        }
+
+        for related in recursion_list {
+            let (hash, result_issuer) = self.hash_n_degree_quads(&related, issuer_copy.clone())?;
+            path.push_str(&format!("_:{}", issuer_copy.issue(&related)));
+            path.push_str(&format!("<{}>", hash));
+            issuer_copy = result_issuer;
+            if exceeds_chosen(&path) {
+                return Ok(None);
+            }
+        }
+
+        match chosen {
+            Some((chosen_path, _)) if path >= *chosen_path => Ok(None),
+            _ => Ok(Some((path, issuer_copy))),
+        }
+    }
+
+    fn spend_work(&mut self) -> Result<()> {
+        self.work += 1;
+        if self.work > MAX_WORK {
+            return Err(Web5Error::Rdf(
+                "canonicalization exceeded the maximum amount of work".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn blank_node_labels(quad: &Quad) -> impl Iterator<Item = &str> {
+    [Some(&quad.subject), Some(&quad.object), quad.graph.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter_map(Term::blank_node_label)
+}
+
+/// Returns a copy of the quad with each blank node label replaced by the one `label_for` maps it to.
+fn relabel(quad: &Quad, label_for: &dyn Fn(&str) -> Option<String>) -> Quad {
+    let relabel_term = |term: &Term| match term {
+        Term::BlankNode(label) => Term::BlankNode(label_for(label).unwrap_or(label.clone())),
+        term => term.clone(),
+    };
+    Quad {
+        subject: relabel_term(&quad.subject),
+        predicate: quad.predicate.clone(),
+        object: relabel_term(&quad.object),
+        graph: quad.graph.as_ref().map(relabel_term),
+    }
+}
+
+/// Rearranges the values into the next lexicographic permutation, returning false once they
+/// are back in sorted order.
+fn next_permutation(values: &mut [String]) -> bool {
+    let Some(pivot) = (1..values.len()).rev().find(|&i| values[i - 1] < values[i]) else {
+        values.reverse();
+        return false;
+    };
+    let successor = (pivot..values.len())
+        .rev()
+        .find(|&i| values[i] > values[pivot - 1])
+        .unwrap_or(pivot);
+    values.swap(pivot - 1, successor);
+    values[pivot..].reverse();
+    true
+}
+
+fn sha256_hex(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iri(value: &str) -> Term {
+        Term::Iri(format!("http://example.com/#{}", value))
+    }
+
+    fn blank(label: &str) -> Term {
+        Term::BlankNode(label.to_string())
+    }
+
+    fn quad(subject: Term, predicate: &str, object: Term) -> Quad {
+        Quad {
+            subject,
+            predicate: iri(predicate),
+            object,
+            graph: None,
+        }
+    }
+
+    mod canonicalize {
+        use super::*;
+
+        #[test]
+        fn test_unique_hashes() {
+            // the first example in the RDFC-1.0 specification
+            let dataset = vec![
+                quad(iri("p"), "q", blank("e0")),
+                quad(iri("p"), "r", blank("e1")),
+                quad(blank("e0"), "s", iri("u")),
+                quad(blank("e1"), "t", iri("u")),
+            ];
+
+            assert_eq!(
+                canonicalize(&dataset).unwrap(),
+                "<http://example.com/#p> <http://example.com/#q> _:c14n0 .\n\
+                 <http://example.com/#p> <http://example.com/#r> _:c14n1 .\n\
+                 _:c14n0 <http://example.com/#s> <http://example.com/#u> .\n\
+                 _:c14n1 <http://example.com/#t> <http://example.com/#u> .\n"
+            );
+        }
+
+        #[test]
+        fn test_shared_hashes() {
+            // the second example in the RDFC-1.0 specification, where _:e2 and _:e3 can only be
+            // told apart through the blank nodes they refer to
+            let dataset = vec![
+                quad(iri("p"), "q", blank("e0")),
+                quad(iri("p"), "q", blank("e1")),
+                quad(blank("e0"), "p", blank("e2")),
+                quad(blank("e1"), "p", blank("e3")),
+                quad(blank("e2"), "r", blank("e3")),
+            ];
+
+            assert_eq!(
+                canonicalize(&dataset).unwrap(),
+                "<http://example.com/#p> <http://example.com/#q> _:c14n2 .\n\
+                 <http://example.com/#p> <http://example.com/#q> _:c14n3 .\n\
+                 _:c14n0 <http://example.com/#r> _:c14n1 .\n\
+                 _:c14n2 <http://example.com/#p> _:c14n1 .\n\
+                 _:c14n3 <http://example.com/#p> _:c14n0 .\n"
+            );
+        }
+
+        #[test]
+        fn test_ignores_labels_and_order() {
+            let dataset = vec![
+                quad(iri("p"), "q", blank("e0")),
+                quad(iri("p"), "q", blank("e1")),
+                quad(blank("e0"), "p", blank("e2")),
+                quad(blank("e1"), "p", blank("e3")),
+                quad(blank("e2"), "r", blank("e3")),
+            ];
+            let relabeled = vec![
+                quad(blank("y"), "r", blank("x")),
+                quad(blank("w"), "p", blank("x")),
+                quad(iri("p"), "q", blank("w")),
+                quad(blank("v"), "p", blank("y")),
+                quad(iri("p"), "q", blank("v")),
+            ];
+
+            assert_eq!(
+                canonicalize(&dataset).unwrap(),
+                canonicalize(&relabeled).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_symmetric_graph() {
+            // a cycle of indistinguishable blank nodes
+            let dataset = vec![
+                quad(blank("a"), "next", blank("b")),
+                quad(blank("b"), "next", blank("c")),
+                quad(blank("c"), "next", blank("a")),
+            ];
+
+            assert_eq!(
+                canonicalize(&dataset).unwrap(),
+                "_:c14n0 <http://example.com/#next> _:c14n1 .\n\
+                 _:c14n1 <http://example.com/#next> _:c14n2 .\n\
+                 _:c14n2 <http://example.com/#next> _:c14n0 .\n"
+            );
+        }
+
+        #[test]
+        fn test_named_graphs() {
+            let dataset = vec![Quad {
+                subject: blank("s"),
+                predicate: iri("p"),
+                object: Term::literal("o", crate::rdf::XSD_STRING),
+                graph: Some(blank("g")),
+            }];
+
+            let canonical = canonicalize(&dataset).unwrap();
+            assert!(
+                canonical.ends_with(" <http://example.com/#p> \"o\" _:c14n0 .\n")
+                    || canonical.ends_with(" <http://example.com/#p> \"o\" _:c14n1 .\n")
+            );
+            assert_eq!(canonical.matches("_:c14n").count(), 2);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+pub mod canonicalization;
+
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+pub const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+pub const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+pub const RDF_JSON: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#JSON";
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+pub const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+pub const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+pub const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+/// A node or value in an RDF graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    /// An absolute IRI, without the enclosing angle brackets.
+    Iri(String),
+    /// A blank node label, without the `_:` prefix.
+    BlankNode(String),
+    /// A literal value with its datatype IRI, and a language tag when the datatype is
+    /// `rdf:langString`.
+    Literal {
+        value: String,
+        datatype: String,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    /// Creates a literal of the given datatype.
+    pub fn literal(value: &str, datatype: &str) -> Self {
+        Term::Literal {
+            value: value.to_string(),
+            datatype: datatype.to_string(),
+            language: None,
+        }
+    }
+
+    /// Creates a language-tagged string literal.
+    pub fn language_literal(value: &str, language: &str) -> Self {
+        Term::Literal {
+            value: value.to_string(),
+            datatype: RDF_LANG_STRING.to_string(),
+            language: Some(language.to_string()),
+        }
+    }
+
+    /// Returns the blank node label if this term is a blank node.
+    pub fn blank_node_label(&self) -> Option<&str> {
+        match self {
+            Term::BlankNode(label) => Some(label),
+            _ => None,
+        }
+    }
+
+    /// Serializes the term in canonical N-Quads form.
+    pub fn to_nquads(&self) -> String {
+        match self {
+            Term::Iri(iri) => format!("<{}>", iri),
+            Term::BlankNode(label) => format!("_:{}", label),
+            Term::Literal {
+                value,
+                datatype,
+                language,
+            } => {
+                let mut serialized = format!("\"{}\"", escape_literal(value));
+                match language {
+                    Some(language) => {
+                        serialized.push('@');
+                        serialized.push_str(language);
+                    }
+                    None if datatype != XSD_STRING => {
+                        serialized.push_str(&format!("^^<{}>", datatype));
+                    }
+                    None => {}
+                }
+                serialized
+            }
+        }
+    }
+}
+
+/// A statement in an RDF dataset, in the default graph when `graph` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Quad {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+    pub graph: Option<Term>,
+}
+
+impl Quad {
+    /// Serializes the quad as a single canonical N-Quads line, including the trailing newline.
+    pub fn to_nquads(&self) -> String {
+        match &self.graph {
+            Some(graph) => format!(
+                "{} {} {} {} .\n",
+                self.subject.to_nquads(),
+                self.predicate.to_nquads(),
+                self.object.to_nquads(),
+                graph.to_nquads()
+            ),
+            None => format!(
+                "{} {} {} .\n",
+                self.subject.to_nquads(),
+                self.predicate.to_nquads(),
+                self.object.to_nquads()
+            ),
+        }
+    }
+}
+
+/// Escapes a literal's lexical form as required by canonical N-Quads.
+fn escape_literal(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '\u{0}'..='\u{1f}' | '\u{7f}' => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod to_nquads {
+        use super::*;
+
+        #[test]
+        fn test_serializes_quads() {
+            let quad = Quad {
+                subject: Term::BlankNode("b0".to_string()),
+                predicate: Term::Iri("http://example.org/name".to_string()),
+                object: Term::language_literal("Alice", "en"),
+                graph: Some(Term::Iri("http://example.org/graph".to_string())),
+            };
+            assert_eq!(
+                quad.to_nquads(),
+                "_:b0 <http://example.org/name> \"Alice\"@en <http://example.org/graph> .\n"
+            );
+
+            let quad = Quad {
+                subject: Term::Iri("did:example:123".to_string()),
+                predicate: Term::Iri("http://example.org/age".to_string()),
+                object: Term::literal("42", XSD_INTEGER),
+                graph: None,
+            };
+            assert_eq!(
+                quad.to_nquads(),
+                "<did:example:123> <http://example.org/age> \"42\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n"
+            );
+        }
+
+        #[test]
+        fn test_omits_xsd_string_and_escapes_literals() {
+            let term = Term::literal("a\"b\\c\nd\re\tf\u{8}g\u{c}h\u{1}i\u{7f}j€", XSD_STRING);
+            assert_eq!(
+                term.to_nquads(),
+                "\"a\\\"b\\\\c\\nd\\re\\tf\\bg\\fh\\u0001i\\u007Fj€\""
+            );
+        }
+    }
+}
@@ -0,0 +1,1506 @@
+use crate::{
+    credentials::VerificationError,
+    crypto::jwk::Jwk,
+    dids::{
+        bearer_did::{BearerDid, SignerOptions},
+        data_model::document::VerificationRelationship,
+    },
+    errors::{Result, Web5Error},
+    jose::{JoseHeader, Jws, Jwt, JwtClaims},
+    json::{JsonValue, ToJson},
+};
+use base64::{engine::general_purpose, Engine};
+use chrono::Utc;
+use rand::{rngs::OsRng, RngCore};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, SystemTime},
+};
+
+/// The `typ` header of an SD-JWT, unless the profile it's issued for registers its own.
+pub const SD_JWT_MEDIA_TYPE: &str = "sd+jwt";
+/// The `typ` header of a Key Binding JWT.
+pub const KB_JWT_MEDIA_TYPE: &str = "kb+jwt";
+/// The hash algorithm of disclosure digests, given by the `_sd_alg` claim.
+pub const SD_ALG: &str = "sha-256";
+/// How long after it's issued a Key Binding JWT is accepted.
+pub const MAX_KEY_BINDING_AGE: Duration = Duration::from_secs(300);
+
+/// How far in the future a Key Binding JWT's `iat` may be, to allow for clock skew.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+const SD_CLAIM: &str = "_sd";
+const SD_ALG_CLAIM: &str = "_sd_alg";
+const ARRAY_ELEMENT_KEY: &str = "...";
+
+/// A salted claim, or array element, concealed in an SD-JWT by the digest of its encoding, as
+/// defined by [SD-JWT](https://www.rfc-editor.org/rfc/rfc9901.html#name-disclosures).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disclosure {
+    pub salt: String,
+    /// The claim name, or `None` for an array element.
+    pub claim_name: Option<String>,
+    pub claim_value: Value,
+    /// The base64url encoding of the `[salt, claim_name, claim_value]` array, as presented.
+    pub encoded: String,
+}
+
+impl Disclosure {
+    /// Creates a disclosure of the claim, or of an array element if `claim_name` is `None`,
+    /// with a random 128-bit salt.
+    pub fn new(claim_name: Option<String>, claim_value: Value) -> Result<Self> {
+        let salt = random_salt();
+        let array = match &claim_name {
+            Some(claim_name) => json!([salt, claim_name, claim_value]),
+            None => json!([salt, claim_value]),
+        };
+        let encoded = general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_string(&array)?);
+
+        Ok(Self {
+            salt,
+            claim_name,
+            claim_value,
+            encoded,
+        })
+    }
+
+    /// Decodes a disclosure as presented in an SD-JWT.
+    pub fn from_encoded(encoded: &str) -> Result<Self> {
+        let malformed =
+            || VerificationError::SelectiveDisclosure(format!("malformed disclosure {}", encoded));
+
+        let decoded = general_purpose::URL_SAFE_NO_PAD
+            .decode(encoded)
+            .map_err(|_| malformed())?;
+        let array = match serde_json::from_slice::<Value>(&decoded).map_err(|_| malformed())? {
+            Value::Array(array) => array,
+            _ => return Err(malformed().into()),
+        };
+
+        let (salt, claim_name, claim_value) = match array.as_slice() {
+            [Value::String(salt), Value::String(claim_name), claim_value] => {
+                (salt, Some(claim_name.clone()), claim_value)
+            }
+            [Value::String(salt), claim_value] => (salt, None, claim_value),
+            _ => return Err(malformed().into()),
+        };
+
+        Ok(Self {
+            salt: salt.clone(),
+            claim_name,
+            claim_value: claim_value.clone(),
+            encoded: encoded.to_string(),
+        })
+    }
+
+    /// The base64url-encoded SHA-256 digest of the encoded disclosure, by which the SD-JWT
+    /// references it.
+    pub fn digest(&self) -> String {
+        sha256_base64url(&self.encoded)
+    }
+}
+
+/// Options for issuing an SD-JWT.
+#[derive(Debug, Clone, Default)]
+pub struct SdJwtCreateOptions {
+    /// The claims to conceal behind disclosures, as [JSON Pointers](https://www.rfc-editor.org/rfc/rfc6901)
+    /// into the claims, such as `/address/street_address` or `/nationalities/0`. A claim nested
+    /// in a disclosed claim is concealed within its parent's disclosure.
+    pub disclosable_claims: Vec<String>,
+
+    /// The number of decoy digests added to each `_sd` array, to hide the number of concealed
+    /// claims. Defaults to none.
+    pub decoy_digests: Option<usize>,
+
+    /// The holder's public key, set as the `cnf` claim, which the holder proves possession of
+    /// with a Key Binding JWT.
+    pub holder_jwk: Option<Jwk>,
+
+    /// The `typ` header. Defaults to `sd+jwt`.
+    pub typ: Option<String>,
+
+    /// The verification method to sign with. If not provided, the first `assertionMethod`
+    /// verification method is used.
+    pub verification_method_id: Option<String>,
+}
+
+/// Options for verifying a presented SD-JWT.
+#[derive(Debug, Clone, Default)]
+pub struct SdJwtVerifyOptions {
+    /// If true, the presentation must include a Key Binding JWT.
+    pub require_key_binding: bool,
+
+    /// The verifier's identifier, which the Key Binding JWT's `aud` claim must contain.
+    pub audience: Option<String>,
+
+    /// The nonce the verifier provided, which the Key Binding JWT's `nonce` claim must match.
+    pub nonce: Option<String>,
+}
+
+impl SdJwtVerifyOptions {
+    /// The audience and nonce to check a Key Binding JWT against, both of which are required when
+    /// key binding is required or a Key Binding JWT is presented.
+    fn key_binding_parameters(&self) -> Result<(&String, &String)> {
+        match (&self.audience, &self.nonce) {
+            (Some(audience), Some(nonce)) => Ok((audience, nonce)),
+            _ => Err(Web5Error::Parameter(
+                "audience and nonce are required to verify a key binding jwt".to_string(),
+            )),
+        }
+    }
+}
+
+/// Represents an [SD-JWT](https://www.rfc-editor.org/rfc/rfc9901.html): an issuer-signed JWT
+/// whose selectively disclosable claims are replaced by digests, the disclosures revealing some
+/// of them, and optionally a Key Binding JWT signed by the holder.
+#[derive(Clone)]
+pub struct SdJwt {
+    pub issuer_jwt: Jwt,
+    pub disclosures: Vec<Disclosure>,
+    pub key_binding_jwt: Option<Jwt>,
+}
+
+impl SdJwt {
+    /// Issues an SD-JWT of the claims, concealing the selected claims behind disclosures.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The claims of the SD-JWT, as if all were disclosed.
+    /// * `bearer_did` - The issuer's DID, which signs the SD-JWT.
+    /// * `options` - Which claims are selectively disclosable, and how to sign.
+    ///
+    /// # Returns
+    ///
+    /// The SD-JWT, with a disclosure for each concealed claim.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let sd_jwt = SdJwt::create(
+    ///     &claims,
+    ///     &issuer_bearer_did,
+    ///     &SdJwtCreateOptions {
+    ///         disclosable_claims: vec!["/given_name".to_string(), "/address/locality".to_string()],
+    ///         decoy_digests: Some(2),
+    ///         ..Default::default()
+    ///     },
+    /// )
+    /// .unwrap();
+    /// let serialized = sd_jwt.serialize();
+    /// ```
+    pub fn create(
+        claims: &JwtClaims,
+        bearer_did: &BearerDid,
+        options: &SdJwtCreateOptions,
+    ) -> Result<Self> {
+        let mut targets = HashSet::new();
+        for pointer in &options.disclosable_claims {
+            let path = parse_pointer(pointer)?;
+            if path.is_empty() {
+                return Err(Web5Error::Parameter(
+                    "the claims cannot be disclosed as a whole".to_string(),
+                ));
+            }
+            targets.insert(path);
+        }
+
+        let mut concealment = Concealment {
+            targets: &targets,
+            found: HashSet::new(),
+            disclosures: vec![],
+            decoy_digests: options.decoy_digests.unwrap_or_default(),
+        };
+        let mut payload = concealment.conceal(serde_json::to_value(claims)?, &mut vec![])?;
+        if let Some(path) = targets
+            .iter()
+            .find(|path| !concealment.found.contains(*path))
+        {
+            return Err(Web5Error::Parameter(format!(
+                "claim {} not found",
+                format_pointer(path)
+            )));
+        }
+
+        let object = payload
+            .as_object_mut()
+            .ok_or(Web5Error::Parameter("claims must be an object".to_string()))?;
+        if !concealment.disclosures.is_empty() {
+            object.insert(SD_ALG_CLAIM.to_string(), Value::String(SD_ALG.to_string()));
+        }
+        if let Some(holder_jwk) = &options.holder_jwk {
+            if !holder_jwk.is_public_key() {
+                return Err(Web5Error::Parameter(
+                    "holder jwk must be a public key".to_string(),
+                ));
+            }
+            object.insert("cnf".to_string(), json!({ "jwk": holder_jwk }));
+        }
+
+        let header = JoseHeader {
+            typ: Some(options.typ.clone().unwrap_or(SD_JWT_MEDIA_TYPE.to_string())),
+            alg: None,
+            kid: None,
+            cty: None,
+            additional_properties: None,
+        };
+        let jws = Jws::from_payload_with_header(
+            serde_json::to_string(&payload)?.as_bytes(),
+            header,
+            bearer_did,
+            &SignerOptions {
+                verification_method_id: options.verification_method_id.clone(),
+                verification_relationship: Some(VerificationRelationship::AssertionMethod),
+                algorithms: None,
+            },
+        )?;
+
+        Ok(Self {
+            issuer_jwt: Jwt::try_from(jws)?,
+            disclosures: concealment.disclosures,
+            key_binding_jwt: None,
+        })
+    }
+
+    /// Constructs an SD-JWT from its serialization, `<issuer jwt>~<disclosure>~...~<kb jwt>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `serialized` - The SD-JWT, with or without a Key Binding JWT.
+    /// * `verify` - If true, verifies the issuer's signature and expiry, that each disclosure is
+    ///   referenced exactly once, and the Key Binding JWT if present, which must have been issued
+    ///   at most [`MAX_KEY_BINDING_AGE`] ago.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let sd_jwt = SdJwt::from_serialized(&serialized, true).await.unwrap();
+    /// let claims = sd_jwt.disclosed_claims().unwrap();
+    /// ```
+    pub async fn from_serialized(serialized: &str, verify: bool) -> Result<Self> {
+        let parts = serialized.split('~').collect::<Vec<&str>>();
+        let (issuer_jwt, disclosures, key_binding_jwt) = match parts.as_slice() {
+            [issuer_jwt, disclosures @ .., key_binding_jwt] => {
+                (*issuer_jwt, disclosures, *key_binding_jwt)
+            }
+            _ => {
+                return Err(VerificationError::SelectiveDisclosure(
+                    "sd-jwt must end with a ~ or a key binding jwt".to_string(),
+                )
+                .into())
+            }
+        };
+
+        let sd_jwt = Self {
+            issuer_jwt: Jwt::from_compact_jws(issuer_jwt, verify).await?,
+            disclosures: disclosures
+                .iter()
+                .map(|disclosure| Disclosure::from_encoded(disclosure))
+                .collect::<Result<Vec<Disclosure>>>()?,
+            key_binding_jwt: match key_binding_jwt {
+                "" => None,
+                // verified against the cnf key, so it needn't have a kid
+                key_binding_jwt => Some(Jwt::try_from(Jws::parse(key_binding_jwt)?)?),
+            },
+        };
+
+        if verify {
+            if let Some(exp) = sd_jwt.issuer_jwt.claims.exp {
+                if exp < SystemTime::now() {
+                    return Err(VerificationError::CredentialExpired.into());
+                }
+            }
+            let payload = sd_jwt.payload()?;
+            reconstruct(&payload, &sd_jwt.disclosures)?;
+            sd_jwt.verify_key_binding(&payload)?;
+        }
+
+        Ok(sd_jwt)
+    }
+
+    /// Verifies a presented SD-JWT, as [`Self::from_serialized`] does, and that its Key Binding
+    /// JWT meets the verifier's requirements. The `audience` and `nonce` options are required
+    /// when key binding is required or a Key Binding JWT is presented.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let sd_jwt = SdJwt::verify(
+    ///     &presentation,
+    ///     &SdJwtVerifyOptions {
+    ///         require_key_binding: true,
+    ///         audience: Some("https://verifier.example.com".to_string()),
+    ///         nonce: Some(nonce),
+    ///     },
+    /// )
+    /// .await
+    /// .unwrap();
+    /// ```
+    pub async fn verify(serialized: &str, options: &SdJwtVerifyOptions) -> Result<Self> {
+        if options.require_key_binding {
+            options.key_binding_parameters()?;
+        }
+
+        let sd_jwt = Self::from_serialized(serialized, true).await?;
+
+        let key_binding_jwt = match &sd_jwt.key_binding_jwt {
+            Some(key_binding_jwt) => key_binding_jwt,
+            None if options.require_key_binding => {
+                return Err(VerificationError::KeyBinding(
+                    "key binding jwt is required".to_string(),
+                )
+                .into())
+            }
+            None => return Ok(sd_jwt),
+        };
+
+        let (audience, nonce) = options.key_binding_parameters()?;
+
+        let aud = key_binding_jwt.claims.aud.clone().unwrap_or_default();
+        if !aud.contains(audience) {
+            return Err(VerificationError::KeyBinding(format!(
+                "aud does not include {}",
+                audience
+            ))
+            .into());
+        }
+
+        if key_binding_claim(key_binding_jwt, "nonce") != Some(nonce) {
+            return Err(VerificationError::KeyBinding("nonce does not match".to_string()).into());
+        }
+
+        Ok(sd_jwt)
+    }
+
+    /// Serializes the SD-JWT as `<issuer jwt>~<disclosure>~...~`, followed by the Key Binding JWT
+    /// if present.
+    pub fn serialize(&self) -> String {
+        let mut serialized = self.serialize_without_key_binding();
+        if let Some(key_binding_jwt) = &self.key_binding_jwt {
+            serialized.push_str(&key_binding_jwt.compact_jws);
+        }
+        serialized
+    }
+
+    /// Reconstructs the claims from the issuer-signed JWT and the disclosures. Concealed claims
+    /// without a disclosure are omitted.
+    pub fn disclosed_claims(&self) -> Result<JwtClaims> {
+        let (claims, _) = reconstruct(&self.payload()?, &self.disclosures)?;
+        Ok(serde_json::from_value(claims)?)
+    }
+
+    /// Selects the disclosures to present, for the holder to reveal only the given claims. The
+    /// disclosures of claims containing them, and of claims nested in them, are included too.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The claims to reveal, as JSON Pointers into the disclosed claims.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the SD-JWT with only the selected disclosures, and without a Key Binding JWT.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let presented = sd_jwt.disclose(&["/given_name".to_string()]).unwrap();
+    /// ```
+    pub fn disclose(&self, claims: &[String]) -> Result<Self> {
+        let (disclosed, paths) = reconstruct(&self.payload()?, &self.disclosures)?;
+
+        let mut selected = vec![];
+        for pointer in claims {
+            if disclosed.pointer(pointer).is_none() {
+                return Err(Web5Error::Parameter(format!("claim {} not found", pointer)));
+            }
+            selected.push(parse_pointer(pointer)?);
+        }
+
+        let disclosures = self
+            .disclosures
+            .iter()
+            .filter(|disclosure| {
+                let path = &paths[&disclosure.digest()];
+                selected
+                    .iter()
+                    .any(|claim| claim.starts_with(path) || path.starts_with(claim))
+            })
+            .cloned()
+            .collect();
+
+        Ok(Self {
+            issuer_jwt: self.issuer_jwt.clone(),
+            disclosures,
+            key_binding_jwt: None,
+        })
+    }
+
+    /// Adds a Key Binding JWT, signed by the holder with the key the SD-JWT's `cnf` claim binds
+    /// it to, proving possession of it to the verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `holder` - The holder's DID, with a verification method for the key in the `cnf` claim.
+    /// * `audience` - The identifier of the verifier the SD-JWT is presented to.
+    /// * `nonce` - The nonce the verifier provided, for freshness.
+    ///
+    /// # Returns
+    ///
+    /// A copy of the SD-JWT with the Key Binding JWT, replacing any existing one.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let presentation = sd_jwt
+    ///     .disclose(&["/given_name".to_string()])?
+    ///     .add_key_binding(&holder_bearer_did, "https://verifier.example.com", &nonce)?
+    ///     .serialize();
+    /// ```
+    pub fn add_key_binding(&self, holder: &BearerDid, audience: &str, nonce: &str) -> Result<Self> {
+        let holder_jwk = holder_jwk(&self.payload()?)?.ok_or(Web5Error::Parameter(
+            "sd-jwt has no cnf claim to bind to".to_string(),
+        ))?;
+        let verification_method = holder
+            .document
+            .verification_method
+            .iter()
+            .find(|vm| same_key(&vm.public_key_jwk, &holder_jwk))
+            .ok_or(Web5Error::Parameter(format!(
+                "{} has no verification method for the key in the cnf claim",
+                holder.did.uri
+            )))?;
+
+        let mut additional_properties = HashMap::new();
+        additional_properties.insert("nonce".to_string(), JsonValue::String(nonce.to_string()));
+        additional_properties.insert("sd_hash".to_string(), JsonValue::String(self.sd_hash()));
+        let claims = JwtClaims {
+            aud: Some(vec![audience.to_string()]),
+            iat: Some(Utc::now().into()),
+            additional_properties: Some(additional_properties),
+            ..Default::default()
+        };
+
+        let jws = Jws::from_payload_with_header(
+            claims.to_json_string()?.as_bytes(),
+            JoseHeader {
+                typ: Some(KB_JWT_MEDIA_TYPE.to_string()),
+                alg: None,
+                kid: None,
+                cty: None,
+                additional_properties: None,
+            },
+            holder,
+            &SignerOptions {
+                verification_method_id: Some(verification_method.id.clone()),
+                verification_relationship: Some(VerificationRelationship::Authentication),
+                algorithms: None,
+            },
+        )?;
+
+        Ok(Self {
+            issuer_jwt: self.issuer_jwt.clone(),
+            disclosures: self.disclosures.clone(),
+            key_binding_jwt: Some(Jwt::try_from(jws)?),
+        })
+    }
+
+    /// The issuer-signed payload, as signed.
+    pub(crate) fn payload(&self) -> Result<Value> {
+        let payload = general_purpose::URL_SAFE_NO_PAD
+            .decode(&self.issuer_jwt.parts[1])
+            .map_err(|e| Web5Error::Encoding(e.to_string()))?;
+        Ok(serde_json::from_slice(&payload)?)
+    }
+
+    fn serialize_without_key_binding(&self) -> String {
+        let mut serialized = format!("{}~", self.issuer_jwt.compact_jws);
+        for disclosure in &self.disclosures {
+            serialized.push_str(&disclosure.encoded);
+            serialized.push('~');
+        }
+        serialized
+    }
+
+    fn sd_hash(&self) -> String {
+        sha256_base64url(&self.serialize_without_key_binding())
+    }
+
+    fn verify_key_binding(&self, payload: &Value) -> Result<()> {
+        let key_binding_jwt = match &self.key_binding_jwt {
+            Some(key_binding_jwt) => key_binding_jwt,
+            None => return Ok(()),
+        };
+
+        if key_binding_jwt.header.typ.as_deref() != Some(KB_JWT_MEDIA_TYPE) {
+            return Err(VerificationError::KeyBinding(format!(
+                "typ must be {}",
+                KB_JWT_MEDIA_TYPE
+            ))
+            .into());
+        }
+
+        let holder_jwk = holder_jwk(payload)?.ok_or(VerificationError::KeyBinding(
+            "sd-jwt has no cnf claim to verify the key binding jwt with".to_string(),
+        ))?;
+        Jwt::from_compact_jws_with_jwk(&key_binding_jwt.compact_jws, &holder_jwk)?;
+
+        let now = SystemTime::now();
+        match key_binding_jwt.claims.iat {
+            Some(iat) if iat <= now + MAX_CLOCK_SKEW && iat + MAX_KEY_BINDING_AGE >= now => {}
+            Some(_) => {
+                return Err(VerificationError::KeyBinding(
+                    "iat is outside the acceptable window".to_string(),
+                )
+                .into())
+            }
+            None => return Err(VerificationError::KeyBinding("missing iat".to_string()).into()),
+        }
+        if key_binding_claim(key_binding_jwt, "sd_hash") != Some(&self.sd_hash()) {
+            return Err(VerificationError::KeyBinding(
+                "sd_hash does not match the presented sd-jwt".to_string(),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+fn key_binding_claim<'a>(key_binding_jwt: &'a Jwt, name: &str) -> Option<&'a String> {
+    match key_binding_jwt
+        .claims
+        .additional_properties
+        .as_ref()?
+        .get(name)
+    {
+        Some(JsonValue::String(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn holder_jwk(payload: &Value) -> Result<Option<Jwk>> {
+    match payload.pointer("/cnf/jwk") {
+        Some(jwk) => Ok(Some(serde_json::from_value(jwk.clone())?)),
+        None => Ok(None),
+    }
+}
+
+fn same_key(a: &Jwk, b: &Jwk) -> bool {
+    a.kty == b.kty && a.crv == b.crv && a.x == b.x && a.y == b.y
+}
+
+fn random_salt() -> String {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    general_purpose::URL_SAFE_NO_PAD.encode(salt)
+}
+
+fn sha256_base64url(input: &str) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(input.as_bytes()))
+}
+
+fn parse_pointer(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    match pointer.strip_prefix('/') {
+        Some(pointer) => Ok(pointer
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect()),
+        None => Err(Web5Error::Parameter(format!(
+            "{} is not a json pointer",
+            pointer
+        ))),
+    }
+}
+
+fn format_pointer(path: &[String]) -> String {
+    path.iter()
+        .map(|token| format!("/{}", token.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn is_reserved_claim_name(name: &str) -> bool {
+    name == SD_CLAIM || name == SD_ALG_CLAIM || name == ARRAY_ELEMENT_KEY
+}
+
+struct Concealment<'a> {
+    targets: &'a HashSet<Vec<String>>,
+    found: HashSet<Vec<String>>,
+    disclosures: Vec<Disclosure>,
+    decoy_digests: usize,
+}
+
+impl Concealment<'_> {
+    /// Replaces the targeted claims in the value by digests, depth first, so a nested claim is
+    /// concealed within its parent's disclosure.
+    fn conceal(&mut self, value: Value, path: &mut Vec<String>) -> Result<Value> {
+        match value {
+            Value::Object(object) => {
+                let mut output = Map::new();
+                let mut digests = vec![];
+                for (name, value) in object {
+                    if is_reserved_claim_name(&name) {
+                        return Err(Web5Error::Parameter(format!(
+                            "claim name {} is reserved",
+                            name
+                        )));
+                    }
+
+                    path.push(name.clone());
+                    let value = self.conceal(value, path)?;
+                    match self.disclose(path, Some(name.clone()), &value)? {
+                        Some(digest) => digests.push(Value::String(digest)),
+                        None => {
+                            output.insert(name, value);
+                        }
+                    }
+                    path.pop();
+                }
+
+                if !digests.is_empty() {
+                    for _ in 0..self.decoy_digests {
+                        digests.push(Value::String(sha256_base64url(&random_salt())));
+                    }
+                    // sorted, so the order of the digests reveals nothing of the claims
+                    digests.sort_by_key(|digest| digest.as_str().map(String::from));
+                    output.insert(SD_CLAIM.to_string(), Value::Array(digests));
+                }
+
+                Ok(Value::Object(output))
+            }
+            Value::Array(elements) => {
+                let mut output = vec![];
+                for (index, element) in elements.into_iter().enumerate() {
+                    path.push(index.to_string());
+                    let element = self.conceal(element, path)?;
+                    match self.disclose(path, None, &element)? {
+                        Some(digest) => output.push(json!({ ARRAY_ELEMENT_KEY: digest })),
+                        None => output.push(element),
+                    }
+                    path.pop();
+                }
+                Ok(Value::Array(output))
+            }
+            value => Ok(value),
+        }
+    }
+
+    fn disclose(
+        &mut self,
+        path: &[String],
+        claim_name: Option<String>,
+        claim_value: &Value,
+    ) -> Result<Option<String>> {
+        if !self.targets.contains(path) {
+            return Ok(None);
+        }
+
+        self.found.insert(path.to_vec());
+        let disclosure = Disclosure::new(claim_name, claim_value.clone())?;
+        let digest = disclosure.digest();
+        self.disclosures.push(disclosure);
+        Ok(Some(digest))
+    }
+}
+
+/// Reconstructs the claims of the payload from the disclosures, returning them with the path of
+/// the claim each disclosure reveals, by digest.
+fn reconstruct(
+    payload: &Value,
+    disclosures: &[Disclosure],
+) -> Result<(Value, HashMap<String, Vec<String>>)> {
+    if let Some(sd_alg) = payload.get(SD_ALG_CLAIM) {
+        if *sd_alg != SD_ALG {
+            return Err(VerificationError::SelectiveDisclosure(format!(
+                "unsupported _sd_alg {}",
+                sd_alg
+            ))
+            .into());
+        }
+    }
+
+    let mut reconstruction = Reconstruction {
+        disclosures: HashMap::new(),
+        paths: HashMap::new(),
+    };
+    for disclosure in disclosures {
+        if reconstruction
+            .disclosures
+            .insert(disclosure.digest(), disclosure)
+            .is_some()
+        {
+            return Err(VerificationError::SelectiveDisclosure(format!(
+                "disclosure {} is presented more than once",
+                disclosure.encoded
+            ))
+            .into());
+        }
+    }
+
+    let mut claims = reconstruction.reconstruct(payload, &mut vec![])?;
+    if let Some(object) = claims.as_object_mut() {
+        object.remove(SD_ALG_CLAIM);
+    }
+
+    if let Some(disclosure) = disclosures
+        .iter()
+        .find(|disclosure| !reconstruction.paths.contains_key(&disclosure.digest()))
+    {
+        return Err(VerificationError::SelectiveDisclosure(format!(
+            "disclosure {} is not referenced by the sd-jwt",
+            disclosure.encoded
+        ))
+        .into());
+    }
+
+    Ok((claims, reconstruction.paths))
+}
+
+struct Reconstruction<'a> {
+    disclosures: HashMap<String, &'a Disclosure>,
+    paths: HashMap<String, Vec<String>>,
+}
+
+impl<'a> Reconstruction<'a> {
+    fn reconstruct(&mut self, value: &Value, path: &mut Vec<String>) -> Result<Value> {
+        match value {
+            Value::Object(object) => {
+                let mut output = Map::new();
+                for (name, value) in object {
+                    if name == SD_CLAIM {
+                        continue;
+                    }
+                    path.push(name.clone());
+                    output.insert(name.clone(), self.reconstruct(value, path)?);
+                    path.pop();
+                }
+
+                let digests = match object.get(SD_CLAIM) {
+                    Some(Value::Array(digests)) => digests.as_slice(),
+                    Some(_) => {
+                        return Err(VerificationError::SelectiveDisclosure(
+                            "_sd must be an array".to_string(),
+                        )
+                        .into())
+                    }
+                    None => &[],
+                };
+                for digest in digests {
+                    let digest = digest
+                        .as_str()
+                        .ok_or(VerificationError::SelectiveDisclosure(
+                            "_sd digests must be strings".to_string(),
+                        ))?;
+                    let disclosure = match self.disclosures.get(digest) {
+                        Some(disclosure) => *disclosure,
+                        // a decoy, or a claim the holder withheld
+                        None => continue,
+                    };
+
+                    let name = disclosure.claim_name.clone().ok_or(
+                        VerificationError::SelectiveDisclosure(format!(
+                            "array element disclosure {} is referenced by _sd",
+                            disclosure.encoded
+                        )),
+                    )?;
+                    if is_reserved_claim_name(&name) || output.contains_key(&name) {
+                        return Err(VerificationError::SelectiveDisclosure(format!(
+                            "disclosed claim {} conflicts with an existing claim",
+                            name
+                        ))
+                        .into());
+                    }
+
+                    path.push(name.clone());
+                    let value = self.reveal(digest, disclosure, path)?;
+                    path.pop();
+                    output.insert(name, value);
+                }
+
+                Ok(Value::Object(output))
+            }
+            Value::Array(elements) => {
+                let mut output = vec![];
+                for element in elements {
+                    path.push(output.len().to_string());
+                    match array_element_digest(element) {
+                        Some(digest) => {
+                            if let Some(disclosure) = self.disclosures.get(digest).copied() {
+                                if disclosure.claim_name.is_some() {
+                                    return Err(VerificationError::SelectiveDisclosure(format!(
+                                        "claim disclosure {} is referenced by an array element",
+                                        disclosure.encoded
+                                    ))
+                                    .into());
+                                }
+                                output.push(self.reveal(digest, disclosure, path)?);
+                            }
+                        }
+                        None => output.push(self.reconstruct(element, path)?),
+                    }
+                    path.pop();
+                }
+                Ok(Value::Array(output))
+            }
+            value => Ok(value.clone()),
+        }
+    }
+
+    fn reveal(
+        &mut self,
+        digest: &str,
+        disclosure: &'a Disclosure,
+        path: &mut Vec<String>,
+    ) -> Result<Value> {
+        if self
+            .paths
+            .insert(digest.to_string(), path.clone())
+            .is_some()
+        {
+            return Err(VerificationError::SelectiveDisclosure(format!(
+                "digest {} is referenced more than once",
+                digest
+            ))
+            .into());
+        }
+        self.reconstruct(&disclosure.claim_value, path)
+    }
+}
+
+fn array_element_digest(element: &Value) -> Option<&str> {
+    match element.as_object() {
+        Some(object) if object.len() == 1 => object.get(ARRAY_ELEMENT_KEY)?.as_str(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dids::methods::did_jwk::DidJwk, errors::Web5Error};
+
+    fn claims(issuer: &BearerDid) -> JwtClaims {
+        serde_json::from_value(json!({
+            "iss": issuer.did.uri,
+            "given_name": "Alice",
+            "family_name": "Smith",
+            "address": {
+                "street_address": "123 Main St",
+                "locality": "Anytown",
+                "country": "US"
+            },
+            "nationalities": ["US", "DE"]
+        }))
+        .unwrap()
+    }
+
+    fn options(disclosable_claims: &[&str], holder: Option<&BearerDid>) -> SdJwtCreateOptions {
+        SdJwtCreateOptions {
+            disclosable_claims: disclosable_claims.iter().map(|c| c.to_string()).collect(),
+            holder_jwk: holder.map(|holder| {
+                holder.document.verification_method[0]
+                    .public_key_jwk
+                    .clone()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn assert_selective_disclosure_error<T>(result: Result<T>, expected: &str) {
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::SelectiveDisclosure(message))) => {
+                assert!(message.contains(expected), "unexpected message {}", message)
+            }
+            Err(e) => panic!("expected selective disclosure error, but got {:?}", e),
+            Ok(_) => panic!("expected selective disclosure error"),
+        }
+    }
+
+    fn assert_key_binding_error<T>(result: Result<T>, expected: &str) {
+        match result {
+            Err(Web5Error::CredentialError(VerificationError::KeyBinding(message))) => {
+                assert!(message.contains(expected), "unexpected message {}", message)
+            }
+            Err(e) => panic!("expected key binding error, but got {:?}", e),
+            Ok(_) => panic!("expected key binding error"),
+        }
+    }
+
+    mod disclosure {
+        use super::*;
+
+        #[test]
+        fn test_digest_of_rfc_example() {
+            let disclosure = Disclosure::from_encoded(
+                "WyJfMjZiYzRMVC1hYzZxMktJNmNCVzVlcyIsICJmYW1pbHlfbmFtZSIsICJNw7ZiaXVzIl0",
+            )
+            .unwrap();
+
+            assert_eq!(disclosure.salt, "_26bc4LT-ac6q2KI6cBW5es");
+            assert_eq!(disclosure.claim_name.as_deref(), Some("family_name"));
+            assert_eq!(disclosure.claim_value, json!("Möbius"));
+            assert_eq!(
+                disclosure.digest(),
+                "X9yH0Ajrdm1Oij4tWso9UzzKJvPoDxwmuEcO3XAdRC0"
+            );
+        }
+
+        #[test]
+        fn test_array_element_round_trip() {
+            let disclosure = Disclosure::new(None, json!({ "country": "DE" })).unwrap();
+            let decoded = Disclosure::from_encoded(&disclosure.encoded).unwrap();
+
+            assert_eq!(decoded, disclosure);
+            assert_eq!(decoded.claim_name, None);
+        }
+
+        #[test]
+        fn test_salts_are_unique() {
+            let a = Disclosure::new(Some("given_name".to_string()), json!("Alice")).unwrap();
+            let b = Disclosure::new(Some("given_name".to_string()), json!("Alice")).unwrap();
+
+            assert_ne!(a.salt, b.salt);
+            assert_ne!(a.digest(), b.digest());
+        }
+
+        #[test]
+        fn test_malformed() {
+            let encoded = general_purpose::URL_SAFE_NO_PAD.encode(r#"["salt"]"#);
+            assert_selective_disclosure_error(
+                Disclosure::from_encoded(&encoded),
+                "malformed disclosure",
+            );
+        }
+    }
+
+    mod create {
+        use super::*;
+
+        #[test]
+        fn test_conceals_selected_claims() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &options(
+                    &["/given_name", "/address/locality", "/nationalities/1"],
+                    None,
+                ),
+            )
+            .unwrap();
+
+            let payload = sd_jwt.payload().unwrap();
+            assert_eq!(
+                sd_jwt.issuer_jwt.header.typ.as_deref(),
+                Some(SD_JWT_MEDIA_TYPE)
+            );
+            assert_eq!(payload[SD_ALG_CLAIM], SD_ALG);
+            assert!(payload.get("given_name").is_none());
+            assert_eq!(payload["family_name"], "Smith");
+            assert!(payload["address"].get("locality").is_none());
+            assert_eq!(payload["address"][SD_CLAIM].as_array().unwrap().len(), 1);
+            assert_eq!(payload["nationalities"][0], "US");
+            assert!(payload["nationalities"][1][ARRAY_ELEMENT_KEY].is_string());
+            assert_eq!(sd_jwt.disclosures.len(), 3);
+        }
+
+        #[test]
+        fn test_nested_claim_is_concealed_within_parent() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &options(&["/address", "/address/country"], None),
+            )
+            .unwrap();
+
+            let address = sd_jwt
+                .disclosures
+                .iter()
+                .find(|d| d.claim_name.as_deref() == Some("address"))
+                .unwrap();
+            assert!(address.claim_value.get("country").is_none());
+            assert_eq!(address.claim_value[SD_CLAIM].as_array().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn test_decoy_digests() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &SdJwtCreateOptions {
+                    decoy_digests: Some(3),
+                    ..options(&["/given_name"], None)
+                },
+            )
+            .unwrap();
+
+            let digests = sd_jwt.payload().unwrap()[SD_CLAIM].clone();
+            assert_eq!(digests.as_array().unwrap().len(), 4);
+            let claims = sd_jwt.disclosed_claims().unwrap();
+            assert_eq!(
+                claims.additional_properties.unwrap()["given_name"],
+                JsonValue::String("Alice".to_string())
+            );
+        }
+
+        #[test]
+        fn test_holder_key_is_bound() {
+            let issuer = DidJwk::create(None).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+            let sd_jwt =
+                SdJwt::create(&claims(&issuer), &issuer, &options(&[], Some(&holder))).unwrap();
+
+            assert_eq!(
+                holder_jwk(&sd_jwt.payload().unwrap()).unwrap(),
+                Some(
+                    holder.document.verification_method[0]
+                        .public_key_jwk
+                        .clone()
+                )
+            );
+        }
+
+        #[test]
+        fn test_claim_not_found() {
+            let issuer = DidJwk::create(None).unwrap();
+            let result = SdJwt::create(&claims(&issuer), &issuer, &options(&["/age"], None));
+
+            match result {
+                Err(Web5Error::Parameter(message)) => assert_eq!(message, "claim /age not found"),
+                _ => panic!("expected parameter error"),
+            }
+        }
+
+        #[test]
+        fn test_reserved_claim_name() {
+            let issuer = DidJwk::create(None).unwrap();
+            let claims: JwtClaims = serde_json::from_value(json!({ "_sd": ["digest"] })).unwrap();
+            let result = SdJwt::create(&claims, &issuer, &options(&[], None));
+
+            match result {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "claim name _sd is reserved")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod from_serialized {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_round_trip() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &options(&["/given_name", "/nationalities/0"], None),
+            )
+            .unwrap();
+            let serialized = sd_jwt.serialize();
+            assert!(serialized.ends_with('~'));
+
+            let parsed = SdJwt::from_serialized(&serialized, true).await.unwrap();
+            assert_eq!(parsed.disclosures, sd_jwt.disclosures);
+            assert!(parsed.key_binding_jwt.is_none());
+
+            let claims = parsed.disclosed_claims().unwrap();
+            assert_eq!(claims.iss, Some(issuer.did.uri.clone()));
+            let additional_properties = claims.additional_properties.unwrap();
+            assert_eq!(
+                additional_properties["given_name"],
+                JsonValue::String("Alice".to_string())
+            );
+            assert_eq!(
+                additional_properties["nationalities"],
+                JsonValue::Array(vec![
+                    JsonValue::String("US".to_string()),
+                    JsonValue::String("DE".to_string())
+                ])
+            );
+            assert!(!additional_properties.contains_key(SD_ALG_CLAIM));
+        }
+
+        #[tokio::test]
+        async fn test_withheld_claims_are_omitted() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &options(&["/given_name", "/nationalities/0"], None),
+            )
+            .unwrap();
+            let serialized = format!("{}~", sd_jwt.issuer_jwt.compact_jws);
+
+            let claims = SdJwt::from_serialized(&serialized, true)
+                .await
+                .unwrap()
+                .disclosed_claims()
+                .unwrap();
+            let additional_properties = claims.additional_properties.unwrap();
+            assert!(!additional_properties.contains_key("given_name"));
+            assert_eq!(
+                additional_properties["nationalities"],
+                JsonValue::Array(vec![JsonValue::String("DE".to_string())])
+            );
+        }
+
+        #[tokio::test]
+        async fn test_unreferenced_disclosure() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt =
+                SdJwt::create(&claims(&issuer), &issuer, &options(&["/given_name"], None)).unwrap();
+            let forged = Disclosure::new(Some("given_name".to_string()), json!("Mallory")).unwrap();
+            let serialized = format!("{}{}~", sd_jwt.serialize(), forged.encoded);
+
+            assert_selective_disclosure_error(
+                SdJwt::from_serialized(&serialized, true).await,
+                "is not referenced by the sd-jwt",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_duplicate_disclosure() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt =
+                SdJwt::create(&claims(&issuer), &issuer, &options(&["/given_name"], None)).unwrap();
+            let serialized = format!("{}{}~", sd_jwt.serialize(), sd_jwt.disclosures[0].encoded);
+
+            assert_selective_disclosure_error(
+                SdJwt::from_serialized(&serialized, true).await,
+                "is presented more than once",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_missing_trailing_tilde() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(&claims(&issuer), &issuer, &options(&[], None)).unwrap();
+
+            assert_selective_disclosure_error(
+                SdJwt::from_serialized(&sd_jwt.issuer_jwt.compact_jws, true).await,
+                "must end with a ~",
+            );
+        }
+    }
+
+    mod disclose {
+        use super::*;
+
+        #[test]
+        fn test_selects_disclosures_of_containing_and_nested_claims() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &options(
+                    &[
+                        "/given_name",
+                        "/address",
+                        "/address/locality",
+                        "/address/country",
+                    ],
+                    None,
+                ),
+            )
+            .unwrap();
+
+            let presented = sd_jwt.disclose(&["/address/locality".to_string()]).unwrap();
+            let mut names = presented
+                .disclosures
+                .iter()
+                .map(|d| d.claim_name.clone().unwrap())
+                .collect::<Vec<String>>();
+            names.sort();
+            assert_eq!(names, vec!["address", "locality"]);
+
+            let presented = sd_jwt.disclose(&["/address".to_string()]).unwrap();
+            assert_eq!(presented.disclosures.len(), 3);
+        }
+
+        #[test]
+        fn test_claim_not_found() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt =
+                SdJwt::create(&claims(&issuer), &issuer, &options(&["/given_name"], None)).unwrap();
+
+            match sd_jwt.disclose(&["/age".to_string()]) {
+                Err(Web5Error::Parameter(message)) => assert_eq!(message, "claim /age not found"),
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+
+    mod verify {
+        use super::*;
+
+        const AUDIENCE: &str = "https://verifier.example.com";
+        const NONCE: &str = "1234567890";
+
+        fn verify_options() -> SdJwtVerifyOptions {
+            SdJwtVerifyOptions {
+                require_key_binding: true,
+                audience: Some(AUDIENCE.to_string()),
+                nonce: Some(NONCE.to_string()),
+            }
+        }
+
+        fn bound_sd_jwt(holder: &BearerDid) -> SdJwt {
+            let issuer = DidJwk::create(None).unwrap();
+            SdJwt::create(
+                &claims(&issuer),
+                &issuer,
+                &options(&["/given_name", "/family_name"], Some(holder)),
+            )
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_key_binding() {
+            let holder = DidJwk::create(None).unwrap();
+            let presentation = bound_sd_jwt(&holder)
+                .disclose(&["/given_name".to_string()])
+                .unwrap()
+                .add_key_binding(&holder, AUDIENCE, NONCE)
+                .unwrap()
+                .serialize();
+
+            let sd_jwt = SdJwt::verify(&presentation, &verify_options())
+                .await
+                .unwrap();
+            let key_binding_jwt = sd_jwt.key_binding_jwt.as_ref().unwrap();
+            assert_eq!(
+                key_binding_jwt.header.typ.as_deref(),
+                Some(KB_JWT_MEDIA_TYPE)
+            );
+
+            let additional_properties = sd_jwt
+                .disclosed_claims()
+                .unwrap()
+                .additional_properties
+                .unwrap();
+            assert!(additional_properties.contains_key("given_name"));
+            assert!(!additional_properties.contains_key("family_name"));
+        }
+
+        #[tokio::test]
+        async fn test_key_binding_required() {
+            let holder = DidJwk::create(None).unwrap();
+            let presentation = bound_sd_jwt(&holder).serialize();
+
+            assert_key_binding_error(
+                SdJwt::verify(&presentation, &verify_options()).await,
+                "key binding jwt is required",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_audience_and_nonce_required() {
+            let holder = DidJwk::create(None).unwrap();
+            let presentation = bound_sd_jwt(&holder)
+                .add_key_binding(&holder, AUDIENCE, NONCE)
+                .unwrap()
+                .serialize();
+
+            let options = [
+                SdJwtVerifyOptions {
+                    audience: None,
+                    ..verify_options()
+                },
+                SdJwtVerifyOptions {
+                    nonce: None,
+                    ..verify_options()
+                },
+                SdJwtVerifyOptions {
+                    require_key_binding: false,
+                    audience: None,
+                    nonce: None,
+                },
+            ];
+            for options in options {
+                match SdJwt::verify(&presentation, &options).await {
+                    Err(Web5Error::Parameter(message)) => assert_eq!(
+                        message,
+                        "audience and nonce are required to verify a key binding jwt"
+                    ),
+                    _ => panic!("expected parameter error"),
+                }
+            }
+
+            let unbound = bound_sd_jwt(&holder).serialize();
+            let result = SdJwt::verify(
+                &unbound,
+                &SdJwtVerifyOptions {
+                    nonce: None,
+                    ..verify_options()
+                },
+            )
+            .await;
+            assert!(matches!(result, Err(Web5Error::Parameter(_))));
+        }
+
+        #[tokio::test]
+        async fn test_audience_mismatch() {
+            let holder = DidJwk::create(None).unwrap();
+            let presentation = bound_sd_jwt(&holder)
+                .add_key_binding(&holder, "https://other.example.com", NONCE)
+                .unwrap()
+                .serialize();
+
+            assert_key_binding_error(
+                SdJwt::verify(&presentation, &verify_options()).await,
+                "aud does not include",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_nonce_mismatch() {
+            let holder = DidJwk::create(None).unwrap();
+            let presentation = bound_sd_jwt(&holder)
+                .add_key_binding(&holder, AUDIENCE, "replayed")
+                .unwrap()
+                .serialize();
+
+            assert_key_binding_error(
+                SdJwt::verify(&presentation, &verify_options()).await,
+                "nonce does not match",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_disclosures_changed_after_binding() {
+            let holder = DidJwk::create(None).unwrap();
+            let mut sd_jwt = bound_sd_jwt(&holder)
+                .add_key_binding(&holder, AUDIENCE, NONCE)
+                .unwrap();
+            sd_jwt.disclosures.pop();
+
+            assert_key_binding_error(
+                SdJwt::verify(&sd_jwt.serialize(), &verify_options()).await,
+                "sd_hash does not match",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_signed_by_other_key() {
+            let holder = DidJwk::create(None).unwrap();
+            let other = DidJwk::create(None).unwrap();
+            let sd_jwt = bound_sd_jwt(&holder);
+
+            match sd_jwt.add_key_binding(&other, AUDIENCE, NONCE) {
+                Err(Web5Error::Parameter(message)) => {
+                    assert!(message.contains("has no verification method for the key"))
+                }
+                _ => panic!("expected parameter error"),
+            }
+
+            let mut forged = sd_jwt.clone();
+            forged.key_binding_jwt = other_holder_binding(&sd_jwt, &other).key_binding_jwt;
+            assert!(SdJwt::verify(&forged.serialize(), &verify_options())
+                .await
+                .is_err());
+        }
+
+        fn other_holder_binding(sd_jwt: &SdJwt, other: &BearerDid) -> SdJwt {
+            let issuer = DidJwk::create(None).unwrap();
+            let rebound = SdJwt::create(&claims(&issuer), &issuer, &options(&[], Some(other)))
+                .unwrap()
+                .add_key_binding(other, AUDIENCE, NONCE)
+                .unwrap();
+            SdJwt {
+                issuer_jwt: sd_jwt.issuer_jwt.clone(),
+                disclosures: sd_jwt.disclosures.clone(),
+                key_binding_jwt: rebound.key_binding_jwt,
+            }
+        }
+
+        /// Binds the SD-JWT with a Key Binding JWT of the given `iat`, signed without a `kid`.
+        fn bind_without_kid(sd_jwt: &SdJwt, holder: &BearerDid, iat: SystemTime) -> SdJwt {
+            let verification_method = &holder.document.verification_method[0];
+            let header = json!({
+                "typ": KB_JWT_MEDIA_TYPE,
+                "alg": verification_method.public_key_jwk.alg,
+            });
+            let claims = JwtClaims {
+                aud: Some(vec![AUDIENCE.to_string()]),
+                iat: Some(iat),
+                additional_properties: Some(HashMap::from([
+                    ("nonce".to_string(), JsonValue::String(NONCE.to_string())),
+                    ("sd_hash".to_string(), JsonValue::String(sd_jwt.sd_hash())),
+                ])),
+                ..Default::default()
+            };
+            let message = format!(
+                "{}.{}",
+                general_purpose::URL_SAFE_NO_PAD.encode(header.to_string()),
+                general_purpose::URL_SAFE_NO_PAD.encode(claims.to_json_string().unwrap())
+            );
+            let signature = holder
+                .get_signer(&verification_method.id)
+                .unwrap()
+                .sign(message.as_bytes())
+                .unwrap();
+            let compact_jws = format!(
+                "{}.{}",
+                message,
+                general_purpose::URL_SAFE_NO_PAD.encode(signature)
+            );
+
+            SdJwt {
+                key_binding_jwt: Some(Jwt::try_from(Jws::parse(&compact_jws).unwrap()).unwrap()),
+                ..sd_jwt.clone()
+            }
+        }
+
+        #[tokio::test]
+        async fn test_key_binding_without_kid() {
+            let holder = DidJwk::create(None).unwrap();
+            let presentation =
+                bind_without_kid(&bound_sd_jwt(&holder), &holder, SystemTime::now()).serialize();
+
+            let sd_jwt = SdJwt::verify(&presentation, &verify_options())
+                .await
+                .unwrap();
+            assert!(sd_jwt.key_binding_jwt.unwrap().kid.is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_stale_key_binding() {
+            let holder = DidJwk::create(None).unwrap();
+            let iat = SystemTime::now() - MAX_KEY_BINDING_AGE - Duration::from_secs(1);
+            let presentation = bind_without_kid(&bound_sd_jwt(&holder), &holder, iat).serialize();
+
+            assert_key_binding_error(
+                SdJwt::verify(&presentation, &verify_options()).await,
+                "iat is outside the acceptable window",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_key_binding_issued_in_the_future() {
+            let holder = DidJwk::create(None).unwrap();
+            let iat = SystemTime::now() + MAX_CLOCK_SKEW + Duration::from_secs(60);
+            let presentation = bind_without_kid(&bound_sd_jwt(&holder), &holder, iat).serialize();
+
+            assert_key_binding_error(
+                SdJwt::verify(&presentation, &verify_options()).await,
+                "iat is outside the acceptable window",
+            );
+        }
+
+        #[tokio::test]
+        async fn test_no_cnf_claim() {
+            let issuer = DidJwk::create(None).unwrap();
+            let sd_jwt = SdJwt::create(&claims(&issuer), &issuer, &options(&[], None)).unwrap();
+            let holder = DidJwk::create(None).unwrap();
+
+            match sd_jwt.add_key_binding(&holder, AUDIENCE, NONCE) {
+                Err(Web5Error::Parameter(message)) => {
+                    assert_eq!(message, "sd-jwt has no cnf claim to bind to")
+                }
+                _ => panic!("expected parameter error"),
+            }
+        }
+    }
+}
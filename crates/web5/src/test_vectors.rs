@@ -28,6 +28,7 @@ impl<I, O> TestVectorFile<I, O> {
 }
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod test_vectors {
     use super::*;
 
@@ -1,4 +1,4 @@
-use crate::utils::warn_if_not_root;
+use crate::utils::{read_password, warn_if_not_root};
 use clap::Subcommand;
 use std::sync::Arc;
 use url::Url;
@@ -18,6 +18,10 @@ use web5::{
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     Jwk {
+        /// Encrypt the output Portable DID under a password, read from the WEB5_PASSWORD
+        /// environment variable, a prompt, or stdin.
+        #[arg(long)]
+        password: bool,
         #[arg(long)]
         no_indent: bool,
         #[arg(long)]
@@ -29,6 +33,13 @@ pub enum Commands {
         service_endpoint: Option<String>,
         #[arg(long = "service-endpoint-type", default_value = "LinkedDomains")]
         service_endpoint_type: String,
+        /// Generate an X25519 key for key agreement, so the DID can receive encrypted messages.
+        #[arg(long)]
+        key_agreement: bool,
+        /// Encrypt the output Portable DID under a password, read from the WEB5_PASSWORD
+        /// environment variable, a prompt, or stdin.
+        #[arg(long)]
+        password: bool,
         #[arg(long)]
         no_indent: bool,
         #[arg(long)]
@@ -41,6 +52,13 @@ pub enum Commands {
         service_endpoint_type: String,
         #[arg(long)]
         no_publish: bool,
+        /// Generate an X25519 key for key agreement, so the DID can receive encrypted messages.
+        #[arg(long)]
+        key_agreement: bool,
+        /// Encrypt the output Portable DID under a password, read from the WEB5_PASSWORD
+        /// environment variable, a prompt, or stdin.
+        #[arg(long)]
+        password: bool,
         #[arg(long)]
         no_indent: bool,
         #[arg(long)]
@@ -51,9 +69,16 @@ pub enum Commands {
 fn print_portable_did(
     mut sink: impl std::io::Write,
     portable_did: PortableDid,
+    password: &bool,
     no_indent: &bool,
     json_escape: &bool,
 ) {
+    if *password {
+        let password = read_password("Portable DID password: ");
+        writeln!(sink, "{}", portable_did.encrypt(&password).unwrap()).unwrap();
+        return;
+    }
+
     let mut output_str = match no_indent {
         true => serde_json::to_string(&portable_did).unwrap(),
         false => serde_json::to_string_pretty(&portable_did).unwrap(),
@@ -70,6 +95,7 @@ impl Commands {
     pub async fn command(&self, sink: impl std::io::Write) {
         match self {
             Commands::Jwk {
+                password,
                 no_indent,
                 json_escape,
             } => {
@@ -85,12 +111,14 @@ impl Commands {
 
                 let portable_did = bearer_did.to_portable_did(key_manager).unwrap();
 
-                print_portable_did(sink, portable_did, no_indent, json_escape);
+                print_portable_did(sink, portable_did, password, no_indent, json_escape);
             }
             Commands::Web {
                 domain,
                 service_endpoint,
                 service_endpoint_type,
+                key_agreement,
+                password,
                 no_indent,
                 json_escape,
             } => {
@@ -100,6 +128,7 @@ impl Commands {
 
                 let mut did_web_create_options = DidWebCreateOptions {
                     key_manager: Some(key_manager.clone()),
+                    key_agreement: Some(*key_agreement),
                     ..Default::default()
                 };
 
@@ -123,7 +152,8 @@ impl Commands {
                     let service = Service {
                         id: format!("did:web:{}#service-1", domain_host),
                         r#type: service_endpoint_type.clone(),
-                        service_endpoint: vec![service_endpoint_url.clone()],
+                        service_endpoint: vec![service_endpoint_url.clone()].into(),
+                        additional_properties: None,
                     };
                     did_web_create_options.service = Some(vec![service]);
                 }
@@ -132,12 +162,14 @@ impl Commands {
 
                 let portable_did = bearer_did.to_portable_did(key_manager).unwrap();
 
-                print_portable_did(sink, portable_did, no_indent, json_escape);
+                print_portable_did(sink, portable_did, password, no_indent, json_escape);
             }
             Commands::Dht {
                 service_endpoint,
                 service_endpoint_type,
                 no_publish,
+                key_agreement,
+                password,
                 no_indent,
                 json_escape,
             } => {
@@ -148,6 +180,7 @@ impl Commands {
                 let mut did_dht_create_options = DidDhtCreateOptions {
                     publish: Some(!no_publish),
                     key_manager: Some(key_manager.clone()),
+                    key_agreement: Some(*key_agreement),
                     ..Default::default()
                 };
 
@@ -156,7 +189,8 @@ impl Commands {
                     let service = Service {
                         id: "did:dht:#service-1".to_string(),
                         r#type: service_endpoint_type.clone(),
-                        service_endpoint: vec![service_endpoint_url.clone()],
+                        service_endpoint: vec![service_endpoint_url.clone()].into(),
+                        additional_properties: None,
                     };
                     did_dht_create_options.service = Some(vec![service]);
                 }
@@ -165,7 +199,7 @@ impl Commands {
 
                 let portable_did = bearer_did.to_portable_did(key_manager).unwrap();
 
-                print_portable_did(sink, portable_did, no_indent, json_escape);
+                print_portable_did(sink, portable_did, password, no_indent, json_escape);
             }
         }
     }
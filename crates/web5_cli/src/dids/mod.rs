@@ -1,4 +1,6 @@
 mod create;
+mod resolver;
+mod web;
 
 use clap::Subcommand;
 use web5::dids::resolution::{
@@ -14,6 +16,14 @@ pub enum Commands {
         #[command(subcommand)]
         did_create_command: create::Commands,
     },
+    Resolver {
+        #[command(subcommand)]
+        did_resolver_command: resolver::Commands,
+    },
+    Web {
+        #[command(subcommand)]
+        did_web_command: web::Commands,
+    },
 }
 
 impl Commands {
@@ -41,6 +51,10 @@ impl Commands {
                 }
             }
             Commands::Create { did_create_command } => did_create_command.command(sink).await,
+            Commands::Resolver {
+                did_resolver_command,
+            } => did_resolver_command.command(sink).await,
+            Commands::Web { did_web_command } => did_web_command.command(sink).await,
         }
     }
 }
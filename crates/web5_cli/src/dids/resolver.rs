@@ -0,0 +1,43 @@
+use clap::Subcommand;
+use std::sync::Arc;
+use web5::dids::resolution::{
+    resolution_options::ResolutionOptions, resolver_service::ResolverService,
+    universal_resolver::UniversalResolverClient,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Serves `GET /1.0/identifiers/{did}` compatible with the Universal Resolver API.
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// A Universal Resolver to delegate unsupported DID methods to.
+        #[arg(long)]
+        universal_resolver_url: Option<String>,
+    },
+}
+
+impl Commands {
+    pub async fn command(&self, mut sink: impl std::io::Write) {
+        match self {
+            Commands::Serve {
+                listen,
+                universal_resolver_url,
+            } => {
+                let resolver_service = ResolverService {
+                    resolution_options: universal_resolver_url.as_ref().map(|url| {
+                        ResolutionOptions {
+                            universal_resolver: Some(Arc::new(UniversalResolverClient::new(url))),
+                            ..Default::default()
+                        }
+                    }),
+                };
+
+                writeln!(sink, "Serving DID resolution on http://{}", listen).unwrap();
+                if let Err(e) = resolver_service.serve(listen).await {
+                    eprintln!("{:?} {}", e, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use clap::Subcommand;
+use std::{fs, sync::Arc};
+use web5::{
+    dids::{
+        data_model::document::Document, methods::did_web::DidWebServer, portable_did::PortableDid,
+    },
+    json::FromJson,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Hosts did:web DID documents, each at its `/.well-known/did.json` or `/{path}/did.json`.
+    ///
+    /// Each file holds either a DID Document or a Portable DID in JSON format. Documents may be
+    /// updated with `PUT` requests holding an update signed by the DID or one of its controllers.
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+        /// The DID Document or Portable DID files to host.
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+}
+
+impl Commands {
+    pub async fn command(&self, mut sink: impl std::io::Write) {
+        match self {
+            Commands::Serve { listen, files } => {
+                let server = Arc::new(DidWebServer::new());
+                for file in files {
+                    let json = match fs::read_to_string(file) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            eprintln!("{:?} {}", e, e);
+                            return;
+                        }
+                    };
+                    let document = match PortableDid::from_json_string(&json) {
+                        Ok(portable_did) => portable_did.document,
+                        Err(_) => match Document::from_json_string(&json) {
+                            Ok(document) => document,
+                            Err(e) => {
+                                eprintln!("{:?} {}", e, e);
+                                return;
+                            }
+                        },
+                    };
+                    let did_uri = document.id.clone();
+                    if let Err(e) = server.host(document) {
+                        eprintln!("{:?} {}", e, e);
+                        return;
+                    }
+                    writeln!(sink, "Hosting {}", did_uri).unwrap();
+                }
+
+                writeln!(sink, "Serving did:web documents on http://{}", listen).unwrap();
+                if let Err(e) = server.serve(listen).await {
+                    eprintln!("{:?} {}", e, e);
+                }
+            }
+        }
+    }
+}
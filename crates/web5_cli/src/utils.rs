@@ -1,4 +1,7 @@
-use std::env;
+use std::{
+    env,
+    io::{self, BufRead, IsTerminal},
+};
 
 // ANSI color codes
 const YELLOW_COLOR: &str = "\x1b[93m";
@@ -22,3 +25,26 @@ pub fn warn_if_not_root() {
         eprintln!("\n{}{}{}\n", YELLOW_COLOR, WARNING_MESSAGE, RESET_COLOR);
     }
 }
+
+// The environment variable a password is read from, so it needn't be typed.
+pub const PASSWORD_ENV_VAR: &str = "WEB5_PASSWORD";
+
+// Function to read a password without it appearing in the process arguments: from the
+// WEB5_PASSWORD environment variable if set, otherwise from a prompt which doesn't echo the
+// input, or the first line of stdin if stdin isn't a terminal
+pub fn read_password(prompt: &str) -> String {
+    if let Ok(password) = env::var(PASSWORD_ENV_VAR) {
+        return password;
+    }
+
+    if io::stdin().is_terminal() {
+        return rpassword::prompt_password(prompt).expect("failed to read password");
+    }
+
+    let mut password = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut password)
+        .expect("failed to read password from stdin");
+    password.trim_end_matches(['\r', '\n']).to_string()
+}
@@ -1,3 +1,4 @@
+use crate::utils::read_password;
 use chrono::{DateTime, FixedOffset};
 use clap::Subcommand;
 use web5::{
@@ -42,6 +43,10 @@ pub enum Commands {
         /// URI is automatically set as the issuer, and the VC is signed.
         #[arg(short, long)]
         portable_did: Option<String>,
+        /// Decrypt the Portable DID, if it was created with --password, under a password read
+        /// from the WEB5_PASSWORD environment variable, a prompt, or stdin.
+        #[arg(long)]
+        portable_did_password: bool,
         /// The DID of the issuer of the credential. Required if --portable-did
         /// is not given. Overrides the issuer of the Portable DID if both are passed.
         #[arg(short, long)]
@@ -99,6 +104,7 @@ impl Commands {
             Commands::Create {
                 credential_subject_id,
                 portable_did,
+                portable_did_password,
                 issuer,
                 expiration_date,
                 credential_status,
@@ -112,9 +118,13 @@ impl Commands {
                 no_indent,
                 json_escape,
             } => {
-                let portable_did = portable_did
-                    .as_ref()
-                    .map(|p| PortableDid::from_json_string(p).unwrap());
+                let portable_did = portable_did.as_ref().map(|p| match portable_did_password {
+                    true => {
+                        let password = read_password("Portable DID password: ");
+                        PortableDid::from_encrypted(p, &password).unwrap()
+                    }
+                    false => PortableDid::from_json_string(p).unwrap(),
+                });
                 let issuer = Issuer::String(match issuer {
                     Some(i) => i.to_string(),
                     None => match &portable_did {